@@ -1,7 +1,8 @@
 use sp_core::{Pair, Public, sr25519};
 use zerodao::{
 	AccountId, BlockNumber, AuraConfig, BalancesConfig, CurrencyId, GenesisConfig, GrandpaConfig,
-	SudoConfig, SystemConfig, WASM_BINARY, Signature, TokensConfig, ZdReputationConfig
+	SudoConfig, SystemConfig, WASM_BINARY, Signature, TokensConfig, ZdReputationConfig,
+	ZdRefreshReputationConfig,
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
@@ -158,7 +159,11 @@ fn testnet_genesis(
 		}),
 		zd_reputation: Some(ZdReputationConfig {
 			period,
+			refresh_period: 14_400,
+			confirm_period: 10,
+			reputations: vec![],
 		}),
+		zd_refresh_reputation: Some(ZdRefreshReputationConfig::default()),
 		orml_tokens: Some(TokensConfig {
             endowed_accounts: endowed_accounts
                 .iter()
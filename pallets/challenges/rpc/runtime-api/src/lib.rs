@@ -0,0 +1,39 @@
+// Copyright 2021 ZeroDAO
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for the Challenges module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use zd_primitives::{AppId, ChallengePreview};
+
+sp_api::decl_runtime_apis! {
+    pub trait ChallengesApi<AccountId, BlockNumber> where
+        AccountId: Codec,
+        BlockNumber: Codec,
+    {
+        /// A dry run of `challenger` launching a challenge against `target` under `app_id`:
+        /// the deposit it takes, the score it disputes, the deadline it gets and whether
+        /// `target` is still within its confirmation period. The preview is the same for any
+        /// `challenger` for now.
+        fn can_challenge(
+            app_id: AppId,
+            challenger: AccountId,
+            target: AccountId,
+        ) -> ChallengePreview<BlockNumber>;
+    }
+}
@@ -21,6 +21,34 @@
 //! specific data operations. The challenge process includes: initiating a challenge, initiating a examine,
 //! responding to a examine, presenting evidence, initiating arbitration, and receiving a reward.
 //!
+//! The side an arbitration went against may appeal it within `AppealPeriod` by staking
+//! `AppealStakingAmount`, the upper level module then resolves the appeal from a privileged
+//! origin, confirming or overturning the arbitration.
+//!
+//! Each `reply` and `evidence` pushes the deadline of its challenge forward by the
+//! `response_window` of the app, up to `max_response_extension` in total, so that the
+//! counterparty is not left without time to respond.
+//!
+//! Appeals are resolved by a committee of `Arbiters`, each staking `ArbiterStakingAmount`
+//! on its vote. The appeal is resolved once `ArbitralThreshold` of them vote for the same
+//! score, the arbiters who voted for another score forfeit their stakes to the treasury.
+//! Only an appeal still unresolved `AppealVotingPeriod` after it was made can be resolved
+//! by the upper level module from its privileged origin.
+//!
+//! `PauseOrigin` may pause the challenges of an app, which rejects new challenges and
+//! stops the clock its timeouts and periods are measured on until it is unpaused.
+//!
+//! With a payout delay set for an app, the amounts won at harvest are held in escrow until
+//! the delay has passed and then claimed with `claim_payout`, `PauseOrigin` may meanwhile
+//! send a disputed payout to the treasury.
+//!
+//! An app may bond its challenges in a currency of its own with `set_deposit_currency`, the
+//! deposits, stakes and prize pools of its challenges are then all held and paid out in it.
+//!
+//! Timed out challenges are settled a few at a time in `on_initialize`, and the offchain
+//! worker of each node also submits an unsigned `settle_expired` for each expired challenge,
+//! so settlement does not wait for a participant to harvest it.
+//!
 //! ### Implementations
 //!
 //! The ZdChallenges pallet provides implementations for the following traits:
@@ -30,45 +58,188 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::unused_unit)]
 
-use frame_support::{ensure, pallet, transactional, traits::Get};
-use frame_system::{self as system};
-use sp_runtime::{traits::Zero, DispatchError, DispatchResult, SaturatedConversion};
+use frame_support::{
+    ensure, pallet,
+    storage::{with_transaction, StoragePrefixedMap, TransactionOutcome},
+    traits::{Contains, Get},
+    transactional,
+    weights::Weight,
+};
+use frame_system::{
+    self as system,
+    offchain::{SendTransactionTypes, SubmitTransaction},
+};
+use sp_runtime::{
+    traits::{Saturating, Zero},
+    DispatchError, DispatchResult, Perbill, SaturatedConversion,
+};
+use sp_std::{boxed::Box, vec::Vec};
+
+use zd_primitives::{
+    fee::SweeperFee, storage::iter_double_map_from, AppConfig, Appeal, AppId, Balance,
+    ChallengePreview, ChallengeRecord, ChallengeState, ChallengeStatus, Metadata, Payout, Pool,
+    Progress, RewardCurve, Ruling, SettlementOutcome, TIRStep,
+};
+use zd_support::{
+    ChallengeBase, ChallengeError, ChallengeHarvested, MultiBaseToken, MultiCurrencyStaking,
+    OnChallengeTransition, Reputation,
+};
 
-use zd_primitives::{fee::SweeperFee, AppId, Balance, ChallengeStatus, Metadata, TIRStep, Pool};
-use zd_support::{ChallengeBase, MultiBaseToken, Reputation};
+/// Maximum number of challenges returned by one call of `challenges_of`.
+const MAX_CHALLENGES_PAGE: u32 = 1_000;
+
+/// Maximum number of challenges one call of `harvest_all` tries to harvest.
+pub const MAX_HARVEST_ALL: u32 = 50;
 
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
 mod tests;
 
-pub use pallet::*;
+pub mod weights;
+pub use weights::WeightInfo;
 
-/// Maximum number of data uploads, this is a setting for security reasons.
-const MAX_UPDATE_COUNT: u32 = 257;
+pub use pallet::*;
 
 #[pallet]
 pub mod pallet {
     use super::*;
 
     use frame_support::pallet_prelude::*;
+    use frame_system::{ensure_signed, pallet_prelude::*};
 
     #[pallet::config]
-    pub trait Config: frame_system::Config {
+    pub trait Config: frame_system::Config + SendTransactionTypes<Call<Self>> {
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
         type CurrencyId: Parameter + Member + Copy + MaybeSerializeDeserialize + Ord;
         type ZdToken: MultiBaseToken<Self::AccountId, Balance>;
+        type CurrencyStaking: MultiCurrencyStaking<Self::AccountId, Self::CurrencyId, Balance>;
         type Reputation: Reputation<Self::AccountId, Self::BlockNumber, TIRStep>;
 
         /// The maximum time for legitimate operations, such as the challenge time window
         /// left for the challenger, the reply time left for the pathfinder, etc., they
-        /// all use this constant.
+        /// all use this constant unless the app has an `AppConfig`.
         #[pallet::constant]
         type ChallengeTimeout: Get<Self::BlockNumber>;
 
-        /// The price of admission to the challenge game.
+        /// The price of admission to the challenge game, unless the app has an `AppConfig`.
         #[pallet::constant]
         type ChallengeStakingAmount: Get<Balance>;
+
+        /// Maximum number of data uploads per `next`, this is a setting for security reasons.
+        /// Used unless the app has an `AppConfig`.
+        #[pallet::constant]
+        type MaxNextSteps: Get<u32>;
+
+        /// Minimum difference between the claimed and the recorded score of a challenge,
+        /// unless the app has an `AppConfig`.
+        #[pallet::constant]
+        type MinDisputeDelta: Get<Self::Score>;
+
+        /// The time `reply` and `evidence` push the deadline of a challenge forward by, so the
+        /// counterparty can still respond to them, unless the app has an `AppConfig`.
+        #[pallet::constant]
+        type ResponseWindow: Get<Self::BlockNumber>;
+
+        /// Maximum total time the deadline of a challenge can be pushed forward by, unless the
+        /// app has an `AppConfig`.
+        #[pallet::constant]
+        type MaxResponseExtension: Get<Self::BlockNumber>;
+
+        /// Maximum `total` of data a `reply` can announce, so that no challenge can be
+        /// stretched into an unbounded number of rounds.
+        #[pallet::constant]
+        type MaxReplyCount: Get<u32>;
+
+        /// Maximum number of data uploaded by one `reply`.
+        #[pallet::constant]
+        type MaxLegsPerReply: Get<u32>;
+
+        /// The origin allowed to set the `AppConfig` of an app.
+        type AppConfigOrigin: EnsureOrigin<Self::Origin>;
+
+        /// The origin allowed to pause and unpause the challenges of an app.
+        type PauseOrigin: EnsureOrigin<Self::Origin>;
+
+        /// Receives what `RewardCurve` withholds from the challenger side.
+        #[pallet::constant]
+        type TreasuryAccountId: Get<Self::AccountId>;
+
+        /// Maximum length of the reference to an off-chain proof attached to a challenge.
+        #[pallet::constant]
+        type MaxEvidenceLen: Get<u32>;
+
+        /// Maximum number of accounts backing a challenge besides the challenger.
+        #[pallet::constant]
+        type MaxBackers: Get<u32>;
+
+        /// Deducted from the deposit of a cancelled challenge and paid to the pathfinder.
+        #[pallet::constant]
+        type CancellationFee: Get<Balance>;
+
+        /// How long after `arbitral` or `settle` the losing side may `appeal`.
+        #[pallet::constant]
+        type AppealPeriod: Get<Self::BlockNumber>;
+
+        /// The bond of an appeal, it should be larger than `ChallengeStakingAmount`.
+        #[pallet::constant]
+        type AppealStakingAmount: Get<Balance>;
+
+        /// The committee voting on appeals, such as a membership pallet.
+        type Arbiters: Contains<Self::AccountId>;
+
+        /// The number of matching votes of `Arbiters` that resolves an appeal.
+        #[pallet::constant]
+        type ArbitralThreshold: Get<u32>;
+
+        /// Staked by an arbiter on each vote, forfeited if the vote loses.
+        #[pallet::constant]
+        type ArbiterStakingAmount: Get<Balance>;
+
+        /// How long the `Arbiters` may vote on an appeal before it can be resolved without
+        /// reaching `ArbitralThreshold`.
+        #[pallet::constant]
+        type AppealVotingPeriod: Get<Self::BlockNumber>;
+
+        /// Maximum number of challenges checked for a timeout in each block.
+        #[pallet::constant]
+        type MaxAutoSettlements: Get<u32>;
+
+        /// Maximum number of `settle_expired` transactions the offchain worker submits in
+        /// each block.
+        #[pallet::constant]
+        type MaxOffchainSettlements: Get<u32>;
+
+        /// The priority of unsigned `settle_expired` transactions.
+        #[pallet::constant]
+        type UnsignedPriority: Get<TransactionPriority>;
+
+        /// Number of harvested challenges kept in the history of each target.
+        #[pallet::constant]
+        type MaxHistory: Get<u32>;
+
+        /// Number of rounds after which a harvested challenge is pruned from the history.
+        #[pallet::constant]
+        type HistoryRounds: Get<u32>;
+
+        /// Maximum number of histories checked for pruning in each block.
+        #[pallet::constant]
+        type MaxHistoryPrunes: Get<u32>;
+
+        /// Data the apps keep alongside their challenges.
+        type Extra: Parameter + Member + Default;
+
+        /// The score the challenges dispute, it may be signed or fixed-point.
+        type Score: Parameter + Member + Copy + Default + Ord + Saturating;
+
+        /// Applies the outcome of the challenges settled when they time out.
+        type OnHarvested: ChallengeHarvested<Self::AccountId, AppId, Self::Score>;
+
+        /// Notified of each step of the challenges.
+        type OnTransition: OnChallengeTransition<Self::AccountId, AppId>;
+
+        /// The weight information of this pallet.
+        type WeightInfo: WeightInfo;
     }
 
     #[pallet::pallet]
@@ -83,20 +254,271 @@ pub mod pallet {
         AppId,
         Twox64Concat,
         T::AccountId,
-        Metadata<T::AccountId, T::BlockNumber>,
+        Metadata<T::AccountId, T::BlockNumber, T::Score>,
+        ValueQuery,
+    >;
+
+    /// The challenges each account has launched, or taken over by arbitration, as
+    /// `(app_id, target)`.
+    #[pallet::storage]
+    pub type ChallengesByChallenger<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::AccountId,
+        Twox64Concat,
+        (AppId, T::AccountId),
+        (),
+        OptionQuery,
+    >;
+
+    /// The challenges against the refreshes of each pathfinder, as `(app_id, target)`.
+    #[pallet::storage]
+    pub type ChallengesByPathfinder<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::AccountId,
+        Twox64Concat,
+        (AppId, T::AccountId),
+        (),
+        OptionQuery,
+    >;
+
+    /// The `Extra` each app keeps alongside its challenges.
+    #[pallet::storage]
+    pub type Extras<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, AppId, Twox64Concat, T::AccountId, T::Extra, ValueQuery>;
+
+    /// How far `reply` and `evidence` have pushed the deadline of each challenge forward.
+    #[pallet::storage]
+    #[pallet::getter(fn get_extension)]
+    pub type Extensions<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        AppId,
+        Twox64Concat,
+        T::AccountId,
+        T::BlockNumber,
         ValueQuery,
     >;
 
+    /// The last `MaxHistory` harvested challenges against each target, oldest first.
+    #[pallet::storage]
+    #[pallet::getter(fn get_history)]
+    pub type Histories<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        AppId,
+        Twox64Concat,
+        T::AccountId,
+        Vec<ChallengeRecord<T::AccountId, T::Score>>,
+        ValueQuery,
+    >;
+
+    /// The key of `Histories` from which the next block resumes pruning.
+    #[pallet::storage]
+    #[pallet::getter(fn prune_cursor)]
+    pub type PruneCursor<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
+
+    /// The challenge parameters of each app, overriding the constants of the pallet.
+    #[pallet::storage]
+    #[pallet::getter(fn app_config)]
+    pub type AppConfigs<T: Config> =
+        StorageMap<_, Twox64Concat, AppId, AppConfig<T::BlockNumber, T::Score>, OptionQuery>;
+
+    /// The outcome of the last settlement of each challenge, kept until it is harvested.
+    #[pallet::storage]
+    #[pallet::getter(fn get_settlement)]
+    pub type Settlements<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        AppId,
+        Twox64Concat,
+        T::AccountId,
+        SettlementOutcome<T::Score>,
+        OptionQuery,
+    >;
+
+    /// The split of the prize pool of each app, `RewardCurve::default` if it has none.
+    #[pallet::storage]
+    #[pallet::getter(fn reward_curve)]
+    pub type RewardCurves<T: Config> = StorageMap<_, Twox64Concat, AppId, RewardCurve, OptionQuery>;
+
+    /// The number of blocks the amounts won at harvest under each app are held in escrow.
+    #[pallet::storage]
+    #[pallet::getter(fn payout_delay)]
+    pub type PayoutDelays<T: Config> =
+        StorageMap<_, Twox64Concat, AppId, T::BlockNumber, OptionQuery>;
+
+    /// The currency the challenges under each app are bonded and paid out in, the base token
+    /// if it has none.
+    #[pallet::storage]
+    #[pallet::getter(fn deposit_currency)]
+    pub type DepositCurrencies<T: Config> =
+        StorageMap<_, Twox64Concat, AppId, T::CurrencyId, OptionQuery>;
+
+    /// The amounts held in escrow for each account from the challenges it won.
+    #[pallet::storage]
+    #[pallet::getter(fn get_payout)]
+    pub type Payouts<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        (AppId, T::AccountId),
+        Twox64Concat,
+        T::AccountId,
+        Payout<T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    /// The number of `Payouts` held in escrow under each app.
+    #[pallet::storage]
+    #[pallet::getter(fn payout_count)]
+    pub type PayoutCounts<T: Config> = StorageMap<_, Twox64Concat, AppId, u32, ValueQuery>;
+
+    /// The block at which each paused app was paused.
+    #[pallet::storage]
+    #[pallet::getter(fn paused_at)]
+    pub type PausedAt<T: Config> = StorageMap<_, Twox64Concat, AppId, T::BlockNumber, OptionQuery>;
+
+    /// The number of blocks each app has spent paused, by which its challenge clock lags.
+    #[pallet::storage]
+    #[pallet::getter(fn paused_for)]
+    pub type PausedFor<T: Config> = StorageMap<_, Twox64Concat, AppId, T::BlockNumber, ValueQuery>;
+
     #[pallet::storage]
     #[pallet::getter(fn last_at)]
     pub type LastAt<T: Config> = StorageMap<_, Twox64Concat, AppId, T::BlockNumber, ValueQuery>;
 
+    /// References to off-chain proofs, such as an IPFS CID, attached to each challenge by
+    /// the accounts taking part in it.
+    #[pallet::storage]
+    #[pallet::getter(fn get_attachment)]
+    pub type Attachments<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        (AppId, T::AccountId),
+        Twox64Concat,
+        T::AccountId,
+        Vec<u8>,
+        OptionQuery,
+    >;
+
+    /// The accounts co-staking each challenge with its challenger, and their stakes.
+    #[pallet::storage]
+    #[pallet::getter(fn get_backers)]
+    pub type Backers<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        AppId,
+        Twox64Concat,
+        T::AccountId,
+        Vec<(T::AccountId, Balance)>,
+        ValueQuery,
+    >;
+
+    /// The last ruling of each challenge, kept until it is harvested or its appeal resolved.
+    #[pallet::storage]
+    #[pallet::getter(fn get_ruling)]
+    pub type Rulings<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        AppId,
+        Twox64Concat,
+        T::AccountId,
+        Ruling<T::AccountId, T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    /// Appeals waiting to be resolved.
+    #[pallet::storage]
+    #[pallet::getter(fn get_appeal)]
+    pub type Appeals<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        AppId,
+        Twox64Concat,
+        T::AccountId,
+        Appeal<T::AccountId, T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    /// The score each arbiter voted for on the appeal of a challenge, and its stake.
+    #[pallet::storage]
+    #[pallet::getter(fn get_appeal_vote)]
+    pub type AppealVotes<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        (AppId, T::AccountId),
+        Twox64Concat,
+        T::AccountId,
+        (T::Score, Balance),
+        OptionQuery,
+    >;
+
+    /// The raw key of the challenge the timeout check of the next block starts from.
+    #[pallet::storage]
+    #[pallet::getter(fn settle_cursor)]
+    pub type SettleCursor<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
+
     #[pallet::event]
     #[pallet::metadata(T::AccountId = "AccountId")]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
         /// Launched a challenge. \[challenger, target, analyst, quantity\]
         Challenged(T::AccountId, T::AccountId, T::AccountId, u32),
+        /// The challenge has been arbitrated, `upheld` if the score of the pathfinder stands.
+        /// \[app_id, who, target, upheld, score\]
+        Arbitrated(AppId, T::AccountId, T::AccountId, bool, T::Score),
+        /// A timed out challenge was settled. \[app_id, target, pathfinder_share\]
+        ChallengeAutoSettled(AppId, T::AccountId, Perbill),
+        /// Finished challenges were harvested in a batch. \[app_id, who, count\]
+        BatchHarvested(AppId, T::AccountId, u32),
+        /// Records older than `HistoryRounds` were pruned from the history of a target.
+        /// \[app_id, target, count\]
+        HistoryPruned(AppId, T::AccountId, u32),
+        /// The challenge parameters of an app were set, `None` restores the defaults.
+        /// \[app_id, config\]
+        AppConfigSet(AppId, Option<AppConfig<T::BlockNumber, T::Score>>),
+        /// A challenge was settled. \[app_id, target, outcome\]
+        Settled(AppId, T::AccountId, SettlementOutcome<T::Score>),
+        /// The reward curve of an app was set, `None` restores the default.
+        /// \[app_id, curve\]
+        RewardCurveSet(AppId, Option<RewardCurve>),
+        /// The challenges of an app were paused. \[app_id\]
+        AppPaused(AppId),
+        /// The challenges of an app were resumed after a pause. \[app_id, paused_blocks\]
+        AppUnpaused(AppId, T::BlockNumber),
+        /// The payout delay of an app was set, `None` pays out at harvest. \[app_id, delay\]
+        PayoutDelaySet(AppId, Option<T::BlockNumber>),
+        /// The deposit currency of an app was set, `None` restores the base token.
+        /// \[app_id, currency\]
+        DepositCurrencySet(AppId, Option<T::CurrencyId>),
+        /// An amount won was held in escrow. \[app_id, who, target, amount, unlock_at\]
+        PayoutEscrowed(AppId, T::AccountId, T::AccountId, Balance, T::BlockNumber),
+        /// An amount held in escrow was claimed. \[app_id, who, target, amount\]
+        PayoutClaimed(AppId, T::AccountId, T::AccountId, Balance),
+        /// A disputed amount held in escrow went to the treasury.
+        /// \[app_id, who, target, amount\]
+        PayoutDisputed(AppId, T::AccountId, T::AccountId, Balance),
+        /// Part of the amount won by the challenger side went to the treasury.
+        /// \[app_id, target, amount\]
+        TreasuryPaid(AppId, T::AccountId, Balance),
+        /// A proof was attached to a challenge. \[app_id, who, target, attachment\]
+        Attached(AppId, T::AccountId, T::AccountId, Vec<u8>),
+        /// A challenge was backed. \[app_id, backer, target, amount\]
+        Backed(AppId, T::AccountId, T::AccountId, Balance),
+        /// The challenger cancelled the challenge. \[app_id, challenger, target, refund\]
+        Cancelled(AppId, T::AccountId, T::AccountId, Balance),
+        /// The ruling was appealed. \[app_id, appellant, target, bond\]
+        Appealed(AppId, T::AccountId, T::AccountId, Balance),
+        /// The appeal was resolved. \[app_id, target, overturned\]
+        AppealResolved(AppId, T::AccountId, bool),
+        /// The deadline of a challenge was pushed forward. \[app_id, target, extension\]
+        DeadlineExtended(AppId, T::AccountId, T::BlockNumber),
+        /// An arbiter voted on an appeal. \[app_id, arbiter, target, score\]
+        AppealVoted(AppId, T::AccountId, T::AccountId, T::Score),
+        /// An arbiter voted against the resolution of an appeal and forfeited its stake.
+        /// \[app_id, arbiter, target, amount\]
+        ArbiterSlashed(AppId, T::AccountId, T::AccountId, Balance),
     }
 
     #[pallet::error]
@@ -125,213 +547,1220 @@ pub mod pallet {
         StatusErr,
         /// Not available for collection
         NotAllowedSweeper,
+        /// No ruling to appeal
+        NoRuling,
+        /// The appeal period is over
+        AppealTimeout,
+        /// An appeal is waiting to be resolved
+        AppealPending,
+        /// No appeal to resolve
+        NoAppeal,
+        /// The arbiters may still vote on the appeal
+        AppealVotingOpen,
+        /// The amount is zero
+        ZeroAmount,
+        /// The challenge has as many backers as allowed
+        TooManyBackers,
+        /// The attachment is longer than `MaxEvidenceLen`
+        AttachmentTooLong,
+        /// The challenges of the app are paused
+        AppPaused,
+        /// The challenges of the app are not paused
+        NotPaused,
+        /// The claimed score is too close to the recorded score
+        DisputeDeltaTooSmall,
+        /// No payout is held in escrow
+        NoPayout,
+        /// The caller is not one of the `Arbiters`
+        NotArbiter,
+        /// The arbiter has already voted on this appeal
+        AlreadyVoted,
+        /// The app has open challenges or payouts held in escrow
+        FundsOutstanding,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+        fn on_initialize(_n: T::BlockNumber) -> Weight {
+            Self::settle_timed_out().saturating_add(Self::prune_histories())
+        }
+
+        fn offchain_worker(_n: T::BlockNumber) {
+            Self::submit_expired_settlements();
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Try to harvest up to `max` challenges under `app_id` that the caller took part in
+        /// as challenger or pathfinder, each as if it had been harvested through its own
+        /// application. Challenges that cannot be harvested yet are skipped.
+        ///
+        /// `max` is at most `MAX_HARVEST_ALL`, the weight is charged for every attempt.
+        #[pallet::weight(T::WeightInfo::harvest_all((*max).min(MAX_HARVEST_ALL)))]
+        pub fn harvest_all(
+            origin: OriginFor<T>,
+            app_id: AppId,
+            max: u32,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let (attempts, count) = Self::try_harvest_many(&who, &app_id, max)?;
+            Self::deposit_event(Event::BatchHarvested(app_id, who, count));
+            Ok(Some(T::WeightInfo::harvest_all(attempts)).into())
+        }
+
+        /// Settle the challenge against `target` under `app_id` once it can be harvested after
+        /// a timeout, as if its challenger had harvested it. Submitted unsigned by the
+        /// offchain worker.
+        #[pallet::weight(T::WeightInfo::settle_expired())]
+        pub fn settle_expired(
+            origin: OriginFor<T>,
+            app_id: AppId,
+            target: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            ensure_none(origin)?;
+            ensure!(Self::is_expired(&app_id, &target), Error::<T>::TooSoon);
+            let challenge = Self::get_metadata_exist(&app_id, &target)?;
+            let share =
+                Self::harvest_notified(&challenge.challenger, &app_id, &target, &challenge)?;
+            Self::deposit_event(Event::ChallengeAutoSettled(app_id, target, share));
+            Ok(().into())
+        }
+
+        /// Prune the records older than `HistoryRounds` from the history of `target` under
+        /// `app_id`, which `on_initialize` also does a few histories at a time.
+        #[pallet::weight(T::WeightInfo::prune_history(T::MaxHistory::get()))]
+        pub fn prune_history(
+            origin: OriginFor<T>,
+            app_id: AppId,
+            target: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+            let history = Histories::<T>::get(&app_id, &target);
+            let len = history.len() as u32;
+            Self::prune(&app_id, &target, history);
+            Ok(Some(T::WeightInfo::prune_history(len)).into())
+        }
+
+        /// Set the challenge parameters of `app_id`, or restore the constants of the pallet
+        /// with `None`. The new deposit also applies to the refunds and shares of the
+        /// challenges already launched, it should only be changed when none is open.
+        #[pallet::weight(T::WeightInfo::set_app_config())]
+        pub fn set_app_config(
+            origin: OriginFor<T>,
+            app_id: AppId,
+            config: Option<AppConfig<T::BlockNumber, T::Score>>,
+        ) -> DispatchResultWithPostInfo {
+            T::AppConfigOrigin::ensure_origin(origin)?;
+            AppConfigs::<T>::mutate_exists(app_id, |c| *c = config.clone());
+            Self::deposit_event(Event::AppConfigSet(app_id, config));
+            Ok(().into())
+        }
+
+        /// Set how the prize pool of the challenges under `app_id` is split, or restore
+        /// `RewardCurve::default` with `None`. Applies to every later settlement and harvest.
+        #[pallet::weight(T::WeightInfo::set_reward_curve())]
+        pub fn set_reward_curve(
+            origin: OriginFor<T>,
+            app_id: AppId,
+            curve: Option<RewardCurve>,
+        ) -> DispatchResultWithPostInfo {
+            T::AppConfigOrigin::ensure_origin(origin)?;
+            RewardCurves::<T>::mutate_exists(app_id, |c| *c = curve);
+            Self::deposit_event(Event::RewardCurveSet(app_id, curve));
+            Ok(().into())
+        }
+
+        /// Set the number of blocks the amounts won at harvest under `app_id` are held in
+        /// escrow before they can be claimed, `None` pays them out at harvest.
+        #[pallet::weight(T::WeightInfo::set_payout_delay())]
+        pub fn set_payout_delay(
+            origin: OriginFor<T>,
+            app_id: AppId,
+            delay: Option<T::BlockNumber>,
+        ) -> DispatchResultWithPostInfo {
+            T::AppConfigOrigin::ensure_origin(origin)?;
+            PayoutDelays::<T>::mutate_exists(app_id, |d| *d = delay);
+            Self::deposit_event(Event::PayoutDelaySet(app_id, delay));
+            Ok(().into())
+        }
+
+        /// Set the currency the challenges under `app_id` are bonded and paid out in, or
+        /// restore the base token with `None`. The `earnings` the app puts in the prize pools
+        /// must be held in the same currency. Fails while the app has open challenges or
+        /// payouts held in escrow, which are in the previous currency.
+        #[pallet::weight(T::WeightInfo::set_deposit_currency())]
+        pub fn set_deposit_currency(
+            origin: OriginFor<T>,
+            app_id: AppId,
+            currency: Option<T::CurrencyId>,
+        ) -> DispatchResultWithPostInfo {
+            T::AppConfigOrigin::ensure_origin(origin)?;
+            ensure!(
+                Metadatas::<T>::iter_prefix(app_id).next().is_none()
+                    && Self::payout_count(app_id).is_zero(),
+                Error::<T>::FundsOutstanding
+            );
+            DepositCurrencies::<T>::mutate_exists(app_id, |c| *c = currency);
+            Self::deposit_event(Event::DepositCurrencySet(app_id, currency));
+            Ok(().into())
+        }
+
+        /// Claim the amount held in escrow for the caller from the challenge against `target`
+        /// under `app_id`, once its payout delay has passed.
+        #[pallet::weight(T::WeightInfo::claim_payout())]
+        pub fn claim_payout(
+            origin: OriginFor<T>,
+            app_id: AppId,
+            target: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let key = (app_id, target.clone());
+            let payout = Payouts::<T>::get(&key, &who).ok_or(Error::<T>::NoPayout)?;
+            ensure!(
+                Self::app_now(&app_id) >= payout.unlock_at,
+                Error::<T>::TooSoon
+            );
+            Payouts::<T>::remove(&key, &who);
+            PayoutCounts::<T>::mutate(app_id, |count| *count = count.saturating_sub(1));
+            Self::release(&app_id, &who, payout.amount)?;
+            Self::deposit_event(Event::PayoutClaimed(app_id, who, target, payout.amount));
+            Ok(().into())
+        }
+
+        /// Send the amount held in escrow for `who` from the challenge against `target` under
+        /// `app_id` to the treasury, when late evidence shows it was wrongly won.
+        #[pallet::weight(T::WeightInfo::dispute_payout())]
+        pub fn dispute_payout(
+            origin: OriginFor<T>,
+            app_id: AppId,
+            target: T::AccountId,
+            who: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            T::PauseOrigin::ensure_origin(origin)?;
+            let payout =
+                Payouts::<T>::take((app_id, target.clone()), &who).ok_or(Error::<T>::NoPayout)?;
+            PayoutCounts::<T>::mutate(app_id, |count| *count = count.saturating_sub(1));
+            Self::release(&app_id, &T::TreasuryAccountId::get(), payout.amount)?;
+            Self::deposit_event(Event::PayoutDisputed(app_id, who, target, payout.amount));
+            Ok(().into())
+        }
+
+        /// Stop new challenges under `app_id` and freeze its challenge clock, so that no
+        /// challenge or appeal period runs out while it is paused. Open challenges can still
+        /// be played and harvested.
+        #[pallet::weight(T::WeightInfo::pause_app())]
+        pub fn pause_app(origin: OriginFor<T>, app_id: AppId) -> DispatchResultWithPostInfo {
+            T::PauseOrigin::ensure_origin(origin)?;
+            ensure!(
+                !PausedAt::<T>::contains_key(app_id),
+                Error::<T>::AppPaused
+            );
+            PausedAt::<T>::insert(app_id, Self::now());
+            Self::deposit_event(Event::AppPaused(app_id));
+            Ok(().into())
+        }
+
+        /// Resume the challenges of `app_id`, its clock picks up where it was paused.
+        #[pallet::weight(T::WeightInfo::unpause_app())]
+        pub fn unpause_app(origin: OriginFor<T>, app_id: AppId) -> DispatchResultWithPostInfo {
+            T::PauseOrigin::ensure_origin(origin)?;
+            let paused_at = PausedAt::<T>::take(app_id).ok_or(Error::<T>::NotPaused)?;
+            let paused_blocks = Self::now().saturating_sub(paused_at);
+            PausedFor::<T>::mutate(app_id, |p| *p = p.saturating_add(paused_blocks));
+            Self::deposit_event(Event::AppUnpaused(app_id, paused_blocks));
+            Ok(().into())
+        }
+    }
+
+    #[pallet::validate_unsigned]
+    impl<T: Config> ValidateUnsigned for Pallet<T> {
+        type Call = Call<T>;
+
+        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            if let Call::settle_expired(app_id, target) = call {
+                if !Self::is_expired(app_id, target) {
+                    return InvalidTransaction::Stale.into();
+                }
+                ValidTransaction::with_tag_prefix("ZdChallenges")
+                    .priority(T::UnsignedPriority::get())
+                    .and_provides((app_id, target))
+                    .longevity(64_u64)
+                    .propagate(true)
+                    .build()
+            } else {
+                InvalidTransaction::Call.into()
+            }
+        }
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    fn now() -> T::BlockNumber {
+        system::Module::<T>::block_number()
+    }
+
+    /// The challenge clock of `app_id` at block `now`, which stands still while the app is
+    /// paused. Every challenge timestamp and period of the app is measured on it.
+    pub(crate) fn app_clock(app_id: &AppId, now: T::BlockNumber) -> T::BlockNumber {
+        let now = PausedAt::<T>::get(app_id).map_or(now, |paused_at| paused_at.min(now));
+        now.saturating_sub(PausedFor::<T>::get(app_id))
+    }
+
+    /// The challenge clock of `app_id` at the current block.
+    pub(crate) fn app_now(app_id: &AppId) -> T::BlockNumber {
+        Self::app_clock(app_id, Self::now())
+    }
+
+    pub(crate) fn ensure_not_paused(app_id: &AppId) -> DispatchResult {
+        ensure!(
+            !PausedAt::<T>::contains_key(app_id),
+            Error::<T>::AppPaused
+        );
+        Ok(())
+    }
+
+    fn get_metadata_exist(
+        app_id: &AppId,
+        target: &T::AccountId,
+    ) -> Result<Metadata<T::AccountId, T::BlockNumber, T::Score>, Error<T>> {
+        <Metadatas<T>>::try_get(&app_id, &target).map_err(|_err| Error::<T>::NonExistent)
+    }
+
+    /// The share of the prize pool of `challenge` under `app_id` due to `pathfinder` if
+    /// harvested now, the rest goes to `challenger`, and the score to apply, if any.
+    fn pathfinder_share(
+        app_id: &AppId,
+        challenge: &Metadata<T::AccountId, T::BlockNumber, T::Score>,
+    ) -> (Perbill, Option<T::Score>) {
+        let is_all_done = challenge.is_all_done();
+        match challenge.status {
+            ChallengeStatus::Free => (Perbill::one(), None),
+            ChallengeStatus::Reply => match is_all_done {
+                true => (Perbill::one(), None),
+                false => (Perbill::zero(), None),
+            },
+            ChallengeStatus::Examine => (Perbill::zero(), Some(challenge.score)),
+            ChallengeStatus::Evidence => match is_all_done {
+                true => (Perbill::zero(), Some(challenge.score)),
+                false => (Perbill::one(), Some(challenge.score)),
+            },
+            ChallengeStatus::Arbitral => match challenge.joint_benefits {
+                true => {
+                    let cut = Self::get_reward_curve(app_id).arbiter_cut;
+                    (Perbill::one().saturating_sub(cut), None)
+                }
+                false => (Perbill::one(), Some(challenge.score)),
+            },
+        }
+    }
+
+    /// The challenge parameters of `app_id`, the constants of the pallet if it has none.
+    pub(crate) fn get_app_config(app_id: &AppId) -> AppConfig<T::BlockNumber, T::Score> {
+        AppConfigs::<T>::get(app_id).unwrap_or_else(|| AppConfig {
+            staking_amount: T::ChallengeStakingAmount::get(),
+            timeout: T::ChallengeTimeout::get().saturated_into::<T::BlockNumber>(),
+            max_next_steps: T::MaxNextSteps::get(),
+            min_dispute_delta: T::MinDisputeDelta::get(),
+            response_window: T::ResponseWindow::get(),
+            max_response_extension: T::MaxResponseExtension::get(),
+        })
+    }
+
+    pub(crate) fn get_reward_curve(app_id: &AppId) -> RewardCurve {
+        RewardCurves::<T>::get(app_id).unwrap_or_default()
+    }
+
+    fn get_challenge_timeout(app_id: &AppId) -> T::BlockNumber {
+        Self::get_app_config(app_id).timeout
+    }
+
+    pub(crate) fn challenge_staking_amount(app_id: &AppId) -> Balance {
+        Self::get_app_config(app_id).staking_amount
+    }
+
+    /// `who` stakes `amount` in the deposit currency of `app_id`.
+    pub(crate) fn staking(app_id: &AppId, who: &T::AccountId, amount: Balance) -> DispatchResult {
+        match DepositCurrencies::<T>::get(app_id) {
+            Some(currency) => T::CurrencyStaking::staking_in(currency, who, &amount),
+            None => T::ZdToken::staking(who, &amount),
+        }
+    }
+
+    /// Release `amount` of the deposit currency of `app_id` to `who`.
+    pub(crate) fn release(app_id: &AppId, who: &T::AccountId, amount: Balance) -> DispatchResult {
+        match DepositCurrencies::<T>::get(app_id) {
+            Some(currency) => T::CurrencyStaking::release_in(currency, who, &amount),
+            None => T::ZdToken::release(who, &amount),
+        }
+    }
+
+    pub(crate) fn checked_sweeper_fee(
+        app_id: &AppId,
+        target: &T::AccountId,
+        challenge: &Metadata<T::AccountId, T::BlockNumber, T::Score>,
+        who: &T::AccountId,
+        total_amount: &Balance,
+    ) -> Result<(Balance, Balance), DispatchError> {
+        let is_sweeper = challenge.challenger != *who && challenge.pathfinder != *who;
+        if is_sweeper {
+            let (sweeper_fee, awards) = total_amount
+                .checked_with_fee(challenge.last_update, Self::app_now(app_id))
+                .ok_or(Error::<T>::NotAllowedSweeper)?;
+            Ok((sweeper_fee, awards))
+        } else {
+            ensure!(
+                Self::is_past_deadline(app_id, target, &challenge.last_update),
+                Error::<T>::TooSoon
+            );
+            Ok((Zero::zero(), *total_amount))
+        }
+    }
+
+    pub(crate) fn remove(app_id: &AppId, target: &T::AccountId) {
+        let challenge = Metadatas::<T>::take(&app_id, &target);
+        ChallengesByChallenger::<T>::remove(&challenge.challenger, (*app_id, target.clone()));
+        ChallengesByPathfinder::<T>::remove(&challenge.pathfinder, (*app_id, target.clone()));
+        Rulings::<T>::remove(&app_id, &target);
+        Settlements::<T>::remove(&app_id, &target);
+        Extensions::<T>::remove(&app_id, &target);
+        Backers::<T>::remove(&app_id, &target);
+        Extras::<T>::remove(&app_id, &target);
+        Attachments::<T>::remove_prefix(&(*app_id, target.clone()));
+    }
+
+    /// Move the challenge against `target` under `app_id` from the `ChallengesByChallenger`
+    /// of `old` to that of `new`.
+    pub(crate) fn reindex_challenger(
+        app_id: &AppId,
+        target: &T::AccountId,
+        old: &T::AccountId,
+        new: &T::AccountId,
+    ) {
+        let key = (*app_id, target.clone());
+        ChallengesByChallenger::<T>::remove(old, &key);
+        ChallengesByChallenger::<T>::insert(new, &key, ());
+    }
+
+    /// Move the challenge against `target` under `app_id` from the `ChallengesByPathfinder`
+    /// of `old` to that of `new`.
+    pub(crate) fn reindex_pathfinder(
+        app_id: &AppId,
+        target: &T::AccountId,
+        old: &T::AccountId,
+        new: &T::AccountId,
+    ) {
+        let key = (*app_id, target.clone());
+        ChallengesByPathfinder::<T>::remove(old, &key);
+        ChallengesByPathfinder::<T>::insert(new, &key, ());
+    }
+
+    /// A page of at most `limit` challenges of `challenger` as `(app_id, target)` after the
+    /// raw storage key `start_key`, and the key to continue from if any remain.
+    ///
+    /// `limit` is capped at `MAX_CHALLENGES_PAGE`.
+    pub fn challenges_of(
+        challenger: &T::AccountId,
+        limit: u32,
+        start_key: Option<Vec<u8>>,
+    ) -> (Vec<(AppId, T::AccountId)>, Option<Vec<u8>>) {
+        let limit = limit.min(MAX_CHALLENGES_PAGE) as usize;
+        let mut challenges: Box<dyn Iterator<Item = (AppId, T::AccountId)> + '_> =
+            match start_key {
+                Some(key) => Box::new(
+                    iter_double_map_from::<T::AccountId, (AppId, T::AccountId), ()>(
+                        &ChallengesByChallenger::<T>::final_prefix(),
+                        Some(key),
+                    )
+                    .take_while(move |(who, _, _)| who == challenger)
+                    .map(|(_, key, _)| key),
+                ),
+                None => Box::new(
+                    ChallengesByChallenger::<T>::iter_prefix(challenger).map(|(key, _)| key),
+                ),
+            };
+        let page = challenges.by_ref().take(limit).collect::<Vec<_>>();
+        let next_key = match (page.last(), challenges.next()) {
+            (Some(key), Some(_)) => Some(ChallengesByChallenger::<T>::hashed_key_for(
+                challenger, key,
+            )),
+            _ => None,
+        };
+        (page, next_key)
+    }
+
+    /// What launching a challenge under `app_id` would take and apply now, the recorded score
+    /// and the confirmation period are left to the app.
+    pub fn preview(app_id: &AppId) -> ChallengePreview<T::BlockNumber> {
+        ChallengePreview {
+            deposit: Self::challenge_staking_amount(app_id),
+            deadline: Self::app_now(app_id) + Self::get_challenge_timeout(app_id),
+            ..ChallengePreview::default()
+        }
+    }
+
+    /// Keep `attachment` as the reference of `who` to an off-chain proof for the challenge
+    /// against `target` under `app_id`, if any.
+    pub(crate) fn attach(
+        app_id: &AppId,
+        who: &T::AccountId,
+        target: &T::AccountId,
+        attachment: Option<Vec<u8>>,
+    ) -> DispatchResult {
+        if let Some(attachment) = attachment {
+            ensure!(
+                attachment.len() <= T::MaxEvidenceLen::get() as usize,
+                Error::<T>::AttachmentTooLong
+            );
+            Attachments::<T>::insert((*app_id, target.clone()), who, &attachment);
+            Self::deposit_event(Event::Attached(*app_id, who.clone(), target.clone(), attachment));
+        }
+        Ok(())
+    }
+
+    /// Return to the backers of the challenge against `target` under `app_id` their stakes,
+    /// returns the total amount released.
+    pub(crate) fn refund_backers(
+        app_id: &AppId,
+        target: &T::AccountId,
+    ) -> Result<Balance, DispatchError> {
+        Backers::<T>::take(app_id, target)
+            .iter()
+            .try_fold(Zero::zero(), |total: Balance, (backer, stake)| {
+                Self::release(app_id, backer, *stake)?;
+                Ok(total.saturating_add(*stake))
+            })
+    }
+
+    /// Pay out `amount` won by `who` in the challenge against `target` under `app_id`, or
+    /// hold it in escrow if the app has a payout delay.
+    pub(crate) fn pay_out(
+        app_id: &AppId,
+        target: &T::AccountId,
+        who: &T::AccountId,
+        amount: Balance,
+    ) -> DispatchResult {
+        match PayoutDelays::<T>::get(app_id) {
+            Some(delay) if !delay.is_zero() => {
+                let unlock_at = Self::app_now(app_id).saturating_add(delay);
+                Payouts::<T>::mutate((*app_id, target.clone()), who, |p| {
+                    if p.is_none() {
+                        PayoutCounts::<T>::mutate(app_id, |count| {
+                            *count = count.saturating_add(1)
+                        });
+                    }
+                    let payout = p.get_or_insert_with(Payout::default);
+                    payout.amount = payout.amount.saturating_add(amount);
+                    payout.unlock_at = unlock_at;
+                });
+                Self::deposit_event(Event::PayoutEscrowed(
+                    *app_id,
+                    who.clone(),
+                    target.clone(),
+                    amount,
+                    unlock_at,
+                ));
+                Ok(())
+            }
+            _ => Self::release(app_id, who, amount),
+        }
+    }
+
+    /// Pay out `amount` won by the challenger side of the challenge against `target`, sharing
+    /// it with `backers` pro rata to their stakes, the challenger counting for its deposit.
+    pub(crate) fn release_challenger_side(
+        app_id: &AppId,
+        target: &T::AccountId,
+        challenge: &Metadata<T::AccountId, T::BlockNumber, T::Score>,
+        backers: &[(T::AccountId, Balance)],
+        amount: Balance,
+    ) -> DispatchResult {
+        let backed = backers
+            .iter()
+            .fold(Zero::zero(), |total: Balance, (_, stake)| total.saturating_add(*stake));
+        let side = Self::challenge_staking_amount(app_id).saturating_add(backed);
+        let mut rest = amount;
+        for (backer, stake) in backers {
+            let backer_amount =
+                Perbill::from_rational_approximation(*stake, side).mul_floor(amount);
+            if backer_amount > 0 {
+                Self::pay_out(app_id, target, backer, backer_amount)?;
+                rest = rest.saturating_sub(backer_amount);
+            }
+        }
+        if rest > 0 {
+            Self::pay_out(app_id, target, &challenge.challenger, rest)?;
+        }
+        Ok(())
+    }
+
+    /// Return to the arbiters who voted for `score` on the appeal of the challenge against
+    /// `target` under `app_id` their stakes, the others forfeit theirs to the treasury.
+    pub(crate) fn settle_arbiters(
+        app_id: &AppId,
+        target: &T::AccountId,
+        score: T::Score,
+    ) -> DispatchResult {
+        for (arbiter, (vote, stake)) in AppealVotes::<T>::drain_prefix((*app_id, target.clone())) {
+            if vote == score {
+                Self::release(app_id, &arbiter, stake)?;
+            } else {
+                Self::release(app_id, &T::TreasuryAccountId::get(), stake)?;
+                Self::deposit_event(Event::ArbiterSlashed(
+                    *app_id,
+                    arbiter,
+                    target.clone(),
+                    stake,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn ensure_no_appeal(app_id: &AppId, target: &T::AccountId) -> DispatchResult {
+        ensure!(
+            !Appeals::<T>::contains_key(app_id, target),
+            Error::<T>::AppealPending
+        );
+        Ok(())
+    }
+
+    /// Record a ruling on `challenge` made by `do_settle`, the loser is the challenger if the
+    /// score of the pathfinder is `upheld` and the pathfinder otherwise.
+    pub(crate) fn rule(
+        app_id: &AppId,
+        target: &T::AccountId,
+        challenge: &Metadata<T::AccountId, T::BlockNumber, T::Score>,
+        upheld: bool,
+    ) {
+        let loser = match upheld {
+            true => challenge.challenger.clone(),
+            false => challenge.pathfinder.clone(),
+        };
+        Rulings::<T>::insert(
+            app_id,
+            target,
+            Ruling {
+                at: Self::app_now(app_id),
+                loser,
+            },
+        );
+    }
+
+    pub(crate) fn do_settle(
+        app_id: &AppId,
+        target: &T::AccountId,
+        challenge: &mut Metadata<T::AccountId, T::BlockNumber, T::Score>,
+        restart: &bool,
+        joint_benefits: &bool,
+        score: &T::Score,
+    ) -> Result<SettlementOutcome<T::Score>, DispatchError> {
+        let pathfinder = challenge.pathfinder.clone();
+        let mut arbitral_fee: Balance = Zero::zero();
+        match restart {
+            true => {
+                // The challenge succeeded, the backers get their stakes back.
+                let backed = Self::refund_backers(app_id, target)?;
+                challenge.pool.staking = challenge.pool.staking.saturating_sub(backed);
+                if *joint_benefits {
+                    arbitral_fee = Self::get_reward_curve(app_id)
+                        .arbiter_cut
+                        .mul_floor(challenge.pool.staking);
+                    challenge.pool.staking -= arbitral_fee;
+                    Self::release(app_id, &challenge.challenger, arbitral_fee)?;
+                }
+                challenge.restart(!joint_benefits);
+            }
+            false => {
+                challenge.joint_benefits = *joint_benefits;
+                challenge.score = *score;
+            }
+        }
+        let outcome =
+            Self::settlement_outcome(app_id, challenge, &pathfinder, arbitral_fee, *restart)?;
+        Settlements::<T>::insert(app_id, target, outcome);
+        Self::deposit_event(Event::Settled(*app_id, target.clone(), outcome));
+        Ok(outcome)
+    }
+
+    /// The split of the prize pool of `challenge` just settled, `pathfinder` being its
+    /// pathfinder before the settlement and `paid` what the settlement paid the challenger.
+    fn settlement_outcome(
+        app_id: &AppId,
+        challenge: &Metadata<T::AccountId, T::BlockNumber, T::Score>,
+        pathfinder: &T::AccountId,
+        paid: Balance,
+        restarted: bool,
+    ) -> Result<SettlementOutcome<T::Score>, DispatchError> {
+        let total_amount = challenge.total_amount().ok_or(Error::<T>::Overflow)?;
+        let (challenger_amount, pathfinder_amount) = match challenge.pathfinder == *pathfinder {
+            true => {
+                let (share, _) = Self::pathfinder_share(app_id, challenge);
+                let pathfinder_amount = share.mul_floor(total_amount);
+                let won = total_amount.saturating_sub(pathfinder_amount);
+                let challenger_amount =
+                    Self::get_reward_curve(app_id).challenger_share.mul_floor(won);
+                (challenger_amount, pathfinder_amount)
+            }
+            // The challenger took the place of the pathfinder, and its pool with it.
+            false => (total_amount, Zero::zero()),
+        };
+        Ok(SettlementOutcome {
+            challenger_amount: challenger_amount.saturating_add(paid),
+            pathfinder_amount,
+            score: challenge.score,
+            restarted,
+        })
+    }
+
+    pub(crate) fn mutate_metadata(
+        app_id: &AppId,
+        target: &T::AccountId,
+        mut f: impl FnMut(&mut Metadata<T::AccountId, T::BlockNumber, T::Score>) -> DispatchResult,
+    ) -> DispatchResult {
+        Metadatas::<T>::try_mutate_exists(app_id, target, |challenge| -> DispatchResult {
+            let challenge = challenge.as_mut().ok_or(Error::<T>::NonExistent)?;
+            f(challenge)?;
+            challenge.last_update = Self::app_now(app_id);
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    pub(crate) fn after_upload(app_id: &AppId) {
+        <LastAt<T>>::mutate(*app_id, |l| *l = Self::app_now(app_id));
+    }
+
+    pub(crate) fn is_challenge_timeout(app_id: &AppId, last_update: &T::BlockNumber) -> bool {
+        Self::app_now(app_id) > (Self::get_challenge_timeout(app_id) + *last_update)
+    }
+
+    /// Whether the challenge against `target` under `app_id`, last updated at `last_update`,
+    /// has timed out once its deadline is pushed forward by its `Extensions`.
+    pub(crate) fn is_past_deadline(
+        app_id: &AppId,
+        target: &T::AccountId,
+        last_update: &T::BlockNumber,
+    ) -> bool {
+        Self::is_challenge_timeout(app_id, &(*last_update + Extensions::<T>::get(app_id, target)))
+    }
+
+    /// Push the deadline of the challenge against `target` under `app_id` forward by the
+    /// `response_window` of the app, up to its `max_response_extension` in total.
+    pub(crate) fn extend_deadline(app_id: &AppId, target: &T::AccountId) {
+        let config = Self::get_app_config(app_id);
+        let extension = Extensions::<T>::get(app_id, target);
+        let extended = extension
+            .saturating_add(config.response_window)
+            .min(config.max_response_extension.max(extension));
+        if extended != extension {
+            Extensions::<T>::insert(app_id, target, extended);
+            Self::deposit_event(Event::DeadlineExtended(*app_id, target.clone(), extended));
+        }
+    }
+
+    /// Checks up to `MaxAutoSettlements` challenges from `SettleCursor` on and settles those
+    /// that have timed out, as if their challenger had harvested them.
+    pub(crate) fn settle_timed_out() -> Weight {
+        let limit = T::MaxAutoSettlements::get() as usize;
+        if limit == 0 {
+            return 0;
+        }
+        let mut challenges = iter_double_map_from::<AppId, T::AccountId, Metadata<_, _, T::Score>>(
+            &Metadatas::<T>::final_prefix(),
+            SettleCursor::<T>::get(),
+        );
+        let checked = challenges.by_ref().take(limit).collect::<Vec<_>>();
+        let complete = challenges.next().is_none();
+        match checked.last() {
+            Some((app_id, target, _)) if !complete => {
+                SettleCursor::<T>::put(Metadatas::<T>::hashed_key_for(app_id, target))
+            }
+            _ => SettleCursor::<T>::kill(),
+        }
+        let mut settled: u64 = 0;
+        let scanned = checked.len() as u64;
+        for (app_id, target, challenge) in checked {
+            if !Self::is_past_deadline(&app_id, &target, &challenge.last_update) {
+                continue;
+            }
+            let outcome = with_transaction(|| {
+                match Self::harvest_notified(&challenge.challenger, &app_id, &target, &challenge) {
+                    Ok(share) => TransactionOutcome::Commit(Some(share)),
+                    Err(_) => TransactionOutcome::Rollback(None),
+                }
+            });
+            if let Some(share) = outcome {
+                settled += 1;
+                Self::deposit_event(Event::ChallengeAutoSettled(app_id, target, share));
+            }
+        }
+        T::DbWeight::get()
+            .reads_writes(scanned.saturating_add(2), 1)
+            .saturating_add(Self::harvest_weight(settled))
+    }
+
+    /// Whether the challenge against `target` under `app_id` timed out and can be harvested.
+    pub(crate) fn is_expired(app_id: &AppId, target: &T::AccountId) -> bool {
+        match Self::state(app_id, target) {
+            Some(ChallengeState::TimedOut) | Some(ChallengeState::Harvestable) => {
+                !Appeals::<T>::contains_key(app_id, target)
+            }
+            _ => false,
+        }
+    }
+
+    /// Submits a `settle_expired` transaction for each of the first `MaxOffchainSettlements`
+    /// expired challenges. A settlement already in the transaction pool is rejected by it, so
+    /// the failures are left to the next block.
+    pub(crate) fn submit_expired_settlements() {
+        let limit = T::MaxOffchainSettlements::get() as usize;
+        let expired = Metadatas::<T>::iter()
+            .filter(|(app_id, target, _)| Self::is_expired(app_id, target))
+            .take(limit)
+            .collect::<Vec<_>>();
+        for (app_id, target, _) in expired {
+            let call = Call::settle_expired(app_id, target);
+            let _ = SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into());
+        }
+    }
+
+    /// The weight of harvesting `count` challenges and notifying `OnHarvested` of them.
+    pub(crate) fn harvest_weight(count: u64) -> Weight {
+        T::WeightInfo::harvest().saturating_mul(count)
+    }
+
+    /// Checks up to `MaxHistoryPrunes` histories from `PruneCursor` on and prunes their
+    /// records older than `HistoryRounds`.
+    pub(crate) fn prune_histories() -> Weight {
+        let limit = T::MaxHistoryPrunes::get() as usize;
+        if limit == 0 {
+            return 0;
+        }
+        let mut histories = iter_double_map_from::<AppId, T::AccountId, Vec<_>>(
+            &Histories::<T>::final_prefix(),
+            PruneCursor::<T>::get(),
+        );
+        let checked = histories.by_ref().take(limit).collect::<Vec<_>>();
+        let complete = histories.next().is_none();
+        match checked.last() {
+            Some((app_id, target, _)) if !complete => {
+                PruneCursor::<T>::put(Histories::<T>::hashed_key_for(app_id, target))
+            }
+            _ => PruneCursor::<T>::kill(),
+        }
+        let scanned = checked.len() as u64;
+        let mut pruned: u64 = 0;
+        for (app_id, target, history) in checked {
+            if Self::prune(&app_id, &target, history) > 0 {
+                pruned += 1;
+            }
+        }
+        T::DbWeight::get().reads_writes(scanned.saturating_add(2), pruned.saturating_add(1))
+    }
+
+    /// Removes the records of `history` older than `HistoryRounds`, returns how many.
+    pub(crate) fn prune(
+        app_id: &AppId,
+        target: &T::AccountId,
+        mut history: Vec<ChallengeRecord<T::AccountId, T::Score>>,
+    ) -> u32 {
+        let oldest = T::Reputation::get_round().saturating_sub(T::HistoryRounds::get());
+        let len = history.len();
+        history.retain(|record| record.round >= oldest);
+        let pruned = (len - history.len()) as u32;
+        if pruned > 0 {
+            match history.is_empty() {
+                true => Histories::<T>::remove(app_id, target),
+                false => Histories::<T>::insert(app_id, target, history),
+            }
+            Self::deposit_event(Event::HistoryPruned(*app_id, target.clone(), pruned));
+        }
+        pruned
+    }
+
+    /// Adds the harvested `challenge` to the history of `target` under `app_id`, dropping the
+    /// oldest record beyond `MaxHistory`.
+    fn archive(
+        app_id: &AppId,
+        target: &T::AccountId,
+        challenge: &Metadata<T::AccountId, T::BlockNumber, T::Score>,
+        pathfinder_share: Perbill,
+    ) {
+        let max = T::MaxHistory::get() as usize;
+        if max == 0 {
+            return;
+        }
+        Histories::<T>::mutate(app_id, target, |history| {
+            history.push(ChallengeRecord {
+                round: T::Reputation::get_round(),
+                challenger: challenge.challenger.clone(),
+                pathfinder: challenge.pathfinder.clone(),
+                status: challenge.status,
+                pathfinder_share,
+                score: challenge.score,
+                remark: challenge.remark,
+            });
+            if history.len() > max {
+                let excess = history.len() - max;
+                history.drain(..excess);
+            }
+        });
+    }
+
+    /// The `ChallengeError` of `error`, telling apart the errors an application may want to
+    /// report as its own.
+    fn classify(error: DispatchError) -> ChallengeError {
+        if error == Error::<T>::NonExistent.into() {
+            ChallengeError::NonExistent
+        } else if error == Error::<T>::StatusErr.into() {
+            ChallengeError::WrongPhase
+        } else if error == Error::<T>::NoPermission.into() {
+            ChallengeError::NotYourTurn
+        } else {
+            ChallengeError::Other(error)
+        }
+    }
+
+    /// `who` harvests `challenge` against `target` under `app_id`, and `OnHarvested` is
+    /// notified of it. Returns the share of the pathfinder.
+    fn harvest_notified(
+        who: &T::AccountId,
+        app_id: &AppId,
+        target: &T::AccountId,
+        challenge: &Metadata<T::AccountId, T::BlockNumber, T::Score>,
+    ) -> Result<Perbill, DispatchError> {
+        let (share, _) = Self::pathfinder_share(app_id, challenge);
+        let score = Self::try_harvest(who, app_id, target)?;
+        T::OnHarvested::on_harvested(app_id, target, &challenge.challenger, share, score)?;
+        Ok(share)
+    }
+}
+
+impl<T: Config> ChallengeBase<T::AccountId, AppId, Balance, T::BlockNumber> for Pallet<T> {
+    type Extra = T::Extra;
+    type Score = T::Score;
+
+    fn set_extra(
+        app_id: &AppId,
+        target: &T::AccountId,
+        extra: T::Extra,
+    ) -> Result<(), ChallengeError> {
+        ensure!(
+            Metadatas::<T>::contains_key(app_id, target),
+            Error::<T>::NonExistent
+        );
+        Extras::<T>::insert(app_id, target, extra);
+        Ok(())
+    }
+
+    fn get_extra(app_id: &AppId, target: &T::AccountId) -> T::Extra {
+        Extras::<T>::get(app_id, target)
+    }
+
+    fn set_metadata(
+        app_id: &AppId,
+        target: &T::AccountId,
+        metadata: &Metadata<T::AccountId, T::BlockNumber, T::Score>,
+    ) {
+        <Metadatas<T>>::mutate(*app_id, target, |m| {
+            Self::reindex_challenger(app_id, target, &m.challenger, &metadata.challenger);
+            Self::reindex_pathfinder(app_id, target, &m.pathfinder, &metadata.pathfinder);
+            *m = metadata.clone();
+        });
+    }
+
+    fn is_all_harvest(app_id: &AppId) -> bool {
+        <Metadatas<T>>::iter_prefix_values(app_id).next().is_none()
     }
 
-    #[pallet::hooks]
-    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+    fn is_all_timeout(app_id: &AppId, now: &T::BlockNumber) -> bool {
+        let last = LastAt::<T>::get(app_id);
+        Self::app_clock(app_id, *now) > last + Self::get_challenge_timeout(app_id)
+    }
 
-    #[pallet::call]
-    impl<T: Config> Pallet<T> {}
-}
+    fn set_status(app_id: &AppId, target: &T::AccountId, status: &ChallengeStatus) {
+        <Metadatas<T>>::mutate(app_id, target, |c| c.set_status(status));
+    }
 
-impl<T: Config> Pallet<T> {
-    fn now() -> T::BlockNumber {
-        system::Module::<T>::block_number()
+    fn outcome(app_id: &AppId, target: &T::AccountId) -> Option<(T::AccountId, Perbill)> {
+        let challenge = Self::get_metadata_exist(app_id, target).ok()?;
+        let (share, _) = Self::pathfinder_share(app_id, &challenge);
+        Some((challenge.challenger, share))
     }
 
-    fn get_metadata_exist(
+    fn state(app_id: &AppId, target: &T::AccountId) -> Option<ChallengeState> {
+        let challenge = Self::get_metadata_exist(app_id, target).ok()?;
+        let ruling = Rulings::<T>::get(app_id, target);
+        let state = match challenge.status {
+            ChallengeStatus::Free => ChallengeState::Settled,
+            ChallengeStatus::Examine => ChallengeState::Examining,
+            ChallengeStatus::Reply => ChallengeState::Replying,
+            ChallengeStatus::Evidence => ChallengeState::Evidence,
+            ChallengeStatus::Arbitral if ruling.is_some() => ChallengeState::Settled,
+            ChallengeStatus::Arbitral => ChallengeState::Arbitral,
+        };
+        if !Self::is_past_deadline(app_id, target, &challenge.last_update) {
+            return Some(state);
+        }
+        Some(match state {
+            ChallengeState::Settled => {
+                let now = Self::app_now(app_id);
+                let in_appeal = Appeals::<T>::contains_key(app_id, target)
+                    || ruling.map_or(false, |r| now <= r.at + T::AppealPeriod::get());
+                match in_appeal {
+                    true => ChallengeState::Settled,
+                    false => ChallengeState::Harvestable,
+                }
+            }
+            _ => ChallengeState::TimedOut,
+        })
+    }
+
+    fn is_challengeable(app_id: &AppId, target: &T::AccountId, now: &T::BlockNumber) -> bool {
+        if PausedAt::<T>::contains_key(app_id) || Appeals::<T>::contains_key(app_id, target) {
+            return false;
+        }
+        let now = Self::app_clock(app_id, *now);
+        let is_free = <Metadatas<T>>::try_get(app_id, target)
+            .map_or(true, |challenge| challenge.status == ChallengeStatus::Free);
+        is_free
+            && Rulings::<T>::get(app_id, target)
+                .map_or(true, |ruling| now > ruling.at + T::AppealPeriod::get())
+    }
+
+    fn harvest(
+        who: &T::AccountId,
         app_id: &AppId,
         target: &T::AccountId,
-    ) -> Result<Metadata<T::AccountId, T::BlockNumber>, Error<T>> {
-        <Metadatas<T>>::try_get(&app_id, &target).map_err(|_err| Error::<T>::NonExistent)
+    ) -> Result<Option<T::Score>, ChallengeError> {
+        Self::try_harvest(who, app_id, target).map_err(Self::classify)
     }
 
-    fn get_challenge_timeout() -> T::BlockNumber {
-        T::ChallengeTimeout::get().saturated_into::<T::BlockNumber>()
+    fn harvest_many(who: &T::AccountId, app_id: &AppId, max: u32) -> Result<u32, ChallengeError> {
+        Self::try_harvest_many(who, app_id, max)
+            .map(|(_, harvested)| harvested)
+            .map_err(Self::classify)
     }
 
-    pub(crate) fn challenge_staking_amount() -> Balance {
-        T::ChallengeStakingAmount::get()
+    fn launch(
+        app_id: &AppId,
+        target: &T::AccountId,
+        metadata: &Metadata<T::AccountId, T::BlockNumber, T::Score>,
+        scores: (T::Score, T::Score),
+        attachment: Option<Vec<u8>>,
+    ) -> Result<(), ChallengeError> {
+        Self::try_launch(app_id, target, metadata, scores, attachment).map_err(Self::classify)
     }
 
-    pub(crate) fn staking(who: &T::AccountId, amount: Balance) -> DispatchResult {
-        T::ZdToken::staking(who, &amount)
+    fn next(
+        app_id: &AppId,
+        who: &T::AccountId,
+        target: &T::AccountId,
+        count: &u32,
+        up: impl FnMut(T::Score, u32, bool) -> Result<(T::Score, u32), DispatchError>,
+    ) -> Result<Progress, ChallengeError> {
+        Self::try_next(app_id, who, target, count, up).map_err(Self::classify)
     }
 
-    pub(crate) fn release(who: &T::AccountId, amount: Balance) -> DispatchResult {
-        T::ZdToken::release(who, &amount)
+    fn examine(
+        app_id: &AppId,
+        who: &T::AccountId,
+        target: &T::AccountId,
+        index: u32,
+    ) -> Result<(), ChallengeError> {
+        Self::try_examine(app_id, who, target, index).map_err(Self::classify)
     }
 
-    pub(crate) fn checked_sweeper_fee(
-        challenge: &Metadata<T::AccountId, T::BlockNumber>,
+    fn reply(
+        app_id: &AppId,
         who: &T::AccountId,
-        total_amount: &Balance,
-    ) -> Result<(Balance, Balance), DispatchError> {
-        let is_sweeper = challenge.challenger != *who && challenge.pathfinder != *who;
-        let now_block_number = system::Module::<T>::block_number();
-        if is_sweeper {
-            let (sweeper_fee, awards) = total_amount
-                .checked_with_fee(challenge.last_update, now_block_number)
-                .ok_or(Error::<T>::NotAllowedSweeper)?;
-            Ok((sweeper_fee, awards))
-        } else {
-            ensure!(
-                Self::is_challenge_timeout(&challenge.last_update),
-                Error::<T>::TooSoon
-            );
-            Ok((Zero::zero(), *total_amount))
-        }
+        target: &T::AccountId,
+        total: u32,
+        count: u32,
+        up: impl Fn(bool, u32, T::Score, &T::Extra) -> Result<T::Score, DispatchError>,
+    ) -> Result<(), ChallengeError> {
+        Self::try_reply(app_id, who, target, total, count, up).map_err(Self::classify)
     }
 
-    pub(crate) fn remove(app_id: &AppId, target: &T::AccountId) {
-        Metadatas::<T>::remove(&app_id, &target);
+    fn evidence(
+        app_id: &AppId,
+        who: &T::AccountId,
+        target: &T::AccountId,
+        attachment: Option<Vec<u8>>,
+        up: impl Fn(u32, T::Score, &T::Extra) -> Result<bool, DispatchError>,
+    ) -> Result<Option<T::Score>, ChallengeError> {
+        Self::try_evidence(app_id, who, target, attachment, up).map_err(Self::classify)
     }
 
-    pub(crate) fn do_settle(
-        challenge: &mut Metadata<T::AccountId, T::BlockNumber>,
-        restart: &bool,
-        joint_benefits: &bool,
-        score: &u64,
-    ) -> DispatchResult {
-        match restart {
-            true => {
-                if *joint_benefits {
-                    let arbitral_fee = challenge
-                        .pool
-                        .staking
-                        .checked_div(2)
-                        .ok_or(Error::<T>::Overflow)?;
-                    challenge.pool.staking -= arbitral_fee;
-                    Self::release(&challenge.challenger, arbitral_fee)?;
-                }
-                challenge.restart(!joint_benefits);
-                Ok(())
-            }
-            false => {
-                challenge.joint_benefits = *joint_benefits;
-                challenge.score = *score;
-                Ok(())
-            }
-        }
+    fn arbitral(
+        app_id: &AppId,
+        who: &T::AccountId,
+        target: &T::AccountId,
+        up: impl Fn(T::Score, u32, &T::Extra) -> Result<(bool, bool, T::Score), DispatchError>,
+    ) -> Result<(), ChallengeError> {
+        Self::try_arbitral(app_id, who, target, up).map_err(Self::classify)
     }
 
-    pub(crate) fn mutate_metadata(
+    fn settle(
         app_id: &AppId,
         target: &T::AccountId,
-        mut f: impl FnMut(&mut Metadata<T::AccountId, T::BlockNumber>) -> DispatchResult,
-    ) -> DispatchResult {
-        Metadatas::<T>::try_mutate_exists(app_id, target, |challenge| -> DispatchResult {
-            let challenge = challenge.as_mut().ok_or(Error::<T>::NonExistent)?;
-            f(challenge)?;
-            challenge.last_update = Self::now();
-            Ok(())
-        })?;
-        Ok(())
+        joint_benefits: bool,
+        restart: bool,
+        score: T::Score,
+    ) -> Result<SettlementOutcome<T::Score>, ChallengeError> {
+        Self::try_settle(app_id, target, joint_benefits, restart, score).map_err(Self::classify)
     }
 
-    pub(crate) fn after_upload(app_id: &AppId) {
-        <LastAt<T>>::mutate(*app_id, |l| *l = Self::now());
+    fn back(
+        app_id: &AppId,
+        who: &T::AccountId,
+        target: &T::AccountId,
+        amount: Balance,
+    ) -> Result<(), ChallengeError> {
+        Self::try_back(app_id, who, target, amount).map_err(Self::classify)
     }
 
-    pub(crate) fn is_challenge_timeout(last_update: &T::BlockNumber) -> bool {
-        let now_block_number = system::Module::<T>::block_number();
-        now_block_number > (Self::get_challenge_timeout() + *last_update)
+    fn cancel(
+        app_id: &AppId,
+        who: &T::AccountId,
+        target: &T::AccountId,
+    ) -> Result<(), ChallengeError> {
+        Self::try_cancel(app_id, who, target).map_err(Self::classify)
     }
-}
 
-impl<T: Config> ChallengeBase<T::AccountId, AppId, Balance, T::BlockNumber> for Pallet<T> {
-    fn set_metadata(
+    fn appeal(
         app_id: &AppId,
+        who: &T::AccountId,
         target: &T::AccountId,
-        metadata: &Metadata<T::AccountId, T::BlockNumber>,
-    ) {
-        <Metadatas<T>>::mutate(*app_id, target, |m| *m = metadata.clone());
+    ) -> Result<(), ChallengeError> {
+        Self::try_appeal(app_id, who, target).map_err(Self::classify)
     }
 
-    fn is_all_harvest(app_id: &AppId) -> bool {
-        <Metadatas<T>>::iter_prefix_values(app_id).next().is_none()
+    fn resolve_appeal(
+        app_id: &AppId,
+        target: &T::AccountId,
+        up: impl Fn(T::Score, u32, &T::Extra) -> Result<(bool, bool, T::Score), DispatchError>,
+    ) -> Result<bool, ChallengeError> {
+        Self::try_resolve_unvoted_appeal(app_id, target, up).map_err(Self::classify)
     }
 
-    fn is_all_timeout(app_id: &AppId, now: &T::BlockNumber) -> bool {
-        let last = LastAt::<T>::get(app_id);
-        *now > last + Self::get_challenge_timeout()
+    fn vote_appeal(
+        app_id: &AppId,
+        arbiter: &T::AccountId,
+        target: &T::AccountId,
+        score: T::Score,
+        up: impl Fn(T::Score, u32, &T::Extra) -> Result<(bool, bool, T::Score), DispatchError>,
+    ) -> Result<Option<bool>, ChallengeError> {
+        Self::try_vote_appeal(app_id, arbiter, target, score, up).map_err(Self::classify)
     }
+}
 
-    fn set_status(app_id: &AppId, target: &T::AccountId, status: &ChallengeStatus) {
-        <Metadatas<T>>::mutate(app_id, target, |c| c.set_status(status));
+impl<T: Config> From<Error<T>> for ChallengeError {
+    fn from(error: Error<T>) -> Self {
+        Pallet::<T>::classify(error.into())
     }
+}
 
+// The fallible `ChallengeBase` methods with the errors of this pallet, which the trait
+// classifies into a `ChallengeError`.
+impl<T: Config> Pallet<T> {
     #[transactional]
-    fn harvest(
+    fn try_harvest(
         who: &T::AccountId,
         app_id: &AppId,
         target: &T::AccountId,
-    ) -> Result<Option<u64>, DispatchError> {
+    ) -> Result<Option<T::Score>, DispatchError> {
         let challenge = Self::get_metadata_exist(app_id, target)?;
-        let total_amount: Balance = challenge.total_amount().ok_or(Error::<T>::Overflow)?;
-        let (sweeper_fee, awards) = Self::checked_sweeper_fee(&challenge, who, &total_amount)?;
-        let mut pathfinder_amount: Balance = Zero::zero();
-        let mut challenger_amount: Balance = Zero::zero();
-        let mut maybe_score: Option<u64> = None;
-        let is_all_done = challenge.is_all_done();
-        match challenge.status {
-            ChallengeStatus::Free => {
-                pathfinder_amount = awards;
-            }
-            ChallengeStatus::Reply => match is_all_done {
-                true => {
-                    pathfinder_amount = awards;
-                }
-                false => {
-                    challenger_amount = awards;
-                }
-            },
-            ChallengeStatus::Examine => {
-                challenger_amount = awards;
-                maybe_score = Some(challenge.score);
-            }
-            ChallengeStatus::Evidence => {
-                maybe_score = Some(challenge.score);
-                match is_all_done {
-                    true => {
-                        challenger_amount = awards;
-                    }
-                    false => {
-                        pathfinder_amount = awards;
-                    }
-                }
-            }
-            ChallengeStatus::Arbitral => match challenge.joint_benefits {
-                true => {
-                    pathfinder_amount = awards / 2;
-                    challenger_amount = awards.saturating_sub(pathfinder_amount);
-                }
-                false => {
-                    pathfinder_amount = awards;
-                    maybe_score = Some(challenge.score);
-                }
-            },
+        Self::ensure_no_appeal(app_id, target)?;
+        if let Some(ruling) = Rulings::<T>::get(app_id, target) {
+            ensure!(
+                Self::app_now(app_id) > ruling.at + T::AppealPeriod::get(),
+                Error::<T>::TooSoon
+            );
         }
+        let total_amount: Balance = challenge.total_amount().ok_or(Error::<T>::Overflow)?;
+        let (sweeper_fee, awards) =
+            Self::checked_sweeper_fee(app_id, target, &challenge, who, &total_amount)?;
+        let (share, maybe_score) = Self::pathfinder_share(app_id, &challenge);
+        let pathfinder_amount = share.mul_floor(awards);
+        let won = awards.saturating_sub(pathfinder_amount);
+        let challenger_amount = Self::get_reward_curve(app_id).challenger_share.mul_floor(won);
+        let treasury_amount = won.saturating_sub(challenger_amount);
         if sweeper_fee > 0 {
-            Self::release(who, sweeper_fee)?;
+            Self::release(app_id, who, sweeper_fee)?;
         }
         if pathfinder_amount > 0 {
-            Self::release(&challenge.pathfinder, pathfinder_amount)?;
+            Self::pay_out(app_id, target, &challenge.pathfinder, pathfinder_amount)?;
         }
         if challenger_amount > 0 {
-            Self::release(&challenge.challenger, challenger_amount)?;
+            Self::release_challenger_side(
+                app_id,
+                target,
+                &challenge,
+                &Backers::<T>::get(app_id, target),
+                challenger_amount,
+            )?;
         };
+        if treasury_amount > 0 {
+            Self::release(app_id, &T::TreasuryAccountId::get(), treasury_amount)?;
+            Self::deposit_event(Event::TreasuryPaid(*app_id, target.clone(), treasury_amount));
+        }
+        Self::archive(app_id, target, &challenge, share);
         Self::remove(app_id, target);
+        T::OnTransition::on_harvested(app_id, target, share)?;
         Ok(maybe_score)
     }
 
+    /// Try to harvest at most `max`, capped at `MAX_HARVEST_ALL`, of the challenges under
+    /// `app_id` indexed for `who` as challenger or pathfinder, returning the number of
+    /// attempts and of harvested challenges.
+    fn try_harvest_many(
+        who: &T::AccountId,
+        app_id: &AppId,
+        max: u32,
+    ) -> Result<(u32, u32), DispatchError> {
+        let participating = ChallengesByChallenger::<T>::iter_prefix(who)
+            .chain(ChallengesByPathfinder::<T>::iter_prefix(who))
+            .filter(|((app, _), _)| app == app_id)
+            .map(|((_, target), _)| target)
+            .take(max.min(MAX_HARVEST_ALL) as usize)
+            .collect::<Vec<_>>();
+        let attempts = participating.len() as u32;
+        let mut harvested: u32 = 0;
+        for target in participating {
+            let challenge = match Metadatas::<T>::try_get(app_id, &target) {
+                Ok(challenge) => challenge,
+                Err(_) => continue,
+            };
+            let is_harvested =
+                with_transaction(|| match Self::harvest_notified(who, app_id, &target, &challenge) {
+                    Ok(_) => TransactionOutcome::Commit(true),
+                    Err(_) => TransactionOutcome::Rollback(false),
+                });
+            if is_harvested {
+                harvested += 1;
+            }
+        }
+        Ok((attempts, harvested))
+    }
+
     #[transactional]
-    fn launch(
+    fn try_launch(
         app_id: &AppId,
         target: &T::AccountId,
-        metadata: &Metadata<T::AccountId, T::BlockNumber>,
+        metadata: &Metadata<T::AccountId, T::BlockNumber, T::Score>,
+        (claimed, recorded): (T::Score, T::Score),
+        attachment: Option<Vec<u8>>,
     ) -> DispatchResult {
-        let now_block_number = system::Module::<T>::block_number();
+        let now_block_number = Self::app_now(app_id);
+        Self::ensure_not_paused(app_id)?;
+        Self::ensure_no_appeal(app_id, target)?;
+        ensure!(
+            claimed.max(recorded).saturating_sub(claimed.min(recorded))
+                >= Self::get_app_config(app_id).min_dispute_delta,
+            Error::<T>::DisputeDeltaTooSmall
+        );
 
         let challenge = match <Metadatas<T>>::try_get(app_id, target) {
             Ok(challenge_storage) => {
@@ -348,7 +1777,7 @@ impl<T: Config> ChallengeBase<T::AccountId, AppId, Balance, T::BlockNumber> for
             .pool
             .staking
             .checked_add(metadata.pool.staking)
-            .and_then(|v| v.checked_add(Self::challenge_staking_amount()))
+            .and_then(|v| v.checked_add(Self::challenge_staking_amount(app_id)))
             .ok_or(Error::<T>::Overflow)?;
 
         let earnings = challenge
@@ -358,7 +1787,7 @@ impl<T: Config> ChallengeBase<T::AccountId, AppId, Balance, T::BlockNumber> for
             .ok_or(Error::<T>::Overflow)?;
 
         <Metadatas<T>>::try_mutate(app_id, target, |m| -> DispatchResult {
-            Self::staking(&metadata.challenger, Self::challenge_staking_amount())?;
+            Self::staking(app_id, &metadata.challenger, Self::challenge_staking_amount(app_id))?;
             *m = Metadata {
                 pool: Pool {
                     staking,
@@ -372,6 +1801,14 @@ impl<T: Config> ChallengeBase<T::AccountId, AppId, Balance, T::BlockNumber> for
             Ok(())
         })?;
 
+        Self::reindex_challenger(app_id, target, &challenge.challenger, &metadata.challenger);
+        Self::reindex_pathfinder(app_id, target, &challenge.pathfinder, &metadata.pathfinder);
+        Rulings::<T>::remove(app_id, target);
+        Settlements::<T>::remove(app_id, target);
+        Extensions::<T>::remove(app_id, target);
+        Extras::<T>::remove(app_id, target);
+        Attachments::<T>::remove_prefix(&(*app_id, target.clone()));
+        Self::attach(app_id, &metadata.challenger, target, attachment)?;
         Self::after_upload(app_id);
 
         Self::deposit_event(Event::Challenged(
@@ -381,22 +1818,26 @@ impl<T: Config> ChallengeBase<T::AccountId, AppId, Balance, T::BlockNumber> for
             metadata.progress.total,
         ));
 
-        Ok(())
+        T::OnTransition::on_launched(app_id, target, &metadata.challenger)
     }
 
     #[transactional]
-    fn next(
+    fn try_next(
         app_id: &AppId,
         who: &T::AccountId,
         target: &T::AccountId,
         count: &u32,
-        mut up: impl FnMut(u64, u32, bool) -> Result<(u64, u32), DispatchError>,
-    ) -> DispatchResult {
+        mut up: impl FnMut(T::Score, u32, bool) -> Result<(T::Score, u32), DispatchError>,
+    ) -> Result<Progress, DispatchError> {
+        let mut progress = Progress::default();
         Self::mutate_metadata(
             app_id,
             target,
-            |challenge: &mut Metadata<T::AccountId, T::BlockNumber>| -> DispatchResult {
-                ensure!(*count <= MAX_UPDATE_COUNT, Error::<T>::TooMany);
+            |challenge: &mut Metadata<T::AccountId, T::BlockNumber, T::Score>| -> DispatchResult {
+                ensure!(
+                    *count <= Self::get_app_config(app_id).max_next_steps,
+                    Error::<T>::TooMany
+                );
 
                 match challenge.status {
                     ChallengeStatus::Reply => {
@@ -415,14 +1856,16 @@ impl<T: Config> ChallengeBase<T::AccountId, AppId, Balance, T::BlockNumber> for
                 let (score, remark) = up(challenge.score, challenge.remark, is_all_done)?;
                 challenge.remark = remark;
                 challenge.score = score;
+                progress = challenge.progress;
                 Self::after_upload(app_id);
                 Ok(())
             },
-        )
+        )?;
+        Ok(progress)
     }
 
     #[transactional]
-    fn examine(
+    fn try_examine(
         app_id: &AppId,
         who: &T::AccountId,
         target: &T::AccountId,
@@ -431,7 +1874,7 @@ impl<T: Config> ChallengeBase<T::AccountId, AppId, Balance, T::BlockNumber> for
         Self::mutate_metadata(
             app_id,
             target,
-            |challenge: &mut Metadata<T::AccountId, T::BlockNumber>| -> DispatchResult {
+            |challenge: &mut Metadata<T::AccountId, T::BlockNumber, T::Score>| -> DispatchResult {
                 ensure!(
                     challenge.status == ChallengeStatus::Reply && challenge.is_all_done(),
                     Error::<T>::NoChallengeAllowed
@@ -448,18 +1891,22 @@ impl<T: Config> ChallengeBase<T::AccountId, AppId, Balance, T::BlockNumber> for
     }
 
     #[transactional]
-    fn reply(
+    fn try_reply(
         app_id: &AppId,
         who: &T::AccountId,
         target: &T::AccountId,
         total: u32,
         count: u32,
-        up: impl Fn(bool, u32, u64) -> Result<u64, DispatchError>,
+        up: impl Fn(bool, u32, T::Score, &T::Extra) -> Result<T::Score, DispatchError>,
     ) -> DispatchResult {
         Self::mutate_metadata(
             app_id,
             target,
-            |challenge: &mut Metadata<T::AccountId, T::BlockNumber>| -> DispatchResult {
+            |challenge: &mut Metadata<T::AccountId, T::BlockNumber, T::Score>| -> DispatchResult {
+                ensure!(
+                    total <= T::MaxReplyCount::get() && count <= T::MaxLegsPerReply::get(),
+                    Error::<T>::TooMany
+                );
                 ensure!(challenge.is_pathfinder(who), Error::<T>::NoPermission);
                 ensure!(
                     challenge.status == ChallengeStatus::Examine,
@@ -470,19 +1917,27 @@ impl<T: Config> ChallengeBase<T::AccountId, AppId, Balance, T::BlockNumber> for
                     Error::<T>::ProgressErr
                 );
                 challenge.status = ChallengeStatus::Reply;
-                challenge.score = up(challenge.is_all_done(), challenge.remark, challenge.score)?;
+                challenge.score = up(
+                    challenge.is_all_done(),
+                    challenge.remark,
+                    challenge.score,
+                    &Extras::<T>::get(app_id, target),
+                )?;
                 Ok(())
             },
-        )
+        )?;
+        Self::extend_deadline(app_id, target);
+        T::OnTransition::on_replied(app_id, target, who)
     }
 
     #[transactional]
-    fn evidence(
+    fn try_evidence(
         app_id: &AppId,
         who: &T::AccountId,
         target: &T::AccountId,
-        up: impl Fn(u32, u64) -> Result<bool, DispatchError>,
-    ) -> Result<Option<u64>, DispatchError> {
+        attachment: Option<Vec<u8>>,
+        up: impl Fn(u32, T::Score, &T::Extra) -> Result<bool, DispatchError>,
+    ) -> Result<Option<T::Score>, DispatchError> {
         let mut challenge =
             <Metadatas<T>>::try_get(app_id, target).map_err(|_| Error::<T>::NonExistent)?;
         ensure!(challenge.is_challenger(who), Error::<T>::NoPermission);
@@ -491,16 +1946,22 @@ impl<T: Config> ChallengeBase<T::AccountId, AppId, Balance, T::BlockNumber> for
             challenge.status != ChallengeStatus::Examine,
             Error::<T>::StatusErr
         );
-        let needs_arbitration = up(challenge.remark, challenge.score)?;
+        let needs_arbitration =
+            up(challenge.remark, challenge.score, &Extras::<T>::get(app_id, target))?;
         let score = challenge.score;
         match needs_arbitration {
             true => challenge.set_status(&ChallengeStatus::Arbitral),
             false => {
+                let backed = Self::refund_backers(app_id, target)?;
+                challenge.pool.staking = challenge.pool.staking.saturating_sub(backed);
                 challenge.restart(true);
             }
         };
         <Metadatas<T>>::mutate(app_id, target, |m| *m = challenge);
+        Self::attach(app_id, who, target, attachment)?;
+        Self::extend_deadline(app_id, target);
         Self::after_upload(app_id);
+        T::OnTransition::on_evidence(app_id, target, who, !needs_arbitration)?;
         Ok(match needs_arbitration {
             false => Some(score),
             true => None,
@@ -508,16 +1969,18 @@ impl<T: Config> ChallengeBase<T::AccountId, AppId, Balance, T::BlockNumber> for
     }
 
     #[transactional]
-    fn arbitral(
+    fn try_arbitral(
         app_id: &AppId,
         who: &T::AccountId,
         target: &T::AccountId,
-        up: impl Fn(u64, u32) -> Result<(bool, bool, u64), DispatchError>,
+        up: impl Fn(T::Score, u32, &T::Extra) -> Result<(bool, bool, T::Score), DispatchError>,
     ) -> DispatchResult {
+        let mut outcome = (false, T::Score::default());
+        Self::ensure_no_appeal(app_id, target)?;
         Self::mutate_metadata(
             app_id,
             target,
-            |challenge: &mut Metadata<T::AccountId, T::BlockNumber>| -> DispatchResult {
+            |challenge: &mut Metadata<T::AccountId, T::BlockNumber, T::Score>| -> DispatchResult {
                 ensure!(
                     challenge.status != ChallengeStatus::Examine,
                     Error::<T>::StatusErr
@@ -525,35 +1988,248 @@ impl<T: Config> ChallengeBase<T::AccountId, AppId, Balance, T::BlockNumber> for
                 ensure!(challenge.is_all_done(), Error::<T>::ProgressErr);
                 if !challenge.is_challenger(who) {
                     ensure!(
-                        Self::is_challenge_timeout(&challenge.last_update),
+                        Self::is_past_deadline(app_id, target, &challenge.last_update),
                         Error::<T>::NoPermission
                     );
-                    Self::staking(who, Self::challenge_staking_amount())?;
+                    Self::staking(app_id, who, Self::challenge_staking_amount(app_id))?;
+                    Self::reindex_challenger(app_id, target, &challenge.challenger, who);
                     challenge.challenger = who.clone();
                 }
-                let (joint_benefits, restart, score) = up(challenge.score, challenge.remark)?;
-                Self::do_settle(challenge, &restart, &joint_benefits, &score)?;
+                let extra = Extras::<T>::get(app_id, target);
+                let (joint_benefits, restart, score) =
+                    up(challenge.score, challenge.remark, &extra)?;
+                // A tie is resolved in favour of the existing record, which is upheld.
+                outcome = (!restart && score == challenge.score, score);
+                Self::rule(app_id, target, challenge, outcome.0);
+                Self::do_settle(app_id, target, challenge, &restart, &joint_benefits, &score)?;
                 Self::after_upload(app_id);
                 Ok(())
             },
-        )
+        )?;
+        Self::deposit_event(Event::Arbitrated(
+            *app_id,
+            who.clone(),
+            target.clone(),
+            outcome.0,
+            outcome.1,
+        ));
+        T::OnTransition::on_arbitrated(app_id, target, outcome.0)
     }
 
     #[transactional]
-    fn settle(
+    fn try_settle(
         app_id: &AppId,
         target: &T::AccountId,
         joint_benefits: bool,
         restart: bool,
-        score: u64,
-    ) -> DispatchResult {
+        score: T::Score,
+    ) -> Result<SettlementOutcome<T::Score>, DispatchError> {
+        Self::ensure_no_appeal(app_id, target)?;
+        let mut upheld = false;
+        let mut outcome = SettlementOutcome::default();
         Self::mutate_metadata(
             app_id,
             target,
-            |challenge: &mut Metadata<T::AccountId, T::BlockNumber>| -> DispatchResult {
+            |challenge: &mut Metadata<T::AccountId, T::BlockNumber, T::Score>| -> DispatchResult {
                 ensure!(challenge.is_all_done(), Error::<T>::ProgressErr);
-                Self::do_settle(challenge, &restart, &joint_benefits, &score)
+                upheld = !restart && score == challenge.score;
+                Self::rule(app_id, target, challenge, upheld);
+                outcome =
+                    Self::do_settle(app_id, target, challenge, &restart, &joint_benefits, &score)?;
+                Ok(())
             },
-        )
+        )?;
+        T::OnTransition::on_settled(app_id, target, upheld)?;
+        Ok(outcome)
+    }
+
+    #[transactional]
+    fn try_back(
+        app_id: &AppId,
+        who: &T::AccountId,
+        target: &T::AccountId,
+        amount: Balance,
+    ) -> DispatchResult {
+        ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+        Self::ensure_no_appeal(app_id, target)?;
+        ensure!(
+            !Rulings::<T>::contains_key(app_id, target),
+            Error::<T>::NoChallengeAllowed
+        );
+        Metadatas::<T>::try_mutate_exists(app_id, target, |challenge| -> DispatchResult {
+            let challenge = challenge.as_mut().ok_or(Error::<T>::NonExistent)?;
+            ensure!(
+                challenge.status != ChallengeStatus::Free,
+                Error::<T>::StatusErr
+            );
+            ensure!(
+                !challenge.is_challenger(who) && !challenge.is_pathfinder(who),
+                Error::<T>::NoPermission
+            );
+            challenge.pool.staking = challenge
+                .pool
+                .staking
+                .checked_add(amount)
+                .ok_or(Error::<T>::Overflow)?;
+            Ok(())
+        })?;
+        Backers::<T>::try_mutate(app_id, target, |backers| -> DispatchResult {
+            match backers.iter_mut().find(|(backer, _)| backer == who) {
+                Some((_, stake)) => *stake = stake.saturating_add(amount),
+                None => {
+                    ensure!(
+                        (backers.len() as u32) < T::MaxBackers::get(),
+                        Error::<T>::TooManyBackers
+                    );
+                    backers.push((who.clone(), amount));
+                }
+            }
+            Ok(())
+        })?;
+        Self::staking(app_id, who, amount)?;
+        Self::deposit_event(Event::Backed(*app_id, who.clone(), target.clone(), amount));
+        Ok(())
+    }
+
+    #[transactional]
+    fn try_cancel(app_id: &AppId, who: &T::AccountId, target: &T::AccountId) -> DispatchResult {
+        let challenge = Self::get_metadata_exist(app_id, target)?;
+        Self::ensure_no_appeal(app_id, target)?;
+        ensure!(challenge.is_challenger(who), Error::<T>::NoPermission);
+        ensure!(
+            challenge.status == ChallengeStatus::Examine || !challenge.is_all_done(),
+            Error::<T>::StatusErr
+        );
+        let backed = Self::refund_backers(app_id, target)?;
+        let total_amount: Balance = challenge
+            .total_amount()
+            .ok_or(Error::<T>::Overflow)?
+            .saturating_sub(backed);
+        let refund = Self::challenge_staking_amount(app_id)
+            .min(total_amount)
+            .saturating_sub(T::CancellationFee::get());
+        let pathfinder_amount = total_amount.saturating_sub(refund);
+        if refund > 0 {
+            Self::release(app_id, who, refund)?;
+        }
+        if pathfinder_amount > 0 {
+            Self::release(app_id, &challenge.pathfinder, pathfinder_amount)?;
+        }
+        Self::remove(app_id, target);
+        Self::deposit_event(Event::Cancelled(*app_id, who.clone(), target.clone(), refund));
+        Ok(())
+    }
+
+    #[transactional]
+    fn try_appeal(app_id: &AppId, who: &T::AccountId, target: &T::AccountId) -> DispatchResult {
+        let ruling = Rulings::<T>::get(app_id, target).ok_or(Error::<T>::NoRuling)?;
+        Self::ensure_no_appeal(app_id, target)?;
+        ensure!(ruling.loser == *who, Error::<T>::NoPermission);
+        ensure!(
+            Self::app_now(app_id) <= ruling.at + T::AppealPeriod::get(),
+            Error::<T>::AppealTimeout
+        );
+        let bond = T::AppealStakingAmount::get();
+        Self::staking(app_id, who, bond)?;
+        Appeals::<T>::insert(
+            app_id,
+            target,
+            Appeal {
+                appellant: who.clone(),
+                bond,
+                at: Self::app_now(app_id),
+            },
+        );
+        Self::after_upload(app_id);
+        Self::deposit_event(Event::Appealed(*app_id, who.clone(), target.clone(), bond));
+        Ok(())
+    }
+
+    /// `try_resolve_appeal` once `AppealVotingPeriod` has passed since the appeal was made,
+    /// without the arbiters reaching `ArbitralThreshold`.
+    fn try_resolve_unvoted_appeal(
+        app_id: &AppId,
+        target: &T::AccountId,
+        up: impl Fn(T::Score, u32, &T::Extra) -> Result<(bool, bool, T::Score), DispatchError>,
+    ) -> Result<bool, DispatchError> {
+        let appeal = Appeals::<T>::get(app_id, target).ok_or(Error::<T>::NoAppeal)?;
+        ensure!(
+            Self::app_now(app_id) > appeal.at + T::AppealVotingPeriod::get(),
+            Error::<T>::AppealVotingOpen
+        );
+        Self::try_resolve_appeal(app_id, target, up)
+    }
+
+    #[transactional]
+    fn try_resolve_appeal(
+        app_id: &AppId,
+        target: &T::AccountId,
+        up: impl Fn(T::Score, u32, &T::Extra) -> Result<(bool, bool, T::Score), DispatchError>,
+    ) -> Result<bool, DispatchError> {
+        let appeal = Appeals::<T>::take(app_id, target).ok_or(Error::<T>::NoAppeal)?;
+        let mut overturned = false;
+        let mut resolved_score = T::Score::default();
+        Self::mutate_metadata(
+            app_id,
+            target,
+            |challenge: &mut Metadata<T::AccountId, T::BlockNumber, T::Score>| -> DispatchResult {
+                let extra = Extras::<T>::get(app_id, target);
+                let (joint_benefits, restart, score) =
+                    up(challenge.score, challenge.remark, &extra)?;
+                // `challenge.score` is the score of the ruling, whichever side it went against.
+                overturned = restart || score != challenge.score;
+                resolved_score = score;
+                Self::do_settle(app_id, target, challenge, &restart, &joint_benefits, &score)?;
+                // A confirmed ruling forfeits the bond to the prize pool.
+                if !overturned {
+                    challenge.pool.staking = challenge.pool.staking.saturating_add(appeal.bond);
+                }
+                Ok(())
+            },
+        )?;
+        if overturned {
+            Self::release(app_id, &appeal.appellant, appeal.bond)?;
+        }
+        Self::settle_arbiters(app_id, target, resolved_score)?;
+        Rulings::<T>::remove(app_id, target);
+        Self::after_upload(app_id);
+        Self::deposit_event(Event::AppealResolved(*app_id, target.clone(), overturned));
+        Ok(overturned)
+    }
+
+    #[transactional]
+    fn try_vote_appeal(
+        app_id: &AppId,
+        arbiter: &T::AccountId,
+        target: &T::AccountId,
+        score: T::Score,
+        up: impl Fn(T::Score, u32, &T::Extra) -> Result<(bool, bool, T::Score), DispatchError>,
+    ) -> Result<Option<bool>, DispatchError> {
+        ensure!(T::Arbiters::contains(arbiter), Error::<T>::NotArbiter);
+        ensure!(
+            Appeals::<T>::contains_key(app_id, target),
+            Error::<T>::NoAppeal
+        );
+        let key = (*app_id, target.clone());
+        ensure!(
+            !AppealVotes::<T>::contains_key(&key, arbiter),
+            Error::<T>::AlreadyVoted
+        );
+        let stake = T::ArbiterStakingAmount::get();
+        Self::staking(app_id, arbiter, stake)?;
+        AppealVotes::<T>::insert(&key, arbiter, (score, stake));
+        Self::deposit_event(Event::AppealVoted(
+            *app_id,
+            arbiter.clone(),
+            target.clone(),
+            score,
+        ));
+        let votes = AppealVotes::<T>::iter_prefix_values(&key)
+            .filter(|(vote, _)| *vote == score)
+            .count() as u32;
+        if votes < T::ArbitralThreshold::get() {
+            return Ok(None);
+        }
+        Self::try_resolve_appeal(app_id, target, up).map(Some)
     }
 }
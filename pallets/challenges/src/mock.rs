@@ -19,13 +19,19 @@ use frame_support::sp_runtime::{
     testing::Header,
     traits::{BlakeTwo256, IdentityLookup},
 };
-use frame_support::{construct_runtime, parameter_types, traits::GenesisBuild};
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::{Contains, GenesisBuild},
+};
 use orml_currencies::BasicCurrencyAdapter;
 use orml_traits::parameter_type_with_key;
 
 pub use frame_system as system;
 use sp_core::H256;
-pub use sp_runtime::{Perbill, Permill};
+pub use sp_runtime::{DispatchResult, Perbill, Permill};
+use std::cell::RefCell;
+use zd_primitives::AppId;
+use zd_support::OnChallengeTransition;
 
 pub type Amount = i128;
 pub type AccountId = u64;
@@ -40,8 +46,10 @@ pub const DAVE: AccountId = 4;
 pub const EVE: AccountId = 5;
 pub const FERDIE: AccountId = 6;
 pub const SWEEPER: AccountId = 7;
+pub const ARBITERS: [AccountId; 3] = [11, 12, 13];
 
 pub const ZDAO: CurrencyId = 1;
+pub const STABLE: CurrencyId = 2;
 
 pub const INIT_PERIOD: BlockNumber = 10;
 
@@ -59,7 +67,7 @@ construct_runtime!(
         System: frame_system::{Module, Call, Config, Storage, Event<T>},
         Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
         ZdReputation: zd_reputation::{Module, Call, Storage, Event<T>, Config<T>},
-        ZdChallenges: zd_challenges::{Module, Call, Storage, Event<T>},
+        ZdChallenges: zd_challenges::{Module, Call, Storage, Event<T>, ValidateUnsigned},
         ZdToken: zd_tokens::{Module, Call, Event<T>},
 
         Tokens: orml_tokens::{Module, Storage, Event<T>, Config<T>},
@@ -69,6 +77,7 @@ construct_runtime!(
 
 parameter_types! {
     pub const SocialPoolAccountId: AccountId = 10000;
+    pub const UserRebateRatio: Perbill = Perbill::zero();
 }
 
 impl zd_tokens::Config for Test {
@@ -79,6 +88,7 @@ impl zd_tokens::Config for Test {
     type SocialPool = SocialPoolAccountId;
     type Amount = Amount;
     type BaceToken = BaceToken;
+    type UserRebateRatio = UserRebateRatio;
 }
 
 parameter_types! {
@@ -89,24 +99,139 @@ parameter_types! {
 parameter_types! {
     pub const ConfirmationPeriod: BlockNumber = 120;
     pub const ChallengePerior: BlockNumber = 100;
+    pub const MaxDelegators: u32 = 2;
 }
 
 impl zd_reputation::Config for Test {
     type Event = Event;
+    type HistoryRounds = HistoryRounds;
+    type MaxDelegators = MaxDelegators;
+    type PriorityPerTier = ();
+    type SlashOrigin = system::EnsureRoot<AccountId>;
 }
 
 parameter_types! {
     pub const ChallengeTimeout: BlockNumber = 10;
     pub const ChallengeStakingAmount: Balance = 100;
+    pub const MaxNextSteps: u32 = 257;
+    pub const MinDisputeDelta: i64 = 0;
+    pub const ResponseWindow: BlockNumber = 0;
+    pub const MaxResponseExtension: BlockNumber = 0;
+    pub const MaxReplyCount: u32 = 200;
+    pub const MaxLegsPerReply: u32 = 150;
+    pub const MaxBackers: u32 = 2;
+    pub const MaxEvidenceLen: u32 = 8;
+    pub const CancellationFee: Balance = 10;
+    pub const AppealPeriod: BlockNumber = 5;
+    pub const AppealStakingAmount: Balance = 200;
+    pub const ArbitralThreshold: u32 = 2;
+    pub const ArbiterStakingAmount: Balance = 50;
+    pub const AppealVotingPeriod: BlockNumber = 10;
+    pub const MaxAutoSettlements: u32 = 2;
+    pub const MaxOffchainSettlements: u32 = 2;
+    pub const UnsignedPriority: u64 = 1 << 20;
+    pub const MaxHistory: u32 = 2;
+    pub const HistoryRounds: u32 = 2;
+    pub const MaxHistoryPrunes: u32 = 2;
+    pub const TreasuryAccountId: AccountId = 10001;
+}
+
+thread_local! {
+    static TRANSITIONS: RefCell<Vec<&'static str>> = RefCell::new(vec![]);
+}
+
+/// Records the transitions of the challenges, in order.
+pub struct TransitionLog;
+
+impl TransitionLog {
+    pub fn take() -> Vec<&'static str> {
+        TRANSITIONS.with(|t| t.borrow_mut().drain(..).collect())
+    }
+
+    fn push(transition: &'static str) -> DispatchResult {
+        TRANSITIONS.with(|t| t.borrow_mut().push(transition));
+        Ok(())
+    }
+}
+
+impl OnChallengeTransition<AccountId, AppId> for TransitionLog {
+    fn on_launched(_: &AppId, _: &AccountId, _: &AccountId) -> DispatchResult {
+        Self::push("launched")
+    }
+
+    fn on_replied(_: &AppId, _: &AccountId, _: &AccountId) -> DispatchResult {
+        Self::push("replied")
+    }
+
+    fn on_evidence(_: &AppId, _: &AccountId, _: &AccountId, _: bool) -> DispatchResult {
+        Self::push("evidence")
+    }
+
+    fn on_arbitrated(_: &AppId, _: &AccountId, _: bool) -> DispatchResult {
+        Self::push("arbitrated")
+    }
+
+    fn on_settled(_: &AppId, _: &AccountId, _: bool) -> DispatchResult {
+        Self::push("settled")
+    }
+
+    fn on_harvested(_: &AppId, _: &AccountId, _: Perbill) -> DispatchResult {
+        Self::push("harvested")
+    }
+}
+
+pub struct Arbiters;
+impl Contains<AccountId> for Arbiters {
+    fn sorted_members() -> Vec<AccountId> {
+        ARBITERS.to_vec()
+    }
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Test
+where
+    Call: From<C>,
+{
+    type OverarchingCall = Call;
+    type Extrinsic = UncheckedExtrinsic;
 }
 
 impl zd_challenges::Config for Test {
     type Event = Event;
     type CurrencyId = CurrencyId;
     type ZdToken = ZdToken;
+    type CurrencyStaking = ZdToken;
     type Reputation = ZdReputation;
     type ChallengeStakingAmount = ChallengeStakingAmount;
     type ChallengeTimeout = ChallengeTimeout;
+    type MaxNextSteps = MaxNextSteps;
+    type MinDisputeDelta = MinDisputeDelta;
+    type ResponseWindow = ResponseWindow;
+    type MaxResponseExtension = MaxResponseExtension;
+    type MaxReplyCount = MaxReplyCount;
+    type MaxLegsPerReply = MaxLegsPerReply;
+    type AppConfigOrigin = system::EnsureRoot<AccountId>;
+    type PauseOrigin = system::EnsureRoot<AccountId>;
+    type TreasuryAccountId = TreasuryAccountId;
+    type MaxBackers = MaxBackers;
+    type MaxEvidenceLen = MaxEvidenceLen;
+    type CancellationFee = CancellationFee;
+    type AppealPeriod = AppealPeriod;
+    type AppealStakingAmount = AppealStakingAmount;
+    type Arbiters = Arbiters;
+    type ArbitralThreshold = ArbitralThreshold;
+    type ArbiterStakingAmount = ArbiterStakingAmount;
+    type AppealVotingPeriod = AppealVotingPeriod;
+    type MaxAutoSettlements = MaxAutoSettlements;
+    type MaxOffchainSettlements = MaxOffchainSettlements;
+    type UnsignedPriority = UnsignedPriority;
+    type MaxHistory = MaxHistory;
+    type HistoryRounds = HistoryRounds;
+    type MaxHistoryPrunes = MaxHistoryPrunes;
+    type Extra = u32;
+    type Score = i64;
+    type OnHarvested = ();
+    type OnTransition = TransitionLog;
+    type WeightInfo = ();
 }
 
 parameter_types! {
@@ -197,6 +322,12 @@ impl Default for ExtBuilder {
                 (BOB, ZDAO, 1000_000_000_000_000u128),
                 (FERDIE, ZDAO, 1000_000_000_000_000u128),
                 (SWEEPER, ZDAO, 1000_000_000_000_000u128),
+                (ALICE, STABLE, 1000_000_000_000_000u128),
+                (FERDIE, STABLE, 1000_000_000_000_000u128),
+                (SWEEPER, STABLE, 1000_000_000_000_000u128),
+                (ARBITERS[0], ZDAO, 1000_000_000_000_000u128),
+                (ARBITERS[1], ZDAO, 1000_000_000_000_000u128),
+                (ARBITERS[2], ZDAO, 1000_000_000_000_000u128),
             ],
         }
     }
@@ -210,6 +341,9 @@ impl ExtBuilder {
 
         zd_reputation::GenesisConfig::<Test> {
             period: self.period,
+            refresh_period: 0,
+            confirm_period: 0,
+            reputations: vec![],
         }
         .assimilate_storage(&mut t)
         .unwrap();
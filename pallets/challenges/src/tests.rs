@@ -16,7 +16,10 @@
 
 use super::*;
 use crate::mock::*;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{OffchainWorker, OnInitialize},
+};
 use zd_primitives::{SWEEPER_PERIOD, Progress, Pool};
 
 const APP_ID: AppId = *b"test    ";
@@ -24,7 +27,7 @@ const CHALLENGER: AccountId = ALICE;
 const PATHINFER: AccountId = BOB;
 const TARGET: AccountId = CHARLIE;
 
-const DEFAULT_METADATA: Metadata<AccountId, BlockNumber> = Metadata {
+const DEFAULT_METADATA: Metadata<AccountId, BlockNumber, i64> = Metadata {
     pool: Pool {
         staking: 0,
         earnings: 0,
@@ -52,7 +55,7 @@ pub struct NewChallengeData {
     earnings: u128,
     staking: u128,
     total: u32,
-    score: u64,
+    score: i64,
     remark: u32,
     init: bool,
     set_now: BlockNumber,
@@ -78,7 +81,7 @@ macro_rules! new_challenge_should_work {
             #[test]
             fn $name() {
                 new_test_ext().execute_with(|| {
-                    let staking_amount = ZdChallenges::challenge_staking_amount();
+                    let staking_amount = ZdChallenges::challenge_staking_amount(&APP_ID);
                     let mut init_staking: u128 = 0;
                     let mut init_earnings: u128 = 0;
                     if $value.init {
@@ -118,7 +121,9 @@ macro_rules! new_challenge_should_work {
                             score: $value.score,
                             remark: $value.remark,
                             ..Metadata::default()
-                        }
+                        },
+                        (0, 0),
+                        None
                     ));
                     assert_eq!(
                         ZdChallenges::get_metadata(&APP_ID, &TARGET),
@@ -221,7 +226,7 @@ fn new_challenge_staking_fail() {
             score: 10,
             remark: 0,
             ..Metadata::default()
-        }).is_err());
+        }, (0, 0), None).is_err());
         assert_eq!(
             ZdChallenges::get_metadata(&APP_ID, &TARGET),
             Metadata::default()
@@ -267,6 +272,8 @@ macro_rules! new_challenge_no_allowed {
                             remark: 0,
                             ..Metadata::default()
                         },
+                        None,
+                    (0, 0),
                     ),Error::<Test>::NoChallengeAllowed);
                 });
             }
@@ -275,7 +282,7 @@ macro_rules! new_challenge_no_allowed {
 }
 
 new_challenge_no_allowed! {
-    new_challenge_no_allowed_5: (0,ChallengeStatus::Evidence,5u64),
+    new_challenge_no_allowed_5: (0,ChallengeStatus::Evidence,5i64),
     new_challenge_no_allowed_6: (0,ChallengeStatus::Examine,21345),
     new_challenge_no_allowed_7: (0,ChallengeStatus::Reply,51),
     new_challenge_no_allowed_8: (0,ChallengeStatus::Arbitral,533314),
@@ -304,7 +311,7 @@ fn next_should_work() {
             &CHALLENGER,
             &TARGET,
             &100,
-            |score, remark, is_all_done| -> Result<(u64, u32), DispatchError> {
+            |score, remark, is_all_done| -> Result<(i64, u32), DispatchError> {
                 assert_eq!(score, DEFAULT_METADATA.score);
                 assert_eq!(remark, DEFAULT_METADATA.remark);
                 assert_eq!(is_all_done, false);
@@ -318,6 +325,85 @@ fn next_should_work() {
     });
 }
 
+#[test]
+fn next_should_resume() {
+    new_test_ext().execute_with(|| {
+        init_challenge(300, 20, ChallengeStatus::Free);
+        System::set_block_number(3);
+        let max_steps = <mock::Test as Config>::MaxNextSteps::get();
+        let up = |score: i64, remark: u32, _: bool| -> Result<(i64, u32), DispatchError> {
+            Ok((score, remark))
+        };
+        assert_noop!(
+            ZdChallenges::next(&APP_ID, &CHALLENGER, &TARGET, &(max_steps + 1), up),
+            Error::<Test>::TooMany
+        );
+        assert_eq!(
+            ZdChallenges::next(&APP_ID, &CHALLENGER, &TARGET, &max_steps, up),
+            Ok(Progress {
+                total: 300,
+                done: 20 + max_steps,
+            })
+        );
+        assert_ok!(ZdChallenges::next(
+            &APP_ID,
+            &CHALLENGER,
+            &TARGET,
+            &(280 - max_steps),
+            |score, remark, is_all_done| -> Result<(i64, u32), DispatchError> {
+                assert!(is_all_done);
+                Ok((score, remark))
+            }
+        ));
+        let metadata = ZdChallenges::get_metadata(&APP_ID, &TARGET);
+        assert_eq!(metadata.progress.done, 300);
+    });
+}
+
+#[test]
+fn app_config_should_work() {
+    new_test_ext().execute_with(|| {
+        let config = AppConfig {
+            staking_amount: 50,
+            timeout: 3,
+            max_next_steps: 2,
+            min_dispute_delta: 0,
+            response_window: 0,
+            max_response_extension: 0,
+        };
+        assert_noop!(
+            ZdChallenges::set_app_config(Origin::signed(ALICE), APP_ID, Some(config.clone())),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(ZdChallenges::set_app_config(Origin::root(), APP_ID, Some(config.clone())));
+        assert_eq!(ZdChallenges::app_config(APP_ID), Some(config.clone()));
+        assert_eq!(ZdChallenges::challenge_staking_amount(&APP_ID), 50);
+        assert!(!ZdChallenges::is_challenge_timeout(&APP_ID, &1));
+        System::set_block_number(5);
+        assert!(ZdChallenges::is_challenge_timeout(&APP_ID, &1));
+
+        init_challenge(300, 20, ChallengeStatus::Free);
+        let up = |score: i64, remark: u32, _: bool| -> Result<(i64, u32), DispatchError> {
+            Ok((score, remark))
+        };
+        assert_noop!(
+            ZdChallenges::next(&APP_ID, &CHALLENGER, &TARGET, &3, up),
+            Error::<Test>::TooMany
+        );
+        assert_ok!(ZdChallenges::next(&APP_ID, &CHALLENGER, &TARGET, &2, up));
+
+        assert_ok!(ZdChallenges::set_app_config(Origin::root(), APP_ID, None));
+        assert_eq!(ZdChallenges::app_config(APP_ID), None);
+        assert_eq!(
+            ZdChallenges::challenge_staking_amount(&APP_ID),
+            ChallengeStakingAmount::get()
+        );
+        let set_event =
+            crate::mock::Event::zd_challenges(crate::Event::AppConfigSet(APP_ID, Some(config)));
+        assert!(System::events().iter().any(|record| record.event == set_event));
+    });
+}
+
 #[test]
 fn next_should_fail() {
     new_test_ext().execute_with(|| {
@@ -329,7 +415,7 @@ fn next_should_fail() {
                 &EVE,
                 &TARGET,
                 &80,
-                |score, remark, _| -> Result<(u64, u32), DispatchError> { Ok((score, remark)) }
+                |score, remark, _| -> Result<(i64, u32), DispatchError> { Ok((score, remark)) }
             ),
             Error::<Test>::NoPermission
         );
@@ -339,7 +425,7 @@ fn next_should_fail() {
                 &CHALLENGER,
                 &TARGET,
                 &300,
-                |score, remark, _| -> Result<(u64, u32), DispatchError> { Ok((score, remark)) }
+                |score, remark, _| -> Result<(i64, u32), DispatchError> { Ok((score, remark)) }
             ),
             Error::<Test>::TooMany
         );
@@ -350,7 +436,7 @@ fn next_should_fail() {
                 &CHALLENGER,
                 &TARGET,
                 &90,
-                |score, remark, _| -> Result<(u64, u32), DispatchError> { Ok((score, remark)) }
+                |score, remark, _| -> Result<(i64, u32), DispatchError> { Ok((score, remark)) }
             ),
             Error::<Test>::ProgressErr
         );
@@ -415,7 +501,7 @@ fn reply_should_work() {
             &TARGET,
             100,
             12,
-            |is_all_done, _, _| -> Result<u64, DispatchError> {
+            |is_all_done, _, _, _| -> Result<i64, DispatchError> {
                 assert_eq!(is_all_done, false);
                 Ok(60)
             }
@@ -438,7 +524,7 @@ fn reply_should_fail() {
                 &TARGET,
                 100,
                 12,
-                |is_all_done, _, _| -> Result<u64, DispatchError> {
+                |is_all_done, _, _, _| -> Result<i64, DispatchError> {
                     assert_eq!(is_all_done, false);
                     Ok(60)
                 }
@@ -453,7 +539,7 @@ fn reply_should_fail() {
                 &TARGET,
                 100,
                 12,
-                |is_all_done, _, _| -> Result<u64, DispatchError> {
+                |is_all_done, _, _, _| -> Result<i64, DispatchError> {
                     assert_eq!(is_all_done, false);
                     Ok(60)
                 }
@@ -468,7 +554,7 @@ fn reply_should_fail() {
                 &TARGET,
                 100,
                 120,
-                |is_all_done, _, _| -> Result<u64, DispatchError> {
+                |is_all_done, _, _, _| -> Result<i64, DispatchError> {
                     assert_eq!(is_all_done, false);
                     Ok(60)
                 }
@@ -478,6 +564,79 @@ fn reply_should_fail() {
     });
 }
 
+#[test]
+fn extra_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ZdChallenges::set_extra(&APP_ID, &TARGET, 7),
+            Error::<Test>::NonExistent
+        );
+        init_challenge(100, 100, ChallengeStatus::Reply);
+        assert_ok!(ZdChallenges::set_extra(&APP_ID, &TARGET, 7));
+        assert_eq!(ZdChallenges::get_extra(&APP_ID, &TARGET), 7);
+        assert_ok!(
+            ZdChallenges::evidence(
+                &APP_ID,
+                &CHALLENGER,
+                &TARGET,
+                None,
+                |_, _, extra| -> Result<bool, DispatchError> {
+                    assert_eq!(*extra, 7);
+                    Ok(true)
+                }
+            ),
+            None
+        );
+        ZdChallenges::remove(&APP_ID, &TARGET);
+        assert_eq!(ZdChallenges::get_extra(&APP_ID, &TARGET), 0);
+    });
+}
+
+#[test]
+fn transitions_should_be_notified() {
+    new_test_ext().execute_with(|| {
+        TransitionLog::take();
+        assert_ok!(ZdChallenges::launch(
+            &APP_ID,
+            &TARGET,
+            &Metadata {
+                challenger: CHALLENGER,
+                pathfinder: PATHINFER,
+                ..Metadata::default()
+            },
+            (0, 0),
+            None
+        ));
+        assert_ok!(ZdChallenges::reply(
+            &APP_ID,
+            &PATHINFER,
+            &TARGET,
+            10,
+            10,
+            |_, _, score, _| -> Result<i64, DispatchError> { Ok(score) }
+        ));
+        assert_ok!(ZdChallenges::evidence(
+            &APP_ID,
+            &CHALLENGER,
+            &TARGET,
+            None,
+            |_, _, _| -> Result<bool, DispatchError> { Ok(true) }
+        ));
+        assert_ok!(ZdChallenges::arbitral(
+            &APP_ID,
+            &CHALLENGER,
+            &TARGET,
+            |score, _, _| -> Result<(bool, bool, i64), DispatchError> { Ok((false, false, score)) }
+        ));
+        System::set_block_number(100);
+        assert_ok!(ZdChallenges::harvest(&PATHINFER, &APP_ID, &TARGET));
+        assert_eq!(
+            TransitionLog::take(),
+            vec!["launched", "replied", "evidence", "arbitrated", "harvested"]
+        );
+    });
+}
+
 #[test]
 fn evidence_should_work() {
     new_test_ext().execute_with(|| {
@@ -487,7 +646,8 @@ fn evidence_should_work() {
                 &APP_ID,
                 &CHALLENGER,
                 &TARGET,
-                |_, _| -> Result<bool, DispatchError> { Ok(true) }
+                None,
+                |_, _, _| -> Result<bool, DispatchError> { Ok(true) }
             ),
             None
         );
@@ -499,7 +659,8 @@ fn evidence_should_work() {
                 &APP_ID,
                 &CHALLENGER,
                 &TARGET,
-                |_, _| -> Result<bool, DispatchError> { Ok(false) }
+                None,
+                |_, _, _| -> Result<bool, DispatchError> { Ok(false) }
             ),
             Some(0)
         );
@@ -518,7 +679,8 @@ fn evidence_should_fail() {
                 &APP_ID,
                 &CHALLENGER,
                 &TARGET,
-                |_, _| -> Result<bool, DispatchError> { Ok(true) }
+                None,
+                |_, _, _| -> Result<bool, DispatchError> { Ok(true) }
             ),
             Error::<Test>::NonExistent
         );
@@ -528,7 +690,8 @@ fn evidence_should_fail() {
                 &APP_ID,
                 &EVE,
                 &TARGET,
-                |_, _| -> Result<bool, DispatchError> { Ok(true) }
+                None,
+                |_, _, _| -> Result<bool, DispatchError> { Ok(true) }
             ),
             Error::<Test>::NoPermission
         );
@@ -537,7 +700,8 @@ fn evidence_should_fail() {
                 &APP_ID,
                 &CHALLENGER,
                 &TARGET,
-                |_, _| -> Result<bool, DispatchError> { Ok(true) }
+                None,
+                |_, _, _| -> Result<bool, DispatchError> { Ok(true) }
             ),
             Error::<Test>::ProgressErr
         );
@@ -547,13 +711,70 @@ fn evidence_should_fail() {
                 &APP_ID,
                 &CHALLENGER,
                 &TARGET,
-                |_, _| -> Result<bool, DispatchError> { Ok(true) }
+                None,
+                |_, _, _| -> Result<bool, DispatchError> { Ok(true) }
             ),
             Error::<Test>::StatusErr
         );
     });
 }
 
+#[test]
+fn attachment_should_work() {
+    new_test_ext().execute_with(|| {
+        let metadata = Metadata {
+            progress: Progress {
+                total: 100,
+                done: 0,
+            },
+            challenger: CHALLENGER,
+            pathfinder: PATHINFER,
+            ..Metadata::default()
+        };
+        assert_noop!(
+            ZdChallenges::launch(&APP_ID, &TARGET, &metadata, (0, 0), Some(vec![0u8; 9])),
+            Error::<Test>::AttachmentTooLong
+        );
+        assert_ok!(ZdChallenges::launch(
+            &APP_ID,
+            &TARGET,
+            &metadata,
+            (0, 0),
+            Some(b"cid".to_vec())
+        ));
+        assert_eq!(
+            ZdChallenges::get_attachment((APP_ID, TARGET), CHALLENGER),
+            Some(b"cid".to_vec())
+        );
+        let attached_event = crate::mock::Event::zd_challenges(crate::Event::Attached(
+            APP_ID,
+            CHALLENGER,
+            TARGET,
+            b"cid".to_vec(),
+        ));
+        assert!(System::events().iter().any(|record| record.event == attached_event));
+
+        init_challenge(100, 100, ChallengeStatus::Reply);
+        assert_ok!(
+            ZdChallenges::evidence(
+                &APP_ID,
+                &CHALLENGER,
+                &TARGET,
+                Some(b"proof".to_vec()),
+                |_, _, _| -> Result<bool, DispatchError> { Ok(true) }
+            ),
+            None
+        );
+        assert_eq!(
+            ZdChallenges::get_attachment((APP_ID, TARGET), CHALLENGER),
+            Some(b"proof".to_vec())
+        );
+
+        ZdChallenges::remove(&APP_ID, &TARGET);
+        assert_eq!(ZdChallenges::get_attachment((APP_ID, TARGET), CHALLENGER), None);
+    });
+}
+
 #[test]
 fn arbitral_should_work() {
     new_test_ext().execute_with(|| {
@@ -562,7 +783,7 @@ fn arbitral_should_work() {
             &APP_ID,
             &CHALLENGER,
             &TARGET,
-            |_,_| -> Result<(bool, bool, u64), DispatchError> {
+            |_,_, _| -> Result<(bool, bool, i64), DispatchError> {
                 // joint_benefits, restart, score
                 Ok((true, false, 18))
             }
@@ -577,7 +798,7 @@ fn arbitral_should_work() {
             &APP_ID,
             &CHALLENGER,
             &TARGET,
-            |_,_| -> Result<(bool, bool, u64), DispatchError> {
+            |_,_, _| -> Result<(bool, bool, i64), DispatchError> {
                 // joint_benefits, restart, score
                 Ok((true, false, 60))
             }
@@ -593,7 +814,7 @@ fn arbitral_should_work() {
             &APP_ID,
             &FERDIE,
             &TARGET,
-            |_,_| -> Result<(bool, bool, u64), DispatchError> {
+            |_,_, _| -> Result<(bool, bool, i64), DispatchError> {
                 // joint_benefits, restart, score
                 Ok((true, false, 60))
             }
@@ -603,7 +824,7 @@ fn arbitral_should_work() {
         assert_eq!(metadata.joint_benefits, true);
         assert_eq!(metadata.score, 60);
         assert_eq!(metadata.last_update, 100);
-        let staking_amount = ZdChallenges::challenge_staking_amount();
+        let staking_amount = ZdChallenges::challenge_staking_amount(&APP_ID);
         assert_eq!(ZdToken::total_staking(), staking_amount);
         assert_eq!(
             ZdToken::free_balance(&FERDIE),
@@ -612,6 +833,42 @@ fn arbitral_should_work() {
     });
 }
 
+macro_rules! arbitral_outcome_test {
+    ($($name:ident: $value:expr,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                new_test_ext().execute_with(|| {
+                    // joint_benefits, restart, score, upheld
+                    let (joint_benefits, restart, score, upheld) = $value;
+                    init_challenge(100, 100, ChallengeStatus::Reply);
+                    assert_ok!(ZdChallenges::arbitral(
+                        &APP_ID,
+                        &CHALLENGER,
+                        &TARGET,
+                        |_,_, _| -> Result<(bool, bool, i64), DispatchError> {
+                            Ok((joint_benefits, restart, score))
+                        }
+                    ));
+                    let arbitrated_event = crate::mock::Event::zd_challenges(
+                        crate::Event::Arbitrated(APP_ID, CHALLENGER, TARGET, upheld, score),
+                    );
+                    assert!(System::events().iter().any(|record| record.event == arbitrated_event));
+                });
+            }
+        )*
+    }
+}
+
+arbitral_outcome_test! {
+    // The challenge restarts and the challenger takes over.
+    arbitral_outcome_restart: (false, true, 0, false),
+    // The arbitrated score replaces the record.
+    arbitral_outcome_replace: (false, false, 18, false),
+    // Same score as the record, the score of the pathfinder is upheld.
+    arbitral_outcome_tie: (true, false, DEFAULT_METADATA.score, true),
+}
+
 #[test]
 fn arbitral_should_fail() {
     new_test_ext().execute_with(|| {
@@ -621,7 +878,7 @@ fn arbitral_should_fail() {
                 &APP_ID,
                 &CHALLENGER,
                 &TARGET,
-                |_,_| -> Result<(bool, bool, u64), DispatchError> {
+                |_,_, _| -> Result<(bool, bool, i64), DispatchError> {
                     // joint_benefits, restart, score
                     Ok((true, false, 60))
                 }
@@ -634,7 +891,7 @@ fn arbitral_should_fail() {
                 &APP_ID,
                 &CHALLENGER,
                 &TARGET,
-                |_,_| -> Result<(bool, bool, u64), DispatchError> {
+                |_,_, _| -> Result<(bool, bool, i64), DispatchError> {
                     // joint_benefits, restart, score
                     Ok((true, false, 60))
                 }
@@ -648,7 +905,7 @@ fn arbitral_should_fail() {
                 &APP_ID,
                 &FERDIE,
                 &TARGET,
-                |_,_| -> Result<(bool, bool, u64), DispatchError> {
+                |_,_, _| -> Result<(bool, bool, i64), DispatchError> {
                     // joint_benefits, restart, score
                     Ok((true, false, 60))
                 }
@@ -660,7 +917,7 @@ fn arbitral_should_fail() {
             &APP_ID,
             &EVE,
             &TARGET,
-            |_,_| -> Result<(bool, bool, u64), DispatchError> {
+            |_,_, _| -> Result<(bool, bool, i64), DispatchError> {
                 // joint_benefits, restart, score
                 Ok((true, false, 60))
             }
@@ -690,7 +947,7 @@ macro_rules! settle_should_work {
                     };
                     <Metadatas<Test>>::insert(&APP_ID,&TARGET,&init_metadata);
                     // init staking pool
-                    assert_ok!(ZdChallenges::staking(&FERDIE, 10000000));
+                    assert_ok!(ZdChallenges::staking(&APP_ID, &FERDIE, 10000000));
 
                     let free_balance = ZdToken::free_balance(&CHALLENGER);
 
@@ -739,6 +996,64 @@ settle_should_work! {
     settle_should_work_6: (10,100,true,false),
 }
 
+#[test]
+fn settle_should_record_outcome() {
+    new_test_ext().execute_with(|| {
+        // init staking pool
+        assert_ok!(ZdChallenges::staking(&APP_ID, &FERDIE, 10000000));
+        let challenge = Metadata {
+            progress: Progress {
+                total: 100,
+                done: 100,
+            },
+            pool: Pool {
+                staking: 1000,
+                earnings: 200,
+            },
+            status: ChallengeStatus::Arbitral,
+            ..DEFAULT_METADATA
+        };
+        for target in [TARGET, DAVE, EVE].iter() {
+            <Metadatas<Test>>::insert(&APP_ID, target, &challenge);
+        }
+
+        // The arbiter takes half of the staking, the pathfinder keeps the rest of the pool.
+        let outcome = SettlementOutcome {
+            challenger_amount: 500,
+            pathfinder_amount: 700,
+            score: 0,
+            restarted: true,
+        };
+        assert_eq!(ZdChallenges::settle(&APP_ID, &TARGET, true, true, 100), Ok(outcome));
+        assert_eq!(ZdChallenges::get_settlement(&APP_ID, &TARGET), Some(outcome));
+        let settled_event =
+            crate::mock::Event::zd_challenges(crate::Event::Settled(APP_ID, TARGET, outcome));
+        assert!(System::events().iter().any(|record| record.event == settled_event));
+
+        // The challenger takes the place of the pathfinder.
+        assert_eq!(
+            ZdChallenges::settle(&APP_ID, &DAVE, false, true, 100),
+            Ok(SettlementOutcome {
+                challenger_amount: 1200,
+                pathfinder_amount: 0,
+                score: 0,
+                restarted: true,
+            })
+        );
+
+        // Both sides share the pool.
+        assert_eq!(
+            ZdChallenges::settle(&APP_ID, &EVE, true, false, 100),
+            Ok(SettlementOutcome {
+                challenger_amount: 600,
+                pathfinder_amount: 600,
+                score: 100,
+                restarted: false,
+            })
+        );
+    });
+}
+
 macro_rules! harvest_should_work {
     ($($name:ident: $value:expr,)*) => {
         $(
@@ -747,7 +1062,7 @@ macro_rules! harvest_should_work {
                 new_test_ext().execute_with(|| {
                     let (who, status, done, joint_benefits,staking,now) = $value;
                     // init staking pool
-                    assert_ok!(ZdChallenges::staking(&FERDIE, 10000000));
+                    assert_ok!(ZdChallenges::staking(&APP_ID, &FERDIE, 10000000));
                     let init_metadata = Metadata {
                         progress: Progress {
                             total: 100,
@@ -842,3 +1157,1141 @@ harvest_should_work! {
     harvest_should_work_12: (PATHINFER,ChallengeStatus::Arbitral,10,false,46454,ChallengeTimeout::get() + 2),
     harvest_should_work_13: (SWEEPER,ChallengeStatus::Free,10,false,0,SWEEPER_PERIOD + 2),
 }
+
+fn set_round(round: u32) {
+    zd_reputation::SystemInfo::<Test>::mutate(|info| info.nonce = round);
+}
+
+fn harvest_in_round(round: u32, score: i64) {
+    set_round(round);
+    <Metadatas<Test>>::insert(
+        &APP_ID,
+        &TARGET,
+        &Metadata {
+            status: ChallengeStatus::Free,
+            score,
+            ..DEFAULT_METADATA
+        },
+    );
+    assert_ok!(ZdChallenges::harvest(&PATHINFER, &APP_ID, &TARGET));
+}
+
+#[test]
+fn harvest_should_archive_challenges() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(ChallengeTimeout::get() + 2);
+        harvest_in_round(1, 10);
+        harvest_in_round(2, 20);
+        harvest_in_round(3, 30);
+
+        // Only the last `MaxHistory` challenges are kept.
+        let history = ZdChallenges::get_history(&APP_ID, &TARGET);
+        assert_eq!(history.len(), MaxHistory::get() as usize);
+        assert_eq!(
+            history[1],
+            ChallengeRecord {
+                round: 3,
+                challenger: CHALLENGER,
+                pathfinder: PATHINFER,
+                status: ChallengeStatus::Free,
+                pathfinder_share: Perbill::one(),
+                score: 30,
+                remark: 0,
+            }
+        );
+        assert_eq!(history[0].round, 2);
+
+        set_round(2 + HistoryRounds::get() + 1);
+        assert_ok!(ZdChallenges::prune_history(Origin::signed(EVE), APP_ID, TARGET));
+        assert_eq!(ZdChallenges::get_history(&APP_ID, &TARGET).len(), 1);
+        let pruned_event =
+            crate::mock::Event::zd_challenges(crate::Event::HistoryPruned(APP_ID, TARGET, 1));
+        assert!(System::events().iter().any(|record| record.event == pruned_event));
+
+        set_round(3 + HistoryRounds::get() + 1);
+        ZdChallenges::on_initialize(System::block_number());
+        assert!(!<Histories<Test>>::contains_key(&APP_ID, &TARGET));
+    });
+}
+
+#[test]
+fn on_initialize_should_settle_timed_out_challenges() {
+    new_test_ext().execute_with(|| {
+        // init staking pool
+        assert_ok!(ZdChallenges::staking(&APP_ID, &FERDIE, 10000000));
+        let challenge = Metadata {
+            pool: Pool {
+                staking: 1000,
+                earnings: 200,
+            },
+            ..DEFAULT_METADATA
+        };
+        let now = ChallengeTimeout::get() + 2;
+        <Metadatas<Test>>::insert(&APP_ID, &TARGET, &challenge);
+        <Metadatas<Test>>::insert(&APP_ID, &DAVE, &challenge);
+        <Metadatas<Test>>::insert(
+            &APP_ID,
+            &EVE,
+            &Metadata {
+                last_update: now,
+                ..challenge.clone()
+            },
+        );
+        System::set_block_number(now);
+        let challenger_balance = ZdToken::free_balance(&CHALLENGER);
+
+        // At most two challenges are checked per block.
+        ZdChallenges::on_initialize(now);
+        assert!(ZdChallenges::settle_cursor().is_some());
+        ZdChallenges::on_initialize(now);
+        assert_eq!(ZdChallenges::settle_cursor(), None);
+
+        // The pathfinder never answered the examine, the challenger takes the pool.
+        assert!(!<Metadatas<Test>>::contains_key(&APP_ID, &TARGET));
+        assert!(!<Metadatas<Test>>::contains_key(&APP_ID, &DAVE));
+        assert!(<Metadatas<Test>>::contains_key(&APP_ID, &EVE));
+        assert_eq!(ZdToken::free_balance(&CHALLENGER), challenger_balance + 2400);
+        for target in [TARGET, DAVE].iter() {
+            let settled_event = crate::mock::Event::zd_challenges(
+                crate::Event::ChallengeAutoSettled(APP_ID, *target, Perbill::zero()),
+            );
+            assert!(System::events().iter().any(|record| record.event == settled_event));
+        }
+    });
+}
+
+#[test]
+fn offchain_worker_should_submit_expired_settlements() {
+    use codec::Decode;
+    use sp_core::offchain::{testing::TestTransactionPoolExt, TransactionPoolExt};
+    use sp_runtime::{
+        traits::ValidateUnsigned,
+        transaction_validity::{InvalidTransaction, TransactionSource},
+    };
+
+    let mut ext = new_test_ext();
+    let (pool, pool_state) = TestTransactionPoolExt::new();
+    ext.register_extension(TransactionPoolExt::new(pool));
+    ext.execute_with(|| {
+        // init staking pool
+        assert_ok!(ZdChallenges::staking(&APP_ID, &FERDIE, 10000000));
+        let challenge = Metadata {
+            pool: Pool {
+                staking: 1000,
+                earnings: 200,
+            },
+            ..DEFAULT_METADATA
+        };
+        let now = ChallengeTimeout::get() + 2;
+        <Metadatas<Test>>::insert(&APP_ID, &TARGET, &challenge);
+        <Metadatas<Test>>::insert(
+            &APP_ID,
+            &EVE,
+            &Metadata {
+                last_update: now,
+                ..challenge.clone()
+            },
+        );
+        System::set_block_number(now);
+
+        ZdChallenges::offchain_worker(now);
+        let transactions = pool_state.read().transactions.clone();
+        assert_eq!(transactions.len(), 1);
+        let tx = frame_system::mocking::MockUncheckedExtrinsic::<Test>::decode(
+            &mut &transactions[0][..],
+        )
+        .unwrap();
+        assert!(tx.signature.is_none());
+        assert_eq!(
+            tx.function,
+            crate::mock::Call::ZdChallenges(crate::Call::settle_expired(APP_ID, TARGET))
+        );
+
+        let validate = |target: AccountId| {
+            <ZdChallenges as ValidateUnsigned>::validate_unsigned(
+                TransactionSource::External,
+                &crate::Call::settle_expired(APP_ID, target),
+            )
+        };
+        assert!(validate(TARGET).is_ok());
+        assert_eq!(validate(EVE), Err(InvalidTransaction::Stale.into()));
+
+        let challenger_balance = ZdToken::free_balance(&CHALLENGER);
+        assert_noop!(
+            ZdChallenges::settle_expired(Origin::signed(ALICE), APP_ID, TARGET),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            ZdChallenges::settle_expired(Origin::none(), APP_ID, EVE),
+            Error::<Test>::TooSoon
+        );
+        assert_ok!(ZdChallenges::settle_expired(Origin::none(), APP_ID, TARGET));
+        assert!(!<Metadatas<Test>>::contains_key(&APP_ID, &TARGET));
+        assert_eq!(ZdToken::free_balance(&CHALLENGER), challenger_balance + 1200);
+        assert_eq!(validate(TARGET), Err(InvalidTransaction::Stale.into()));
+    });
+}
+
+#[test]
+fn harvest_all_should_work() {
+    new_test_ext().execute_with(|| {
+        // init staking pool
+        assert_ok!(ZdChallenges::staking(&APP_ID, &FERDIE, 10000000));
+        let challenge = Metadata {
+            pool: Pool {
+                staking: 1000,
+                earnings: 200,
+            },
+            ..DEFAULT_METADATA
+        };
+        let now = ChallengeTimeout::get() + 2;
+        // Through `set_metadata` to index them by challenger and pathfinder.
+        ZdChallenges::set_metadata(&APP_ID, &TARGET, &challenge);
+        ZdChallenges::set_metadata(&APP_ID, &DAVE, &challenge);
+        ZdChallenges::set_metadata(
+            &APP_ID,
+            &EVE,
+            &Metadata {
+                last_update: now,
+                ..challenge.clone()
+            },
+        );
+        ZdChallenges::set_metadata(
+            &APP_ID,
+            &FERDIE,
+            &Metadata {
+                challenger: DAVE,
+                pathfinder: EVE,
+                ..challenge.clone()
+            },
+        );
+        System::set_block_number(now);
+        let challenger_balance = ZdToken::free_balance(&CHALLENGER);
+
+        // The challenge that has not timed out takes one of the three attempts.
+        assert_ok!(ZdChallenges::harvest_all(Origin::signed(CHALLENGER), APP_ID, 3));
+        assert_eq!(ZdToken::free_balance(&CHALLENGER), challenger_balance + 2400);
+        let harvested_event =
+            crate::mock::Event::zd_challenges(crate::Event::BatchHarvested(APP_ID, CHALLENGER, 2));
+        assert!(System::events().iter().any(|record| record.event == harvested_event));
+
+        // The challenge that has not timed out and the one of another challenger are left.
+        assert_ok!(ZdChallenges::harvest_all(Origin::signed(CHALLENGER), APP_ID, 10));
+        assert_eq!(ZdToken::free_balance(&CHALLENGER), challenger_balance + 2400);
+        assert!(!<Metadatas<Test>>::contains_key(&APP_ID, &TARGET));
+        assert!(!<Metadatas<Test>>::contains_key(&APP_ID, &DAVE));
+        assert!(<Metadatas<Test>>::contains_key(&APP_ID, &EVE));
+        assert!(<Metadatas<Test>>::contains_key(&APP_ID, &FERDIE));
+
+        // The pathfinder of a challenge can harvest it too.
+        assert_ok!(ZdChallenges::harvest_all(Origin::signed(EVE), APP_ID, u32::MAX));
+        assert!(!<Metadatas<Test>>::contains_key(&APP_ID, &FERDIE));
+        assert!(!ChallengesByChallenger::<Test>::contains_key(&DAVE, (APP_ID, FERDIE)));
+        assert!(!ChallengesByPathfinder::<Test>::contains_key(&EVE, (APP_ID, FERDIE)));
+    });
+}
+
+#[test]
+fn state_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(ZdChallenges::state(&APP_ID, &TARGET), None);
+        init_challenge(100, 0, ChallengeStatus::Examine);
+        assert_eq!(ZdChallenges::state(&APP_ID, &TARGET), Some(ChallengeState::Examining));
+        init_challenge(100, 10, ChallengeStatus::Reply);
+        assert_eq!(ZdChallenges::state(&APP_ID, &TARGET), Some(ChallengeState::Replying));
+        init_challenge(100, 100, ChallengeStatus::Arbitral);
+        assert_eq!(ZdChallenges::state(&APP_ID, &TARGET), Some(ChallengeState::Arbitral));
+
+        let now = ChallengeTimeout::get() + 2;
+        System::set_block_number(now);
+        assert_eq!(ZdChallenges::state(&APP_ID, &TARGET), Some(ChallengeState::TimedOut));
+        Rulings::<Test>::insert(
+            &APP_ID,
+            &TARGET,
+            Ruling {
+                at: now,
+                loser: PATHINFER,
+            },
+        );
+        assert_eq!(ZdChallenges::state(&APP_ID, &TARGET), Some(ChallengeState::Settled));
+        System::set_block_number(now + AppealPeriod::get() + 1);
+        assert_eq!(ZdChallenges::state(&APP_ID, &TARGET), Some(ChallengeState::Harvestable));
+    });
+}
+
+#[test]
+fn is_challengeable_should_work() {
+    new_test_ext().execute_with(|| {
+        let now = ZdChallenges::now();
+        assert!(ZdChallenges::is_challengeable(&APP_ID, &TARGET, &now));
+        init_challenge(100, 0, ChallengeStatus::Examine);
+        assert!(!ZdChallenges::is_challengeable(&APP_ID, &TARGET, &now));
+        init_challenge(100, 0, ChallengeStatus::Free);
+        assert!(ZdChallenges::is_challengeable(&APP_ID, &TARGET, &now));
+
+        Rulings::<Test>::insert(
+            &APP_ID,
+            &TARGET,
+            Ruling {
+                at: now,
+                loser: PATHINFER,
+            },
+        );
+        assert!(!ZdChallenges::is_challengeable(&APP_ID, &TARGET, &now));
+        let after_appeal_period = now + AppealPeriod::get() + 1;
+        assert!(ZdChallenges::is_challengeable(&APP_ID, &TARGET, &after_appeal_period));
+
+        Appeals::<Test>::insert(
+            &APP_ID,
+            &TARGET,
+            Appeal {
+                appellant: PATHINFER,
+                bond: AppealStakingAmount::get(),
+                at: now,
+            },
+        );
+        assert!(!ZdChallenges::is_challengeable(&APP_ID, &TARGET, &after_appeal_period));
+    });
+}
+
+fn arbitral_with_score(score: i64) {
+    assert_ok!(ZdChallenges::arbitral(
+        &APP_ID,
+        &CHALLENGER,
+        &TARGET,
+        |_,_, _| -> Result<(bool, bool, i64), DispatchError> {
+            // joint_benefits, restart, score
+            Ok((false, false, score))
+        }
+    ));
+}
+
+#[test]
+fn appeal_should_work() {
+    new_test_ext().execute_with(|| {
+        init_challenge(100, 100, ChallengeStatus::Reply);
+        assert_noop!(
+            ZdChallenges::appeal(&APP_ID, &PATHINFER, &TARGET),
+            Error::<Test>::NoRuling
+        );
+        // The arbitration changed the score, so it went against the pathfinder.
+        arbitral_with_score(18);
+        assert_noop!(
+            ZdChallenges::appeal(&APP_ID, &CHALLENGER, &TARGET),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(ZdChallenges::appeal(&APP_ID, &PATHINFER, &TARGET));
+        let bond = AppealStakingAmount::get();
+        assert_eq!(
+            ZdChallenges::get_appeal(&APP_ID, &TARGET),
+            Some(Appeal {
+                appellant: PATHINFER,
+                bond,
+                at: System::block_number(),
+            })
+        );
+        assert_eq!(ZdToken::total_staking(), bond);
+        assert_noop!(
+            ZdChallenges::appeal(&APP_ID, &PATHINFER, &TARGET),
+            Error::<Test>::AppealPending
+        );
+
+        // The challenge is locked until the appeal is resolved.
+        System::set_block_number(1000);
+        assert_noop!(
+            ZdChallenges::harvest(&CHALLENGER, &APP_ID, &TARGET),
+            Error::<Test>::AppealPending
+        );
+        assert_noop!(
+            ZdChallenges::settle(&APP_ID, &TARGET, false, false, 20),
+            Error::<Test>::AppealPending
+        );
+    });
+}
+
+#[test]
+fn appeal_should_timeout() {
+    new_test_ext().execute_with(|| {
+        init_challenge(100, 100, ChallengeStatus::Reply);
+        // A tie keeps the record, so the ruling went against the challenger.
+        arbitral_with_score(DEFAULT_METADATA.score);
+        System::set_block_number(AppealPeriod::get() + 2);
+        assert_noop!(
+            ZdChallenges::appeal(&APP_ID, &CHALLENGER, &TARGET),
+            Error::<Test>::AppealTimeout
+        );
+    });
+}
+
+macro_rules! resolve_appeal_test {
+    ($($name:ident: $value:expr,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                new_test_ext().execute_with(|| {
+                    // score, overturned
+                    let (score, overturned) = $value;
+                    init_challenge(100, 100, ChallengeStatus::Reply);
+                    arbitral_with_score(18);
+                    assert_ok!(ZdChallenges::appeal(&APP_ID, &PATHINFER, &TARGET));
+                    let staking = ZdChallenges::get_metadata(&APP_ID, &TARGET).pool.staking;
+                    let pathfinder_balance = ZdToken::free_balance(&PATHINFER);
+                    let up = |_: i64, _: u32, _: &u32| -> Result<(bool, bool, i64), DispatchError> {
+                        Ok((false, false, score))
+                    };
+                    // The arbiters may still vote on the appeal.
+                    let voting_ends_at = System::block_number() + AppealVotingPeriod::get();
+                    System::set_block_number(voting_ends_at);
+                    assert_noop!(
+                        ZdChallenges::resolve_appeal(&APP_ID, &TARGET, up),
+                        Error::<Test>::AppealVotingOpen
+                    );
+                    System::set_block_number(voting_ends_at + 1);
+
+                    assert_eq!(
+                        ZdChallenges::resolve_appeal(
+                            &APP_ID,
+                            &TARGET,
+                            |_,_, _| -> Result<(bool, bool, i64), DispatchError> {
+                                Ok((false, false, score))
+                            }
+                        ),
+                        Ok(overturned)
+                    );
+                    let bond = AppealStakingAmount::get();
+                    let metadata = ZdChallenges::get_metadata(&APP_ID, &TARGET);
+                    assert_eq!(metadata.score, score);
+                    match overturned {
+                        true => {
+                            assert_eq!(metadata.pool.staking, staking);
+                            assert_eq!(ZdToken::free_balance(&PATHINFER), pathfinder_balance + bond);
+                        }
+                        false => {
+                            assert_eq!(metadata.pool.staking, staking + bond);
+                            assert_eq!(ZdToken::free_balance(&PATHINFER), pathfinder_balance);
+                        }
+                    }
+                    assert_eq!(ZdChallenges::get_appeal(&APP_ID, &TARGET), None);
+                    assert_eq!(ZdChallenges::get_ruling(&APP_ID, &TARGET), None);
+                    let resolved_event = crate::mock::Event::zd_challenges(
+                        crate::Event::AppealResolved(APP_ID, TARGET, overturned),
+                    );
+                    assert!(System::events().iter().any(|record| record.event == resolved_event));
+                });
+            }
+        )*
+    }
+}
+
+resolve_appeal_test! {
+    // The arbitrated score is confirmed and the bond forfeited.
+    resolve_appeal_confirm: (18, false),
+    // The arbitrated score is replaced and the bond returned.
+    resolve_appeal_overturn: (30, true),
+}
+
+#[test]
+fn upheld_ruling_should_be_overturned_by_appeal() {
+    new_test_ext().execute_with(|| {
+        init_challenge(100, 100, ChallengeStatus::Reply);
+        // The score of the pathfinder is upheld and the ruling goes against the challenger.
+        arbitral_with_score(DEFAULT_METADATA.score);
+        let arbitrated_event = crate::mock::Event::zd_challenges(crate::Event::Arbitrated(
+            APP_ID,
+            CHALLENGER,
+            TARGET,
+            true,
+            DEFAULT_METADATA.score,
+        ));
+        assert!(System::events().iter().any(|record| record.event == arbitrated_event));
+        assert_eq!(ZdChallenges::get_ruling(&APP_ID, &TARGET).unwrap().loser, CHALLENGER);
+
+        assert_ok!(ZdChallenges::appeal(&APP_ID, &CHALLENGER, &TARGET));
+        System::set_block_number(System::block_number() + AppealVotingPeriod::get() + 1);
+        assert_eq!(
+            ZdChallenges::resolve_appeal(
+                &APP_ID,
+                &TARGET,
+                |_, _, _| -> Result<(bool, bool, i64), DispatchError> { Ok((false, false, 18)) }
+            ),
+            Ok(true)
+        );
+        assert_eq!(ZdChallenges::get_metadata(&APP_ID, &TARGET).score, 18);
+    });
+}
+
+#[test]
+fn cancel_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ZdChallenges::launch(
+            &APP_ID,
+            &TARGET,
+            &Metadata {
+                progress: Progress {
+                    total: 100,
+                    done: 0,
+                },
+                challenger: CHALLENGER,
+                pathfinder: PATHINFER,
+                ..Metadata::default()
+            },
+            (0, 0),
+            None
+        ));
+        assert_noop!(
+            ZdChallenges::cancel(&APP_ID, &PATHINFER, &TARGET),
+            Error::<Test>::NoPermission
+        );
+        let challenger_balance = ZdToken::free_balance(&CHALLENGER);
+        let pathfinder_balance = ZdToken::free_balance(&PATHINFER);
+
+        assert_ok!(ZdChallenges::cancel(&APP_ID, &CHALLENGER, &TARGET));
+        let fee = CancellationFee::get();
+        let refund = ZdChallenges::challenge_staking_amount(&APP_ID) - fee;
+        assert_eq!(ZdToken::free_balance(&CHALLENGER), challenger_balance + refund);
+        assert_eq!(ZdToken::free_balance(&PATHINFER), pathfinder_balance + fee);
+        assert!(!<Metadatas<Test>>::contains_key(&APP_ID, &TARGET));
+        let cancelled_event = crate::mock::Event::zd_challenges(
+            crate::Event::Cancelled(APP_ID, CHALLENGER, TARGET, refund),
+        );
+        assert!(System::events().iter().any(|record| record.event == cancelled_event));
+    });
+}
+
+#[test]
+fn cancel_should_fail_after_reply() {
+    new_test_ext().execute_with(|| {
+        init_challenge(100, 100, ChallengeStatus::Reply);
+        assert_noop!(
+            ZdChallenges::cancel(&APP_ID, &CHALLENGER, &TARGET),
+            Error::<Test>::StatusErr
+        );
+    });
+}
+
+#[test]
+fn challenge_error_should_classify() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            ZdChallenges::cancel(&APP_ID, &CHALLENGER, &TARGET),
+            Err(ChallengeError::NonExistent)
+        );
+        init_challenge(100, 100, ChallengeStatus::Reply);
+        assert_eq!(
+            ZdChallenges::cancel(&APP_ID, &DAVE, &TARGET),
+            Err(ChallengeError::NotYourTurn)
+        );
+        assert_eq!(
+            ZdChallenges::cancel(&APP_ID, &CHALLENGER, &TARGET),
+            Err(ChallengeError::WrongPhase)
+        );
+        assert_eq!(
+            ZdChallenges::resolve_appeal(
+                &APP_ID,
+                &TARGET,
+                |score, _, _| -> Result<(bool, bool, i64), DispatchError> {
+                    Ok((false, false, score))
+                },
+            ),
+            Err(ChallengeError::Other(Error::<Test>::NoAppeal.into()))
+        );
+    });
+}
+
+fn launch_backed(earnings: Balance, backing: Balance) {
+    // init staking pool
+    assert_ok!(ZdChallenges::staking(&APP_ID, &SWEEPER, 10000000));
+    assert_ok!(ZdChallenges::launch(
+        &APP_ID,
+        &TARGET,
+        &Metadata {
+            pool: Pool {
+                staking: 0,
+                earnings,
+            },
+            progress: Progress {
+                total: 100,
+                done: 0,
+            },
+            challenger: CHALLENGER,
+            pathfinder: PATHINFER,
+            ..Metadata::default()
+        },
+        (0, 0),
+        None
+    ));
+    assert_ok!(ZdChallenges::back(&APP_ID, &FERDIE, &TARGET, backing));
+}
+
+#[test]
+fn back_should_work() {
+    new_test_ext().execute_with(|| {
+        let free_balance = ZdToken::free_balance(&FERDIE);
+        launch_backed(0, 300);
+        assert_ok!(ZdChallenges::back(&APP_ID, &FERDIE, &TARGET, 200));
+        assert_eq!(ZdChallenges::get_backers(&APP_ID, &TARGET), vec![(FERDIE, 500)]);
+        assert_eq!(ZdToken::free_balance(&FERDIE), free_balance - 500);
+        assert_eq!(
+            ZdChallenges::get_metadata(&APP_ID, &TARGET).pool.staking,
+            ZdChallenges::challenge_staking_amount(&APP_ID) + 500
+        );
+        let backed_event = crate::mock::Event::zd_challenges(
+            crate::Event::Backed(APP_ID, FERDIE, TARGET, 200),
+        );
+        assert!(System::events().iter().any(|record| record.event == backed_event));
+
+        assert_noop!(
+            ZdChallenges::back(&APP_ID, &SWEEPER, &TARGET, 0),
+            Error::<Test>::ZeroAmount
+        );
+        assert_noop!(
+            ZdChallenges::back(&APP_ID, &CHALLENGER, &TARGET, 100),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            ZdChallenges::back(&APP_ID, &PATHINFER, &TARGET, 100),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(ZdChallenges::back(&APP_ID, &SWEEPER, &TARGET, 100));
+        assert_noop!(
+            ZdChallenges::back(&APP_ID, &DAVE, &TARGET, 100),
+            Error::<Test>::TooManyBackers
+        );
+    });
+}
+
+#[test]
+fn harvest_should_share_with_backers() {
+    new_test_ext().execute_with(|| {
+        launch_backed(400, 300);
+        System::set_block_number(ChallengeTimeout::get() + 2);
+        let challenger_balance = ZdToken::free_balance(&CHALLENGER);
+        let backer_balance = ZdToken::free_balance(&FERDIE);
+
+        // The pathfinder never replied, the challenger side takes the pool of 800 of which
+        // the backer staked 300 and the challenger 100.
+        assert_ok!(ZdChallenges::harvest(&CHALLENGER, &APP_ID, &TARGET));
+        assert_eq!(ZdToken::free_balance(&FERDIE), backer_balance + 600);
+        assert_eq!(ZdToken::free_balance(&CHALLENGER), challenger_balance + 200);
+        assert!(ZdChallenges::get_backers(&APP_ID, &TARGET).is_empty());
+    });
+}
+
+#[test]
+fn harvest_should_slash_backers() {
+    new_test_ext().execute_with(|| {
+        launch_backed(400, 300);
+        <Metadatas<Test>>::mutate(&APP_ID, &TARGET, |c| {
+            c.status = ChallengeStatus::Reply;
+            c.progress.done = 100;
+        });
+        System::set_block_number(ChallengeTimeout::get() + 2);
+        let pathfinder_balance = ZdToken::free_balance(&PATHINFER);
+        let backer_balance = ZdToken::free_balance(&FERDIE);
+
+        assert_ok!(ZdChallenges::harvest(&PATHINFER, &APP_ID, &TARGET));
+        assert_eq!(ZdToken::free_balance(&PATHINFER), pathfinder_balance + 800);
+        assert_eq!(ZdToken::free_balance(&FERDIE), backer_balance);
+    });
+}
+
+#[test]
+fn reward_curve_should_work() {
+    new_test_ext().execute_with(|| {
+        let curve = RewardCurve {
+            challenger_share: Perbill::from_percent(75),
+            arbiter_cut: Perbill::from_percent(20),
+        };
+        assert_noop!(
+            ZdChallenges::set_reward_curve(Origin::signed(ALICE), APP_ID, Some(curve)),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(ZdChallenges::set_reward_curve(Origin::root(), APP_ID, Some(curve)));
+        assert_eq!(ZdChallenges::reward_curve(APP_ID), Some(curve));
+
+        launch_backed(400, 300);
+        <Metadatas<Test>>::mutate(&APP_ID, &TARGET, |c| {
+            c.status = ChallengeStatus::Arbitral;
+            c.joint_benefits = true;
+        });
+        assert_eq!(
+            ZdChallenges::outcome(&APP_ID, &TARGET),
+            Some((CHALLENGER, Perbill::from_percent(80)))
+        );
+
+        <Metadatas<Test>>::mutate(&APP_ID, &TARGET, |c| c.status = ChallengeStatus::Examine);
+        System::set_block_number(ChallengeTimeout::get() + 2);
+        let challenger_balance = ZdToken::free_balance(&CHALLENGER);
+        let backer_balance = ZdToken::free_balance(&FERDIE);
+        let treasury_balance = ZdToken::free_balance(&TreasuryAccountId::get());
+
+        // The challenger side is paid 600 of the pool of 800, the treasury keeps the rest.
+        assert_ok!(ZdChallenges::harvest(&CHALLENGER, &APP_ID, &TARGET));
+        assert_eq!(ZdToken::free_balance(&FERDIE), backer_balance + 450);
+        assert_eq!(ZdToken::free_balance(&CHALLENGER), challenger_balance + 150);
+        assert_eq!(
+            ZdToken::free_balance(&TreasuryAccountId::get()),
+            treasury_balance + 200
+        );
+        let paid_event =
+            crate::mock::Event::zd_challenges(crate::Event::TreasuryPaid(APP_ID, TARGET, 200));
+        assert!(System::events().iter().any(|record| record.event == paid_event));
+
+        assert_ok!(ZdChallenges::set_reward_curve(Origin::root(), APP_ID, None));
+        assert_eq!(ZdChallenges::reward_curve(APP_ID), None);
+        assert_eq!(ZdChallenges::get_reward_curve(&APP_ID), RewardCurve::default());
+    });
+}
+
+#[test]
+fn pause_app_should_work() {
+    new_test_ext().execute_with(|| {
+        init_challenge(100, 0, ChallengeStatus::Examine);
+        assert_noop!(
+            ZdChallenges::pause_app(Origin::signed(ALICE), APP_ID),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            ZdChallenges::unpause_app(Origin::root(), APP_ID),
+            Error::<Test>::NotPaused
+        );
+        System::set_block_number(5);
+        assert_ok!(ZdChallenges::pause_app(Origin::root(), APP_ID));
+        assert_noop!(
+            ZdChallenges::pause_app(Origin::root(), APP_ID),
+            Error::<Test>::AppPaused
+        );
+        assert_noop!(
+            ZdChallenges::launch(
+                &APP_ID,
+                &FERDIE,
+                &Metadata {
+                    challenger: CHALLENGER,
+                    pathfinder: PATHINFER,
+                    ..Metadata::default()
+                },
+                (0, 0),
+                None
+            ),
+            Error::<Test>::AppPaused
+        );
+        assert!(!ZdChallenges::is_challengeable(&APP_ID, &FERDIE, &5));
+
+        // The clock stood still at 5 while the app was paused.
+        System::set_block_number(ChallengeTimeout::get() + 5);
+        assert!(!ZdChallenges::is_challenge_timeout(&APP_ID, &1));
+        assert_ok!(ZdChallenges::unpause_app(Origin::root(), APP_ID));
+        assert_eq!(ZdChallenges::paused_for(APP_ID), ChallengeTimeout::get());
+        let unpaused_event = crate::mock::Event::zd_challenges(crate::Event::AppUnpaused(
+            APP_ID,
+            ChallengeTimeout::get(),
+        ));
+        assert!(System::events().iter().any(|record| record.event == unpaused_event));
+
+        System::set_block_number(2 * ChallengeTimeout::get() + 1);
+        assert!(!ZdChallenges::is_challenge_timeout(&APP_ID, &1));
+        System::set_block_number(2 * ChallengeTimeout::get() + 2);
+        assert!(ZdChallenges::is_challenge_timeout(&APP_ID, &1));
+        assert_eq!(ZdChallenges::app_now(&APP_ID), ChallengeTimeout::get() + 2);
+    });
+}
+
+#[test]
+fn cancel_should_refund_backers() {
+    new_test_ext().execute_with(|| {
+        let backer_balance = ZdToken::free_balance(&FERDIE);
+        launch_backed(0, 300);
+        assert_ok!(ZdChallenges::cancel(&APP_ID, &CHALLENGER, &TARGET));
+        assert_eq!(ZdToken::free_balance(&FERDIE), backer_balance);
+        assert!(ZdChallenges::get_backers(&APP_ID, &TARGET).is_empty());
+    });
+}
+
+#[test]
+fn challenges_by_challenger_should_work() {
+    new_test_ext().execute_with(|| {
+        for target in [TARGET, DAVE].iter() {
+            assert_ok!(ZdChallenges::launch(
+                &APP_ID,
+                target,
+                &Metadata {
+                    challenger: CHALLENGER,
+                    pathfinder: PATHINFER,
+                    ..Metadata::default()
+                },
+                (0, 0),
+                None
+            ));
+        }
+        let (all, next_key) = ZdChallenges::challenges_of(&CHALLENGER, 10, None);
+        assert_eq!(all.len(), 2);
+        assert!(all.contains(&(APP_ID, TARGET)) && all.contains(&(APP_ID, DAVE)));
+        assert_eq!(next_key, None);
+
+        let (first, next_key) = ZdChallenges::challenges_of(&CHALLENGER, 1, None);
+        assert_eq!(first.len(), 1);
+        assert!(next_key.is_some());
+        let (second, next_key) = ZdChallenges::challenges_of(&CHALLENGER, 1, next_key);
+        assert_eq!(second.len(), 1);
+        assert_ne!(first, second);
+        assert_eq!(next_key, None);
+        assert!(ZdChallenges::challenges_of(&PATHINFER, 10, None).0.is_empty());
+
+        assert_ok!(ZdChallenges::cancel(&APP_ID, &CHALLENGER, &TARGET));
+        assert_eq!(
+            ZdChallenges::challenges_of(&CHALLENGER, 10, None).0,
+            vec![(APP_ID, DAVE)]
+        );
+    });
+}
+
+#[test]
+fn min_dispute_delta_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ZdChallenges::set_app_config(
+            Origin::root(),
+            APP_ID,
+            Some(AppConfig {
+                staking_amount: ChallengeStakingAmount::get(),
+                timeout: ChallengeTimeout::get(),
+                max_next_steps: MaxNextSteps::get(),
+                min_dispute_delta: 5,
+                response_window: 0,
+                max_response_extension: 0,
+            })
+        ));
+        let metadata = Metadata {
+            challenger: CHALLENGER,
+            pathfinder: PATHINFER,
+            ..Metadata::default()
+        };
+        assert_noop!(
+            ZdChallenges::launch(&APP_ID, &TARGET, &metadata, (14, 10), None),
+            Error::<Test>::DisputeDeltaTooSmall
+        );
+        assert_noop!(
+            ZdChallenges::launch(&APP_ID, &TARGET, &metadata, (10, 14), None),
+            Error::<Test>::DisputeDeltaTooSmall
+        );
+        assert_ok!(ZdChallenges::launch(&APP_ID, &TARGET, &metadata, (5, 10), None));
+    });
+}
+
+#[test]
+fn signed_score_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ZdChallenges::set_app_config(
+            Origin::root(),
+            APP_ID,
+            Some(AppConfig {
+                staking_amount: ChallengeStakingAmount::get(),
+                timeout: ChallengeTimeout::get(),
+                max_next_steps: MaxNextSteps::get(),
+                min_dispute_delta: 5,
+                response_window: 0,
+                max_response_extension: 0,
+            })
+        ));
+        let metadata = Metadata {
+            challenger: CHALLENGER,
+            pathfinder: PATHINFER,
+            score: -3,
+            ..Metadata::default()
+        };
+        assert_noop!(
+            ZdChallenges::launch(&APP_ID, &TARGET, &metadata, (-3, 1), None),
+            Error::<Test>::DisputeDeltaTooSmall
+        );
+        assert_ok!(ZdChallenges::launch(&APP_ID, &TARGET, &metadata, (-3, 2), None));
+        assert_eq!(ZdChallenges::get_metadata(&APP_ID, &TARGET).score, -3);
+    });
+}
+
+#[test]
+fn reply_should_be_bounded() {
+    new_test_ext().execute_with(|| {
+        init_challenge(100, 100, ChallengeStatus::Examine);
+        let up = |_: bool, _: u32, score: i64, _: &u32| -> Result<i64, DispatchError> { Ok(score) };
+        assert_noop!(
+            ZdChallenges::reply(
+                &APP_ID,
+                &PATHINFER,
+                &TARGET,
+                MaxReplyCount::get() + 1,
+                1,
+                up
+            ),
+            Error::<Test>::TooMany
+        );
+        assert_noop!(
+            ZdChallenges::reply(
+                &APP_ID,
+                &PATHINFER,
+                &TARGET,
+                MaxReplyCount::get(),
+                MaxLegsPerReply::get() + 1,
+                up
+            ),
+            Error::<Test>::TooMany
+        );
+        assert_ok!(ZdChallenges::reply(
+            &APP_ID,
+            &PATHINFER,
+            &TARGET,
+            MaxReplyCount::get(),
+            MaxLegsPerReply::get(),
+            up
+        ));
+        assert_eq!(
+            ZdChallenges::get_metadata(&APP_ID, &TARGET).progress.done,
+            MaxLegsPerReply::get()
+        );
+    });
+}
+
+#[test]
+fn payout_delay_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ZdChallenges::set_payout_delay(Origin::signed(ALICE), APP_ID, Some(5)),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(ZdChallenges::set_payout_delay(Origin::root(), APP_ID, Some(5)));
+        assert_eq!(ZdChallenges::payout_delay(APP_ID), Some(5));
+
+        launch_backed(400, 300);
+        System::set_block_number(ChallengeTimeout::get() + 2);
+        let challenger_balance = ZdToken::free_balance(&CHALLENGER);
+        let backer_balance = ZdToken::free_balance(&FERDIE);
+        let treasury_balance = ZdToken::free_balance(&TreasuryAccountId::get());
+        assert_ok!(ZdChallenges::harvest(&CHALLENGER, &APP_ID, &TARGET));
+        assert_eq!(ZdToken::free_balance(&CHALLENGER), challenger_balance);
+        assert_eq!(ZdToken::free_balance(&FERDIE), backer_balance);
+
+        let unlock_at = ChallengeTimeout::get() + 7;
+        assert_eq!(ZdChallenges::payout_count(APP_ID), 2);
+        assert_noop!(
+            ZdChallenges::set_deposit_currency(Origin::root(), APP_ID, Some(STABLE)),
+            Error::<Test>::FundsOutstanding
+        );
+        let payout = ZdChallenges::get_payout((APP_ID, TARGET), CHALLENGER).unwrap();
+        assert_eq!(payout.unlock_at, unlock_at);
+        let escrowed_event = crate::mock::Event::zd_challenges(crate::Event::PayoutEscrowed(
+            APP_ID,
+            CHALLENGER,
+            TARGET,
+            payout.amount,
+            unlock_at,
+        ));
+        assert!(System::events().iter().any(|record| record.event == escrowed_event));
+        assert_noop!(
+            ZdChallenges::claim_payout(Origin::signed(CHALLENGER), APP_ID, TARGET),
+            Error::<Test>::TooSoon
+        );
+        assert_noop!(
+            ZdChallenges::claim_payout(Origin::signed(DAVE), APP_ID, TARGET),
+            Error::<Test>::NoPayout
+        );
+
+        let backer_payout = ZdChallenges::get_payout((APP_ID, TARGET), FERDIE).unwrap();
+        assert_noop!(
+            ZdChallenges::dispute_payout(Origin::signed(ALICE), APP_ID, TARGET, FERDIE),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(ZdChallenges::dispute_payout(Origin::root(), APP_ID, TARGET, FERDIE));
+        assert_eq!(
+            ZdToken::free_balance(&TreasuryAccountId::get()),
+            treasury_balance + backer_payout.amount
+        );
+        assert_eq!(ZdChallenges::get_payout((APP_ID, TARGET), FERDIE), None);
+        assert_eq!(ZdChallenges::payout_count(APP_ID), 1);
+
+        System::set_block_number(unlock_at);
+        assert_ok!(ZdChallenges::claim_payout(Origin::signed(CHALLENGER), APP_ID, TARGET));
+        assert_eq!(
+            ZdToken::free_balance(&CHALLENGER),
+            challenger_balance + payout.amount
+        );
+        assert_eq!(ZdChallenges::get_payout((APP_ID, TARGET), CHALLENGER), None);
+        assert_eq!(ZdToken::free_balance(&FERDIE), backer_balance);
+        assert_eq!(ZdChallenges::payout_count(APP_ID), 0);
+        assert_ok!(ZdChallenges::set_deposit_currency(Origin::root(), APP_ID, Some(STABLE)));
+    });
+}
+
+#[test]
+fn preview_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(2);
+        let preview = ZdChallenges::preview(&APP_ID);
+        assert_eq!(preview.deposit, ChallengeStakingAmount::get());
+        assert_eq!(preview.deadline, 2 + ChallengeTimeout::get());
+        assert_eq!(preview.score, 0);
+        assert!(!preview.in_confirmation);
+
+        let config = AppConfig {
+            staking_amount: 50,
+            timeout: 3,
+            max_next_steps: 2,
+            min_dispute_delta: 0,
+            response_window: 0,
+            max_response_extension: 0,
+        };
+        assert_ok!(ZdChallenges::set_app_config(Origin::root(), APP_ID, Some(config)));
+        let preview = ZdChallenges::preview(&APP_ID);
+        assert_eq!(preview.deposit, 50);
+        assert_eq!(preview.deadline, 5);
+    });
+}
+
+#[test]
+fn deposit_currency_should_work() {
+    use orml_traits::MultiCurrency;
+
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ZdChallenges::set_deposit_currency(Origin::signed(ALICE), APP_ID, Some(STABLE)),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(ZdChallenges::set_deposit_currency(Origin::root(), APP_ID, Some(STABLE)));
+        assert_eq!(ZdChallenges::deposit_currency(APP_ID), Some(STABLE));
+
+        let challenger_balance = ZdToken::free_balance(&CHALLENGER);
+        let backer_balance = ZdToken::free_balance(&FERDIE);
+        let challenger_stable = Tokens::free_balance(STABLE, &CHALLENGER);
+        let backer_stable = Tokens::free_balance(STABLE, &FERDIE);
+        launch_backed(400, 300);
+        assert_eq!(ZdToken::free_balance(&CHALLENGER), challenger_balance);
+        assert_eq!(ZdToken::free_balance(&FERDIE), backer_balance);
+        assert_eq!(
+            Tokens::free_balance(STABLE, &CHALLENGER),
+            challenger_stable - ChallengeStakingAmount::get()
+        );
+        assert_eq!(Tokens::free_balance(STABLE, &FERDIE), backer_stable - 300);
+        assert_noop!(
+            ZdChallenges::set_deposit_currency(Origin::root(), APP_ID, None),
+            Error::<Test>::FundsOutstanding
+        );
+
+        System::set_block_number(ChallengeTimeout::get() + 2);
+        assert_ok!(ZdChallenges::harvest(&CHALLENGER, &APP_ID, &TARGET));
+        assert_eq!(ZdToken::free_balance(&CHALLENGER), challenger_balance);
+        assert_eq!(ZdToken::free_balance(&FERDIE), backer_balance);
+        assert!(Tokens::free_balance(STABLE, &CHALLENGER) > challenger_stable);
+
+        assert_ok!(ZdChallenges::set_deposit_currency(Origin::root(), APP_ID, None));
+        assert_eq!(ZdChallenges::deposit_currency(APP_ID), None);
+    });
+}
+
+#[test]
+fn vote_appeal_should_work() {
+    new_test_ext().execute_with(|| {
+        init_challenge(100, 100, ChallengeStatus::Reply);
+        arbitral_with_score(18);
+        let up = |_: i64, _: u32, _: &u32| -> Result<(bool, bool, i64), DispatchError> {
+            Ok((false, false, 30))
+        };
+        assert_noop!(
+            ZdChallenges::vote_appeal(&APP_ID, &ARBITERS[0], &TARGET, 30, up),
+            Error::<Test>::NoAppeal
+        );
+        assert_ok!(ZdChallenges::appeal(&APP_ID, &PATHINFER, &TARGET));
+        assert_noop!(
+            ZdChallenges::vote_appeal(&APP_ID, &ALICE, &TARGET, 30, up),
+            Error::<Test>::NotArbiter
+        );
+
+        let stake = ArbiterStakingAmount::get();
+        let balance = ZdToken::free_balance(&ARBITERS[0]);
+        let treasury_balance = ZdToken::free_balance(&TreasuryAccountId::get());
+        assert_eq!(
+            ZdChallenges::vote_appeal(&APP_ID, &ARBITERS[0], &TARGET, 30, up),
+            Ok(None)
+        );
+        assert_eq!(ZdToken::free_balance(&ARBITERS[0]), balance - stake);
+        assert_eq!(
+            ZdChallenges::get_appeal_vote((APP_ID, TARGET), ARBITERS[0]),
+            Some((30, stake))
+        );
+        assert_noop!(
+            ZdChallenges::vote_appeal(&APP_ID, &ARBITERS[0], &TARGET, 30, up),
+            Error::<Test>::AlreadyVoted
+        );
+        assert_eq!(
+            ZdChallenges::vote_appeal(&APP_ID, &ARBITERS[1], &TARGET, 18, up),
+            Ok(None)
+        );
+
+        // The second vote for 30 overturns the ruling and slashes the arbiter who voted 18.
+        assert_eq!(
+            ZdChallenges::vote_appeal(&APP_ID, &ARBITERS[2], &TARGET, 30, up),
+            Ok(Some(true))
+        );
+        assert_eq!(ZdChallenges::get_appeal(&APP_ID, &TARGET), None);
+        assert_eq!(ZdChallenges::get_metadata(&APP_ID, &TARGET).score, 30);
+        assert_eq!(ZdToken::free_balance(&ARBITERS[0]), balance);
+        assert_eq!(ZdToken::free_balance(&ARBITERS[1]), balance - stake);
+        assert_eq!(ZdToken::free_balance(&ARBITERS[2]), balance);
+        assert_eq!(
+            ZdToken::free_balance(&TreasuryAccountId::get()),
+            treasury_balance + stake
+        );
+        let slashed_event = crate::mock::Event::zd_challenges(crate::Event::ArbiterSlashed(
+            APP_ID,
+            ARBITERS[1],
+            TARGET,
+            stake,
+        ));
+        assert!(System::events().iter().any(|record| record.event == slashed_event));
+        assert_eq!(ZdChallenges::get_appeal_vote((APP_ID, TARGET), ARBITERS[0]), None);
+    });
+}
+
+#[test]
+fn deadline_extension_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ZdChallenges::set_app_config(
+            Origin::root(),
+            APP_ID,
+            Some(AppConfig {
+                staking_amount: ChallengeStakingAmount::get(),
+                timeout: ChallengeTimeout::get(),
+                max_next_steps: MaxNextSteps::get(),
+                min_dispute_delta: 0,
+                response_window: 4,
+                max_response_extension: 6,
+            })
+        ));
+        init_challenge(100, 0, ChallengeStatus::Examine);
+        assert_ok!(ZdChallenges::reply(
+            &APP_ID,
+            &PATHINFER,
+            &TARGET,
+            100,
+            100,
+            |_, _, score, _| -> Result<i64, DispatchError> { Ok(score) }
+        ));
+        assert_eq!(ZdChallenges::get_extension(&APP_ID, &TARGET), 4);
+        let extended_event =
+            crate::mock::Event::zd_challenges(crate::Event::DeadlineExtended(APP_ID, TARGET, 4));
+        assert!(System::events().iter().any(|record| record.event == extended_event));
+
+        System::set_block_number(ChallengeTimeout::get() + 5);
+        assert!(ZdChallenges::is_challenge_timeout(&APP_ID, &1));
+        assert!(!ZdChallenges::is_past_deadline(&APP_ID, &TARGET, &1));
+        assert_ok!(ZdChallenges::evidence(
+            &APP_ID,
+            &CHALLENGER,
+            &TARGET,
+            None,
+            |_, _, _| -> Result<bool, DispatchError> { Ok(true) }
+        ));
+        // The extension is capped at `max_response_extension`.
+        assert_eq!(ZdChallenges::get_extension(&APP_ID, &TARGET), 6);
+        System::set_block_number(ChallengeTimeout::get() + 7);
+        assert!(!ZdChallenges::is_past_deadline(&APP_ID, &TARGET, &1));
+        System::set_block_number(ChallengeTimeout::get() + 8);
+        assert!(ZdChallenges::is_past_deadline(&APP_ID, &TARGET, &1));
+    });
+}
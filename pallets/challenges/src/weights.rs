@@ -0,0 +1,231 @@
+//! Weights for zd_challenges
+//!
+//! These are hand-estimated upper bounds, not benchmark results: there are no benchmarks
+//! for zd_challenges yet. The execution times are twice the estimate for each call, the
+//! reads and writes count every storage item a call may touch. Replace them with the
+//! output of the benchmark CLI once the benchmarks exist.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(clippy::unnecessary_cast)]
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for zd_challenges.
+pub trait WeightInfo {
+    fn launch() -> Weight;
+    fn next(a: u32, ) -> Weight;
+    fn examine() -> Weight;
+    fn reply(a: u32, ) -> Weight;
+    fn evidence() -> Weight;
+    fn arbitral() -> Weight;
+    fn harvest() -> Weight;
+    fn harvest_all(h: u32, ) -> Weight;
+    fn settle_expired() -> Weight;
+    fn prune_history(h: u32, ) -> Weight;
+    fn set_app_config() -> Weight;
+    fn set_reward_curve() -> Weight;
+    fn set_payout_delay() -> Weight;
+    fn set_deposit_currency() -> Weight;
+    fn claim_payout() -> Weight;
+    fn dispute_payout() -> Weight;
+    fn pause_app() -> Weight;
+    fn unpause_app() -> Weight;
+}
+
+/// Estimated weights for zd_challenges on the recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn launch() -> Weight {
+        (122_800_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
+    }
+    fn next(a: u32, ) -> Weight {
+        (57_800_000 as Weight)
+            .saturating_add((2_420_000 as Weight).saturating_mul(a as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn examine() -> Weight {
+        (49_400_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn reply(a: u32, ) -> Weight {
+        (62_400_000 as Weight)
+            .saturating_add((2_920_000 as Weight).saturating_mul(a as Weight))
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn evidence() -> Weight {
+        (99_600_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn arbitral() -> Weight {
+        (116_600_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn harvest() -> Weight {
+        (195_200_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes(11 as Weight))
+    }
+    fn harvest_all(h: u32, ) -> Weight {
+        (36_400_000 as Weight)
+            .saturating_add((208_600_000 as Weight).saturating_mul(h as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().reads((13 as Weight).saturating_mul(h as Weight)))
+            .saturating_add(T::DbWeight::get().writes((11 as Weight).saturating_mul(h as Weight)))
+    }
+    fn settle_expired() -> Weight {
+        (217_800_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(15 as Weight))
+            .saturating_add(T::DbWeight::get().writes(11 as Weight))
+    }
+    fn prune_history(h: u32, ) -> Weight {
+        (38_200_000 as Weight)
+            .saturating_add((1_220_000 as Weight).saturating_mul(h as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_app_config() -> Weight {
+        (27_200_000 as Weight)
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_reward_curve() -> Weight {
+        (26_400_000 as Weight)
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_payout_delay() -> Weight {
+        (25_800_000 as Weight)
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_deposit_currency() -> Weight {
+        (63_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn claim_payout() -> Weight {
+        (85_400_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn dispute_payout() -> Weight {
+        (76_800_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn pause_app() -> Weight {
+        (31_600_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn unpause_app() -> Weight {
+        (38_600_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn launch() -> Weight {
+        (122_800_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+    }
+    fn next(a: u32, ) -> Weight {
+        (57_800_000 as Weight)
+            .saturating_add((2_420_000 as Weight).saturating_mul(a as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn examine() -> Weight {
+        (49_400_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn reply(a: u32, ) -> Weight {
+        (62_400_000 as Weight)
+            .saturating_add((2_920_000 as Weight).saturating_mul(a as Weight))
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn evidence() -> Weight {
+        (99_600_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn arbitral() -> Weight {
+        (116_600_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn harvest() -> Weight {
+        (195_200_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(11 as Weight))
+    }
+    fn harvest_all(h: u32, ) -> Weight {
+        (36_400_000 as Weight)
+            .saturating_add((208_600_000 as Weight).saturating_mul(h as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((13 as Weight).saturating_mul(h as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((11 as Weight).saturating_mul(h as Weight)))
+    }
+    fn settle_expired() -> Weight {
+        (217_800_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(15 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(11 as Weight))
+    }
+    fn prune_history(h: u32, ) -> Weight {
+        (38_200_000 as Weight)
+            .saturating_add((1_220_000 as Weight).saturating_mul(h as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_app_config() -> Weight {
+        (27_200_000 as Weight)
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_reward_curve() -> Weight {
+        (26_400_000 as Weight)
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_payout_delay() -> Weight {
+        (25_800_000 as Weight)
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_deposit_currency() -> Weight {
+        (63_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn claim_payout() -> Weight {
+        (85_400_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn dispute_payout() -> Weight {
+        (76_800_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn pause_app() -> Weight {
+        (31_600_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn unpause_app() -> Weight {
+        (38_600_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+}
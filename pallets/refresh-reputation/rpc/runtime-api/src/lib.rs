@@ -0,0 +1,53 @@
+// Copyright 2021 ZeroDAO
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for the Refresh Reputation module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    pub trait RefreshReputationApi<AccountId, Balance> where
+        AccountId: Codec,
+        Balance: Codec,
+    {
+        /// Whether the reputation of `target` refreshed by `pathfinder` can be challenged now,
+        /// including whether `ChallengeBase` has room for a new challenge against it.
+        fn is_challengeable(pathfinder: AccountId, target: AccountId) -> bool;
+
+        /// The social balances of `accounts`, at most `MaxUpdateCount` are returned.
+        fn social_balances(accounts: Vec<AccountId>) -> Vec<Balance>;
+
+        /// Whether the payroll of `pathfinder` in `round` matches its update records.
+        fn check_payroll_integrity(round: u32, pathfinder: AccountId) -> bool;
+
+        /// Whether a sweeper can harvest the earnings of `pathfinder` in `round` now.
+        fn can_sweeper_harvest(round: u32, pathfinder: AccountId) -> bool;
+
+        /// The pending payroll of `pathfinder` in `round` as `(count, total_fee)`, the number
+        /// of its outstanding update records and the update staking they lock.
+        fn pathfinder_exposure(round: u32, pathfinder: AccountId) -> (u32, Balance, u32, Balance);
+
+        /// A page of at most `limit` payrolls after `start_key` as `(round, pathfinder, count,
+        /// total_amount)`, and the key to pass to the next call if any remain.
+        fn all_pending_payrolls(
+            limit: u32,
+            start_key: Option<Vec<u8>>,
+        ) -> (Vec<(u32, AccountId, u32, Balance)>, Option<Vec<u8>>);
+    }
+}
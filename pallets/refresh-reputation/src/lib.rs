@@ -34,7 +34,24 @@
 //! - `start` - Turn on reputation refreshing.
 //! - `refresh` - Accepts an array of users and a tuple of reputation values, and refreshes the reputation values
 //! of all users within the array.
+//! - `refresh_in` - `refresh` in a reputation domain other than the default one.
+//! - `withdraw_refresh` - Withdraw an unchallenged refresh during its confirmation period.
+//! - `amend_refresh` - Amend the score of an unchallenged refresh during its confirmation period.
+//! - `refresh_unsigned` - Submit a pathfinder-signed refresh as an unsigned transaction.
+//! - `register_pathfinder` - Bond to become a pathfinder, only pathfinders can refresh.
+//! - `deregister_pathfinder` - Stop refreshing and start unbonding.
+//! - `withdraw_bond` - Withdraw the bond after the unbonding period.
+//! - `force_new_round` - Root ends a stuck round, carrying over or sweeping its payrolls.
+//! - `lift_suspension` - Root lifts the suspension of a pathfinder that lost too many challenges.
+//! - `set_parameters` - Governance sets the staking amount and the social currency ratios.
+//! - `approve_staking_currency` / `revoke_staking_currency` - Governance manages the currencies
+//! pathfinders may stake in besides `BaceToken`.
+//! - `set_staking_currency` - A pathfinder chooses the currency it stakes in.
+//! - `opt_out` / `opt_in` - Users stop or resume being refreshed by pathfinders.
+//! - `commit_refresh` / `reveal_refresh` - Refresh in two phases, so that batches cannot be copied.
+//! - `forfeit_commitment` - Forfeit the deposit of a commitment that was never revealed.
 //! - `harvest_ref_all` - Callers receive all their refresh proceeds.
+//! - `harvest_ref_partial` - Callers receive the proceeds of a bounded number of their records.
 //! - `harvest_ref_all_sweeper` - `sweeper` collects `pathfinder` timeout for unclaimed refresh proceeds.
 //! - `harvest_challenge` - Callers receive the proceeds of the challenge.
 //! - `challenge` - Challenge the reputation value of the incoming user.
@@ -47,16 +64,29 @@
 use frame_support::{
     codec::{Decode, Encode},
     ensure, pallet,
+    storage::{
+        migration::StorageIterator, with_transaction, StoragePrefixedMap, TransactionOutcome,
+    },
     traits::Get,
-    transactional, RuntimeDebug,
+    transactional,
+    weights::{DispatchClass, Weight},
+    ReversibleStorageHasher, RuntimeDebug, Twox64Concat,
+};
+use frame_system::{self as system, ensure_root, ensure_signed};
+use sp_runtime::{
+    traits::{Hash, IdentifyAccount, Saturating, Verify, Zero},
+    DispatchError, DispatchResult, Perbill, SaturatedConversion,
 };
-use frame_system::{self as system, ensure_signed};
-use sp_runtime::{traits::Zero, DispatchError, DispatchResult};
 use sp_std::vec::Vec;
 use zd_primitives::{
-    fee::SweeperFee, AppId, Balance, ChallengeStatus, Metadata, Pool, Progress, TIRStep,
+    fee::{SweeperCurve, SweeperFee},
+    storage::iter_double_map_from,
+    AppId, Balance, ChallengeStatus, DomainId, Metadata, Pool, Progress, TIRStep, DEFAULT_DOMAIN,
+};
+use zd_support::{
+    ChallengeBase, ChallengeError, ChallengeHarvested, MultiBaseToken, MultiCurrencyStaking,
+    PathfinderBase, Reputation, SeedsBase, TrustBase,
 };
-use zd_support::{ChallengeBase, MultiBaseToken, Reputation, SeedsBase, TrustBase};
 
 #[cfg(test)]
 mod mock;
@@ -68,13 +98,30 @@ pub use weights::WeightInfo;
 
 pub use pallet::*;
 
-const APP_ID: AppId = *b"repu    ";
+/// The `AppId` of the challenges launched by this pallet.
+pub const APP_ID: AppId = *b"repu    ";
+
+/// The `nonce` of a reputation update round.
+pub type RoundIndex = u32;
 
 /// Maximum number of active paths
 const MAX_NODE_COUNT: usize = 5;
 /// Maximum number of refreshes for the same address
 const MAX_REFRESH: u32 = 500;
 
+/// The largest share of the normal block length a `refresh` of `MaxUpdateCount` users may take.
+const MAX_UPDATE_LENGTH_RATIO: Perbill = Perbill::from_percent(10);
+
+/// Maximum number of matured payrolls of previous rounds settled by one call of `start`.
+pub const MAX_START_SETTLEMENTS: u32 = 100;
+
+/// Maximum number of payrolls returned by one call of `all_pending_payrolls`.
+const MAX_PAYROLLS_PAGE: u32 = 1_000;
+
+/// Maximum number of `Payrolls` checked by `do_try_state`.
+#[cfg(any(feature = "try-runtime", test))]
+const TRY_STATE_MAX_PAYROLLS: usize = 1_000;
+
 /// Update records of the target user's reputation value.
 #[derive(Encode, Decode, Clone, Default, RuntimeDebug)]
 pub struct Record<BlockNumber, Balance> {
@@ -83,6 +130,27 @@ pub struct Record<BlockNumber, Balance> {
 
     /// The handling fee obtained for this refresh.
     pub fee: Balance,
+
+    /// The domain the reputation was refreshed in.
+    pub domain: DomainId,
+}
+
+/// The key of the `Records` of `pathfinder` in `round`, the second key of `Records` is the
+/// refreshed target.
+///
+/// Encoded as the tuple `(round, pathfinder)`, the first key of `Records` since
+/// `Releases::V2`. Before it, `Records` were keyed by the pathfinder alone, `on_runtime_upgrade`
+/// moves them under the current round.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct RecordKey<AccountId> {
+    pub round: RoundIndex,
+    pub pathfinder: AccountId,
+}
+
+impl<AccountId> RecordKey<AccountId> {
+    pub fn new(round: RoundIndex, pathfinder: AccountId) -> Self {
+        RecordKey { round, pathfinder }
+    }
 }
 
 /// The earnings record for `pathfinder`.
@@ -98,15 +166,128 @@ pub struct Payroll<Balance, BlockNumber> {
     pub update_at: BlockNumber,
 }
 
-/// Returns the total amount due to `pathfinder`, including the amount of the staking and the earnings.
+/// Returns the total amount of `BaceToken` due to `pathfinder`, including the amount of the
+/// staking and the earnings. A staking in another currency is not included.
 impl<BlockNumber> Payroll<Balance, BlockNumber> {
-    fn total_amount<T: Config>(&self) -> Balance {
-        T::UpdateStakingAmount::get()
-            .saturating_mul(self.count.into())
-            .saturating_add(self.total_fee)
+    fn total_amount<T: Config>(&self, round: RoundIndex, pathfinder: &T::AccountId) -> Balance {
+        let staking = match Pallet::<T>::payroll_currency(round, pathfinder) {
+            Some(_) => Zero::zero(),
+            None => Pallet::<T>::staking_amount(round).saturating_mul(self.count.into()),
+        };
+        staking.saturating_add(self.total_fee)
+    }
+}
+
+/// The layouts of the storage of this pallet, see `StorageVersion`.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+pub enum Releases {
+    /// `Payrolls` keyed by pathfinder, `Records` by pathfinder and target.
+    V1,
+    /// `Payrolls` keyed by round and pathfinder, `Records` by `RecordKey` and target.
+    V2,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V1
     }
 }
 
+/// Why an entry of `refresh_best_effort` was skipped.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum SkipReason {
+    /// The user has already been refreshed in this round, or refreshed by the caller in
+    /// another domain.
+    AlreadyRefreshed,
+
+    /// The user appears earlier in the same batch.
+    Duplicate,
+
+    /// The caller's payroll has reached `MAX_REFRESH` or `MaxPayrollRecords`.
+    ExceedMaxRefresh,
+
+    /// The user has opted out of being refreshed.
+    OptedOut,
+}
+
+/// How a refresh of a user already refreshed in the round by another pathfinder is treated,
+/// see `Config::ConflictPolicy`.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum RefreshConflict {
+    /// The refresh is rejected.
+    Reject,
+
+    /// The refresh of `DEFAULT_DOMAIN` supersedes the other one while it is in its
+    /// confirmation period, if the bond of the caller is higher, the staking of the
+    /// superseded pathfinder is refunded. `refresh_best_effort` still skips the user.
+    Override,
+}
+
+/// A registered pathfinder.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct PathfinderInfo<Balance, BlockNumber, Hash> {
+    /// The amount bonded, which can be slashed.
+    pub bond: Balance,
+
+    /// Hash of the pathfinder's off-chain endpoint.
+    pub endpoint: Option<Hash>,
+
+    /// The block from which the bond can be withdrawn, set by `deregister_pathfinder`.
+    pub unbonding_at: Option<BlockNumber>,
+}
+
+/// The challenges lost by a pathfinder and its suspension from refreshing.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct PathfinderStatus {
+    /// The number of challenges lost since `window_start`.
+    pub losses: u32,
+
+    /// The round of the first loss counted in `losses`.
+    pub window_start: RoundIndex,
+
+    /// The pathfinder cannot refresh before this round.
+    pub suspended_until: RoundIndex,
+}
+
+/// The statistics of a round of refreshes.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct RoundStat<Balance, BlockNumber> {
+    /// The block the round was started at by `start`.
+    pub started_at: BlockNumber,
+
+    /// The number of users refreshed.
+    pub users: u32,
+
+    /// The number of distinct pathfinders that refreshed.
+    pub pathfinders: u32,
+
+    /// The total fees shared from the refreshed users.
+    pub total_fee: Balance,
+
+    /// The total update staking of the refreshes in `BaceToken`.
+    pub total_staked: Balance,
+
+    /// The number of challenges launched.
+    pub challenges: u32,
+}
+
+/// A batch of refreshes signed by `pathfinder`, which any account can submit on its behalf.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct RefreshPayload<AccountId> {
+    /// The account that is charged the staking and receives the payroll.
+    pub pathfinder: AccountId,
+
+    /// The users to refresh and their reputation values.
+    pub user_scores: Vec<(AccountId, u32)>,
+
+    /// The round the payload is valid for.
+    pub round: RoundIndex,
+
+    /// The `payload_nonce` of `pathfinder` in `round` the payload is valid for, each nonce
+    /// is used once.
+    pub nonce: u32,
+}
+
 /// The path to a trusting relationship from seed to user.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
 pub struct Path<AccountId> {
@@ -142,24 +323,117 @@ pub mod pallet {
         type Reputation: Reputation<Self::AccountId, Self::BlockNumber, TIRStep>;
         type TrustBase: TrustBase<Self::AccountId>;
         type SeedsBase: SeedsBase<Self::AccountId>;
-        type ChallengeBase: ChallengeBase<Self::AccountId, AppId, Balance, Self::BlockNumber>;
+        type ChallengeBase: ChallengeBase<
+            Self::AccountId,
+            AppId,
+            Balance,
+            Self::BlockNumber,
+            Score = u64,
+        >;
+        type CurrencyId: Parameter + Member + Copy + MaybeSerializeDeserialize + Ord;
+        type CurrencyStaking: MultiCurrencyStaking<Self::AccountId, Self::CurrencyId, Balance>;
 
         /// Maximum number of uploads.
+        ///
+        /// Each entry of `refresh` encodes to an `AccountId` and a `u32`, 36 bytes with 32 byte
+        /// accounts, `MaxUpdateCount` entries must fit in 10% of the normal block length.
         #[pallet::constant]
         type MaxUpdateCount: Get<u32>;
 
-        /// The amount to be staking.
+        /// Maximum number of unharvested refreshes in the payroll of a pathfinder, it must
+        /// harvest the payroll before refreshing more users in the round.
+        #[pallet::constant]
+        type MaxPayrollRecords: Get<u32>;
+
+        /// Maximum unharvested amount, staking and fees, in the payroll of a pathfinder.
+        #[pallet::constant]
+        type MaxPayrollAmount: Get<Balance>;
+
+        /// The amount to be staking, until `set_parameters` sets another one.
         #[pallet::constant]
         type UpdateStakingAmount: Get<Balance>;
 
-        /// After this number of blocks has been exceeded, the data will be validated.
+        /// Sweepers may settle a payroll this many blocks before `SweeperCurve` allows them,
+        /// without a fee, so that proxy calls delayed by network latency still succeed.
+        #[pallet::constant]
+        type ProxyGracePeriod: Get<Self::BlockNumber>;
+
+        /// Each previous consecutive round started by the same account, up to this number,
+        /// decays the sweeper fee it receives from `start` by `1 / (RewardDecayWindow + 1)`.
+        #[pallet::constant]
+        type RewardDecayWindow: Get<u32>;
+
+        /// The account receiving the decayed part of the `start` sweeper fee and the part of
+        /// slashed stakes not awarded to challengers.
+        type TreasuryAccountId: Get<Self::AccountId>;
+
+        /// The part of the staking of a pathfinder losing a challenge awarded to the
+        /// challenger, the rest goes to `TreasuryAccountId`.
+        #[pallet::constant]
+        type ChallengerSlashRatio: Get<Perbill>;
+
+        /// The signature of a pathfinder over a `RefreshPayload`.
+        type OffchainSignature: Verify<Signer = Self::OffchainPublic> + Parameter;
+
+        /// The public key verifying `OffchainSignature`, identifying the pathfinder account.
+        type OffchainPublic: IdentifyAccount<AccountId = Self::AccountId>;
+
+        /// The minimum bond to register as a pathfinder.
+        #[pallet::constant]
+        type PathfinderBond: Get<Balance>;
+
+        /// The number of blocks after `deregister_pathfinder` before the bond can be withdrawn.
+        #[pallet::constant]
+        type UnbondingPeriod: Get<Self::BlockNumber>;
+
+        /// A pathfinder losing this number of challenges within `LossWindow` rounds is
+        /// suspended.
         #[pallet::constant]
-        type ConfirmationPeriod: Get<Self::BlockNumber>;
+        type MaxChallengeLosses: Get<u32>;
 
-        /// After this period, no new refreshes can be added, this is to prevent malicious delays
-        /// leading to long update periods.
+        /// The number of rounds in which the challenge losses of a pathfinder are counted.
         #[pallet::constant]
-        type RefRepuTiomeOut: Get<Self::BlockNumber>;
+        type LossWindow: Get<RoundIndex>;
+
+        /// The number of rounds a suspended pathfinder cannot refresh.
+        #[pallet::constant]
+        type SuspensionRounds: Get<RoundIndex>;
+
+        /// The number of blocks after `start` in which `commit_refresh` is accepted, reveals
+        /// are only accepted after it.
+        #[pallet::constant]
+        type CommitPeriod: Get<Self::BlockNumber>;
+
+        /// The deposit of a `commit_refresh`, forfeited to `TreasuryAccountId` if it is never
+        /// revealed.
+        #[pallet::constant]
+        type CommitDeposit: Get<Balance>;
+
+        /// The maximum number of `Records` checked for pruning in each `on_initialize`.
+        #[pallet::constant]
+        type MaxRecordPrunes: Get<u32>;
+
+        /// The priority of unsigned `refresh_unsigned` transactions.
+        #[pallet::constant]
+        type UnsignedPriority: Get<TransactionPriority>;
+
+        /// The author of the current block, it starts the round in `on_initialize` as soon
+        /// as it can start and receives the fee of `start`, `None` leaves it to callers.
+        type BlockAuthor: Get<Option<Self::AccountId>>;
+
+        /// The share of overdue proceeds `harvest_ref_all_sweeper` awards to the sweeper.
+        type SweeperCurve: Get<SweeperCurve>;
+
+        /// Whether a refresh of a user already refreshed in the round by another pathfinder is
+        /// rejected or supersedes it.
+        #[pallet::constant]
+        type ConflictPolicy: Get<RefreshConflict>;
+
+        /// The origin allowed to `set_parameters`.
+        type ParametersOrigin: EnsureOrigin<Self::Origin>;
+
+        /// The origin allowed to `resolve_appeal` once the arbiters failed to, such as a council.
+        type AppealOrigin: EnsureOrigin<Self::Origin>;
 
         /// The weight information of this pallet.
         type WeightInfo: WeightInfo;
@@ -169,30 +443,55 @@ pub mod pallet {
     #[pallet::generate_store(pub(super) trait Store)]
     pub struct Pallet<T>(_);
 
+    /// The layout of the storage, set at genesis and moved on by `on_runtime_upgrade`.
+    #[pallet::storage]
+    #[pallet::getter(fn storage_version)]
+    pub type StorageVersion<T: Config> = StorageValue<_, Releases, ValueQuery>;
+
     /// Start time for this round of refreshments.
     #[pallet::storage]
     #[pallet::getter(fn started_at)]
     pub type StartedAt<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
 
-    /// The bill payable for `AccountId`.
+    /// The bill payable for `AccountId` for its refreshes in round `RoundIndex`.
     #[pallet::storage]
     #[pallet::getter(fn get_payroll)]
-    pub type Payrolls<T: Config> =
-        StorageMap<_, Twox64Concat, T::AccountId, Payroll<Balance, T::BlockNumber>, ValueQuery>;
+    pub type Payrolls<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        RoundIndex,
+        Twox64Concat,
+        T::AccountId,
+        Payroll<Balance, T::BlockNumber>,
+        ValueQuery,
+    >;
 
-    /// `pathfinder` updated records for the `target` user.
+    /// `RecordKey { round, pathfinder }` updated records for the `target` user.
+    ///
+    /// Records are removed together with the payroll of `(round, pathfinder)` when it is
+    /// harvested or settled by `start`, those left without a payroll once past the
+    /// confirmation period are pruned in `on_initialize`.
     #[pallet::storage]
     #[pallet::getter(fn update_record)]
     pub type Records<T: Config> = StorageDoubleMap<
         _,
         Twox64Concat,
-        T::AccountId,
+        RecordKey<T::AccountId>,
         Twox64Concat,
         T::AccountId,
         Record<T::BlockNumber, Balance>,
         ValueQuery,
     >;
 
+    /// The round and pathfinder of the last refresh of `target` in `DEFAULT_DOMAIN`, the
+    /// `RecordKey` of the record a challenge against `target` would dispute.
+    ///
+    /// Not removed with the record, it only points at a record while one exists under that key.
+    #[pallet::storage]
+    #[pallet::getter(fn refreshed_by)]
+    pub type RefreshedBy<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, (RoundIndex, T::AccountId), OptionQuery>;
+
     /// The path of the trust relationship from `seed` to `target`.
     #[pallet::storage]
     #[pallet::getter(fn get_path)]
@@ -206,26 +505,259 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// The sweeper fee released to the caller of `start` in round `u32`.
+    #[pallet::storage]
+    #[pallet::getter(fn round_proxy_fee)]
+    pub type RoundProxyFee<T: Config> = StorageMap<_, Twox64Concat, u32, Balance, ValueQuery>;
+
+    /// The account that started the last round and the number of consecutive rounds it
+    /// has started.
+    #[pallet::storage]
+    #[pallet::getter(fn round_trigger)]
+    pub type RoundTrigger<T: Config> = StorageValue<_, (T::AccountId, u32), OptionQuery>;
+
+    /// The registered pathfinders, only they can refresh.
+    #[pallet::storage]
+    #[pallet::getter(fn pathfinder)]
+    pub type Pathfinders<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        T::AccountId,
+        PathfinderInfo<Balance, T::BlockNumber, T::Hash>,
+        OptionQuery,
+    >;
+
+    /// The next nonce of the `RefreshPayload`s of `AccountId` in round `RoundIndex`, whether
+    /// relayed or submitted unsigned.
+    #[pallet::storage]
+    #[pallet::getter(fn payload_nonce)]
+    pub type PayloadNonces<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        RoundIndex,
+        Twox64Concat,
+        T::AccountId,
+        u32,
+        ValueQuery,
+    >;
+
+    /// The pathfinder and the staking of the challenged refresh of `AccountId`, settled when
+    /// the challenge is harvested.
+    #[pallet::storage]
+    #[pallet::getter(fn challenged_stake)]
+    pub type ChallengedStakes<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, (T::AccountId, Balance), OptionQuery>;
+
+    /// The currency of the staking of the challenged refresh of `AccountId`, if it is not
+    /// `BaceToken`.
+    #[pallet::storage]
+    pub type ChallengedCurrencies<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, T::CurrencyId, OptionQuery>;
+
+    /// The challenge losses and suspension of `AccountId`.
+    #[pallet::storage]
+    #[pallet::getter(fn pathfinder_status)]
+    pub type PathfinderStatuses<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, PathfinderStatus, ValueQuery>;
+
+    /// The Merkle roots, see `batch_root`, of the batches refreshed by `(round, pathfinder)`
+    /// and the block they were refreshed at.
+    ///
+    /// Unlike `Records`, they are kept after the payroll is harvested, so that the submitted
+    /// scores can still be proven with `verify_score`.
+    #[pallet::storage]
+    #[pallet::getter(fn commitment)]
+    pub type Commitments<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        (RoundIndex, T::AccountId),
+        Twox64Concat,
+        T::Hash,
+        T::BlockNumber,
+        OptionQuery,
+    >;
+
+    /// The unrevealed `commit_refresh` of `AccountId` in round `RoundIndex` and its deposit.
+    #[pallet::storage]
+    #[pallet::getter(fn refresh_commit)]
+    pub type RefreshCommits<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        RoundIndex,
+        Twox64Concat,
+        T::AccountId,
+        (T::Hash, Balance),
+        OptionQuery,
+    >;
+
+    /// The statistics of round `RoundIndex`.
+    #[pallet::storage]
+    #[pallet::getter(fn round_stats)]
+    pub type RoundStats<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        RoundIndex,
+        RoundStat<Balance, T::BlockNumber>,
+        ValueQuery,
+    >;
+
+    /// Set by `force_new_round`, the next `start` does not wait for unharvested challenges.
+    #[pallet::storage]
+    pub type ForceStart<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+    /// The hashed key of the last `Payrolls` entry scanned by `start` or `settle_round`, the
+    /// next call continues after it. `None` if no settlement is in progress.
+    #[pallet::storage]
+    #[pallet::getter(fn settlement_cursor)]
+    pub type SettlementCursor<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
+
+    /// The hashed key of the last `Records` entry checked by `prune_records`, the next
+    /// `on_initialize` continues after it.
+    #[pallet::storage]
+    pub type PruneCursor<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
+
+    /// The staking amount set by `set_parameters`, `UpdateStakingAmount` if never set.
+    #[pallet::storage]
+    pub type StakingAmount<T: Config> = StorageValue<_, Balance, OptionQuery>;
+
+    /// The staking amount of each round, fixed when the round starts so that a change by
+    /// `set_parameters` leaves the payrolls of that round intact.
+    #[pallet::storage]
+    pub type RoundStakingAmounts<T: Config> =
+        StorageMap<_, Twox64Concat, RoundIndex, Balance, OptionQuery>;
+
+    /// The currencies approved for the update staking besides `BaceToken`, and the amount to
+    /// be staked in each.
+    #[pallet::storage]
+    #[pallet::getter(fn staking_currency_amount)]
+    pub type StakingCurrencies<T: Config> =
+        StorageMap<_, Twox64Concat, T::CurrencyId, Balance, OptionQuery>;
+
+    /// The approved currency `AccountId` stakes in when refreshing, `BaceToken` if none.
+    #[pallet::storage]
+    #[pallet::getter(fn staking_currency_of)]
+    pub type PathfinderCurrencies<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, T::CurrencyId, OptionQuery>;
+
+    /// The currency and amount per user of the staking of a payroll not staked in
+    /// `BaceToken`, fixed by the first refresh of the payroll.
+    #[pallet::storage]
+    #[pallet::getter(fn payroll_currency)]
+    pub type PayrollCurrencies<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        RoundIndex,
+        Twox64Concat,
+        T::AccountId,
+        (T::CurrencyId, Balance),
+        OptionQuery,
+    >;
+
+    /// The users that opted out of being refreshed by pathfinders.
+    #[pallet::storage]
+    pub type OptedOut<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, (), OptionQuery>;
+
     #[pallet::event]
-    #[pallet::metadata(T::AccountId = "AccountId")]
+    #[pallet::metadata(T::AccountId = "AccountId", T::CurrencyId = "CurrencyId")]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
         /// Some reputations have been updated. \[pathfinder, count, fee\]
         ReputationRefreshed(T::AccountId, u32, Balance),
         /// Reputation renewal has begun \[who\]
         Started(T::AccountId),
-        /// Refreshed earnings are harvested \[pathfinder, amount\]
-        RefreshedHarvested(T::AccountId, Balance),
-        /// Refreshed earnings are harvested \[pathfinder, sweeper, pathfinder_amount, sweeper_amount\]
-        RefreshedHarvestedBySweeper(T::AccountId, T::AccountId, Balance, Balance),
         /// Refreshed earnings are harvested \[pathfinder, target\]
         ChallengeHarvested(T::AccountId, T::AccountId),
         /// A new challenge has been launched \[challenger, target\]
         Challenge(T::AccountId, T::AccountId),
         /// A new arbitral has been launched \[challenger, target\]
         Arbitral(T::AccountId, T::AccountId),
+        /// A challenge has been backed \[backer, target, amount\]
+        ChallengeBacked(T::AccountId, T::AccountId, Balance),
+        /// A challenge has been cancelled by its challenger \[challenger, target\]
+        ChallengeCancelled(T::AccountId, T::AccountId),
+        /// The ruling of a challenge has been appealed \[appellant, target\]
+        Appealed(T::AccountId, T::AccountId),
+        /// The appeal of a challenge has been resolved \[target, overturned\]
+        AppealResolved(T::AccountId, bool),
         /// The new path is uploaded \[challenger, target\]
         PathUpdated(T::AccountId, T::AccountId),
+        /// The payroll has been transferred \[from, to\]
+        PayrollTransferred(T::AccountId, T::AccountId),
+        /// The payroll has been merged into an existing payroll \[from, to\]
+        PayrollMerged(T::AccountId, T::AccountId),
+        /// Matured payrolls were drained without starting the round, because challenges
+        /// remain uncollected or not all `Payrolls` have been scanned yet \[who, count\]
+        PayrollsDrained(T::AccountId, u32),
+        /// Some refreshes have been retracted \[pathfinder, count, fee\]
+        RefreshRetracted(T::AccountId, u32, Balance),
+        /// Matured refreshed earnings are harvested \[pathfinder, amount, still_locked\]
+        RefreshedPartiallyHarvested(T::AccountId, Balance, u32),
+        /// The payrolls of the previous round have been settled \[round, who, fee\]
+        RoundSettled(u32, T::AccountId, Balance),
+        /// Targets with unchanged scores were skipped by `refresh_changed_only` \[pathfinder, count\]
+        UnchangedSkipped(T::AccountId, u32),
+        /// The sweeper fee of a repeated caller of `start` has decayed \[who, streak, decayed\]
+        RewardDecayed(T::AccountId, u32, Balance),
+        /// Entries of `refresh_best_effort` were skipped \[pathfinder, skipped\]
+        RefreshSkipped(T::AccountId, Vec<(u32, SkipReason)>),
+        /// A refresh was withdrawn during its confirmation period \[pathfinder, target, fee\]
+        RefreshWithdrawn(T::AccountId, T::AccountId, Balance),
+        /// The score of a refresh was amended during its confirmation period \[pathfinder, target, score\]
+        RefreshAmended(T::AccountId, T::AccountId, u32),
+        /// A signed refresh payload was submitted by a relayer \[relayer, pathfinder\]
+        PayloadRelayed(T::AccountId, T::AccountId),
+        /// A pathfinder registered with a bond \[pathfinder, bond\]
+        PathfinderRegistered(T::AccountId, Balance),
+        /// A pathfinder deregistered, its bond unlocks at the block \[pathfinder, unbonding_at\]
+        PathfinderDeregistered(T::AccountId, T::BlockNumber),
+        /// The bond of a pathfinder was withdrawn \[pathfinder, bond\]
+        BondWithdrawn(T::AccountId, Balance),
+        /// The bond of a pathfinder was slashed \[pathfinder, to, amount\]
+        BondSlashed(T::AccountId, T::AccountId, Balance),
+        /// The staking of a pathfinder losing a challenge was slashed
+        /// \[pathfinder, challenger, to_challenger, to_treasury\]
+        PathfinderSlashed(T::AccountId, T::AccountId, Balance, Balance),
+        /// A pathfinder lost too many challenges and cannot refresh before the round
+        /// \[pathfinder, suspended_until\]
+        PathfinderSuspended(T::AccountId, RoundIndex),
+        /// The suspension of a pathfinder was lifted \[pathfinder\]
+        SuspensionLifted(T::AccountId),
+        /// A user opted out of being refreshed \[who\]
+        UserOptedOut(T::AccountId),
+        /// A user opted in to being refreshed again \[who\]
+        UserOptedIn(T::AccountId),
+        /// A pathfinder committed to a batch of refreshes \[pathfinder, hash\]
+        RefreshCommitted(T::AccountId, T::Hash),
+        /// An unrevealed commitment was forfeited \[pathfinder, deposit\]
+        CommitmentForfeited(T::AccountId, Balance),
+        /// The reputation of a user was refreshed, `old` is its last verified reputation
+        /// \[user, old, new, pathfinder\]
+        UserScoreRefreshed(T::AccountId, Option<u32>, u32, T::AccountId),
+        /// The refreshes and challenges of a round are over \[round, stats\]
+        RoundClosed(RoundIndex, RoundStat<Balance, T::BlockNumber>),
+        /// The round was ended by `force_new_round` \[carry_over\]
+        RoundForced(bool),
+        /// A payroll was harvested by its pathfinder \[pathfinder, amount, count\]
+        PayrollHarvested(T::AccountId, Balance, u32),
+        /// A payroll was settled on behalf of its pathfinder by a sweeper or the caller of
+        /// `start`, `proxy_fee` is before any decay \[proxy, pathfinder, proxy_fee, amount\]
+        PayrollProxySettled(T::AccountId, T::AccountId, Balance, Balance),
+        /// A payroll was swept to the treasury by `force_new_round` \[round, pathfinder, amount\]
+        PayrollSwept(RoundIndex, T::AccountId, Balance),
+        /// The staking and fee parameters were updated
+        /// \[staking_amount, share_ratio, fee_ratio, self_ratio\]
+        ParametersUpdated(Balance, Perbill, Perbill, Perbill),
+        /// A currency was approved for staking \[currency, amount\]
+        StakingCurrencyApproved(T::CurrencyId, Balance),
+        /// A currency is no longer approved for staking \[currency\]
+        StakingCurrencyRevoked(T::CurrencyId),
+        /// A pathfinder chose the currency it stakes in \[pathfinder, currency\]
+        StakingCurrencySet(T::AccountId, Option<T::CurrencyId>),
+        /// Some reputations have been updated in a domain \[pathfinder, domain, count, fee\]
+        ReputationRefreshedIn(T::AccountId, DomainId, u32, Balance),
+        /// The refresh of a user by another pathfinder was superseded, its staking is
+        /// refunded and the fee returned to the user \[pathfinder, superseded, user, fee\]
+        RefreshSuperseded(T::AccountId, T::AccountId, T::AccountId, Balance),
     }
 
     #[pallet::error]
@@ -270,10 +802,135 @@ pub mod pallet {
         ScoreTooLow,
         /// Exceed the refresh limit
         ExceedMaxRefresh,
+        /// The payload nonce is out of date
+        StaleNonce,
+        /// No payroll exists
+        NoPayroll,
+        /// The payroll already exists
+        PayrollAlreadyExist,
+        /// The same user is recorded under both payrolls
+        RecordConflict,
+        /// Unable to transfer to yourself
+        SamePathfinder,
+        /// Insufficient balance to staking for the refresh
+        InsufficientStake,
+        /// The update record does not exist
+        RecordNotExist,
+        /// Only refreshes in the current block can be retracted
+        RetractTooLate,
+        /// No records have passed the confirmation period
+        NoMaturedRecord,
+        /// The confirmation period of the record has elapsed
+        ConfirmationElapsed,
+        /// The payload is not signed by its pathfinder
+        BadSignature,
+        /// The payload was signed for another round
+        StaleRound,
+        /// The account is not a registered pathfinder
+        NotPathfinder,
+        /// The account is already registered as a pathfinder
+        AlreadyPathfinder,
+        /// The bond is below `PathfinderBond`
+        BondTooLow,
+        /// The pathfinder is already unbonding
+        AlreadyUnbonding,
+        /// The pathfinder is not unbonding or the unbonding period has not passed
+        StillBonded,
+        /// The pathfinder is suspended for losing too many challenges
+        PathfinderSuspended,
+        /// The pathfinder is not suspended
+        NotSuspended,
+        /// The user has opted out of being refreshed
+        UserOptedOut,
+        /// The user has already opted out
+        AlreadyOptedOut,
+        /// The user has not opted out
+        NotOptedOut,
+        /// The commit period of this round has elapsed
+        CommitPeriodElapsed,
+        /// The caller has already committed in this round
+        CommitmentExists,
+        /// No commitment exists
+        NoCommitment,
+        /// Reveals are only accepted after the commit period
+        RevealTooEarly,
+        /// The revealed batch does not match the commitment
+        CommitmentMismatch,
+        /// The commitment can still be revealed
+        RevealNotElapsed,
+        /// The staking amount must not be zero
+        ZeroStakingAmount,
+        /// The currency is not approved for staking
+        CurrencyNotApproved,
+        /// The payrolls are staked in different currencies
+        CurrencyMismatch,
+        /// The payroll would exceed `MaxPayrollRecords` or `MaxPayrollAmount`
+        PayrollCapReached,
+        /// The challenge does not exist
+        ChallengeNotExist,
+        /// The challenge does not accept this call in its current state
+        ChallengeWrongPhase,
+        /// The caller may not act on the challenge now
+        NotYourTurn,
+        /// The pathfinder already refreshed the user in this round
+        RecordExists,
+        /// Only refreshes of `DEFAULT_DOMAIN` can be amended or challenged
+        NotDefaultDomain,
+        /// `user_scores` has more than `MAX_REFRESH` entries
+        TooManyUserScores,
+        /// The user was refreshed in this round by a pathfinder the caller cannot supersede
+        CannotSupersede,
+    }
+
+    #[pallet::genesis_config]
+    pub struct GenesisConfig {}
+
+    #[cfg(feature = "std")]
+    impl Default for GenesisConfig {
+        fn default() -> Self {
+            GenesisConfig {}
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> GenesisBuild<T> for GenesisConfig {
+        fn build(&self) {
+            StorageVersion::<T>::put(Releases::V2);
+        }
     }
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        /// Start the round with the block author as the caller, see `BlockAuthor`.
+        fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+            Self::auto_start().saturating_add(Self::prune_records())
+        }
+
+        /// Move the storage to the latest layout of `Releases`.
+        fn on_runtime_upgrade() -> Weight {
+            match Self::storage_version() {
+                Releases::V1 => Self::migrate_to_v2(),
+                Releases::V2 => T::DbWeight::get().reads(1),
+            }
+        }
+
+        /// Check the accounting invariants at the end of every block, only compiled
+        /// with the `debug-invariants` feature.
+        #[cfg(feature = "debug-invariants")]
+        fn on_finalize(_n: BlockNumberFor<T>) {
+            if let Err(e) = Self::check_invariants() {
+                frame_support::debug::error!("zd-refresh-reputation invariant violated: {}", e);
+                #[cfg(test)]
+                panic!("{}", e);
+            }
+        }
+
+        fn integrity_test() {
+            if let Err(e) = Self::check_update_length() {
+                panic!("zd-refresh-reputation misconfigured: {}", e);
+            }
+        }
+    }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
@@ -285,152 +942,989 @@ pub mod pallet {
         /// will receive a percentage of the amount.
         /// - `pathfinder` gets a first-mover advantage, preempting users with higher renewal fees.
         ///
+        /// At most `MAX_START_SETTLEMENTS` `Payrolls` are scanned from `SettlementCursor` and
+        /// the matured ones are settled, with the fee of `harvest_ref_all_sweeper` along
+        /// `SweeperCurve`, once it allows sweepers on them. The round is only started by the
+        /// call that scans the last of them, earlier calls drain the matured `Payrolls` they
+        /// scanned and move the cursor, see `settle_round`.
+        ///
+        /// Settling a `Payroll` also removes the `Records` of its pathfinder in its round.
+        /// These have all passed the confirmation period and can no longer be challenged, left
+        /// in place they would outlive their payroll and could never be harvested.
+        ///
+        /// If challenges remain uncollected but some `Payrolls` have passed the confirmation
+        /// period, these are settled instead and the round is not started, the caller can try
+        /// again once the challenges are collected.
+        ///
+        /// An account that started consecutive rounds receives a decayed fee, the rest goes to
+        /// `TreasuryAccountId`, see `RewardDecayWindow`. This also holds for the fee of the
+        /// calls that only drain `Payrolls`.
+        ///
+        /// Starting the round opens its refreshes through `open_refresh` of `Reputation`, which
+        /// times the refresh and confirmation phases of its `RoundInfo` from this block.
+        ///
+        /// If `BlockAuthor` is configured, the block author starts the round in `on_initialize`
+        /// as soon as there are no uncollected challenges.
+        ///
         /// Is a no-op if:
         ///
-        /// 1 Challenges that remain uncollected, or
+        /// 1 Challenges that remain uncollected and no `Payrolls` can be drained, or
         /// 2 Already started, or
         /// 3 Minimum interval not exceeded.
-        #[pallet::weight(T::WeightInfo::start())]
+        ///
+        /// The weight is charged for `MAX_START_SETTLEMENTS` settlements and refunded down
+        /// to the number of `Payrolls` actually settled.
+        #[pallet::weight(T::WeightInfo::start(MAX_START_SETTLEMENTS))]
         #[transactional]
         pub fn start(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
-            Self::check_step_and_not_stared()?;
-
-            ensure!(
-                T::ChallengeBase::is_all_harvest(&APP_ID),
-                Error::<T>::ChallengeNotClaimed
-            );
-
-            let total_fee = Payrolls::<T>::drain()
-                .try_fold::<_, _, Result<Balance, DispatchError>>(
-                    0u128,
-                    |acc: Balance, (pathfinder, payroll)| {
-                        let (sweeper_fee, without_fee) = payroll.total_amount::<T>().with_fee();
+            let count = Self::do_start(&who, MAX_START_SETTLEMENTS)?;
+            Ok(Some(T::WeightInfo::start(count)).into())
+        }
 
-                        T::MultiBaseToken::release(&pathfinder, &without_fee)?;
+        /// `start`, scanning at most `max_payrolls` `Payrolls`.
+        ///
+        /// Settles a busy round in several calls that each fit in a block, the round starts
+        /// once the last `Payrolls` have been scanned.
+        ///
+        /// `max_payrolls` is at least `1` and at most `MAX_START_SETTLEMENTS`.
+        #[pallet::weight(T::WeightInfo::start(max_payrolls.max(1).min(MAX_START_SETTLEMENTS)))]
+        #[transactional]
+        pub fn settle_round(
+            origin: OriginFor<T>,
+            max_payrolls: u32,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let limit = max_payrolls.max(1).min(MAX_START_SETTLEMENTS);
+            let count = Self::do_start(&who, limit)?;
+            Ok(Some(T::WeightInfo::start(count)).into())
+        }
 
-                        acc.checked_add(sweeper_fee)
-                            .ok_or_else(|| Error::<T>::Overflow.into())
-                    },
-                )?;
-            T::MultiBaseToken::release(&who, &total_fee)?;
-            <StartedAt<T>>::put(Self::now());
-            Self::deposit_event(Event::Started(who));
-            Ok(().into())
+        /// End the current round even if refreshes or challenges are still pending, and let the
+        /// next `start` proceed without waiting for unharvested challenges.
+        ///
+        /// If `carry_over` is `true` the payrolls stay harvestable under their round, otherwise
+        /// at most `MAX_START_SETTLEMENTS` of them are scanned from `SettlementCursor` and those
+        /// past the confirmation period are swept to `TreasuryAccountId` with their records.
+        /// The others can still be challenged and are left to later calls or `settle_round`.
+        ///
+        /// The dispatch origin for this call must be _Root_.
+        #[pallet::weight(T::WeightInfo::force_new_round(MAX_START_SETTLEMENTS))]
+        #[transactional]
+        pub fn force_new_round(
+            origin: OriginFor<T>,
+            carry_over: bool,
+        ) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+            if <StartedAt<T>>::exists() {
+                Self::close_round();
+            }
+            <ForceStart<T>>::put(true);
+            let mut count = 0u32;
+            if !carry_over {
+                let now_block_number = Self::now();
+                let (payrolls, _) = Self::scan_payrolls(MAX_START_SETTLEMENTS);
+                count = payrolls.len() as u32;
+                let matured = payrolls.into_iter().filter(|(_, _, payroll)| {
+                    Self::can_harvest(payroll, &now_block_number).is_ok()
+                });
+                for (round, pathfinder, payroll) in matured {
+                    let amount = payroll.total_amount::<T>(round, &pathfinder);
+                    let treasury = T::TreasuryAccountId::get();
+                    Self::release_currency_stake(round, &pathfinder, &treasury, payroll.count)?;
+                    Payrolls::<T>::remove(round, &pathfinder);
+                    PayrollCurrencies::<T>::remove(round, &pathfinder);
+                    <Records<T>>::remove_prefix(&RecordKey::new(round, pathfinder.clone()));
+                    T::MultiBaseToken::release(&treasury, &amount)?;
+                    Self::deposit_event(Event::PayrollSwept(round, pathfinder, amount));
+                }
+            }
+            Self::deposit_event(Event::RoundForced(carry_over));
+            Ok(Some(T::WeightInfo::force_new_round(count)).into())
         }
 
-        /// Refresh the reputation value of a group of users.
-        #[pallet::weight(T::WeightInfo::refresh((user_scores.len() as u32).max(1u32)))]
+        /// Register the caller as a pathfinder, staking `bond`.
+        ///
+        /// `endpoint` is an optional hash of the pathfinder's off-chain endpoint.
+        #[pallet::weight(T::WeightInfo::register_pathfinder())]
         #[transactional]
-        pub fn refresh(
+        pub fn register_pathfinder(
             origin: OriginFor<T>,
-            user_scores: Vec<(T::AccountId, u32)>,
+            bond: Balance,
+            endpoint: Option<T::Hash>,
         ) -> DispatchResultWithPostInfo {
-            let pathfinder = ensure_signed(origin)?;
-            let user_count = user_scores.len();
+            let who = ensure_signed(origin)?;
             ensure!(
-                user_count as u32 <= T::MaxUpdateCount::get(),
-                Error::<T>::QuantityLimitReached
+                !Pathfinders::<T>::contains_key(&who),
+                Error::<T>::AlreadyPathfinder
             );
-            Self::check_step_and_stared()?;
-            let now_block_number = Self::now();
-            Self::check_timeout(&now_block_number)?;
-
-            let old_count = Self::get_payroll(&pathfinder).count;
-            ensure!(
-                old_count.saturating_add(user_count as u32) < MAX_REFRESH,
-                Error::<T>::ExceedMaxRefresh
+            ensure!(bond >= T::PathfinderBond::get(), Error::<T>::BondTooLow);
+            T::MultiBaseToken::staking(&who, &bond)?;
+            Pathfinders::<T>::insert(
+                &who,
+                PathfinderInfo {
+                    bond,
+                    endpoint,
+                    unbonding_at: None,
+                },
             );
+            Self::deposit_event(Event::PathfinderRegistered(who, bond));
+            Ok(().into())
+        }
 
-            let amount = T::UpdateStakingAmount::get()
-                .checked_mul(user_count as Balance)
-                .ok_or(Error::<T>::Overflow)?;
-            T::MultiBaseToken::staking(&pathfinder, &amount)?;
-            let total_fee = user_scores
-                .iter()
-                .try_fold::<_, _, Result<Balance, DispatchError>>(
-                    Zero::zero(),
-                    |acc_amount, user_score| {
-                        let fee = Self::do_refresh(&pathfinder, user_score, &now_block_number)?;
-                        acc_amount
-                            .checked_add(fee)
-                            .ok_or_else(|| Error::<T>::Overflow.into())
-                    },
-                )?;
-            Self::mutate_payroll(
-                &pathfinder,
-                &total_fee,
-                &(user_count as u32),
-                &now_block_number,
-            )?;
-
-            T::Reputation::set_last_refresh_at();
-
-            Self::deposit_event(Event::ReputationRefreshed(
-                pathfinder,
-                user_count as u32,
-                total_fee,
-            ));
+        /// Stop the caller from refreshing, its bond can be withdrawn with `withdraw_bond`
+        /// after `UnbondingPeriod`.
+        #[pallet::weight(T::WeightInfo::deregister_pathfinder())]
+        pub fn deregister_pathfinder(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let unbonding_at = Self::now() + T::UnbondingPeriod::get();
+            Pathfinders::<T>::try_mutate(&who, |maybe_info| -> DispatchResult {
+                let info = maybe_info.as_mut().ok_or(Error::<T>::NotPathfinder)?;
+                ensure!(info.unbonding_at.is_none(), Error::<T>::AlreadyUnbonding);
+                info.unbonding_at = Some(unbonding_at);
+                Ok(())
+            })?;
+            Self::deposit_event(Event::PathfinderDeregistered(who, unbonding_at));
             Ok(().into())
         }
 
-        /// The caller receives all their earnings and clears all update records.
-        ///
-        /// An `Err` will be returned if the user's last update has not passed the confirmation period.
-        ///
-        /// NOTE: This is more economical and efficient than collecting each item in turn.
-        #[pallet::weight(T::WeightInfo::harvest_ref_all())]
+        /// Withdraw the bond of the caller once its unbonding period has passed.
+        #[pallet::weight(T::WeightInfo::withdraw_bond())]
         #[transactional]
-        pub fn harvest_ref_all(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
-            let pathfinder = ensure_signed(origin)?;
-            Self::next_step();
-            let now_block_number = Self::now();
-            let payroll = Payrolls::<T>::take(&pathfinder);
-            Self::can_harvest(&payroll, &now_block_number)?;
-            let total_amount = payroll.total_amount::<T>();
-            T::MultiBaseToken::release(&pathfinder, &total_amount)?;
-            <Records<T>>::remove_prefix(&pathfinder);
-            Self::deposit_event(Event::RefreshedHarvested(pathfinder, total_amount));
+        pub fn withdraw_bond(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let info = Self::pathfinder(&who).ok_or(Error::<T>::NotPathfinder)?;
+            ensure!(
+                info.unbonding_at.map_or(false, |at| at <= Self::now()),
+                Error::<T>::StillBonded
+            );
+            Pathfinders::<T>::remove(&who);
+            PathfinderCurrencies::<T>::remove(&who);
+            T::MultiBaseToken::release(&who, &info.bond)?;
+            Self::deposit_event(Event::BondWithdrawn(who, info.bond));
             Ok(().into())
         }
 
-        /// `sweeper` collects `pathfinder` overdue proceeds.
+        /// Lift the suspension of `pathfinder` and forget its challenge losses.
         ///
-        /// `sweeper` receives a percentage of the proceeds from it.
-        ///
-        /// NOTE: It is the responsibility of `pathfinder` to secure the cleanliness of the data on the chain
+        /// The dispatch origin for this call must be _Root_.
+        #[pallet::weight(T::WeightInfo::lift_suspension())]
+        pub fn lift_suspension(
+            origin: OriginFor<T>,
+            pathfinder: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+            ensure!(Self::is_suspended(&pathfinder), Error::<T>::NotSuspended);
+            PathfinderStatuses::<T>::remove(&pathfinder);
+            Self::deposit_event(Event::SuspensionLifted(pathfinder));
+            Ok(().into())
+        }
+
+        /// Set the staking amount of a refreshed user and the `share_ratio`, `fee_ratio` and
+        /// `self_ratio` in which social currency is split when it is refreshed.
+        ///
+        /// The staking amount applies from the next round, the ratios immediately. The ratios
+        /// must not exceed 100% combined, including the fixed burned part.
+        ///
+        /// The dispatch origin for this call must be `ParametersOrigin`.
+        #[pallet::weight(T::WeightInfo::set_parameters())]
+        #[transactional]
+        pub fn set_parameters(
+            origin: OriginFor<T>,
+            staking_amount: Balance,
+            share_ratio: Perbill,
+            fee_ratio: Perbill,
+            self_ratio: Perbill,
+        ) -> DispatchResultWithPostInfo {
+            T::ParametersOrigin::ensure_origin(origin)?;
+            ensure!(!staking_amount.is_zero(), Error::<T>::ZeroStakingAmount);
+            T::MultiBaseToken::set_social_ratios(share_ratio, self_ratio, fee_ratio)?;
+            <StakingAmount<T>>::put(staking_amount);
+            Self::deposit_event(Event::ParametersUpdated(
+                staking_amount,
+                share_ratio,
+                fee_ratio,
+                self_ratio,
+            ));
+            Ok(().into())
+        }
+
+        /// Approve `currency` for the staking of refreshes with `amount` per user, or update
+        /// its amount. Payrolls keep the amount of their first refresh.
+        ///
+        /// The dispatch origin for this call must be `ParametersOrigin`.
+        #[pallet::weight(T::WeightInfo::approve_staking_currency())]
+        pub fn approve_staking_currency(
+            origin: OriginFor<T>,
+            currency: T::CurrencyId,
+            amount: Balance,
+        ) -> DispatchResultWithPostInfo {
+            T::ParametersOrigin::ensure_origin(origin)?;
+            ensure!(!amount.is_zero(), Error::<T>::ZeroStakingAmount);
+            StakingCurrencies::<T>::insert(currency, amount);
+            Self::deposit_event(Event::StakingCurrencyApproved(currency, amount));
+            Ok(().into())
+        }
+
+        /// Withdraw the approval of `currency`, pathfinders staking in it have to choose
+        /// another currency before refreshing a new payroll.
+        ///
+        /// The dispatch origin for this call must be `ParametersOrigin`.
+        #[pallet::weight(T::WeightInfo::revoke_staking_currency())]
+        pub fn revoke_staking_currency(
+            origin: OriginFor<T>,
+            currency: T::CurrencyId,
+        ) -> DispatchResultWithPostInfo {
+            T::ParametersOrigin::ensure_origin(origin)?;
+            ensure!(
+                StakingCurrencies::<T>::contains_key(currency),
+                Error::<T>::CurrencyNotApproved
+            );
+            StakingCurrencies::<T>::remove(currency);
+            Self::deposit_event(Event::StakingCurrencyRevoked(currency));
+            Ok(().into())
+        }
+
+        /// Set the approved currency the caller stakes in when refreshing, `None` for
+        /// `BaceToken`.
+        ///
+        /// It applies from the next payroll of the caller, the payrolls it has already
+        /// refreshed keep their currency.
+        #[pallet::weight(T::WeightInfo::set_staking_currency())]
+        pub fn set_staking_currency(
+            origin: OriginFor<T>,
+            currency: Option<T::CurrencyId>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                <Self as PathfinderBase<_, _>>::is_pathfinder(&who),
+                Error::<T>::NotPathfinder
+            );
+            match currency {
+                Some(currency) => {
+                    ensure!(
+                        StakingCurrencies::<T>::contains_key(currency),
+                        Error::<T>::CurrencyNotApproved
+                    );
+                    PathfinderCurrencies::<T>::insert(&who, currency);
+                }
+                None => PathfinderCurrencies::<T>::remove(&who),
+            }
+            Self::deposit_event(Event::StakingCurrencySet(who, currency));
+            Ok(().into())
+        }
+
+        /// Opt the caller out of being refreshed, pathfinders can then neither update its
+        /// reputation nor share its social balance.
+        #[pallet::weight(T::WeightInfo::opt_out())]
+        pub fn opt_out(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(!Self::is_opted_out(&who), Error::<T>::AlreadyOptedOut);
+            OptedOut::<T>::insert(&who, ());
+            Self::deposit_event(Event::UserOptedOut(who));
+            Ok(().into())
+        }
+
+        /// Allow pathfinders to refresh the caller again.
+        #[pallet::weight(T::WeightInfo::opt_in())]
+        pub fn opt_in(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(Self::is_opted_out(&who), Error::<T>::NotOptedOut);
+            OptedOut::<T>::remove(&who);
+            Self::deposit_event(Event::UserOptedIn(who));
+            Ok(().into())
+        }
+
+        /// Refresh the reputation value of a group of users.
+        ///
+        /// `user_scores` is bounded by `MAX_REFRESH` and then by `MaxUpdateCount`, a longer
+        /// list is rejected with `TooManyUserScores` or `QuantityLimitReached` before any
+        /// storage is read, and the weight only counts the trusts of the first
+        /// `MaxUpdateCount` users.
+        #[pallet::weight(T::WeightInfo::refresh(
+            (user_scores.len() as u32).max(1u32),
+            Pallet::<T>::total_trust_count(&user_scores[..]),
+        ))]
+        #[transactional]
+        pub fn refresh(
+            origin: OriginFor<T>,
+            user_scores: Vec<(T::AccountId, u32)>,
+        ) -> DispatchResultWithPostInfo {
+            let pathfinder = ensure_signed(origin)?;
+            Self::ensure_bounded(&user_scores[..])?;
+            let user_count = user_scores.len();
+            ensure!(
+                user_count as u32 <= T::MaxUpdateCount::get(),
+                Error::<T>::QuantityLimitReached
+            );
+            Self::check_step_and_stared()?;
+            let now_block_number = Self::now();
+            Self::check_timeout(&now_block_number)?;
+
+            let total_fee = Self::refresh_users(
+                &pathfinder,
+                &DEFAULT_DOMAIN,
+                &user_scores[..],
+                &now_block_number,
+            )?;
+
+            Self::deposit_event_indexed(
+                &[&pathfinder],
+                Event::ReputationRefreshed(pathfinder.clone(), user_count as u32, total_fee),
+            );
+            Ok(().into())
+        }
+
+        /// Refresh the reputation value of a group of users in `domain`, with the same checks,
+        /// staking and fees as `refresh`.
+        ///
+        /// The refreshes can be retracted and withdrawn as those of `refresh`, but only those
+        /// of `DEFAULT_DOMAIN` can be amended or challenged, the paths proving a score are
+        /// those of `DEFAULT_DOMAIN`.
+        #[pallet::weight(T::WeightInfo::refresh(
+            (user_scores.len() as u32).max(1u32),
+            Pallet::<T>::total_trust_count(&user_scores[..]),
+        ))]
+        #[transactional]
+        pub fn refresh_in(
+            origin: OriginFor<T>,
+            domain: DomainId,
+            user_scores: Vec<(T::AccountId, u32)>,
+        ) -> DispatchResultWithPostInfo {
+            let pathfinder = ensure_signed(origin)?;
+            Self::ensure_bounded(&user_scores[..])?;
+            let user_count = user_scores.len();
+            ensure!(
+                user_count as u32 <= T::MaxUpdateCount::get(),
+                Error::<T>::QuantityLimitReached
+            );
+            Self::check_step_and_stared()?;
+            let now_block_number = Self::now();
+            Self::check_timeout(&now_block_number)?;
+
+            let total_fee =
+                Self::refresh_users(&pathfinder, &domain, &user_scores[..], &now_block_number)?;
+
+            Self::deposit_event_indexed(
+                &[&pathfinder],
+                Event::ReputationRefreshedIn(
+                    pathfinder.clone(),
+                    domain,
+                    user_count as u32,
+                    total_fee,
+                ),
+            );
+            Ok(().into())
+        }
+
+        /// Commit to a batch of refreshes to be revealed with `reveal_refresh`, `hash` is the
+        /// `commitment_hash` of the batch and a secret salt.
+        ///
+        /// Accepted during the first `CommitPeriod` blocks of the round, `CommitDeposit` is
+        /// staked until the batch is revealed.
+        #[pallet::weight(T::WeightInfo::commit_refresh())]
+        #[transactional]
+        pub fn commit_refresh(origin: OriginFor<T>, hash: T::Hash) -> DispatchResultWithPostInfo {
+            let pathfinder = ensure_signed(origin)?;
+            Self::check_step_and_stared()?;
+            ensure!(
+                <Self as PathfinderBase<_, _>>::is_pathfinder(&pathfinder),
+                Error::<T>::NotPathfinder
+            );
+            ensure!(
+                Self::now() < Self::commit_period_end(),
+                Error::<T>::CommitPeriodElapsed
+            );
+            let round = Self::current_round();
+            ensure!(
+                !RefreshCommits::<T>::contains_key(round, &pathfinder),
+                Error::<T>::CommitmentExists
+            );
+            let deposit = T::CommitDeposit::get();
+            T::MultiBaseToken::staking(&pathfinder, &deposit)?;
+            RefreshCommits::<T>::insert(round, &pathfinder, (hash, deposit));
+            Self::deposit_event(Event::RefreshCommitted(pathfinder, hash));
+            Ok(().into())
+        }
+
+        /// Reveal the batch committed with `commit_refresh` and refresh it as `refresh` does,
+        /// the deposit is returned to the caller.
+        ///
+        /// Accepted after the commit period until the refresh timeout, the payroll is only
+        /// credited if `user_scores` and `salt` match the commitment.
+        #[pallet::weight(T::WeightInfo::refresh(
+            (user_scores.len() as u32).max(1u32),
+            Pallet::<T>::total_trust_count(&user_scores[..]),
+        ).saturating_add(T::DbWeight::get().reads_writes(1, 1)))]
+        #[transactional]
+        pub fn reveal_refresh(
+            origin: OriginFor<T>,
+            user_scores: Vec<(T::AccountId, u32)>,
+            salt: T::Hash,
+        ) -> DispatchResultWithPostInfo {
+            let pathfinder = ensure_signed(origin)?;
+            Self::ensure_bounded(&user_scores[..])?;
+            let user_count = user_scores.len();
+            ensure!(
+                user_count as u32 <= T::MaxUpdateCount::get(),
+                Error::<T>::QuantityLimitReached
+            );
+            Self::check_step_and_stared()?;
+            let now_block_number = Self::now();
+            Self::check_timeout(&now_block_number)?;
+            ensure!(
+                now_block_number >= Self::commit_period_end(),
+                Error::<T>::RevealTooEarly
+            );
+            let round = Self::current_round();
+            let (hash, deposit) =
+                RefreshCommits::<T>::take(round, &pathfinder).ok_or(Error::<T>::NoCommitment)?;
+            ensure!(
+                Self::commitment_hash(&pathfinder, &user_scores[..], &salt) == hash,
+                Error::<T>::CommitmentMismatch
+            );
+
+            let total_fee = Self::refresh_users(
+                &pathfinder,
+                &DEFAULT_DOMAIN,
+                &user_scores[..],
+                &now_block_number,
+            )?;
+            T::MultiBaseToken::release(&pathfinder, &deposit)?;
+
+            Self::deposit_event_indexed(
+                &[&pathfinder],
+                Event::ReputationRefreshed(pathfinder.clone(), user_count as u32, total_fee),
+            );
+            Ok(().into())
+        }
+
+        /// Forfeit the deposit of a commitment of `pathfinder` in `round` that can no longer
+        /// be revealed to `TreasuryAccountId`, anyone can call it.
+        #[pallet::weight(T::WeightInfo::forfeit_commitment())]
+        #[transactional]
+        pub fn forfeit_commitment(
+            origin: OriginFor<T>,
+            round: RoundIndex,
+            pathfinder: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+            ensure!(
+                round < Self::current_round() || Self::check_timeout(&Self::now()).is_err(),
+                Error::<T>::RevealNotElapsed
+            );
+            let (_, deposit) =
+                RefreshCommits::<T>::take(round, &pathfinder).ok_or(Error::<T>::NoCommitment)?;
+            T::MultiBaseToken::release(&T::TreasuryAccountId::get(), &deposit)?;
+            Self::deposit_event(Event::CommitmentForfeited(pathfinder, deposit));
+            Ok(().into())
+        }
+
+        /// Same as `refresh`, but targets whose submitted score equals their current
+        /// reputation are dropped from the batch, and no staking, fee or record is charged
+        /// for them.
+        ///
+        /// The dropped targets are not refreshed in this round, they can still be refreshed
+        /// by others.
+        #[pallet::weight(T::WeightInfo::refresh(
+            (user_scores.len() as u32).max(1u32),
+            Pallet::<T>::total_trust_count(&user_scores[..]),
+        ))]
+        #[transactional]
+        pub fn refresh_changed_only(
+            origin: OriginFor<T>,
+            user_scores: Vec<(T::AccountId, u32)>,
+        ) -> DispatchResultWithPostInfo {
+            let pathfinder = ensure_signed(origin)?;
+            Self::ensure_bounded(&user_scores[..])?;
+            ensure!(
+                user_scores.len() as u32 <= T::MaxUpdateCount::get(),
+                Error::<T>::QuantityLimitReached
+            );
+            Self::check_step_and_stared()?;
+            let now_block_number = Self::now();
+            Self::check_timeout(&now_block_number)?;
+
+            let changed = user_scores
+                .iter()
+                .filter(|(who, score)| T::Reputation::get_reputation(who) != Some(*score))
+                .cloned()
+                .collect::<Vec<_>>();
+            let skipped = (user_scores.len() - changed.len()) as u32;
+
+            if !changed.is_empty() {
+                let total_fee = Self::refresh_users(
+                    &pathfinder,
+                    &DEFAULT_DOMAIN,
+                    &changed[..],
+                    &now_block_number,
+                )?;
+                Self::deposit_event_indexed(
+                    &[&pathfinder],
+                    Event::ReputationRefreshed(pathfinder.clone(), changed.len() as u32, total_fee),
+                );
+            }
+            Self::deposit_event(Event::UnchangedSkipped(pathfinder, skipped));
+            Ok(().into())
+        }
+
+        /// Same as `refresh`, but entries that cannot be refreshed are skipped instead of
+        /// failing the whole batch, staking is only charged for the accepted ones.
+        ///
+        /// The index and `SkipReason` of each skipped entry are listed in `RefreshSkipped`.
+        #[pallet::weight(T::WeightInfo::refresh(
+            (user_scores.len() as u32).max(1u32),
+            Pallet::<T>::total_trust_count(&user_scores[..]),
+        ))]
+        #[transactional]
+        pub fn refresh_best_effort(
+            origin: OriginFor<T>,
+            user_scores: Vec<(T::AccountId, u32)>,
+        ) -> DispatchResultWithPostInfo {
+            let pathfinder = ensure_signed(origin)?;
+            Self::ensure_bounded(&user_scores[..])?;
+            ensure!(
+                user_scores.len() as u32 <= T::MaxUpdateCount::get(),
+                Error::<T>::QuantityLimitReached
+            );
+            Self::check_step_and_stared()?;
+            let now_block_number = Self::now();
+            Self::check_timeout(&now_block_number)?;
+
+            let (accepted, skipped) = Self::split_refreshable(&pathfinder, &user_scores[..]);
+            if !accepted.is_empty() {
+                let total_fee = Self::refresh_users(
+                    &pathfinder,
+                    &DEFAULT_DOMAIN,
+                    &accepted[..],
+                    &now_block_number,
+                )?;
+                Self::deposit_event_indexed(
+                    &[&pathfinder],
+                    Event::ReputationRefreshed(pathfinder.clone(), accepted.len() as u32, total_fee),
+                );
+            }
+            if !skipped.is_empty() {
+                Self::deposit_event(Event::RefreshSkipped(pathfinder, skipped));
+            }
+            Ok(().into())
+        }
+
+        /// Submit a `RefreshPayload` signed by its pathfinder, the caller only pays the
+        /// transaction fee.
+        ///
+        /// The staking and payroll belong to `payload.pathfinder`, as if it had called
+        /// `refresh` itself. A payload can't be replayed, it must carry the next
+        /// `payload_nonce` of its pathfinder in the round, which it consumes, and it is
+        /// rejected in any other round.
+        #[pallet::weight(T::WeightInfo::refresh(
+            (payload.user_scores.len() as u32).max(1u32),
+            Pallet::<T>::total_trust_count(&payload.user_scores[..]),
+        ))]
+        #[transactional]
+        pub fn refresh_signed_payload(
+            origin: OriginFor<T>,
+            payload: RefreshPayload<T::AccountId>,
+            signature: T::OffchainSignature,
+        ) -> DispatchResultWithPostInfo {
+            let relayer = ensure_signed(origin)?;
+            Self::ensure_bounded(&payload.user_scores[..])?;
+            let user_count = payload.user_scores.len();
+            ensure!(
+                user_count as u32 <= T::MaxUpdateCount::get(),
+                Error::<T>::QuantityLimitReached
+            );
+            Self::check_payload(&payload, &signature)?;
+            Self::check_step_and_stared()?;
+            let now_block_number = Self::now();
+            Self::check_timeout(&now_block_number)?;
+
+            let pathfinder = payload.pathfinder;
+            let total_fee = Self::refresh_users(
+                &pathfinder,
+                &DEFAULT_DOMAIN,
+                &payload.user_scores[..],
+                &now_block_number,
+            )?;
+            Self::use_payload_nonce(&payload.round, &pathfinder, payload.nonce);
+
+            Self::deposit_event_indexed(
+                &[&pathfinder],
+                Event::ReputationRefreshed(pathfinder.clone(), user_count as u32, total_fee),
+            );
+            Self::deposit_event(Event::PayloadRelayed(relayer, pathfinder));
+            Ok(().into())
+        }
+
+        /// Submit a `RefreshPayload` signed by its pathfinder as an unsigned transaction,
+        /// typically from the pathfinder's offchain worker.
+        ///
+        /// The payload is checked and its nonce consumed as by `refresh_signed_payload`.
+        #[pallet::weight(T::WeightInfo::refresh(
+            (payload.user_scores.len() as u32).max(1u32),
+            Pallet::<T>::total_trust_count(&payload.user_scores[..]),
+        ))]
+        #[transactional]
+        pub fn refresh_unsigned(
+            origin: OriginFor<T>,
+            payload: RefreshPayload<T::AccountId>,
+            signature: T::OffchainSignature,
+        ) -> DispatchResultWithPostInfo {
+            ensure_none(origin)?;
+            Self::ensure_bounded(&payload.user_scores[..])?;
+            let user_count = payload.user_scores.len();
+            ensure!(
+                user_count as u32 <= T::MaxUpdateCount::get(),
+                Error::<T>::QuantityLimitReached
+            );
+            Self::check_payload(&payload, &signature)?;
+            Self::check_step_and_stared()?;
+            let now_block_number = Self::now();
+            Self::check_timeout(&now_block_number)?;
+
+            let pathfinder = payload.pathfinder;
+            let total_fee = Self::refresh_users(
+                &pathfinder,
+                &DEFAULT_DOMAIN,
+                &payload.user_scores[..],
+                &now_block_number,
+            )?;
+            Self::use_payload_nonce(&payload.round, &pathfinder, payload.nonce);
+
+            Self::deposit_event_indexed(
+                &[&pathfinder],
+                Event::ReputationRefreshed(pathfinder.clone(), user_count as u32, total_fee),
+            );
+            Ok(().into())
+        }
+
+        /// Retract the refreshes of `targets` made by the caller in the current block.
+        ///
+        /// The staking is refunded to the caller and the fee is returned to each target, the
+        /// parts already shared with trustees cannot be reversed.
+        #[pallet::weight(T::WeightInfo::retract_refresh((targets.len() as u32).max(1u32)))]
+        #[transactional]
+        pub fn retract_refresh(
+            origin: OriginFor<T>,
+            targets: Vec<T::AccountId>,
+        ) -> DispatchResultWithPostInfo {
+            let pathfinder = ensure_signed(origin)?;
+            let count = targets.len() as u32;
+            ensure!(
+                count <= T::MaxUpdateCount::get(),
+                Error::<T>::QuantityLimitReached
+            );
+            Self::check_step_and_stared()?;
+            let now_block_number = Self::now();
+            let round = Self::current_round();
+            let records_key = RecordKey::new(round, pathfinder.clone());
+
+            let total_fee = targets
+                .iter()
+                .try_fold::<_, _, Result<Balance, DispatchError>>(
+                    Zero::zero(),
+                    |acc_amount, target| {
+                        let record = <Records<T>>::try_get(&records_key, target)
+                            .map_err(|_| Error::<T>::RecordNotExist)?;
+                        ensure!(
+                            record.update_at == now_block_number,
+                            Error::<T>::RetractTooLate
+                        );
+                        <Records<T>>::remove(&records_key, target);
+                        T::Reputation::revert_reputation_in(&record.domain, target)?;
+                        T::MultiBaseToken::release(target, &record.fee)?;
+                        acc_amount
+                            .checked_add(record.fee)
+                            .ok_or_else(|| Error::<T>::Overflow.into())
+                    },
+                )?;
+
+            Self::release_stake(round, &pathfinder, count)?;
+            Self::deduct_payroll(round, &pathfinder, count, total_fee)?;
+
+            Self::deposit_event(Event::RefreshRetracted(pathfinder, count, total_fee));
+            Ok(().into())
+        }
+
+        /// Withdraw the caller's refresh of `target` while it is still in the confirmation
+        /// period and has not been challenged.
+        ///
+        /// The reputation of `target` is restored, the staking is refunded to the caller and
+        /// the fee is returned to `target`, the parts already shared with trustees cannot be
+        /// reversed.
+        #[pallet::weight(T::WeightInfo::withdraw_refresh())]
+        #[transactional]
+        pub fn withdraw_refresh(
+            origin: OriginFor<T>,
+            target: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            let pathfinder = ensure_signed(origin)?;
+            Self::check_step_and_stared()?;
+            let round = Self::current_round();
+            let records_key = RecordKey::new(round, pathfinder.clone());
+            let record = Self::confirming_record(&records_key, &target)?;
+
+            <Records<T>>::remove(&records_key, &target);
+            T::Reputation::revert_reputation_in(&record.domain, &target)?;
+            T::MultiBaseToken::release(&target, &record.fee)?;
+            Self::release_stake(round, &pathfinder, 1)?;
+            Self::deduct_payroll(round, &pathfinder, 1, record.fee)?;
+
+            Self::deposit_event(Event::RefreshWithdrawn(pathfinder, target, record.fee));
+            Ok(().into())
+        }
+
+        /// Amend the score of the caller's refresh of `target` while it is still in the
+        /// confirmation period and has not been challenged.
+        ///
+        /// The staking and fee are kept, the confirmation period of the record and the
+        /// caller's payroll restarts from the current block.
+        #[pallet::weight(T::WeightInfo::amend_refresh())]
+        #[transactional]
+        pub fn amend_refresh(
+            origin: OriginFor<T>,
+            target: T::AccountId,
+            score: u32,
+        ) -> DispatchResultWithPostInfo {
+            let pathfinder = ensure_signed(origin)?;
+            Self::check_step_and_stared()?;
+            let now_block_number = Self::now();
+            Self::check_timeout(&now_block_number)?;
+            let round = Self::current_round();
+            let records_key = RecordKey::new(round, pathfinder.clone());
+            let record = Self::confirming_record(&records_key, &target)?;
+            ensure!(record.domain == DEFAULT_DOMAIN, Error::<T>::NotDefaultDomain);
+            ensure!(!Self::is_opted_out(&target), Error::<T>::UserOptedOut);
+            ensure!(
+                T::Reputation::get_reputation_new(&target) != Some(score),
+                Error::<T>::SameReputation
+            );
+
+            T::Reputation::mutate_reputation(&target, &score);
+            <Records<T>>::mutate(&records_key, &target, |record| {
+                record.update_at = now_block_number
+            });
+            <Payrolls<T>>::mutate(round, &pathfinder, |payroll| {
+                payroll.update_at = now_block_number
+            });
+
+            Self::deposit_event(Event::RefreshAmended(pathfinder, target, score));
+            Ok(().into())
+        }
+
+        /// The caller receives all their earnings of `round` and clears its update records.
+        ///
+        /// An `Err` will be returned if the user's last update has not passed the confirmation period.
+        ///
+        /// NOTE: This is more economical and efficient than collecting each item in turn.
+        #[pallet::weight(T::WeightInfo::harvest_ref_all())]
+        #[transactional]
+        pub fn harvest_ref_all(
+            origin: OriginFor<T>,
+            round: RoundIndex,
+        ) -> DispatchResultWithPostInfo {
+            let pathfinder = ensure_signed(origin)?;
+            Self::next_step();
+            let now_block_number = Self::now();
+            let payroll = Payrolls::<T>::take(round, &pathfinder);
+            Self::can_harvest(&payroll, &now_block_number)?;
+            let total_amount = payroll.total_amount::<T>(round, &pathfinder);
+            T::MultiBaseToken::release(&pathfinder, &total_amount)?;
+            Self::release_currency_stake(round, &pathfinder, &pathfinder, payroll.count)?;
+            PayrollCurrencies::<T>::remove(round, &pathfinder);
+            <Records<T>>::remove_prefix(&RecordKey::new(round, pathfinder.clone()));
+            Self::deposit_event_indexed(
+                &[&pathfinder],
+                Event::PayrollHarvested(pathfinder.clone(), total_amount, payroll.count),
+            );
+            Ok(().into())
+        }
+
+        /// Transfer the caller's payroll and update records of `round` to `to`.
+        ///
+        /// If `to` already has a payroll, the transfer is only allowed when `merge` is `true`,
+        /// the counts and fees are then summed and the records are moved under `to`.
+        ///
+        /// Is a no-op if:
+        ///
+        /// - The caller has no payroll, or
+        /// - The same user is recorded under both `pathfinder`, since this would conflate
+        /// two challenge windows, or
+        /// - The merged payroll exceeds the refresh limit.
+        #[pallet::weight(T::WeightInfo::transfer_payroll())]
+        #[transactional]
+        pub fn transfer_payroll(
+            origin: OriginFor<T>,
+            round: RoundIndex,
+            to: T::AccountId,
+            merge: bool,
+        ) -> DispatchResultWithPostInfo {
+            let from = ensure_signed(origin)?;
+            ensure!(from != to, Error::<T>::SamePathfinder);
+            let payroll =
+                Payrolls::<T>::try_get(round, &from).map_err(|_| Error::<T>::NoPayroll)?;
+            let from_key = RecordKey::new(round, from.clone());
+            let to_key = RecordKey::new(round, to.clone());
+            let is_merge = Payrolls::<T>::contains_key(round, &to);
+            let new_payroll = if is_merge {
+                ensure!(merge, Error::<T>::PayrollAlreadyExist);
+                ensure!(
+                    Self::payroll_currency(round, &from) == Self::payroll_currency(round, &to),
+                    Error::<T>::CurrencyMismatch
+                );
+                ensure!(
+                    !Records::<T>::iter_prefix(&from_key)
+                        .any(|(who, _)| Records::<T>::contains_key(&to_key, &who)),
+                    Error::<T>::RecordConflict
+                );
+                let to_payroll = Payrolls::<T>::get(round, &to);
+                Payroll {
+                    count: to_payroll
+                        .count
+                        .checked_add(payroll.count)
+                        .ok_or(Error::<T>::Overflow)?,
+                    total_fee: to_payroll
+                        .total_fee
+                        .checked_add(payroll.total_fee)
+                        .ok_or(Error::<T>::Overflow)?,
+                    update_at: to_payroll.update_at.max(payroll.update_at),
+                }
+            } else {
+                payroll
+            };
+            ensure!(new_payroll.count <= MAX_REFRESH, Error::<T>::ExceedMaxRefresh);
+
+            for (who, record) in Records::<T>::drain_prefix(&from_key) {
+                RefreshedBy::<T>::mutate(&who, |by| {
+                    if *by == Some((round, from.clone())) {
+                        *by = Some((round, to.clone()))
+                    }
+                });
+                Records::<T>::insert(&to_key, &who, record);
+            }
+            Payrolls::<T>::remove(round, &from);
+            Payrolls::<T>::insert(round, &to, new_payroll);
+            if let Some(currency) = PayrollCurrencies::<T>::take(round, &from) {
+                PayrollCurrencies::<T>::insert(round, &to, currency);
+            }
+
+            Self::deposit_event(match is_merge {
+                true => Event::PayrollMerged(from, to),
+                false => Event::PayrollTransferred(from, to),
+            });
+            Ok(().into())
+        }
+
+        /// The caller receives the earnings of the records of `round` that have passed the
+        /// confirmation period, records still in the period keep their staking locked.
+        ///
+        /// This is useful when only part of the records have matured, use `harvest_ref_all`
+        /// once all of them have.
+        #[pallet::weight(T::WeightInfo::harvest_ref_matured())]
+        #[transactional]
+        pub fn harvest_ref_matured(
+            origin: OriginFor<T>,
+            round: RoundIndex,
+        ) -> DispatchResultWithPostInfo {
+            let pathfinder = ensure_signed(origin)?;
+            Self::next_step();
+            let now_block_number = Self::now();
+            let records_key = RecordKey::new(round, pathfinder.clone());
+            let matured = <Records<T>>::iter_prefix(&records_key)
+                .take(MAX_REFRESH as usize)
+                .filter(|(_, record)| Self::is_matured(&record.update_at, &now_block_number))
+                .collect::<Vec<_>>();
+            ensure!(!matured.is_empty(), Error::<T>::NoMaturedRecord);
+
+            let (total_amount, still_locked) =
+                Self::harvest_records(round, &pathfinder, &matured[..])?;
+
+            Self::deposit_event_indexed(
+                &[&pathfinder],
+                Event::RefreshedPartiallyHarvested(pathfinder.clone(), total_amount, still_locked),
+            );
+            Ok(().into())
+        }
+
+        /// The caller receives the earnings of at most `max_records` records of a `round`
+        /// payroll that has passed the confirmation period, only those records are removed.
+        ///
+        /// Lets a pathfinder with many records harvest across several blocks, the rest of the
+        /// payroll can be harvested by later calls or `harvest_ref_all`.
+        #[pallet::weight(T::WeightInfo::harvest_ref_partial(max_records.min(MAX_REFRESH)))]
+        #[transactional]
+        pub fn harvest_ref_partial(
+            origin: OriginFor<T>,
+            round: RoundIndex,
+            max_records: u32,
+        ) -> DispatchResultWithPostInfo {
+            let pathfinder = ensure_signed(origin)?;
+            Self::next_step();
+            let payroll =
+                Payrolls::<T>::try_get(round, &pathfinder).map_err(|_| Error::<T>::NoPayroll)?;
+            Self::can_harvest(&payroll, &Self::now())?;
+            let records = <Records<T>>::iter_prefix(&RecordKey::new(round, pathfinder.clone()))
+                .take(max_records.min(MAX_REFRESH) as usize)
+                .collect::<Vec<_>>();
+            ensure!(!records.is_empty(), Error::<T>::NoMaturedRecord);
+
+            let (total_amount, still_locked) =
+                Self::harvest_records(round, &pathfinder, &records[..])?;
+
+            Self::deposit_event_indexed(
+                &[&pathfinder],
+                Event::RefreshedPartiallyHarvested(pathfinder.clone(), total_amount, still_locked),
+            );
+            Ok(().into())
+        }
+
+        /// `sweeper` collects `pathfinder` overdue proceeds of `round`.
+        ///
+        /// `sweeper` receives a percentage of the proceeds from it, growing with the time since
+        /// the last update along `SweeperCurve`.
+        ///
+        /// NOTE: It is the responsibility of `pathfinder` to secure the cleanliness of the data on the chain
         /// by collecting the proceeds and clearing the data in a timely manner. The `sweeper` policy ensures
         /// that the system runs smoothly.
         #[pallet::weight(T::WeightInfo::harvest_ref_all_sweeper())]
         #[transactional]
         pub fn harvest_ref_all_sweeper(
             origin: OriginFor<T>,
+            round: RoundIndex,
             pathfinder: T::AccountId,
         ) -> DispatchResultWithPostInfo {
             let sweeper = ensure_signed(origin)?;
             Self::next_step();
-            let payroll = Payrolls::<T>::take(&pathfinder);
+            let payroll = Payrolls::<T>::try_get(round, &pathfinder)
+                .map_err(|_| Error::<T>::NoPayroll)?;
+            Payrolls::<T>::remove(round, &pathfinder);
             let now_block_number = Self::now();
             Self::can_harvest(&payroll, &now_block_number)?;
-            let (sweeper_fee, without_fee) = payroll
-                .total_amount::<T>()
-                .checked_with_fee(payroll.update_at, Self::now())
-                .ok_or(Error::<T>::FailedSweeper)?;
-            <Records<T>>::remove_prefix(&pathfinder);
+            let (sweeper_fee, without_fee) = Self::sweeper_split(
+                payroll.total_amount::<T>(round, &pathfinder),
+                &payroll.update_at,
+                &now_block_number,
+            )
+            .ok_or(Error::<T>::FailedSweeper)?;
+            Self::release_currency_stake(round, &pathfinder, &pathfinder, payroll.count)?;
+            PayrollCurrencies::<T>::remove(round, &pathfinder);
+            <Records<T>>::remove_prefix(&RecordKey::new(round, pathfinder.clone()));
             T::MultiBaseToken::release(&sweeper, &sweeper_fee)?;
             T::MultiBaseToken::release(&pathfinder, &without_fee)?;
-            Self::deposit_event(Event::RefreshedHarvestedBySweeper(
-                pathfinder,
-                sweeper,
-                without_fee,
-                sweeper_fee,
-            ));
+            Self::deposit_event_indexed(
+                &[&sweeper, &pathfinder],
+                Event::PayrollProxySettled(sweeper, pathfinder, sweeper_fee, without_fee),
+            );
             Ok(().into())
         }
 
         /// Receive the benefits of a challenge against `target`.
         ///
         /// The caller must be the winner of the challenge.
+        ///
+        /// If the pathfinder lost the challenge, its staking for the refresh of `target` is
+        /// split between the challenger and `TreasuryAccountId` by `ChallengerSlashRatio`,
+        /// otherwise it is returned to the pathfinder.
         #[pallet::weight(T::WeightInfo::harvest_challenge())]
         #[transactional]
         pub fn harvest_challenge(
@@ -439,15 +1933,56 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
             Self::next_step();
-            T::ChallengeBase::harvest(&who, &APP_ID, &target)?;
+            let outcome = T::ChallengeBase::outcome(&APP_ID, &target);
+            T::ChallengeBase::harvest(&who, &APP_ID, &target).map_err(Self::challenge_error)?;
+            if let Some((challenger, share)) = outcome {
+                Self::settle_challenged_stake(&target, &challenger, share)?;
+            }
             Self::deposit_event(Event::ChallengeHarvested(who, target));
             Ok(().into())
         }
 
+        /// Back the challenge to `target` by staking `amount` alongside the challenger.
+        ///
+        /// The backers share what the challenger side wins pro rata to their stakes and lose
+        /// them if it loses. They get their stakes back if the challenge is cancelled.
+        #[pallet::weight(T::WeightInfo::back_challenge())]
+        #[transactional]
+        pub fn back_challenge(
+            origin: OriginFor<T>,
+            target: T::AccountId,
+            amount: Balance,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            Self::check_step()?;
+            T::ChallengeBase::back(&APP_ID, &who, &target, amount).map_err(Self::challenge_error)?;
+            Self::deposit_event(Event::ChallengeBacked(who, target, amount));
+            Ok(().into())
+        }
+
+        /// Cancel the challenge to `target` launched by mistake.
+        ///
+        /// Only the challenger may cancel, and only before all the paths are uploaded. The
+        /// challenge deposit minus `CancellationFee` is refunded, the rest of the prize pool
+        /// goes to the pathfinder and so does its staking for the refresh of `target`.
+        #[pallet::weight(T::WeightInfo::cancel_challenge())]
+        #[transactional]
+        pub fn cancel_challenge(
+            origin: OriginFor<T>,
+            target: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            let challenger = ensure_signed(origin)?;
+            T::ChallengeBase::cancel(&APP_ID, &challenger, &target).map_err(Self::challenge_error)?;
+            Self::settle_challenged_stake(&target, &challenger, Perbill::one())?;
+            Self::deposit_event(Event::ChallengeCancelled(challenger, target));
+            Ok(().into())
+        }
+
         /// Launching the challenge.
         ///
         /// A challenge to the `target` reputation updated by `pathfinder` requires a total
         /// of `quantity` of paths to be uploaded, the correct reputation score is `score`.
+        /// `attachment` may reference an off-chain proof, such as an IPFS CID.
         ///
         /// Is a no-op if:
         ///
@@ -467,6 +2002,7 @@ pub mod pallet {
             pathfinder: T::AccountId,
             quantity: u32,
             score: u32,
+            attachment: Option<Vec<u8>>,
         ) -> DispatchResultWithPostInfo {
             let challenger = ensure_signed(origin)?;
             Self::check_step()?;
@@ -477,15 +2013,31 @@ pub mod pallet {
             let reputation =
                 T::Reputation::get_reputation_new(&target).ok_or(Error::<T>::ReputationError)?;
             ensure!(score != reputation, Error::<T>::SameReputation);
-            let record = <Records<T>>::take(&pathfinder, &target);
+            let round = Self::current_round();
+            let records_key = RecordKey::new(round, pathfinder.clone());
+            let record = <Records<T>>::try_get(&records_key, &target)
+                .map_err(|_| Error::<T>::RecordNotExist)?;
+            ensure!(record.domain == DEFAULT_DOMAIN, Error::<T>::NotDefaultDomain);
             ensure!(
-                record.update_at + T::ConfirmationPeriod::get() > Self::now(),
+                Self::is_in_confirmation(&record.update_at),
                 Error::<T>::ChallengeTimeout
             );
-            Payrolls::<T>::mutate(&pathfinder, |f| {
+            <Records<T>>::remove(&records_key, &target);
+            Payrolls::<T>::mutate(round, &pathfinder, |f| {
                 f.total_fee = f.total_fee.saturating_sub(record.fee);
                 f.count = f.count.saturating_sub(1);
             });
+            let stake = match Self::payroll_currency(round, &pathfinder) {
+                Some((currency, amount)) => {
+                    ChallengedCurrencies::<T>::insert(&target, currency);
+                    amount
+                }
+                None => Self::staking_amount(round),
+            };
+            <ChallengedStakes<T>>::insert(&target, (pathfinder.clone(), stake));
+            RoundStats::<T>::mutate(round, |stat| {
+                stat.challenges = stat.challenges.saturating_add(1);
+            });
 
             T::ChallengeBase::launch(
                 &APP_ID,
@@ -504,7 +2056,10 @@ pub mod pallet {
                     },
                     ..Metadata::default()
                 },
-            )?;
+                (score.into(), reputation.into()),
+                attachment,
+            )
+            .map_err(Self::challenge_error)?;
 
             T::ChallengeBase::set_status(&APP_ID, &target, &ChallengeStatus::Arbitral);
             Self::deposit_event(Event::Challenge(challenger, target));
@@ -538,18 +2093,96 @@ pub mod pallet {
                 &APP_ID,
                 &who,
                 &target,
-                |score, remark| -> Result<(bool, bool, u64), _> {
-                    let score = score as u32;
+                |score, remark, _| -> Result<(bool, bool, _), _> {
+                    let score = score.saturated_into::<u32>();
                     let new_score =
                         Self::do_update_path_verify(&target, &seeds[..], &paths[..], score)?;
                     T::Reputation::mutate_reputation(&target, &new_score);
                     Ok((new_score == remark, false, new_score.into()))
                 },
-            )?;
+            )
+            .map_err(Self::challenge_error)?;
             Self::deposit_event(Event::Arbitral(who, target));
             Ok(().into())
         }
 
+        /// Appeal the last arbitration of the challenge to `target`.
+        ///
+        /// Only the side the arbitration went against may appeal, within `AppealPeriod` of
+        /// it, staking `AppealStakingAmount`. The challenge cannot be harvested until the
+        /// arbiters resolve the appeal with `vote_appeal`, or `AppealOrigin` with
+        /// `resolve_appeal` once they have not within the voting period.
+        #[pallet::weight(T::WeightInfo::appeal())]
+        #[transactional]
+        pub fn appeal(origin: OriginFor<T>, target: T::AccountId) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            Self::check_step()?;
+            T::ChallengeBase::appeal(&APP_ID, &who, &target).map_err(Self::challenge_error)?;
+            Self::deposit_event(Event::Appealed(who, target));
+            Ok(().into())
+        }
+
+        /// Resolve the appeal of the challenge to `target` with the correct reputation `score`.
+        ///
+        /// The arbitration is confirmed if `score` is the arbitrated score, the appeal bond then
+        /// goes to the prize pool. Otherwise it is overturned, the reputation of `target` is set
+        /// to `score` and the bond returned to the appellant.
+        ///
+        /// Only allowed once the voting period of the appeal has passed without the arbiters
+        /// reaching a quorum with `vote_appeal`.
+        ///
+        /// The dispatch origin for this call must be `AppealOrigin`.
+        #[pallet::weight(T::WeightInfo::resolve_appeal())]
+        #[transactional]
+        pub fn resolve_appeal(
+            origin: OriginFor<T>,
+            target: T::AccountId,
+            score: u32,
+        ) -> DispatchResultWithPostInfo {
+            T::AppealOrigin::ensure_origin(origin)?;
+            let overturned = T::ChallengeBase::resolve_appeal(
+                &APP_ID,
+                &target,
+                |_, remark, _| -> Result<(bool, bool, _), _> {
+                    T::Reputation::mutate_reputation(&target, &score);
+                    Ok((score == remark, false, score.into()))
+                },
+            )
+            .map_err(Self::challenge_error)?;
+            Self::deposit_event(Event::AppealResolved(target, overturned));
+            Ok(().into())
+        }
+
+        /// Vote, as one of the arbiters of the challenge system, that `score` is the correct
+        /// reputation of the appealed challenge to `target`.
+        ///
+        /// The appeal is resolved as by `resolve_appeal` once enough arbiters have voted for
+        /// the same `score`.
+        #[pallet::weight(T::WeightInfo::vote_appeal())]
+        #[transactional]
+        pub fn vote_appeal(
+            origin: OriginFor<T>,
+            target: T::AccountId,
+            score: u32,
+        ) -> DispatchResultWithPostInfo {
+            let arbiter = ensure_signed(origin)?;
+            let resolved = T::ChallengeBase::vote_appeal(
+                &APP_ID,
+                &arbiter,
+                &target,
+                score.into(),
+                |_, remark, _| -> Result<(bool, bool, _), _> {
+                    T::Reputation::mutate_reputation(&target, &score);
+                    Ok((score == remark, false, score.into()))
+                },
+            )
+            .map_err(Self::challenge_error)?;
+            if let Some(overturned) = resolved {
+                Self::deposit_event(Event::AppealResolved(target, overturned));
+            }
+            Ok(().into())
+        }
+
         /// Challenger upload path.
         ///
         /// Accepts the correct path `paths` for `seeds` under `target` , `seeds` and `paths`
@@ -584,25 +2217,99 @@ pub mod pallet {
                     }
                     Ok((new_score as u64, remark))
                 },
-            )?;
+            )
+            .map_err(Self::challenge_error)?;
             Self::deposit_event(Event::PathUpdated(challenger, target));
             Ok(().into())
         }
     }
+
+    #[pallet::validate_unsigned]
+    impl<T: Config> ValidateUnsigned for Pallet<T> {
+        type Call = Call<T>;
+
+        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            if let Call::refresh_unsigned(payload, signature) = call {
+                Self::check_payload(payload, signature).map_err(|e| match e {
+                    Error::<T>::BadSignature => InvalidTransaction::BadProof,
+                    Error::<T>::TooManyUserScores => InvalidTransaction::ExhaustsResources,
+                    _ => InvalidTransaction::Stale,
+                })?;
+                ValidTransaction::with_tag_prefix("ZdRefreshReputation")
+                    .priority(T::UnsignedPriority::get())
+                    .and_provides((payload.round, payload.pathfinder.clone(), payload.nonce))
+                    .longevity(64_u64)
+                    .propagate(true)
+                    .build()
+            } else {
+                InvalidTransaction::Call.into()
+            }
+        }
+    }
 }
 
 impl<T: Config> Pallet<T> {
+    /// Move `Payrolls` and `Records` from their `Releases::V1` layout, keyed by pathfinder
+    /// alone, to `Releases::V2`, returning the weight consumed.
+    ///
+    /// `V1` kept a single payroll per pathfinder, which `start` settled before the next
+    /// round, so all of them are moved under the current round. `V1` records had no domain,
+    /// they are all refreshes of `DEFAULT_DOMAIN` and indexed in `RefreshedBy`.
+    pub(crate) fn migrate_to_v2() -> Weight {
+        let payrolls = StorageIterator::<Payroll<Balance, T::BlockNumber>>::new(
+            Payrolls::<T>::module_prefix(),
+            Payrolls::<T>::storage_prefix(),
+        )
+        .drain()
+        .collect::<Vec<_>>();
+        let records = StorageIterator::<(T::BlockNumber, Balance)>::new(
+            Records::<T>::module_prefix(),
+            Records::<T>::storage_prefix(),
+        )
+        .drain()
+        .collect::<Vec<_>>();
+
+        let round = Self::current_round();
+        for (raw_key, payroll) in payrolls.iter() {
+            if let Ok(pathfinder) = T::AccountId::decode(&mut Twox64Concat::reverse(raw_key)) {
+                Payrolls::<T>::insert(round, &pathfinder, payroll);
+            }
+        }
+        for (raw_key, (update_at, fee)) in records.iter() {
+            let mut key = Twox64Concat::reverse(raw_key);
+            let pathfinder = T::AccountId::decode(&mut key);
+            let target = T::AccountId::decode(&mut Twox64Concat::reverse(key));
+            if let (Ok(pathfinder), Ok(target)) = (pathfinder, target) {
+                let record = Record {
+                    update_at: *update_at,
+                    fee: *fee,
+                    domain: DEFAULT_DOMAIN,
+                };
+                RefreshedBy::<T>::insert(&target, (round, pathfinder.clone()));
+                Records::<T>::insert(&RecordKey::new(round, pathfinder), &target, record);
+            }
+        }
+        StorageVersion::<T>::put(Releases::V2);
+
+        let moved = payrolls.len().saturating_add(records.len()) as Weight;
+        T::DbWeight::get().reads_writes(
+            moved.saturating_add(2),
+            moved.saturating_mul(2).saturating_add(records.len() as Weight) + 1,
+        )
+    }
+
     // pub
 
-    /// Add `pathfinder` `amount` of accounts payable, and `count` updates to the original,
-    /// and set last active time to `now`.
+    /// Add `pathfinder` `amount` of accounts payable in `round`, and `count` updates to the
+    /// original, and set last active time to `now`.
     pub fn mutate_payroll(
+        round: RoundIndex,
         pathfinder: &T::AccountId,
         amount: &Balance,
         count: &u32,
         now: &T::BlockNumber,
     ) -> DispatchResult {
-        <Payrolls<T>>::try_mutate(&pathfinder, |f| -> DispatchResult {
+        <Payrolls<T>>::try_mutate(round, &pathfinder, |f| -> DispatchResult {
             let total_fee = f
                 .total_fee
                 .checked_add(*amount)
@@ -618,24 +2325,366 @@ impl<T: Config> Pallet<T> {
         })
     }
 
-    /// Add or modify a challenge record for `who` under `pathfinder`, where the processing fee
-    /// obtained is `fee`, and set the update time to `now`.
-    pub fn mutate_record(
-        pathfinder: &T::AccountId,
-        who: &T::AccountId,
-        fee: &Balance,
-        now: &T::BlockNumber,
-    ) {
-        <Records<T>>::mutate(&pathfinder, &who, |r| {
-            *r = Record {
-                update_at: *now,
-                fee: *fee,
+    /// Add or modify a challenge record for `who` under `pathfinder` in `round`, where the
+    /// processing fee obtained is `fee`, and set the update time to `now`.
+    pub fn mutate_record(
+        round: RoundIndex,
+        pathfinder: &T::AccountId,
+        who: &T::AccountId,
+        fee: &Balance,
+        now: &T::BlockNumber,
+    ) {
+        Self::mutate_record_in(round, pathfinder, &DEFAULT_DOMAIN, who, fee, now)
+    }
+
+    /// `mutate_record` of a refresh of `who` in `domain`.
+    pub fn mutate_record_in(
+        round: RoundIndex,
+        pathfinder: &T::AccountId,
+        domain: &DomainId,
+        who: &T::AccountId,
+        fee: &Balance,
+        now: &T::BlockNumber,
+    ) {
+        <Records<T>>::mutate(&RecordKey::new(round, pathfinder.clone()), &who, |r| {
+            *r = Record {
+                update_at: *now,
+                fee: *fee,
+                domain: *domain,
+            }
+        });
+        if *domain == DEFAULT_DOMAIN {
+            RefreshedBy::<T>::insert(who, (round, pathfinder.clone()));
+        }
+    }
+
+    /// Check that a `refresh` of `MaxUpdateCount` users is within `MAX_UPDATE_LENGTH_RATIO`
+    /// of the normal block length.
+    pub fn check_update_length() -> Result<(), &'static str> {
+        Self::check_update_length_of(T::MaxUpdateCount::get())
+    }
+
+    /// A page of at most `limit` payrolls after the raw storage key `start_key`, as
+    /// `(round, pathfinder, count, total_amount)`, and the key to continue from if any remain.
+    ///
+    /// `limit` is capped at `MAX_PAYROLLS_PAGE`.
+    pub fn all_pending_payrolls(
+        limit: u32,
+        start_key: Option<Vec<u8>>,
+    ) -> (Vec<(RoundIndex, T::AccountId, u32, Balance)>, Option<Vec<u8>>) {
+        let limit = limit.min(MAX_PAYROLLS_PAGE) as usize;
+        let mut payrolls =
+            iter_double_map_from::<RoundIndex, T::AccountId, Payroll<Balance, T::BlockNumber>>(
+                &Payrolls::<T>::final_prefix(),
+                start_key,
+            );
+        let page = payrolls
+            .by_ref()
+            .take(limit)
+            .map(|(round, pathfinder, payroll)| {
+                let total_amount = payroll.total_amount::<T>(round, &pathfinder);
+                (round, pathfinder, payroll.count, total_amount)
+            })
+            .collect::<Vec<_>>();
+        let next_key = match (page.last(), payrolls.next()) {
+            (Some((round, pathfinder, _, _)), Some(_)) => {
+                Some(Payrolls::<T>::hashed_key_for(round, pathfinder))
+            }
+            _ => None,
+        };
+        (page, next_key)
+    }
+
+    /// Whether `harvest_ref_all_sweeper` for `pathfinder` in `round` would succeed now.
+    pub fn can_sweeper_harvest(round: RoundIndex, pathfinder: &T::AccountId) -> bool {
+        let payroll = match Payrolls::<T>::try_get(round, pathfinder) {
+            Ok(payroll) => payroll,
+            Err(_) => return false,
+        };
+        let now_block_number = Self::now();
+        Self::can_harvest(&payroll, &now_block_number).is_ok()
+            && Self::is_sweepable(&payroll.update_at, &now_block_number)
+            && T::MultiBaseToken::get_total_staking()
+                >= payroll.total_amount::<T>(round, pathfinder)
+    }
+
+    /// The exposure of `pathfinder` in `round`, as `(count, total_fee)` of its pending
+    /// `Payroll`, the number of its outstanding `Records` and the update staking they lock,
+    /// in the currency of the payroll.
+    pub fn pathfinder_exposure(
+        round: RoundIndex,
+        pathfinder: &T::AccountId,
+    ) -> (u32, Balance, u32, Balance) {
+        let payroll = Self::get_payroll(round, pathfinder);
+        let (records, _) = Self::sum_records(round, pathfinder);
+        let staking_amount = match Self::payroll_currency(round, pathfinder) {
+            Some((_, amount)) => amount,
+            None => Self::staking_amount(round),
+        };
+        let staked = staking_amount.saturating_mul(records as Balance);
+        (payroll.count, payroll.total_fee, records, staked)
+    }
+
+    /// The amount staked for each user refreshed in `round`.
+    pub fn staking_amount(round: RoundIndex) -> Balance {
+        RoundStakingAmounts::<T>::get(round).unwrap_or_else(T::UpdateStakingAmount::get)
+    }
+
+    /// The staking amount the next round will start with.
+    pub fn next_staking_amount() -> Balance {
+        <StakingAmount<T>>::get().unwrap_or_else(T::UpdateStakingAmount::get)
+    }
+
+    /// Whether the `Payroll` of `pathfinder` in `round` matches the count and fees of its
+    /// `Records`.
+    pub fn check_payroll_integrity(round: RoundIndex, pathfinder: &T::AccountId) -> bool {
+        let payroll = Self::get_payroll(round, pathfinder);
+        let (count, total_fee) = Self::sum_records(round, pathfinder);
+        payroll.count == count && payroll.total_fee == total_fee
+    }
+
+    /// The total number of users trusted by the users in `user_scores` before the refresh
+    /// started, this is how many accounts `refresh` shares to.
+    pub fn total_trust_count(user_scores: &[(T::AccountId, u32)]) -> u32 {
+        user_scores
+            .iter()
+            .take(T::MaxUpdateCount::get() as usize)
+            .fold(0u32, |acc, (who, _)| {
+                acc.saturating_add(T::TrustBase::get_trust_count_old(who) as u32)
+            })
+    }
+
+    /// The social balances of `accounts`, which drive the refresh fees.
+    ///
+    /// Only the first `MaxUpdateCount` accounts are read, as many as can be refreshed at once.
+    pub fn social_balances(accounts: &[T::AccountId]) -> Vec<Balance> {
+        accounts
+            .iter()
+            .take(T::MaxUpdateCount::get() as usize)
+            .map(|who| T::MultiBaseToken::social_balance(who))
+            .collect()
+    }
+
+    /// The Merkle root of a batch of `user_scores`.
+    ///
+    /// Each leaf is the hash of a `(user, score)` pair, each pair of nodes is hashed in sorted
+    /// order and the last node of a level with an odd number of nodes is carried up as is.
+    pub fn batch_root(user_scores: &[(T::AccountId, u32)]) -> T::Hash {
+        let mut nodes = Self::batch_leaves(user_scores);
+        while nodes.len() > 1 {
+            nodes = Self::merkle_level(&nodes[..]);
+        }
+        nodes.pop().unwrap_or_default()
+    }
+
+    /// The proof of the entry at `index` of `user_scores` against `batch_root`, that is the
+    /// siblings of the nodes on its way to the root.
+    pub fn batch_proof(user_scores: &[(T::AccountId, u32)], mut index: usize) -> Vec<T::Hash> {
+        let mut nodes = Self::batch_leaves(user_scores);
+        let mut proof = Vec::new();
+        while nodes.len() > 1 {
+            if let Some(sibling) = nodes.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+            nodes = Self::merkle_level(&nodes[..]);
+        }
+        proof
+    }
+
+    /// Whether `pathfinder` refreshed `who` with `score` in `round`, in the batch committed
+    /// with `root`, as proven by `proof` from `batch_proof`.
+    pub fn verify_score(
+        round: RoundIndex,
+        pathfinder: &T::AccountId,
+        root: &T::Hash,
+        who: &T::AccountId,
+        score: u32,
+        proof: &[T::Hash],
+    ) -> bool {
+        let leaf = T::Hashing::hash_of(&(who, score));
+        let computed = proof
+            .iter()
+            .fold(leaf, |node, sibling| Self::merkle_pair(&node, sibling));
+        computed == *root && <Commitments<T>>::contains_key(&(round, pathfinder.clone()), root)
+    }
+
+    /// The hash to `commit_refresh` for revealing `user_scores` with `salt` by `pathfinder`.
+    pub fn commitment_hash(
+        pathfinder: &T::AccountId,
+        user_scores: &[(T::AccountId, u32)],
+        salt: &T::Hash,
+    ) -> T::Hash {
+        T::Hashing::hash_of(&(pathfinder, user_scores, salt))
+    }
+
+    /// Whether `who` has opted out of being refreshed.
+    pub fn is_opted_out(who: &T::AccountId) -> bool {
+        OptedOut::<T>::contains_key(who)
+    }
+
+    /// Whether `pathfinder` is suspended from refreshing in the current round.
+    pub fn is_suspended(pathfinder: &T::AccountId) -> bool {
+        Self::current_round() < Self::pathfinder_status(pathfinder).suspended_until
+    }
+
+    /// The error of this pallet for `error` returned by `ChallengeBase`.
+    pub(crate) fn challenge_error(error: ChallengeError) -> DispatchError {
+        match error {
+            ChallengeError::NonExistent => Error::<T>::ChallengeNotExist.into(),
+            ChallengeError::WrongPhase => Error::<T>::ChallengeWrongPhase.into(),
+            ChallengeError::NotYourTurn => Error::<T>::NotYourTurn.into(),
+            ChallengeError::Other(e) => e,
+        }
+    }
+
+    /// Whether the reputation of `target` refreshed by `pathfinder` can be challenged now.
+    ///
+    /// This is the same check as `challenge`, returns `false` if there is no record in the
+    /// current round or `ChallengeBase` would not accept a new challenge against `target`.
+    pub fn is_challengeable(pathfinder: &T::AccountId, target: &T::AccountId) -> bool {
+        let now = Self::now();
+        Self::check_step().is_ok()
+            && T::ChallengeBase::is_challengeable(&APP_ID, target, &now)
+            && <Records<T>>::try_get(
+                &RecordKey::new(Self::current_round(), pathfinder.clone()),
+                target,
+            )
+            .map(|record| {
+                record.domain == DEFAULT_DOMAIN && Self::is_in_confirmation(&record.update_at)
+            })
+            .unwrap_or(false)
+    }
+
+    /// The reputation of `target` a challenge would dispute, and whether its refresh in the
+    /// current round, found through `RefreshedBy`, is still within the confirmation period.
+    pub fn challenge_state(target: &T::AccountId) -> (u64, bool) {
+        let score = T::Reputation::get_reputation_new(target).unwrap_or_default();
+        let in_confirmation = Self::refreshed_by(target)
+            .filter(|(round, _)| *round == Self::current_round())
+            .and_then(|(round, pathfinder)| {
+                <Records<T>>::try_get(&RecordKey::new(round, pathfinder), target).ok()
+            })
+            .map(|record| {
+                record.domain == DEFAULT_DOMAIN && Self::is_in_confirmation(&record.update_at)
+            })
+            .unwrap_or(false);
+        (score.into(), in_confirmation)
+    }
+
+    /// Verify that every `Payroll` agrees with the `Records` of its `pathfinder`, that no
+    /// record outlives its payroll, and that the staking pool covers the total amount owed
+    /// to all `pathfinder` and their bonds.
+    ///
+    /// The staking pool of `MultiBaseToken` is shared with the other pallets staking through
+    /// it, such as refresh-seeds and challenges, so it holds at least what this pallet owes
+    /// rather than exactly that. It only equals the total amount when no other pallet stakes.
+    pub fn check_invariants() -> Result<(), &'static str> {
+        let mut total_amount: Balance = Zero::zero();
+        for (round, pathfinder, payroll) in Payrolls::<T>::iter() {
+            let (count, total_fee) = Self::sum_records(round, &pathfinder);
+            ensure!(payroll.count == count, "Payroll count does not match records");
+            ensure!(
+                payroll.total_fee == total_fee,
+                "Payroll fee does not match records"
+            );
+            total_amount =
+                total_amount.saturating_add(payroll.total_amount::<T>(round, &pathfinder));
+        }
+        for (RecordKey { round, pathfinder }, _, _) in Records::<T>::iter() {
+            ensure!(
+                Payrolls::<T>::contains_key(round, &pathfinder),
+                "Record outlives its payroll"
+            );
+        }
+        for (_, info) in Pathfinders::<T>::iter() {
+            total_amount = total_amount.saturating_add(info.bond);
+        }
+        for (target, (_, stake)) in ChallengedStakes::<T>::iter() {
+            if !ChallengedCurrencies::<T>::contains_key(&target) {
+                total_amount = total_amount.saturating_add(stake);
+            }
+        }
+        ensure!(
+            T::MultiBaseToken::get_total_staking() >= total_amount,
+            "Staking pool does not cover payrolls"
+        );
+        Ok(())
+    }
+
+    /// Verify the state at block `n` for `try-runtime` checks on real chain state:
+    ///
+    /// - The ratios of social currency splitting sum to at most 100%.
+    /// - Every `Payroll` agrees with the `Records` of its `pathfinder`.
+    /// - No `Payroll` or `Record` has been updated after `n`.
+    /// - The staking pool covers the total amount owed to all `pathfinder`.
+    ///
+    /// At most `TRY_STATE_MAX_PAYROLLS` payrolls are checked, the staking pool is only
+    /// checked if all of them were.
+    #[cfg(any(feature = "try-runtime", test))]
+    pub fn do_try_state(n: T::BlockNumber) -> Result<(), &'static str> {
+        use zd_primitives::per_social_currency;
+
+        let (share, reserved, fee) = T::MultiBaseToken::social_ratios();
+        let ratios = [share, reserved, per_social_currency::PRE_BURN, fee];
+        ensure!(
+            ratios
+                .iter()
+                .map(|ratio| ratio.deconstruct() as u64)
+                .sum::<u64>()
+                <= Perbill::one().deconstruct() as u64,
+            "Social currency ratios exceed 100%"
+        );
+
+        let mut total_amount: Balance = Zero::zero();
+        let mut checked = 0usize;
+        for (round, pathfinder, payroll) in Payrolls::<T>::iter() {
+            if checked == TRY_STATE_MAX_PAYROLLS {
+                return Ok(());
             }
-        });
+            checked += 1;
+            ensure!(payroll.update_at <= n, "Payroll updated in the future");
+            ensure!(
+                Records::<T>::iter_prefix_values(&RecordKey::new(round, pathfinder.clone()))
+                    .take(MAX_REFRESH as usize + 1)
+                    .all(|record| record.update_at <= n),
+                "Record updated in the future"
+            );
+            let (count, total_fee) = Self::sum_records(round, &pathfinder);
+            ensure!(payroll.count == count, "Payroll count does not match records");
+            ensure!(
+                payroll.total_fee == total_fee,
+                "Payroll fee does not match records"
+            );
+            total_amount =
+                total_amount.saturating_add(payroll.total_amount::<T>(round, &pathfinder));
+        }
+        ensure!(
+            T::MultiBaseToken::get_total_staking() >= total_amount,
+            "Staking pool does not cover payrolls"
+        );
+        Ok(())
     }
 
     // pub(crate)
 
+    pub(crate) fn check_update_length_of(max_update_count: u32) -> Result<(), &'static str> {
+        ensure!(
+            max_update_count <= MAX_REFRESH,
+            "MaxUpdateCount is larger than MAX_REFRESH"
+        );
+        let entry_length = (T::AccountId::default(), 0u32).encode().len() as u64;
+        let max_length = *<T as system::Config>::BlockLength::get()
+            .max
+            .get(DispatchClass::Normal) as u64;
+        ensure!(
+            entry_length.saturating_mul(max_update_count.into())
+                <= MAX_UPDATE_LENGTH_RATIO.mul_floor(max_length),
+            "MaxUpdateCount is too large for the block length"
+        );
+        Ok(())
+    }
+
     pub(crate) fn check_step() -> DispatchResult {
         ensure!(
             T::Reputation::is_step(&TIRStep::Reputation),
@@ -648,31 +2697,150 @@ impl<T: Config> Pallet<T> {
         if <StartedAt<T>>::exists() {
             let now = Self::now();
             let is_last_ref_timeout =
-                T::Reputation::get_last_refresh_at() + T::ConfirmationPeriod::get() < now;
+                T::Reputation::get_last_refresh_at() + Self::confirmation_period() < now;
             let is_cha_all_timeout = T::ChallengeBase::is_all_timeout(&APP_ID, &now);
             if is_last_ref_timeout && is_cha_all_timeout {
-                T::TrustBase::remove_all_tmp();
-                T::Reputation::set_free();
-                <StartedAt<T>>::kill();
+                Self::close_round();
+            }
+        }
+    }
+
+    /// Stake for and refresh `user_scores` in `domain` under `pathfinder` at `now`, returning
+    /// the total fee.
+    ///
+    /// A pathfinder keeps one record per target and round, so a target it already refreshed
+    /// in the round, in any domain, is rejected with `RecordExists`. A target another
+    /// pathfinder refreshed in `DEFAULT_DOMAIN` is superseded under `RefreshConflict::Override`.
+    pub(crate) fn refresh_users(
+        pathfinder: &T::AccountId,
+        domain: &DomainId,
+        user_scores: &[(T::AccountId, u32)],
+        now: &T::BlockNumber,
+    ) -> Result<Balance, DispatchError> {
+        ensure!(
+            <Self as PathfinderBase<_, _>>::is_pathfinder(pathfinder),
+            Error::<T>::NotPathfinder
+        );
+        ensure!(!Self::is_suspended(pathfinder), Error::<T>::PathfinderSuspended);
+        ensure!(
+            !user_scores.iter().any(|(who, _)| Self::is_opted_out(who)),
+            Error::<T>::UserOptedOut
+        );
+        let user_count = user_scores.len();
+        let round = Self::current_round();
+        let records_key = RecordKey::new(round, pathfinder.clone());
+        ensure!(
+            !user_scores
+                .iter()
+                .any(|(who, _)| Records::<T>::contains_key(&records_key, who)),
+            Error::<T>::RecordExists
+        );
+        let old_count = Self::get_payroll(round, pathfinder).count;
+        ensure!(
+            old_count.saturating_add(user_count as u32) < MAX_REFRESH,
+            Error::<T>::ExceedMaxRefresh
+        );
+        ensure!(
+            old_count.saturating_add(user_count as u32) <= T::MaxPayrollRecords::get(),
+            Error::<T>::PayrollCapReached
+        );
+
+        if *domain == DEFAULT_DOMAIN && T::ConflictPolicy::get() == RefreshConflict::Override {
+            for (who, _) in user_scores.iter() {
+                Self::supersede(round, pathfinder, who)?;
+            }
+        }
+
+        let (currency, staking_amount) = Self::refresh_staking(round, pathfinder)?;
+        let amount = staking_amount
+            .checked_mul(user_count as Balance)
+            .ok_or(Error::<T>::Overflow)?;
+        match currency {
+            Some(currency) => T::CurrencyStaking::staking_in(currency, pathfinder, &amount)?,
+            None => {
+                ensure!(
+                    T::MultiBaseToken::free_balance(pathfinder)
+                        .saturating_add(T::MultiBaseToken::pending_balance(pathfinder))
+                        >= amount,
+                    Error::<T>::InsufficientStake
+                );
+                T::MultiBaseToken::staking(pathfinder, &amount)?;
+            }
+        }
+        let old_scores = user_scores
+            .iter()
+            .map(|(who, _)| T::Reputation::get_reputation_in(domain, who))
+            .collect::<Vec<_>>();
+        // Whether each score changed is read from the old score in `UserScoreRefreshed`.
+        T::Reputation::bulk_refresh_in(domain, user_scores)?;
+        let total_fee = user_scores
+            .iter()
+            .try_fold::<_, _, Result<Balance, DispatchError>>(
+                Zero::zero(),
+                |acc_amount, (who, _)| {
+                    let fee = Self::do_refresh(round, pathfinder, domain, who, now)?;
+                    acc_amount
+                        .checked_add(fee)
+                        .ok_or_else(|| Error::<T>::Overflow.into())
+                },
+            )?;
+        let is_new_pathfinder = !Payrolls::<T>::contains_key(round, pathfinder);
+        Self::mutate_payroll(round, pathfinder, &total_fee, &(user_count as u32), now)?;
+        ensure!(
+            Self::get_payroll(round, pathfinder).total_amount::<T>(round, pathfinder)
+                <= T::MaxPayrollAmount::get(),
+            Error::<T>::PayrollCapReached
+        );
+        RoundStats::<T>::mutate(round, |stat| {
+            stat.users = stat.users.saturating_add(user_count as u32);
+            stat.pathfinders = stat.pathfinders.saturating_add(is_new_pathfinder as u32);
+            stat.total_fee = stat.total_fee.saturating_add(total_fee);
+            if currency.is_none() {
+                stat.total_staked = stat.total_staked.saturating_add(amount);
             }
+        });
+        <Commitments<T>>::insert(
+            &(round, pathfinder.clone()),
+            Self::batch_root(user_scores),
+            now,
+        );
+        for ((who, score), old) in user_scores.iter().zip(old_scores) {
+            Self::deposit_event_indexed(
+                &[who],
+                Event::UserScoreRefreshed(who.clone(), old, *score, pathfinder.clone()),
+            );
         }
+
+        T::Reputation::set_last_refresh_at();
+        Ok(total_fee)
     }
 
+    /// Share the social balance of the refreshed `who` and record the fee under `pathfinder`
+    /// in `round`, the reputation of `who` in `domain` must already be refreshed.
     pub(crate) fn do_refresh(
+        round: RoundIndex,
         pathfinder: &T::AccountId,
-        user_score: &(T::AccountId, u32),
+        domain: &DomainId,
+        who: &T::AccountId,
         update_at: &T::BlockNumber,
     ) -> Result<Balance, DispatchError> {
-        T::Reputation::refresh_reputation(user_score)?;
-        let who = &user_score.0;
-        let fee = Self::share(who);
-        Self::mutate_record(pathfinder, who, &fee, update_at);
+        let fee = Self::share(who)?;
+        Self::mutate_record_in(round, pathfinder, domain, who, &fee, update_at);
         Ok(fee)
     }
 
-    pub(crate) fn share(user: &T::AccountId) -> Balance {
-        let targets = T::TrustBase::get_trust_old(user);
-        T::MultiBaseToken::share(user, &targets[..])
+    /// Share the social balance of `user` among the accounts it trusts, except those with a
+    /// frozen reputation.
+    pub(crate) fn share(user: &T::AccountId) -> Result<Balance, DispatchError> {
+        let targets = T::TrustBase::get_trust_old(user)
+            .into_iter()
+            .filter(|target| !T::Reputation::is_frozen(target))
+            .map(|target| {
+                let strength = T::TrustBase::trust_strength_old(user, &target);
+                (target, strength)
+            })
+            .collect::<Vec<_>>();
+        T::MultiBaseToken::share_with_weights(user, &targets[..])
     }
 
     pub(crate) fn get_dist(
@@ -755,6 +2923,162 @@ impl<T: Config> Pallet<T> {
 
     // private
 
+    /// Withdraw the refresh of `who` in `round` by another pathfinder, found through
+    /// `RefreshedBy`, for `pathfinder` to refresh it.
+    ///
+    /// The refresh must still be in its confirmation period and the bond of `pathfinder` must
+    /// be higher than that of the superseded pathfinder, its staking is refunded and the fee
+    /// returned to `who` as by `withdraw_refresh`.
+    fn supersede(
+        round: RoundIndex,
+        pathfinder: &T::AccountId,
+        who: &T::AccountId,
+    ) -> DispatchResult {
+        let other = match Self::refreshed_by(who) {
+            Some((by_round, other)) if by_round == round && other != *pathfinder => other,
+            _ => return Ok(()),
+        };
+        let records_key = RecordKey::new(round, other.clone());
+        if !Records::<T>::contains_key(&records_key, who) {
+            return Ok(());
+        }
+        let record = Self::confirming_record(&records_key, who)
+            .map_err(|_| Error::<T>::CannotSupersede)?;
+        let bond_of = |account: &T::AccountId| Self::pathfinder(account).map(|info| info.bond);
+        ensure!(
+            bond_of(pathfinder) > bond_of(&other),
+            Error::<T>::CannotSupersede
+        );
+
+        <Records<T>>::remove(&records_key, who);
+        RefreshedBy::<T>::remove(who);
+        T::Reputation::revert_reputation_in(&record.domain, who)?;
+        T::MultiBaseToken::release(who, &record.fee)?;
+        Self::release_stake(round, &other, 1)?;
+        Self::deduct_payroll(round, &other, 1, record.fee)?;
+
+        Self::deposit_event_indexed(
+            &[pathfinder, &other, who],
+            Event::RefreshSuperseded(pathfinder.clone(), other, who.clone(), record.fee),
+        );
+        Ok(())
+    }
+
+    /// `start` for `who` scanning at most `limit` `Payrolls`, returning the number of
+    /// `Payrolls` scanned.
+    fn do_start(who: &T::AccountId, limit: u32) -> Result<u32, DispatchError> {
+        Self::check_step_and_not_stared()?;
+
+        let now_block_number = Self::now();
+        let waiting = !<ForceStart<T>>::get() && !T::ChallengeBase::is_all_harvest(&APP_ID);
+        let (scanned, count, total_fee, complete) =
+            Self::settle_matured_payrolls(who, &now_block_number, limit)?;
+        if waiting || !complete {
+            ensure!(!complete || count > 0, Error::<T>::ChallengeNotClaimed);
+            Self::release_start_fee(who, total_fee, false)?;
+            Self::deposit_event(Event::PayrollsDrained(who.clone(), count));
+            return Ok(scanned);
+        }
+
+        <ForceStart<T>>::kill();
+        Self::release_start_fee(who, total_fee, true)?;
+        let round = Self::current_round();
+        RoundStakingAmounts::<T>::insert(round, Self::next_staking_amount());
+        RoundProxyFee::<T>::insert(round, total_fee);
+        RoundStats::<T>::mutate(round, |stat| stat.started_at = now_block_number);
+        Self::deposit_event(Event::RoundSettled(round, who.clone(), total_fee));
+        <StartedAt<T>>::put(now_block_number);
+        T::Reputation::open_refresh();
+        Self::deposit_event_indexed(&[who], Event::Started(who.clone()));
+        Ok(scanned)
+    }
+
+    /// Release the sweeper fee `fee` collected by `start` to `who`, decayed by its streak of
+    /// consecutive rounds, and the decayed part to `TreasuryAccountId`.
+    fn release_start_fee(who: &T::AccountId, fee: Balance, started: bool) -> DispatchResult {
+        let (reward, decayed) = Self::decay_reward(who, fee, started);
+        T::MultiBaseToken::release(who, &reward)?;
+        if !decayed.is_zero() {
+            T::MultiBaseToken::release(&T::TreasuryAccountId::get(), &decayed)?;
+        }
+        Ok(())
+    }
+
+    /// Start the round, or advance its settlement, on behalf of the block author once it can
+    /// start without waiting for challenges, returning the weight consumed.
+    ///
+    /// Nothing is written if `start` would fail.
+    fn auto_start() -> Weight {
+        let author = match T::BlockAuthor::get() {
+            Some(author) => author,
+            None => return Zero::zero(),
+        };
+        let checked = T::DbWeight::get().reads(3);
+        if Self::check_step_and_not_stared().is_err()
+            || !(<ForceStart<T>>::get() || T::ChallengeBase::is_all_harvest(&APP_ID))
+        {
+            return checked;
+        }
+        let started = with_transaction(|| match Self::do_start(&author, MAX_START_SETTLEMENTS) {
+            Ok(count) => TransactionOutcome::Commit(Some(count)),
+            Err(_) => TransactionOutcome::Rollback(None),
+        });
+        let count = started.unwrap_or(MAX_START_SETTLEMENTS);
+        checked.saturating_add(T::WeightInfo::start(count))
+    }
+
+    /// Checks up to `MaxRecordPrunes` records from `PruneCursor` on and removes those whose
+    /// payroll is gone and that have passed the confirmation period.
+    fn prune_records() -> Weight {
+        let limit = T::MaxRecordPrunes::get() as usize;
+        if limit == 0 {
+            return 0;
+        }
+        let mut records =
+            iter_double_map_from::<RecordKey<T::AccountId>, T::AccountId, Record<_, Balance>>(
+                &Records::<T>::final_prefix(),
+                PruneCursor::<T>::get(),
+            );
+        let checked = records.by_ref().take(limit).collect::<Vec<_>>();
+        let complete = records.next().is_none();
+        match checked.last() {
+            Some((key, target, _)) if !complete => {
+                PruneCursor::<T>::put(Records::<T>::hashed_key_for(key, target))
+            }
+            _ => PruneCursor::<T>::kill(),
+        }
+        let now = Self::now();
+        let scanned = checked.len() as u64;
+        let mut pruned: u64 = 0;
+        for (key, target, record) in checked {
+            if Payrolls::<T>::contains_key(key.round, &key.pathfinder)
+                || !Self::is_matured(&record.update_at, &now)
+            {
+                continue;
+            }
+            Records::<T>::remove(&key, &target);
+            RefreshedBy::<T>::mutate_exists(&target, |by| {
+                if *by == Some((key.round, key.pathfinder.clone())) {
+                    *by = None
+                }
+            });
+            pruned += 1;
+        }
+        T::DbWeight::get().reads_writes(
+            scanned.saturating_mul(2).saturating_add(2),
+            pruned.saturating_mul(2).saturating_add(1),
+        )
+    }
+
+    /// End the refreshes of the current round.
+    fn close_round() {
+        T::TrustBase::remove_all_tmp();
+        T::Reputation::set_free();
+        <StartedAt<T>>::kill();
+        let round = Self::current_round();
+        Self::deposit_event(Event::RoundClosed(round, Self::round_stats(round)));
+    }
+
     fn check_step_and_stared() -> DispatchResult {
         Self::check_step()?;
         ensure!(<StartedAt<T>>::exists(), Error::<T>::NotYetStarted);
@@ -765,6 +3089,216 @@ impl<T: Config> Pallet<T> {
         system::Module::<T>::block_number()
     }
 
+    fn commit_period_end() -> T::BlockNumber {
+        <StartedAt<T>>::get() + T::CommitPeriod::get()
+    }
+
+    fn current_round() -> RoundIndex {
+        T::Reputation::get_round()
+    }
+
+    /// Split `user_scores` into the entries `pathfinder` can refresh and the indices of
+    /// those it cannot, with the reason.
+    fn split_refreshable(
+        pathfinder: &T::AccountId,
+        user_scores: &[(T::AccountId, u32)],
+    ) -> (Vec<(T::AccountId, u32)>, Vec<(u32, SkipReason)>) {
+        let round = Self::current_round();
+        let records_key = RecordKey::new(round, pathfinder.clone());
+        let capacity = MAX_REFRESH
+            .saturating_sub(1)
+            .min(T::MaxPayrollRecords::get())
+            .saturating_sub(Self::get_payroll(round, pathfinder).count);
+        let mut accepted: Vec<(T::AccountId, u32)> = Vec::new();
+        let mut skipped = Vec::new();
+        for (index, (who, score)) in user_scores.iter().enumerate() {
+            let reason = if accepted.iter().any(|(a, _)| a == who) {
+                Some(SkipReason::Duplicate)
+            } else if Self::is_opted_out(who) {
+                Some(SkipReason::OptedOut)
+            } else if T::Reputation::get_reputation_new(who).is_some()
+                || Records::<T>::contains_key(&records_key, who)
+            {
+                Some(SkipReason::AlreadyRefreshed)
+            } else if accepted.len() as u32 >= capacity {
+                Some(SkipReason::ExceedMaxRefresh)
+            } else {
+                None
+            };
+            match reason {
+                Some(reason) => skipped.push((index as u32, reason)),
+                None => accepted.push((who.clone(), *score)),
+            }
+        }
+        (accepted, skipped)
+    }
+
+    /// The Merkle leaves of `user_scores`.
+    fn batch_leaves(user_scores: &[(T::AccountId, u32)]) -> Vec<T::Hash> {
+        user_scores
+            .iter()
+            .map(|user_score| T::Hashing::hash_of(user_score))
+            .collect()
+    }
+
+    /// Hash each pair of `nodes` into the next level of a Merkle tree.
+    fn merkle_level(nodes: &[T::Hash]) -> Vec<T::Hash> {
+        nodes
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => Self::merkle_pair(left, right),
+                _ => pair[0],
+            })
+            .collect()
+    }
+
+    /// Hash two sibling nodes, in sorted order so that proofs need no position.
+    fn merkle_pair(a: &T::Hash, b: &T::Hash) -> T::Hash {
+        match a <= b {
+            true => T::Hashing::hash_of(&(a, b)),
+            false => T::Hashing::hash_of(&(b, a)),
+        }
+    }
+
+    /// Deposit `event` with the hash of each account in `accounts` as a topic, so that
+    /// the activity of an account can be filtered without scanning all events.
+    fn deposit_event_indexed(accounts: &[&T::AccountId], event: Event<T>) {
+        let topics = accounts
+            .iter()
+            .map(|who| T::Hashing::hash_of(who))
+            .collect::<Vec<_>>();
+        let event = <T as Config>::Event::from(event).into();
+        system::Module::<T>::deposit_event_indexed(&topics[..], event);
+    }
+
+    /// The number of `Records` under `pathfinder` in `round` and the sum of their fees.
+    ///
+    /// At most `MAX_REFRESH + 1` records are read, which is enough to tell that a payroll
+    /// does not match.
+    fn sum_records(round: RoundIndex, pathfinder: &T::AccountId) -> (u32, Balance) {
+        Records::<T>::iter_prefix_values(&RecordKey::new(round, pathfinder.clone()))
+            .take(MAX_REFRESH as usize + 1)
+            .fold(
+                (0u32, Zero::zero()),
+                |acc: (u32, Balance), record| {
+                    (acc.0.saturating_add(1), acc.1.saturating_add(record.fee))
+                },
+            )
+    }
+
+    /// Release the `payroll` of `pathfinder` in `round` without the sweeper fee along
+    /// `SweeperCurve` at `now` and clear its records on behalf of `proxy`, returning the
+    /// sweeper fee.
+    fn settle_payroll(
+        proxy: &T::AccountId,
+        round: RoundIndex,
+        pathfinder: &T::AccountId,
+        payroll: &Payroll<Balance, T::BlockNumber>,
+        now: &T::BlockNumber,
+    ) -> Result<Balance, DispatchError> {
+        let (sweeper_fee, without_fee) = Self::sweeper_split(
+            payroll.total_amount::<T>(round, pathfinder),
+            &payroll.update_at,
+            now,
+        )
+        .ok_or(Error::<T>::FailedSweeper)?;
+        T::MultiBaseToken::release(pathfinder, &without_fee)?;
+        Self::release_currency_stake(round, pathfinder, pathfinder, payroll.count)?;
+        PayrollCurrencies::<T>::remove(round, pathfinder);
+        <Records<T>>::remove_prefix(&RecordKey::new(round, pathfinder.clone()));
+        Self::deposit_event_indexed(
+            &[proxy, pathfinder],
+            Event::PayrollProxySettled(
+                proxy.clone(),
+                pathfinder.clone(),
+                sweeper_fee,
+                without_fee,
+            ),
+        );
+        Ok(sweeper_fee)
+    }
+
+    /// At most `limit` payrolls from `SettlementCursor`, which is moved past them, and
+    /// whether the last payroll was scanned, in which case the cursor is cleared.
+    fn scan_payrolls(
+        limit: u32,
+    ) -> (
+        Vec<(RoundIndex, T::AccountId, Payroll<Balance, T::BlockNumber>)>,
+        bool,
+    ) {
+        let mut payrolls =
+            iter_double_map_from::<RoundIndex, T::AccountId, Payroll<Balance, T::BlockNumber>>(
+                &Payrolls::<T>::final_prefix(),
+                <SettlementCursor<T>>::get(),
+            );
+        let scanned = payrolls.by_ref().take(limit as usize).collect::<Vec<_>>();
+        let complete = payrolls.next().is_none();
+        match scanned.last() {
+            Some((round, pathfinder, _)) if !complete => {
+                <SettlementCursor<T>>::put(Payrolls::<T>::hashed_key_for(round, pathfinder))
+            }
+            _ => <SettlementCursor<T>>::kill(),
+        }
+        (scanned, complete)
+    }
+
+    /// Scan at most `limit` payrolls with `scan_payrolls` and settle those that have passed
+    /// the confirmation period and that `SweeperCurve` allows sweepers on, on behalf of
+    /// `proxy`.
+    ///
+    /// Returns how many were scanned and settled, their total sweeper fee and whether the
+    /// last payroll was scanned.
+    fn settle_matured_payrolls(
+        proxy: &T::AccountId,
+        now: &T::BlockNumber,
+        limit: u32,
+    ) -> Result<(u32, u32, Balance, bool), DispatchError> {
+        let (scanned, complete) = Self::scan_payrolls(limit);
+        let matured = scanned
+            .iter()
+            .filter(|(_, _, payroll)| {
+                Self::can_harvest(payroll, now).is_ok()
+                    && Self::is_sweepable(&payroll.update_at, now)
+            })
+            .collect::<Vec<_>>();
+        let total_fee = matured
+            .iter()
+            .try_fold::<_, _, Result<Balance, DispatchError>>(
+                Zero::zero(),
+                |acc: Balance, (round, pathfinder, payroll)| {
+                    Payrolls::<T>::remove(round, pathfinder);
+                    let sweeper_fee =
+                        Self::settle_payroll(proxy, *round, pathfinder, payroll, now)?;
+                    acc.checked_add(sweeper_fee)
+                        .ok_or_else(|| Error::<T>::Overflow.into())
+                },
+            )?;
+        Ok((scanned.len() as u32, matured.len() as u32, total_fee, complete))
+    }
+
+    /// Split `fee` into the reward for `who` and the part decayed by its streak of
+    /// consecutive rounds, recording `who` as the caller of `start` if it `started` the round.
+    ///
+    /// Calls of `start` that only drain `Payrolls` are decayed by the streak of `who` too, so
+    /// that splitting a settlement into calls does not escape the decay.
+    fn decay_reward(who: &T::AccountId, fee: Balance, started: bool) -> (Balance, Balance) {
+        let streak = match Self::round_trigger() {
+            Some((last, streak)) if last == *who => streak,
+            _ => 0,
+        };
+        if started {
+            RoundTrigger::<T>::put((who.clone(), streak.saturating_add(1)));
+        }
+
+        let window = T::RewardDecayWindow::get();
+        let decayed = Perbill::from_rational_approximation(streak.min(window), window + 1)
+            .mul_floor(fee);
+        if !decayed.is_zero() {
+            Self::deposit_event(Event::RewardDecayed(who.clone(), streak, decayed));
+        }
+        (fee - decayed, decayed)
+    }
+
     fn check_step_and_not_stared() -> DispatchResult {
         Self::check_step()?;
         ensure!(!<StartedAt<T>>::exists(), Error::<T>::AlreadyStarted);
@@ -776,17 +3310,335 @@ impl<T: Config> Pallet<T> {
         now: &T::BlockNumber,
     ) -> DispatchResult {
         ensure!(
-            payroll.update_at + T::ConfirmationPeriod::get() < *now,
+            Self::is_matured(&payroll.update_at, now),
             Error::<T>::ExcessiveBumberOfSeeds
         );
         Ok(())
     }
 
+    /// Remove `records` of `pathfinder` in `round`, take them out of its payroll and release
+    /// their staking and fees to `pathfinder`.
+    ///
+    /// Returns the amount released and the number of records left in the payroll.
+    fn harvest_records(
+        round: RoundIndex,
+        pathfinder: &T::AccountId,
+        records: &[(T::AccountId, Record<T::BlockNumber, Balance>)],
+    ) -> Result<(Balance, u32), DispatchError> {
+        let records_key = RecordKey::new(round, pathfinder.clone());
+        let total_fee = records
+            .iter()
+            .try_fold::<_, _, Result<Balance, DispatchError>>(
+                Zero::zero(),
+                |acc, (who, record)| {
+                    <Records<T>>::remove(&records_key, who);
+                    acc.checked_add(record.fee)
+                        .ok_or_else(|| Error::<T>::Overflow.into())
+                },
+            )?;
+        let count = records.len() as u32;
+        let total_amount = match Self::payroll_currency(round, pathfinder) {
+            Some(_) => total_fee,
+            None => Self::staking_amount(round)
+                .checked_mul(count as Balance)
+                .and_then(|amount| amount.checked_add(total_fee))
+                .ok_or(Error::<T>::Overflow)?,
+        };
+        Self::release_currency_stake(round, pathfinder, pathfinder, count)?;
+
+        let still_locked = Self::deduct_payroll(round, pathfinder, count, total_fee)?;
+        T::MultiBaseToken::release(pathfinder, &total_amount)?;
+        Ok((total_amount, still_locked))
+    }
+
+    /// Take `count` records and `fee` out of the payroll of `pathfinder` in `round`, the
+    /// payroll is removed once no record is left.
+    ///
+    /// Returns the number of records left in the payroll.
+    fn deduct_payroll(
+        round: RoundIndex,
+        pathfinder: &T::AccountId,
+        count: u32,
+        fee: Balance,
+    ) -> Result<u32, DispatchError> {
+        <Payrolls<T>>::try_mutate_exists(
+            round,
+            pathfinder,
+            |maybe_payroll| -> Result<u32, DispatchError> {
+                let payroll = maybe_payroll.as_mut().ok_or(Error::<T>::NoPayroll)?;
+                payroll.count = payroll
+                    .count
+                    .checked_sub(count)
+                    .ok_or(Error::<T>::Overflow)?;
+                payroll.total_fee = payroll
+                    .total_fee
+                    .checked_sub(fee)
+                    .ok_or(Error::<T>::Overflow)?;
+                let still_locked = payroll.count;
+                if still_locked == 0 {
+                    *maybe_payroll = None;
+                    PayrollCurrencies::<T>::remove(round, pathfinder);
+                }
+                Ok(still_locked)
+            },
+        )
+    }
+
+    /// The currency, `None` for `BaceToken`, and the amount per user `pathfinder` stakes
+    /// for a refresh in `round`, fixed for the payroll by its first refresh.
+    fn refresh_staking(
+        round: RoundIndex,
+        pathfinder: &T::AccountId,
+    ) -> Result<(Option<T::CurrencyId>, Balance), DispatchError> {
+        if Payrolls::<T>::contains_key(round, pathfinder) {
+            return Ok(match Self::payroll_currency(round, pathfinder) {
+                Some((currency, amount)) => (Some(currency), amount),
+                None => (None, Self::staking_amount(round)),
+            });
+        }
+        match Self::staking_currency_of(pathfinder) {
+            Some(currency) => {
+                let amount = Self::staking_currency_amount(currency)
+                    .ok_or(Error::<T>::CurrencyNotApproved)?;
+                PayrollCurrencies::<T>::insert(round, pathfinder, (currency, amount));
+                Ok((Some(currency), amount))
+            }
+            None => {
+                PayrollCurrencies::<T>::remove(round, pathfinder);
+                Ok((None, Self::staking_amount(round)))
+            }
+        }
+    }
+
+    /// Release the staking of `count` records of `pathfinder` in `round` to it, in the
+    /// currency of its payroll.
+    fn release_stake(round: RoundIndex, pathfinder: &T::AccountId, count: u32) -> DispatchResult {
+        match Self::payroll_currency(round, pathfinder) {
+            Some(_) => Self::release_currency_stake(round, pathfinder, pathfinder, count),
+            None => {
+                let amount = Self::staking_amount(round)
+                    .checked_mul(count as Balance)
+                    .ok_or(Error::<T>::Overflow)?;
+                T::MultiBaseToken::release(pathfinder, &amount)
+            }
+        }
+    }
+
+    /// Release the staking of `count` records of `pathfinder` in `round` to `to` if the
+    /// payroll is not staked in `BaceToken`, `Payroll::total_amount` covers it otherwise.
+    fn release_currency_stake(
+        round: RoundIndex,
+        pathfinder: &T::AccountId,
+        to: &T::AccountId,
+        count: u32,
+    ) -> DispatchResult {
+        match Self::payroll_currency(round, pathfinder) {
+            Some((currency, amount)) => {
+                let amount = amount
+                    .checked_mul(count as Balance)
+                    .ok_or(Error::<T>::Overflow)?;
+                T::CurrencyStaking::release_in(currency, to, &amount)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// The record of `target` under `records_key`, it must not have been challenged and
+    /// must still be in the confirmation period.
+    fn confirming_record(
+        records_key: &RecordKey<T::AccountId>,
+        target: &T::AccountId,
+    ) -> Result<Record<T::BlockNumber, Balance>, DispatchError> {
+        let record =
+            <Records<T>>::try_get(records_key, target).map_err(|_| Error::<T>::RecordNotExist)?;
+        ensure!(
+            Self::is_in_confirmation(&record.update_at),
+            Error::<T>::ConfirmationElapsed
+        );
+        Ok(record)
+    }
+
+    /// Settle the staking of the challenged refresh of `target`, `share` is the part of the
+    /// challenge awards received by the pathfinder.
+    ///
+    /// A pathfinder receiving nothing has lost and is slashed, otherwise the staking is
+    /// returned to it.
+    fn settle_challenged_stake(
+        target: &T::AccountId,
+        challenger: &T::AccountId,
+        share: Perbill,
+    ) -> DispatchResult {
+        let (pathfinder, stake) = match <ChallengedStakes<T>>::take(target) {
+            Some(challenged) => challenged,
+            None => return Ok(()),
+        };
+        let currency = ChallengedCurrencies::<T>::take(target);
+        let release = |who: &T::AccountId, amount: &Balance| match currency {
+            Some(currency) => T::CurrencyStaking::release_in(currency, who, amount),
+            None => T::MultiBaseToken::release(who, amount),
+        };
+        if !share.is_zero() {
+            return release(&pathfinder, &stake);
+        }
+        let to_challenger = T::ChallengerSlashRatio::get().mul_floor(stake);
+        let to_treasury = stake.saturating_sub(to_challenger);
+        release(challenger, &to_challenger)?;
+        release(&T::TreasuryAccountId::get(), &to_treasury)?;
+        Self::note_challenge_lost(&pathfinder);
+        Self::deposit_event(Event::PathfinderSlashed(
+            pathfinder,
+            challenger.clone(),
+            to_challenger,
+            to_treasury,
+        ));
+        Ok(())
+    }
+
+    /// Count a challenge lost by `pathfinder` in the current round, suspending it for
+    /// `SuspensionRounds` once it has lost `MaxChallengeLosses` within `LossWindow` rounds.
+    fn note_challenge_lost(pathfinder: &T::AccountId) {
+        let round = Self::current_round();
+        let suspended = PathfinderStatuses::<T>::mutate(pathfinder, |status| {
+            if status.losses == 0
+                || round >= status.window_start.saturating_add(T::LossWindow::get())
+            {
+                status.losses = 0;
+                status.window_start = round;
+            }
+            status.losses = status.losses.saturating_add(1);
+            if status.losses < T::MaxChallengeLosses::get() {
+                return None;
+            }
+            status.losses = 0;
+            status.suspended_until = round.saturating_add(T::SuspensionRounds::get());
+            Some(status.suspended_until)
+        });
+        if let Some(suspended_until) = suspended {
+            Self::deposit_event(Event::PathfinderSuspended(pathfinder.clone(), suspended_until));
+        }
+    }
+
+    /// Check that `payload` is bounded as by `ensure_bounded` and signed by its pathfinder,
+    /// and that it is for the current round and its nonce has not been used in it.
+    fn check_payload(
+        payload: &RefreshPayload<T::AccountId>,
+        signature: &T::OffchainSignature,
+    ) -> Result<(), Error<T>> {
+        Self::ensure_bounded(&payload.user_scores[..])?;
+        ensure!(
+            signature.verify(&payload.encode()[..], &payload.pathfinder),
+            Error::<T>::BadSignature
+        );
+        ensure!(
+            payload.round == Self::current_round(),
+            Error::<T>::StaleRound
+        );
+        ensure!(
+            Self::payload_nonce(payload.round, &payload.pathfinder) == payload.nonce,
+            Error::<T>::StaleNonce
+        );
+        Ok(())
+    }
+
+    /// Rejects `user_scores` longer than `MAX_REFRESH`, before any storage is read.
+    fn ensure_bounded(user_scores: &[(T::AccountId, u32)]) -> Result<(), Error<T>> {
+        ensure!(
+            user_scores.len() as u32 <= MAX_REFRESH,
+            Error::<T>::TooManyUserScores
+        );
+        Ok(())
+    }
+
+    /// Consume `nonce`, the `payload_nonce` of `pathfinder` in `round`.
+    fn use_payload_nonce(round: &RoundIndex, pathfinder: &T::AccountId, nonce: u32) {
+        PayloadNonces::<T>::insert(round, pathfinder, nonce.saturating_add(1));
+    }
+
+    fn is_matured(update_at: &T::BlockNumber, now: &T::BlockNumber) -> bool {
+        *update_at + Self::confirmation_period() < *now
+    }
+
+    fn is_in_confirmation(update_at: &T::BlockNumber) -> bool {
+        *update_at + Self::confirmation_period() > Self::now()
+    }
+
+    /// The number of blocks after a refresh in which it can be challenged, the length of the
+    /// confirmation phase of the current `RoundInfo`.
+    fn confirmation_period() -> T::BlockNumber {
+        let round = T::Reputation::round_info();
+        round.confirm_ends_at.saturating_sub(round.refresh_ends_at)
+    }
+
+    /// Whether sweepers may settle a payroll last updated at `update_at` at `now`, which
+    /// `ProxyGracePeriod` allows ahead of `SweeperCurve`.
+    fn is_sweepable(update_at: &T::BlockNumber, now: &T::BlockNumber) -> bool {
+        T::SweeperCurve::get()
+            .is_allowed(*update_at, now.saturating_add(T::ProxyGracePeriod::get()))
+    }
+
+    /// The sweeper fee along `SweeperCurve` and the rest of `amount`, for a payroll last
+    /// updated at `update_at`. The fee is zero within `ProxyGracePeriod` before the curve
+    /// allows sweepers, and `None` before that.
+    fn sweeper_split(
+        amount: Balance,
+        update_at: &T::BlockNumber,
+        now: &T::BlockNumber,
+    ) -> Option<(Balance, Balance)> {
+        match amount.checked_with_curve(*update_at, *now, &T::SweeperCurve::get()) {
+            Some(split) => Some(split),
+            None if Self::is_sweepable(update_at, now) => Some((Zero::zero(), amount)),
+            None => None,
+        }
+    }
+
+    /// Refreshes are accepted while the current round is `Updating`, before the
+    /// `refresh_ends_at` of its `RoundInfo`.
     fn check_timeout(now: &T::BlockNumber) -> DispatchResult {
         ensure!(
-            *now < <StartedAt<T>>::get() + T::RefRepuTiomeOut::get(),
+            *now < T::Reputation::round_info().refresh_ends_at,
             Error::<T>::RefreshTiomeOut
         );
         Ok(())
     }
 }
+
+impl<T: Config> PathfinderBase<T::AccountId, Balance> for Pallet<T> {
+    fn is_pathfinder(who: &T::AccountId) -> bool {
+        Self::pathfinder(who).map_or(false, |info| info.unbonding_at.is_none())
+    }
+
+    fn bond_of(who: &T::AccountId) -> Balance {
+        Self::pathfinder(who).map_or(Zero::zero(), |info| info.bond)
+    }
+
+    fn slash_bond(
+        who: &T::AccountId,
+        amount: Balance,
+        to: &T::AccountId,
+    ) -> Result<Balance, DispatchError> {
+        let slashed = Pathfinders::<T>::try_mutate(who, |maybe_info| -> Result<_, DispatchError> {
+            let info = maybe_info.as_mut().ok_or(Error::<T>::NotPathfinder)?;
+            let slashed = amount.min(info.bond);
+            info.bond -= slashed;
+            Ok(slashed)
+        })?;
+        T::MultiBaseToken::release(to, &slashed)?;
+        Self::deposit_event(Event::BondSlashed(who.clone(), to.clone(), slashed));
+        Ok(slashed)
+    }
+}
+
+impl<T: Config> ChallengeHarvested<T::AccountId, AppId, u64> for Pallet<T> {
+    fn on_harvested(
+        app_id: &AppId,
+        target: &T::AccountId,
+        challenger: &T::AccountId,
+        share: Perbill,
+        _score: Option<u64>,
+    ) -> DispatchResult {
+        if *app_id != APP_ID {
+            return Ok(());
+        }
+        Self::settle_challenged_stake(target, challenger, share)
+    }
+}
@@ -13,16 +13,26 @@
 // limitations under the License.
 
 use crate as zd_refresh_reputation;
+use crate::RefreshConflict;
+use codec::{Decode, Encode};
 use sp_runtime::{
-    traits::{BlakeTwo256, IdentityLookup}, testing::Header,
+    traits::{BlakeTwo256, IdentifyAccount, IdentityLookup, Lazy, Verify}, testing::Header,
+    RuntimeDebug,
+};
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::{Contains, GenesisBuild, Get},
 };
-use frame_support::{construct_runtime, parameter_types, traits::GenesisBuild};
 use frame_system as system;
 use orml_currencies::BasicCurrencyAdapter;
 use orml_traits::parameter_type_with_key;
 use sp_core::H256;
 use sp_runtime::Perbill;
-use zd_primitives::Balance;
+use std::cell::RefCell;
+use zd_primitives::{
+    fee::{SweeperCurve, SWEEPER_CURVE},
+    Balance,
+};
 
 pub type Amount = i128;
 pub type AccountId = u32;
@@ -49,6 +59,7 @@ pub const SUB_CHALLENGER: AccountId = 14;
 pub const SEED4: AccountId = 15;
 
 pub const ZDAO: CurrencyId = 1;
+pub const STABLE: CurrencyId = 2;
 
 pub const INIT_PERIOD: BlockNumber = 10;
 
@@ -92,10 +103,9 @@ parameter_types! {
     pub const BlockHashCount: u32 = 250;
     pub const SS58Prefix: u8 = 42;
 
-    pub const ShareRatio: Perbill = Perbill::from_percent(80);
-    pub const FeeRation: Perbill = Perbill::from_percent(3);
-    pub const SelfRation: Perbill = Perbill::from_percent(3);
     pub const MaxUpdateCount: u32 = 4;
+    pub storage MaxPayrollRecords: u32 = 1_000;
+    pub storage MaxPayrollAmount: Balance = Balance::max_value();
 
     pub const DampingFactor: Perbill = Perbill::from_percent(100);
     pub const ExistentialDeposit: u128 = 500;
@@ -119,33 +129,154 @@ impl zd_trust::Config for Test {
 	type WeightInfo = ();
 }
 
+parameter_types! {
+    pub const MaxDelegators: u32 = 2;
+}
+
 impl zd_reputation::Config for Test {
     type Event = Event;
+    type HistoryRounds = HistoryRounds;
+    type MaxDelegators = MaxDelegators;
+    type PriorityPerTier = ();
+    type SlashOrigin = system::EnsureRoot<AccountId>;
 }
 
 impl zd_refresh_reputation::Config for Test {
     type Event = Event;
     type MultiBaseToken = ZdToken;
     type MaxUpdateCount = MaxUpdateCount;
+    type MaxPayrollRecords = MaxPayrollRecords;
+    type MaxPayrollAmount = MaxPayrollAmount;
     type UpdateStakingAmount = UpdateStakingAmount;
-    type ConfirmationPeriod = ConfirmationPeriod;
     type Reputation = ZdReputation;
     type TrustBase = ZdTrust;
     type ChallengeBase = Challenges;
+    type CurrencyId = CurrencyId;
+    type CurrencyStaking = ZdToken;
     type SeedsBase = ZdSeeds;
-    type RefRepuTiomeOut = RefRepuTiomeOut;
+    type ProxyGracePeriod = ProxyGracePeriod;
+    type RewardDecayWindow = RewardDecayWindow;
+    type TreasuryAccountId = TreasuryAccountId;
+    type ChallengerSlashRatio = ChallengerSlashRatio;
+    type OffchainSignature = TestSignature;
+    type OffchainPublic = TestSigner;
+    type PathfinderBond = PathfinderBond;
+    type UnbondingPeriod = UnbondingPeriod;
+    type MaxChallengeLosses = MaxChallengeLosses;
+    type LossWindow = LossWindow;
+    type SuspensionRounds = SuspensionRounds;
+    type CommitPeriod = CommitPeriod;
+    type CommitDeposit = CommitDeposit;
+    type MaxRecordPrunes = MaxRecordPrunes;
+    type BlockAuthor = BlockAuthor;
+    type SweeperCurve = SweeperFeeCurve;
+    type ConflictPolicy = ConflictPolicy;
+    type ParametersOrigin = system::EnsureRoot<AccountId>;
+    type AppealOrigin = system::EnsureRoot<AccountId>;
+    type UnsignedPriority = UnsignedPriority;
     type WeightInfo = ();
 }
 
+/// A signature that is valid for `.0` over exactly the message `.1`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct TestSignature(pub AccountId, pub Vec<u8>);
+
+impl Verify for TestSignature {
+    type Signer = TestSigner;
+
+    fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &AccountId) -> bool {
+        self.0 == *signer && self.1 == msg.get()
+    }
+}
+
+thread_local! {
+    static BLOCK_AUTHOR: RefCell<Option<AccountId>> = RefCell::new(None);
+}
+
+/// The block author, `None` unless set.
+pub struct BlockAuthor;
+
+impl BlockAuthor {
+    pub fn set(author: Option<AccountId>) {
+        BLOCK_AUTHOR.with(|a| *a.borrow_mut() = author);
+    }
+}
+
+impl Get<Option<AccountId>> for BlockAuthor {
+    fn get() -> Option<AccountId> {
+        BLOCK_AUTHOR.with(|a| *a.borrow())
+    }
+}
+
+pub struct TestSigner(pub AccountId);
+
+impl IdentifyAccount for TestSigner {
+    type AccountId = AccountId;
+
+    fn into_account(self) -> AccountId {
+        self.0
+    }
+}
+
 parameter_types! {
     /// The reputation must be refreshed within this time period.
     pub const RefRepuTiomeOut: BlockNumber = 14_400;
+    pub const ProxyGracePeriod: BlockNumber = 10;
+    pub const RewardDecayWindow: u32 = 2;
+    pub const TreasuryAccountId: AccountId = 10001;
+    pub const ChallengerSlashRatio: Perbill = Perbill::from_percent(60);
+    pub const UnsignedPriority: u64 = 1 << 20;
+    pub const PathfinderBond: Balance = 100;
+    pub const UnbondingPeriod: BlockNumber = 50;
+    pub const MaxChallengeLosses: u32 = 2;
+    pub const LossWindow: u32 = 3;
+    pub const SuspensionRounds: u32 = 2;
+    pub const CommitPeriod: BlockNumber = 5;
+    pub const CommitDeposit: Balance = 1_000;
+    pub const MaxRecordPrunes: u32 = 2;
     /// Amount needed for staking when refreshing reputation and seeds.
     pub const UpdateStakingAmount: Balance = 1_000_000_000;
 	/// Response time period of challenge system.
 	pub const ChallengeTimeout: BlockNumber = 100;
     /// Response time period of challenge system.
 	pub const ChallengeStakingAmount: Balance = 100;
+	/// Maximum number of data uploads per call of challenge system.
+	pub const MaxNextSteps: u32 = 257;
+	pub const MinDisputeDelta: u64 = 0;
+	pub const ResponseWindow: BlockNumber = 0;
+	pub const MaxResponseExtension: BlockNumber = 0;
+	pub const MaxReplyCount: u32 = 1000;
+	pub const MaxLegsPerReply: u32 = 257;
+	pub const MaxBackers: u32 = 20;
+	pub const MaxEvidenceLen: u32 = 64;
+	pub const CancellationFee: Balance = 10;
+	pub const AppealPeriod: BlockNumber = 50;
+	pub const AppealStakingAmount: Balance = 200;
+	pub const ArbitralThreshold: u32 = 2;
+	pub const ArbiterStakingAmount: Balance = 50;
+	pub const AppealVotingPeriod: BlockNumber = 100;
+	pub const MaxAutoSettlements: u32 = 10;
+	pub const MaxOffchainSettlements: u32 = 10;
+	pub const MaxHistory: u32 = 10;
+	pub const HistoryRounds: u32 = 5;
+	pub const MaxHistoryPrunes: u32 = 10;
+    pub storage SweeperFeeCurve: SweeperCurve = SWEEPER_CURVE;
+    pub storage ConflictPolicy: RefreshConflict = RefreshConflict::Reject;
+}
+
+pub struct Arbiters;
+impl Contains<AccountId> for Arbiters {
+    fn sorted_members() -> Vec<AccountId> {
+        vec![ALICE, BOB, FERDIE]
+    }
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Test
+where
+    Call: From<C>,
+{
+    type OverarchingCall = Call;
+    type Extrinsic = UncheckedExtrinsic;
 }
 
 impl zd_challenges::Config for Test {
@@ -153,8 +284,38 @@ impl zd_challenges::Config for Test {
     type CurrencyId = CurrencyId;
     type Reputation = ZdReputation;
     type ZdToken = ZdToken;
+    type CurrencyStaking = ZdToken;
     type ChallengeStakingAmount = ChallengeStakingAmount;
     type ChallengeTimeout = ChallengeTimeout;
+    type MaxNextSteps = MaxNextSteps;
+    type MinDisputeDelta = MinDisputeDelta;
+    type ResponseWindow = ResponseWindow;
+    type MaxResponseExtension = MaxResponseExtension;
+    type MaxReplyCount = MaxReplyCount;
+    type MaxLegsPerReply = MaxLegsPerReply;
+    type AppConfigOrigin = system::EnsureRoot<AccountId>;
+    type PauseOrigin = system::EnsureRoot<AccountId>;
+    type TreasuryAccountId = TreasuryAccountId;
+    type MaxBackers = MaxBackers;
+    type MaxEvidenceLen = MaxEvidenceLen;
+    type CancellationFee = CancellationFee;
+    type AppealPeriod = AppealPeriod;
+    type AppealStakingAmount = AppealStakingAmount;
+    type Arbiters = Arbiters;
+    type ArbitralThreshold = ArbitralThreshold;
+    type ArbiterStakingAmount = ArbiterStakingAmount;
+    type AppealVotingPeriod = AppealVotingPeriod;
+    type MaxAutoSettlements = MaxAutoSettlements;
+    type MaxOffchainSettlements = MaxOffchainSettlements;
+    type UnsignedPriority = UnsignedPriority;
+    type MaxHistory = MaxHistory;
+    type HistoryRounds = HistoryRounds;
+    type MaxHistoryPrunes = MaxHistoryPrunes;
+    type Extra = ();
+    type Score = u64;
+    type OnHarvested = ZdRefreshReputation;
+    type OnTransition = ();
+    type WeightInfo = ();
 }
 
 impl orml_tokens::Config for Test {
@@ -169,6 +330,7 @@ impl orml_tokens::Config for Test {
 
 parameter_types! {
     pub const SocialPoolAccountId: AccountId = 10000;
+    pub const UserRebateRatio: Perbill = Perbill::zero();
 }
 
 impl zd_tokens::Config for Test {
@@ -179,6 +341,7 @@ impl zd_tokens::Config for Test {
     type SocialPool = SocialPoolAccountId;
     type Amount = Amount;
     type BaceToken = BaceToken;
+    type UserRebateRatio = UserRebateRatio;
 }
 
 impl system::Config for Test {
@@ -215,12 +378,12 @@ construct_runtime!(
         System: frame_system::{Module, Call, Config, Storage, Event<T>},
         Balances: pallet_balances::{Module, Call, Storage, Event<T>},
         ZdReputation: zd_reputation::{Module, Call, Storage, Event<T>},
-        ZdRefreshReputation: zd_refresh_reputation::{Module, Call, Storage, Event<T>},
+        ZdRefreshReputation: zd_refresh_reputation::{Module, Call, Storage, Event<T>, ValidateUnsigned},
         ZdSeeds: zd_seeds::{Module, Call, Storage, Event<T>},
         Currencies: orml_currencies::{Module, Call, Event<T>},
         ZdTrust: zd_trust::{Module, Call, Event<T>},
         Tokens: orml_tokens::{Module, Storage, Event<T>, Config<T>},
-        Challenges: zd_challenges::{Module, Storage, Event<T>},
+        Challenges: zd_challenges::{Module, Call, Storage, Event<T>, ValidateUnsigned},
         ZdToken: zd_tokens::{Module, Call, Event<T>},
     }
 );
@@ -239,6 +402,7 @@ impl Default for ExtBuilder {
                 (FERDIE, ZDAO, 1000_000_000_000_000u128),
                 (PATHFINDER, ZDAO, 1000_000_000_000_000u128),
                 (CHALLENGER, ZDAO, 1000_000_000_000_000u128),
+                (CHALLENGER, STABLE, 1000_000_000_000_000u128),
                 (SWEEPRT, ZDAO, 1000_000_000u128),
                 (SUB_CHALLENGER, ZDAO, 1000_000_000_000_000u128),
             ],
@@ -261,6 +425,9 @@ impl ExtBuilder {
 
         zd_reputation::GenesisConfig::<Test> {
             period: self.period,
+            refresh_period: RefRepuTiomeOut::get(),
+            confirm_period: ConfirmationPeriod::get(),
+            reputations: vec![],
         }
         .assimilate_storage(&mut t)
         .unwrap();
@@ -15,7 +15,7 @@
 use super::*;
 use crate::mock::{Event, *};
 use frame_support::{assert_err_ignore_postinfo, assert_noop, assert_ok};
-use zd_primitives::per_social_currency;
+use zd_primitives::{per_social_currency, Phase, SWEEPER_PERIOD, SWEEPER_PICKUP_RATIO};
 
 fn new_test_ext() -> sp_io::TestExternalities {
     let mut ext = ExtBuilder::default().build();
@@ -23,6 +23,18 @@ fn new_test_ext() -> sp_io::TestExternalities {
     ext
 }
 
+fn round() -> RoundIndex {
+    ZdReputation::get_round()
+}
+
+fn register(who: AccountId) {
+    assert_ok!(ZdRefreshReputation::register_pathfinder(
+        Origin::signed(who),
+        PathfinderBond::get(),
+        None
+    ));
+}
+
 #[test]
 fn start_should_work() {
     new_test_ext().execute_with(|| {
@@ -34,6 +46,68 @@ fn start_should_work() {
     });
 }
 
+#[test]
+fn on_initialize_should_start_for_block_author() {
+    use frame_support::traits::OnInitialize;
+
+    new_test_ext().execute_with(|| {
+        ZdReputation::set_step(&TIRStep::Reputation);
+        System::set_block_number(2000);
+        assert_eq!(
+            <ZdRefreshReputation as OnInitialize<BlockNumber>>::on_initialize(2000),
+            0
+        );
+        assert!(!<StartedAt<Test>>::exists());
+
+        BlockAuthor::set(Some(ALICE));
+        <ZdRefreshReputation as OnInitialize<BlockNumber>>::on_initialize(2000);
+        assert_eq!(ZdRefreshReputation::started_at(), 2000);
+        let new_event = Event::zd_refresh_reputation(crate::Event::Started(ALICE));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+
+        System::reset_events();
+        <ZdRefreshReputation as OnInitialize<BlockNumber>>::on_initialize(2001);
+        assert!(System::events().is_empty());
+        BlockAuthor::set(None);
+    });
+}
+
+#[test]
+fn on_initialize_should_prune_records_without_payroll() {
+    use frame_support::traits::OnInitialize;
+
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        System::set_block_number(2000);
+        let orphans = RecordKey::new(round(), EVE);
+        for (who, update_at) in [(ALICE, 1), (BOB, 1), (CHARLIE, 2000)].iter() {
+            <Records<Test>>::insert(
+                &orphans,
+                who,
+                Record {
+                    update_at: *update_at,
+                    fee: 1,
+                    domain: DEFAULT_DOMAIN,
+                },
+            );
+        }
+
+        // `MaxRecordPrunes` records are checked per block, from `PruneCursor` on.
+        <ZdRefreshReputation as OnInitialize<BlockNumber>>::on_initialize(2000);
+        assert!(<PruneCursor<Test>>::exists());
+        <ZdRefreshReputation as OnInitialize<BlockNumber>>::on_initialize(2000);
+        <ZdRefreshReputation as OnInitialize<BlockNumber>>::on_initialize(2000);
+        assert!(!<PruneCursor<Test>>::exists());
+
+        assert!(!<Records<Test>>::contains_key(&orphans, &ALICE));
+        assert!(!<Records<Test>>::contains_key(&orphans, &BOB));
+        // Still in the confirmation period.
+        assert!(<Records<Test>>::contains_key(&orphans, &CHARLIE));
+        // Its payroll has not been harvested.
+        assert!(<Records<Test>>::contains_key(&RecordKey::new(round(), PATHFINDER), &TARGET));
+    });
+}
+
 const INIT_PAYROLLS: [Payroll<Balance, BlockNumber>; 6] = [
     Payroll {
         count: 11,
@@ -127,7 +201,7 @@ fn start_with_payrolls() {
         assert_ok!(ZdToken::staking(&FERDIE, &1_000_000_000_000u128));
 
         for (i, payroll) in INIT_PAYROLLS.iter().enumerate() {
-            <Payrolls<Test>>::insert(&INIT_ACCOUNT[i].account, payroll);
+            <Payrolls<Test>>::insert(round(), &INIT_ACCOUNT[i].account, payroll);
         }
 
         let who_balance = ZdToken::free_balance(&SWEEPRT);
@@ -184,6 +258,11 @@ fn refresh_should_work() {
             Error::<Test>::NotYetStarted
         );
         assert_ok!(ZdRefreshReputation::start(Origin::signed(PATHFINDER)));
+        assert_noop!(
+            ZdRefreshReputation::refresh(Origin::signed(PATHFINDER), user_scores.clone()),
+            Error::<Test>::NotPathfinder
+        );
+        register(PATHFINDER);
         assert_noop!(
             ZdRefreshReputation::refresh(Origin::signed(PATHFINDER), user_scores_too_long),
             Error::<Test>::QuantityLimitReached
@@ -198,7 +277,7 @@ fn refresh_should_work() {
 
         for a in INIT_ACCOUNT[..4].iter() {
             assert_eq!(
-                <Records<Test>>::get(&PATHFINDER, a.account).fee,
+                <Records<Test>>::get(&RecordKey::new(round(), PATHFINDER), a.account).fee,
                 per_social_currency::PRE_FEE.mul_floor(a.soc_amount)
             );
         }
@@ -209,10 +288,10 @@ fn refresh_should_work() {
             .sum();
 
         assert_eq!(
-            <Payrolls<Test>>::get(&PATHFINDER).count,
+            <Payrolls<Test>>::get(round(), &PATHFINDER).count,
             user_scores.len() as u32
         );
-        assert_eq!(<Payrolls<Test>>::get(&PATHFINDER).total_fee, total_fee);
+        assert_eq!(<Payrolls<Test>>::get(round(), &PATHFINDER).total_fee, total_fee);
 
         let new_event = Event::zd_refresh_reputation(crate::Event::ReputationRefreshed(PATHFINDER,user_scores.len() as u32,total_fee));
         assert!(System::events().iter().any(|record| record.event == new_event));
@@ -256,7 +335,7 @@ macro_rules! next_step_should_work {
 next_step_should_work! {
     next_step_should_work_0: (10,true),
     next_step_should_work_1: (5000, false),
-    next_step_should_work_2: (<mock::Test as Config>::ConfirmationPeriod::get() + 2, false),
+    next_step_should_work_2: (ConfirmationPeriod::get() + 2, false),
     next_step_should_work_3: (20,true),
     next_step_should_work_4: (62,true),
 }
@@ -267,7 +346,7 @@ macro_rules! harvest_ref_all_should_work {
             #[test]
             fn $name() {
                 new_test_ext().execute_with(|| {
-                    <Payrolls<Test>>::insert(&PATHFINDER, Payroll {
+                    <Payrolls<Test>>::insert(round(), &PATHFINDER, Payroll {
                         count: $value.0,
                         total_fee: $value.1,
                         update_at: 1,
@@ -275,21 +354,22 @@ macro_rules! harvest_ref_all_should_work {
                     let total_amount = UpdateStakingAmount::get() * $value.0 + $value.1;
                     assert_ok!(ZdToken::staking(&ALICE, &1_000_000_000_000u128));
                     for a in INIT_ACCOUNT.iter() {
-                        <Records<Test>>::insert(&PATHFINDER,&a.account,Record {
+                        <Records<Test>>::insert(&RecordKey::new(round(), PATHFINDER), &a.account,Record {
                             update_at: 11,
                             fee: 111,
+                            domain: DEFAULT_DOMAIN,
                         });
                     }
                     System::set_block_number(500);
                     let old_balances = ZdToken::free_balance(&PATHFINDER);
-                    assert_ok!(ZdRefreshReputation::harvest_ref_all(Origin::signed(PATHFINDER)));
+                    assert_ok!(ZdRefreshReputation::harvest_ref_all(Origin::signed(PATHFINDER), round()));
                     let new_balances = ZdToken::free_balance(&PATHFINDER);
                     assert_eq!(new_balances - old_balances, total_amount);
                     for a in INIT_ACCOUNT.iter() {
-                        assert!(<Records<Test>>::try_get(&PATHFINDER,&a.account).is_err());
+                        assert!(<Records<Test>>::try_get(&RecordKey::new(round(), PATHFINDER), &a.account).is_err());
                     }
-                    let new_event = Event::zd_refresh_reputation(crate::Event::RefreshedHarvested(PATHFINDER, total_amount));
-                    assert!(System::events().iter().any(|record| record.event == new_event));
+                    let new_event = Event::zd_refresh_reputation(crate::Event::PayrollHarvested(PATHFINDER, total_amount, $value.0));
+                    assert_eq!(System::events().last().map(|record| &record.event), Some(&new_event));
                 });
             }
         )*
@@ -335,7 +415,9 @@ fn init_sys(score: u32) {
 
     ZdReputation::set_step(&TIRStep::Reputation);
     <StartedAt<Test>>::put(1);
+    ZdReputation::open_refresh();
 
+    register(PATHFINDER);
     assert_ok!(ZdRefreshReputation::refresh(
         Origin::signed(PATHFINDER),
         vec![(TARGET, score)]
@@ -351,9 +433,10 @@ fn challenge_should_work() {
             TARGET,
             PATHFINDER,
             3,
-            20
+            20,
+            None
         ));
-        let payroll = ZdRefreshReputation::get_payroll(PATHFINDER);
+        let payroll = ZdRefreshReputation::get_payroll(round(), &PATHFINDER);
         assert_eq!(payroll.total_fee, 0);
         assert_eq!(payroll.count, 0);
         assert_eq!(payroll.update_at, 1);
@@ -367,11 +450,25 @@ fn challenge_should_fail() {
     new_test_ext().execute_with(|| {
         init_sys(100);
         assert_noop!(
-            ZdRefreshReputation::challenge(Origin::signed(CHALLENGER), TARGET, PATHFINDER, 3, 100),
+            ZdRefreshReputation::challenge(
+                Origin::signed(CHALLENGER),
+                TARGET,
+                PATHFINDER,
+                3,
+                100,
+                None
+            ),
             Error::<Test>::SameReputation
         );
         assert_noop!(
-            ZdRefreshReputation::challenge(Origin::signed(CHALLENGER), TARGET, PATHFINDER, 10, 55),
+            ZdRefreshReputation::challenge(
+                Origin::signed(CHALLENGER),
+                TARGET,
+                PATHFINDER,
+                10,
+                55,
+                None
+            ),
             Error::<Test>::ExcessiveBumberOfSeeds
         );
         assert_noop!(
@@ -380,18 +477,270 @@ fn challenge_should_fail() {
                 TARGET,
                 PATHFINDER,
                 u32::MAX,
-                55
+                55,
+                None
             ),
             Error::<Test>::ExcessiveBumberOfSeeds
         );
-        System::set_block_number(<mock::Test as Config>::ConfirmationPeriod::get() + 100);
+        System::set_block_number(ConfirmationPeriod::get() + 100);
         assert_err_ignore_postinfo!(
-            ZdRefreshReputation::challenge(Origin::signed(CHALLENGER), TARGET, PATHFINDER, 3, 55),
+            ZdRefreshReputation::challenge(
+                Origin::signed(CHALLENGER),
+                TARGET,
+                PATHFINDER,
+                3,
+                55,
+                None
+            ),
             Error::<Test>::ChallengeTimeout
         );
     });
 }
 
+#[test]
+fn challenge_should_require_record() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        <Payrolls<Test>>::insert(
+            round(),
+            &ALICE,
+            Payroll {
+                count: 1,
+                total_fee: 10,
+                update_at: 1,
+            },
+        );
+        // `ALICE` has a payroll but did not refresh `TARGET`.
+        assert_noop!(
+            ZdRefreshReputation::challenge(Origin::signed(CHALLENGER), TARGET, ALICE, 3, 20, None),
+            Error::<Test>::RecordNotExist
+        );
+        assert_ok!(ZdRefreshReputation::challenge(
+            Origin::signed(CHALLENGER),
+            TARGET,
+            PATHFINDER,
+            3,
+            20,
+            None
+        ));
+        assert!(<Records<Test>>::try_get(&RecordKey::new(round(), PATHFINDER), &TARGET).is_err());
+        assert_eq!(ZdRefreshReputation::get_payroll(round(), &ALICE).count, 1);
+    });
+}
+
+#[test]
+fn record_key_should_encode_as_tuple() {
+    assert_eq!(
+        RecordKey::new(3, PATHFINDER).encode(),
+        (3 as RoundIndex, PATHFINDER).encode()
+    );
+}
+
+#[test]
+fn harvest_challenge_should_slash_pathfinder() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        assert_ok!(ZdRefreshReputation::challenge(
+            Origin::signed(CHALLENGER),
+            TARGET,
+            PATHFINDER,
+            3,
+            20,
+            None
+        ));
+        let stake = UpdateStakingAmount::get();
+        assert_eq!(
+            ZdRefreshReputation::challenged_stake(&TARGET),
+            Some((PATHFINDER, stake))
+        );
+        Challenges::set_status(&APP_ID, &TARGET, &ChallengeStatus::Examine);
+        System::set_block_number(ChallengeTimeout::get() + 2);
+        let treasury_balance = ZdToken::free_balance(&TreasuryAccountId::get());
+        assert_ok!(ZdRefreshReputation::harvest_challenge(
+            Origin::signed(CHALLENGER),
+            TARGET
+        ));
+        let to_challenger = ChallengerSlashRatio::get().mul_floor(stake);
+        let to_treasury = stake - to_challenger;
+        assert_eq!(
+            ZdToken::free_balance(&TreasuryAccountId::get()),
+            treasury_balance + to_treasury
+        );
+        assert_eq!(ZdRefreshReputation::challenged_stake(&TARGET), None);
+        let new_event = Event::zd_refresh_reputation(crate::Event::PathfinderSlashed(
+            PATHFINDER,
+            CHALLENGER,
+            to_challenger,
+            to_treasury,
+        ));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+        assert_ok!(ZdRefreshReputation::check_invariants());
+    });
+}
+
+#[test]
+fn back_challenge_should_work() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        assert_ok!(ZdRefreshReputation::challenge(
+            Origin::signed(CHALLENGER),
+            TARGET,
+            PATHFINDER,
+            3,
+            20,
+            None
+        ));
+        let free_balance = ZdToken::free_balance(&ALICE);
+        assert_ok!(ZdRefreshReputation::back_challenge(Origin::signed(ALICE), TARGET, 50));
+        assert_eq!(ZdToken::free_balance(&ALICE), free_balance - 50);
+        assert_eq!(Challenges::get_backers(&APP_ID, &TARGET), vec![(ALICE, 50)]);
+        let new_event =
+            Event::zd_refresh_reputation(crate::Event::ChallengeBacked(ALICE, TARGET, 50));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+    });
+}
+
+#[test]
+fn cancel_challenge_should_work() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        assert_ok!(ZdRefreshReputation::challenge(
+            Origin::signed(CHALLENGER),
+            TARGET,
+            PATHFINDER,
+            3,
+            20,
+            None
+        ));
+        assert_noop!(
+            ZdRefreshReputation::cancel_challenge(Origin::signed(ALICE), TARGET),
+            Error::<Test>::NotYourTurn
+        );
+        let challenger_balance = ZdToken::free_balance(&CHALLENGER);
+        assert_ok!(ZdRefreshReputation::cancel_challenge(
+            Origin::signed(CHALLENGER),
+            TARGET
+        ));
+        assert_eq!(
+            ZdToken::free_balance(&CHALLENGER),
+            challenger_balance + ChallengeStakingAmount::get() - CancellationFee::get()
+        );
+        assert_eq!(ZdRefreshReputation::challenged_stake(&TARGET), None);
+        let new_event =
+            Event::zd_refresh_reputation(crate::Event::ChallengeCancelled(CHALLENGER, TARGET));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+        assert_ok!(ZdRefreshReputation::check_invariants());
+    });
+}
+
+#[test]
+fn resolve_appeal_should_work() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        assert_ok!(ZdRefreshReputation::challenge(
+            Origin::signed(CHALLENGER),
+            TARGET,
+            PATHFINDER,
+            0,
+            20,
+            None
+        ));
+        // The arbitration sides with the challenger.
+        assert_ok!(Challenges::settle(&APP_ID, &TARGET, false, false, 20));
+        ZdReputation::mutate_reputation(&TARGET, &20);
+        assert_ok!(ZdRefreshReputation::appeal(Origin::signed(PATHFINDER), TARGET));
+        let new_event = Event::zd_refresh_reputation(crate::Event::Appealed(PATHFINDER, TARGET));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+
+        assert_noop!(
+            ZdRefreshReputation::resolve_appeal(Origin::signed(ALICE), TARGET, 100),
+            DispatchError::BadOrigin
+        );
+        // The arbiters may still vote on the appeal.
+        assert_noop!(
+            ZdRefreshReputation::resolve_appeal(Origin::root(), TARGET, 100),
+            zd_challenges::Error::<Test>::AppealVotingOpen
+        );
+        System::set_block_number(1 + AppealVotingPeriod::get() + 1);
+        assert_ok!(ZdRefreshReputation::resolve_appeal(Origin::root(), TARGET, 100));
+        assert_eq!(ZdReputation::get_reputation_new(&TARGET), Some(100));
+        let new_event = Event::zd_refresh_reputation(crate::Event::AppealResolved(TARGET, true));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+        assert_noop!(
+            ZdRefreshReputation::resolve_appeal(Origin::root(), TARGET, 100),
+            zd_challenges::Error::<Test>::NoAppeal
+        );
+    });
+}
+
+#[test]
+fn vote_appeal_should_work() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        assert_ok!(ZdRefreshReputation::challenge(
+            Origin::signed(CHALLENGER),
+            TARGET,
+            PATHFINDER,
+            0,
+            20,
+            None
+        ));
+        assert_ok!(Challenges::settle(&APP_ID, &TARGET, false, false, 20));
+        ZdReputation::mutate_reputation(&TARGET, &20);
+        assert_ok!(ZdRefreshReputation::appeal(Origin::signed(PATHFINDER), TARGET));
+
+        assert_noop!(
+            ZdRefreshReputation::vote_appeal(Origin::signed(CHALLENGER), TARGET, 100),
+            zd_challenges::Error::<Test>::NotArbiter
+        );
+        assert_ok!(ZdRefreshReputation::vote_appeal(Origin::signed(ALICE), TARGET, 100));
+        assert_eq!(ZdReputation::get_reputation_new(&TARGET), Some(20));
+        assert_ok!(ZdRefreshReputation::vote_appeal(Origin::signed(BOB), TARGET, 100));
+        assert_eq!(ZdReputation::get_reputation_new(&TARGET), Some(100));
+        let new_event = Event::zd_refresh_reputation(crate::Event::AppealResolved(TARGET, true));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+        assert_noop!(
+            ZdRefreshReputation::vote_appeal(Origin::signed(FERDIE), TARGET, 100),
+            zd_challenges::Error::<Test>::NoAppeal
+        );
+    });
+}
+
+#[test]
+fn pathfinder_suspension_should_work() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        ZdRefreshReputation::note_challenge_lost(&PATHFINDER);
+        assert!(!ZdRefreshReputation::is_suspended(&PATHFINDER));
+        ZdRefreshReputation::note_challenge_lost(&PATHFINDER);
+        assert!(ZdRefreshReputation::is_suspended(&PATHFINDER));
+        let suspended_until = round() + SuspensionRounds::get();
+        let new_event = Event::zd_refresh_reputation(crate::Event::PathfinderSuspended(
+            PATHFINDER,
+            suspended_until,
+        ));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+        assert_noop!(
+            ZdRefreshReputation::refresh(Origin::signed(PATHFINDER), vec![(ALICE, 20)]),
+            Error::<Test>::PathfinderSuspended
+        );
+        assert_noop!(
+            ZdRefreshReputation::lift_suspension(Origin::signed(ALICE), PATHFINDER),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(ZdRefreshReputation::lift_suspension(Origin::root(), PATHFINDER));
+        assert!(!ZdRefreshReputation::is_suspended(&PATHFINDER));
+        assert_eq!(
+            ZdRefreshReputation::pathfinder_status(&PATHFINDER),
+            PathfinderStatus::default()
+        );
+        assert_noop!(
+            ZdRefreshReputation::lift_suspension(Origin::root(), PATHFINDER),
+            Error::<Test>::NotSuspended
+        );
+    });
+}
+
 #[test]
 fn challenge_update_should_work() {
     new_test_ext().execute_with(|| {
@@ -401,7 +750,8 @@ fn challenge_update_should_work() {
             TARGET,
             PATHFINDER,
             3,
-            20
+            20,
+            None
         ));
         /*
         vec![SEED1, ALICE, TARGET],
@@ -458,7 +808,8 @@ fn challenge_update_should_fail() {
             TARGET,
             PATHFINDER,
             3,
-            20
+            20,
+            None
         ));
 
         assert_err_ignore_postinfo!(
@@ -534,7 +885,8 @@ fn arbitral_should_work() {
             TARGET,
             PATHFINDER,
             3,
-            20
+            20,
+            None
         ));
         /*
         vec![SEED1, ALICE, TARGET],
@@ -651,7 +1003,8 @@ fn arbitral_should_fail() {
             TARGET,
             PATHFINDER,
             3,
-            20
+            20,
+            None
         ));
         assert_ok!(ZdRefreshReputation::challenge_update(
             Origin::signed(CHALLENGER),
@@ -734,3 +1087,2096 @@ fn arbitral_should_fail() {
         );
     });
 }
+
+#[test]
+fn check_invariants_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ZdRefreshReputation::check_invariants());
+        init_sys(100);
+        assert_ok!(ZdRefreshReputation::check_invariants());
+
+        <Payrolls<Test>>::mutate(round(), &PATHFINDER, |p| p.count += 1);
+        assert!(ZdRefreshReputation::check_invariants().is_err());
+
+        <Payrolls<Test>>::mutate(round(), &PATHFINDER, |p| {
+            p.count -= 1;
+            p.total_fee += 1;
+        });
+        assert!(ZdRefreshReputation::check_invariants().is_err());
+
+        <Payrolls<Test>>::remove(round(), &PATHFINDER);
+        assert_eq!(
+            ZdRefreshReputation::check_invariants(),
+            Err("Record outlives its payroll")
+        );
+    });
+}
+
+#[cfg(feature = "debug-invariants")]
+#[test]
+#[should_panic]
+fn on_finalize_should_panic_on_corrupted_state() {
+    use frame_support::traits::OnFinalize;
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        <Records<Test>>::remove(&RecordKey::new(round(), PATHFINDER), &TARGET);
+        <ZdRefreshReputation as OnFinalize<BlockNumber>>::on_finalize(1);
+    });
+}
+
+#[test]
+fn harvest_ref_all_sweeper_should_rollback() {
+    new_test_ext().execute_with(|| {
+        let total_fee = 1000u128;
+        <Payrolls<Test>>::insert(
+            round(),
+            &PATHFINDER,
+            Payroll {
+                count: 0,
+                total_fee,
+                update_at: 1,
+            },
+        );
+        let (sweeper_fee, without_fee) = total_fee.with_fee();
+        // Enough to pay `sweeper`, but not `pathfinder`.
+        assert_ok!(ZdToken::staking(&ALICE, &(without_fee - 1)));
+        assert!(sweeper_fee < without_fee - 1);
+        System::set_block_number(2000);
+        assert_noop!(
+            ZdRefreshReputation::harvest_ref_all_sweeper(Origin::signed(SWEEPRT), round(), PATHFINDER),
+            zd_tokens::Error::<Test>::StakingAmountTooLow
+        );
+    });
+}
+
+#[test]
+fn refresh_should_be_atomic() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ZdReputation::new_round());
+        ZdReputation::set_step(&TIRStep::Reputation);
+        System::set_block_number(2000);
+        assert_ok!(ZdRefreshReputation::start(Origin::signed(PATHFINDER)));
+        register(PATHFINDER);
+        // The second entry fails after the staking and the first refresh are applied.
+        assert_noop!(
+            ZdRefreshReputation::refresh(Origin::signed(PATHFINDER), vec![(ALICE, 1), (ALICE, 2)]),
+            zd_reputation::Error::<Test>::ReputationAlreadyUpdated
+        );
+    });
+}
+
+#[test]
+fn refresh_should_reject_user_refreshed_by_another_pathfinder() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        register(CHALLENGER);
+        let social_balance = ZdToken::social_balance(&TARGET);
+        assert_noop!(
+            ZdRefreshReputation::refresh(Origin::signed(CHALLENGER), vec![(TARGET, 50)]),
+            zd_reputation::Error::<Test>::ReputationAlreadyUpdated
+        );
+        assert_ok!(ZdRefreshReputation::refresh_best_effort(
+            Origin::signed(CHALLENGER),
+            vec![(TARGET, 50)]
+        ));
+        // The social balance of `TARGET` is only shared by the first refresh.
+        assert_eq!(ZdToken::social_balance(&TARGET), social_balance);
+        assert_eq!(ZdReputation::get_reputation_new(&TARGET), Some(100));
+        assert!(<Payrolls<Test>>::try_get(round(), &CHALLENGER).is_err());
+    });
+}
+
+#[test]
+fn refresh_should_supersede_under_override_policy() {
+    new_test_ext().execute_with(|| {
+        ConflictPolicy::set(&RefreshConflict::Override);
+        init_sys(100);
+        System::set_block_number(100);
+        register(FERDIE);
+        assert_noop!(
+            ZdRefreshReputation::refresh(Origin::signed(FERDIE), vec![(TARGET, 50)]),
+            Error::<Test>::CannotSupersede
+        );
+
+        assert_ok!(ZdRefreshReputation::register_pathfinder(
+            Origin::signed(CHALLENGER),
+            PathfinderBond::get() + 1,
+            None
+        ));
+        let fee = <Records<Test>>::get(&RecordKey::new(round(), PATHFINDER), &TARGET).fee;
+        let pathfinder_balance = ZdToken::free_balance(&PATHFINDER);
+        assert_ok!(ZdRefreshReputation::refresh(
+            Origin::signed(CHALLENGER),
+            vec![(TARGET, 50)]
+        ));
+        assert!(<Records<Test>>::try_get(&RecordKey::new(round(), PATHFINDER), &TARGET).is_err());
+        assert!(<Payrolls<Test>>::try_get(round(), &PATHFINDER).is_err());
+        assert_eq!(
+            ZdToken::free_balance(&PATHFINDER),
+            pathfinder_balance + <mock::Test as Config>::UpdateStakingAmount::get()
+        );
+        assert_eq!(ZdReputation::get_reputation_new(&TARGET), Some(50));
+        assert_eq!(
+            ZdRefreshReputation::refreshed_by(&TARGET),
+            Some((round(), CHALLENGER))
+        );
+        let new_event = Event::zd_refresh_reputation(crate::Event::RefreshSuperseded(
+            CHALLENGER, PATHFINDER, TARGET, fee,
+        ));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+    });
+}
+
+#[test]
+fn start_should_be_atomic() {
+    new_test_ext().execute_with(|| {
+        ZdReputation::set_step(&TIRStep::Reputation);
+        System::set_block_number(2000);
+        for pathfinder in [ALICE, BOB].iter() {
+            <Payrolls<Test>>::insert(
+                round(),
+                pathfinder,
+                Payroll {
+                    count: 0,
+                    total_fee: 1000,
+                    update_at: 1,
+                },
+            );
+        }
+        // Only one of the payrolls can be released.
+        assert_ok!(ZdToken::staking(&FERDIE, &1500u128));
+        assert_noop!(
+            ZdRefreshReputation::start(Origin::signed(SWEEPRT)),
+            zd_tokens::Error::<Test>::StakingAmountTooLow
+        );
+    });
+}
+
+#[test]
+fn is_challengeable_should_work() {
+    new_test_ext().execute_with(|| {
+        assert!(!ZdRefreshReputation::is_challengeable(&PATHFINDER, &TARGET));
+        init_sys(100);
+        assert!(ZdRefreshReputation::is_challengeable(&PATHFINDER, &TARGET));
+        assert!(!ZdRefreshReputation::is_challengeable(&PATHFINDER, &ALICE));
+
+        let confirmation_period = ConfirmationPeriod::get();
+        System::set_block_number(confirmation_period);
+        assert!(ZdRefreshReputation::is_challengeable(&PATHFINDER, &TARGET));
+        System::set_block_number(confirmation_period + 1);
+        assert!(!ZdRefreshReputation::is_challengeable(&PATHFINDER, &TARGET));
+        assert_err_ignore_postinfo!(
+            ZdRefreshReputation::challenge(
+                Origin::signed(CHALLENGER),
+                TARGET,
+                PATHFINDER,
+                3,
+                55,
+                None
+            ),
+            Error::<Test>::ChallengeTimeout
+        );
+    });
+}
+
+#[test]
+fn challenge_state_should_work() {
+    new_test_ext().execute_with(|| {
+        assert!(!ZdRefreshReputation::challenge_state(&TARGET).1);
+        init_sys(100);
+        assert_eq!(ZdRefreshReputation::refreshed_by(&TARGET), Some((round(), PATHFINDER)));
+        assert_eq!(ZdRefreshReputation::challenge_state(&TARGET), (100, true));
+        assert!(!ZdRefreshReputation::challenge_state(&ALICE).1);
+
+        assert_ok!(ZdRefreshReputation::transfer_payroll(
+            Origin::signed(PATHFINDER),
+            round(),
+            ALICE,
+            false
+        ));
+        assert_eq!(ZdRefreshReputation::refreshed_by(&TARGET), Some((round(), ALICE)));
+        assert_eq!(ZdRefreshReputation::challenge_state(&TARGET), (100, true));
+
+        let confirmation_period = ConfirmationPeriod::get();
+        System::set_block_number(confirmation_period + 1);
+        assert_eq!(ZdRefreshReputation::challenge_state(&TARGET), (100, false));
+    });
+}
+
+#[test]
+fn transfer_payroll_should_work() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        let payroll = ZdRefreshReputation::get_payroll(round(), &PATHFINDER);
+        assert_ok!(ZdRefreshReputation::transfer_payroll(
+            Origin::signed(PATHFINDER),
+            round(),
+            ALICE,
+            false
+        ));
+        assert!(<Payrolls<Test>>::try_get(round(), &PATHFINDER).is_err());
+        assert!(<Records<Test>>::try_get(&RecordKey::new(round(), PATHFINDER), &TARGET).is_err());
+        assert_eq!(ZdRefreshReputation::get_payroll(round(), &ALICE), payroll);
+        assert!(<Records<Test>>::try_get(&RecordKey::new(round(), ALICE), &TARGET).is_ok());
+        let new_event =
+            Event::zd_refresh_reputation(crate::Event::PayrollTransferred(PATHFINDER, ALICE));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+    });
+}
+
+#[test]
+fn transfer_payroll_should_merge() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        let payroll = ZdRefreshReputation::get_payroll(round(), &PATHFINDER);
+        <Payrolls<Test>>::insert(
+            round(),
+            &ALICE,
+            Payroll {
+                count: 1,
+                total_fee: 10,
+                update_at: 3,
+            },
+        );
+        ZdRefreshReputation::mutate_record(round(), &ALICE, &BOB, &10, &3);
+        assert_noop!(
+            ZdRefreshReputation::transfer_payroll(Origin::signed(PATHFINDER), round(), ALICE, false),
+            Error::<Test>::PayrollAlreadyExist
+        );
+        assert_ok!(ZdRefreshReputation::transfer_payroll(
+            Origin::signed(PATHFINDER),
+            round(),
+            ALICE,
+            true
+        ));
+        assert_eq!(
+            ZdRefreshReputation::get_payroll(round(), &ALICE),
+            Payroll {
+                count: payroll.count + 1,
+                total_fee: payroll.total_fee + 10,
+                update_at: 3,
+            }
+        );
+        assert!(<Records<Test>>::try_get(&RecordKey::new(round(), ALICE), &TARGET).is_ok());
+        assert!(<Records<Test>>::try_get(&RecordKey::new(round(), ALICE), &BOB).is_ok());
+        let new_event =
+            Event::zd_refresh_reputation(crate::Event::PayrollMerged(PATHFINDER, ALICE));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+    });
+}
+
+#[test]
+fn transfer_payroll_should_fail() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ZdRefreshReputation::transfer_payroll(Origin::signed(PATHFINDER), round(), ALICE, true),
+            Error::<Test>::NoPayroll
+        );
+        init_sys(100);
+        assert_noop!(
+            ZdRefreshReputation::transfer_payroll(Origin::signed(PATHFINDER), round(), PATHFINDER, true),
+            Error::<Test>::SamePathfinder
+        );
+        <Payrolls<Test>>::insert(
+            round(),
+            &ALICE,
+            Payroll {
+                count: 1,
+                total_fee: 10,
+                update_at: 3,
+            },
+        );
+        ZdRefreshReputation::mutate_record(round(), &ALICE, &TARGET, &10, &3);
+        assert_noop!(
+            ZdRefreshReputation::transfer_payroll(Origin::signed(PATHFINDER), round(), ALICE, true),
+            Error::<Test>::RecordConflict
+        );
+    });
+}
+
+#[test]
+fn refresh_should_time_out_without_grace() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        let refresh_ends_at = ZdReputation::round_info().refresh_ends_at;
+        assert_eq!(refresh_ends_at, 1 + RefRepuTiomeOut::get());
+
+        System::set_block_number(refresh_ends_at - 1);
+        assert_ok!(ZdRefreshReputation::refresh(
+            Origin::signed(PATHFINDER),
+            vec![(ALICE, 10)]
+        ));
+        // `ProxyGracePeriod` only applies to sweepers.
+        System::set_block_number(refresh_ends_at);
+        assert_noop!(
+            ZdRefreshReputation::refresh(Origin::signed(PATHFINDER), vec![(BOB, 10)]),
+            Error::<Test>::RefreshTiomeOut
+        );
+    });
+}
+
+#[test]
+fn refresh_should_follow_round_phase() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ZdReputation::new_round());
+        ZdReputation::set_step(&TIRStep::Reputation);
+        register(PATHFINDER);
+        System::set_block_number(50);
+        assert_ok!(ZdRefreshReputation::start(Origin::signed(SWEEPRT)));
+        let round_info = ZdReputation::round_info();
+        assert_eq!(round_info.refresh_ends_at, 50 + RefRepuTiomeOut::get());
+        assert_eq!(
+            round_info.confirm_ends_at,
+            round_info.refresh_ends_at + ConfirmationPeriod::get()
+        );
+        assert_eq!(ZdReputation::phase(), Phase::Updating);
+
+        System::set_block_number(round_info.refresh_ends_at - 1);
+        assert_eq!(ZdReputation::phase_at(System::block_number()), Phase::Updating);
+        assert_ok!(ZdRefreshReputation::refresh(
+            Origin::signed(PATHFINDER),
+            vec![(ALICE, 10)]
+        ));
+
+        System::set_block_number(round_info.refresh_ends_at);
+        assert_eq!(ZdReputation::phase_at(System::block_number()), Phase::Confirming);
+        assert_noop!(
+            ZdRefreshReputation::refresh(Origin::signed(PATHFINDER), vec![(BOB, 10)]),
+            Error::<Test>::RefreshTiomeOut
+        );
+    });
+}
+
+#[test]
+fn refresh_event_should_have_topic() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        let topic = <Test as frame_system::Config>::Hashing::hash_of(&PATHFINDER);
+        let record = System::events()
+            .into_iter()
+            .find(|record| {
+                matches!(
+                    record.event,
+                    Event::zd_refresh_reputation(crate::Event::ReputationRefreshed(..))
+                )
+            })
+            .unwrap();
+        assert_eq!(record.topics, vec![topic]);
+        assert_eq!(System::event_topics(&topic).len(), 1);
+    });
+}
+
+#[test]
+fn start_should_drain_matured_payrolls() {
+    new_test_ext().execute_with(|| {
+        ZdReputation::set_step(&TIRStep::Reputation);
+        System::set_block_number(2000);
+        assert_ok!(ZdToken::staking(&FERDIE, &1_000_000_000_000u128));
+        Challenges::set_metadata(&APP_ID, &TARGET, &Metadata::default());
+
+        assert_noop!(
+            ZdRefreshReputation::start(Origin::signed(SWEEPRT)),
+            Error::<Test>::ChallengeNotClaimed
+        );
+
+        <Payrolls<Test>>::insert(round(), &ALICE, INIT_PAYROLLS[0].clone());
+        <Payrolls<Test>>::insert(
+            round(),
+            &BOB,
+            Payroll {
+                count: 1,
+                total_fee: 10,
+                update_at: 1990,
+            },
+        );
+        let alice_balance = ZdToken::free_balance(&ALICE);
+        assert_ok!(ZdRefreshReputation::start(Origin::signed(SWEEPRT)));
+
+        let (fee, awards) = INIT_PAYROLLS[0].total_amount::<Test>(round(), &ALICE).with_fee();
+        assert_eq!(ZdToken::free_balance(&ALICE), alice_balance + awards);
+        let new_event = Event::zd_refresh_reputation(crate::Event::PayrollProxySettled(
+            SWEEPRT, ALICE, fee, awards,
+        ));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+        assert!(<Payrolls<Test>>::try_get(round(), &ALICE).is_err());
+        assert!(<Payrolls<Test>>::try_get(round(), &BOB).is_ok());
+        assert!(!<StartedAt<Test>>::exists());
+        let new_event = Event::zd_refresh_reputation(crate::Event::PayrollsDrained(SWEEPRT, 1));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+
+        assert_noop!(
+            ZdRefreshReputation::start(Origin::signed(SWEEPRT)),
+            Error::<Test>::ChallengeNotClaimed
+        );
+
+        zd_challenges::Metadatas::<Test>::remove(&APP_ID, &TARGET);
+        assert_ok!(ZdRefreshReputation::start(Origin::signed(SWEEPRT)));
+        assert!(<Payrolls<Test>>::try_get(round(), &BOB).is_ok());
+        assert!(<StartedAt<Test>>::exists());
+    });
+}
+
+#[test]
+fn old_round_payroll_should_not_block_new_round() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        let old_round = round();
+        let payroll = ZdRefreshReputation::get_payroll(old_round, &PATHFINDER);
+        assert_eq!(payroll.count, 1);
+
+        <StartedAt<Test>>::kill();
+        zd_reputation::SystemInfo::<Test>::mutate(|s| s.nonce += 1);
+        System::set_block_number(50);
+        assert_ok!(ZdRefreshReputation::start(Origin::signed(SWEEPRT)));
+        assert_ne!(round(), old_round);
+        assert_eq!(ZdRefreshReputation::get_payroll(old_round, &PATHFINDER), payroll);
+        assert!(<Payrolls<Test>>::try_get(round(), &PATHFINDER).is_err());
+
+        System::set_block_number(200);
+        let pathfinder_balance = ZdToken::free_balance(&PATHFINDER);
+        assert_ok!(ZdRefreshReputation::harvest_ref_all(
+            Origin::signed(PATHFINDER),
+            old_round
+        ));
+        assert_eq!(
+            ZdToken::free_balance(&PATHFINDER),
+            pathfinder_balance + payroll.total_amount::<Test>(old_round, &PATHFINDER)
+        );
+        assert!(<Payrolls<Test>>::try_get(old_round, &PATHFINDER).is_err());
+        assert!(<Records<Test>>::try_get(&RecordKey::new(old_round, PATHFINDER), &TARGET).is_err());
+    });
+}
+
+#[test]
+fn refresh_should_fail_with_insufficient_stake() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        assert_noop!(
+            ZdRefreshReputation::refresh(Origin::signed(DAVE), vec![(ALICE, 10)]),
+            Error::<Test>::NotPathfinder
+        );
+        register(SWEEPRT);
+        assert_noop!(
+            ZdRefreshReputation::refresh(Origin::signed(SWEEPRT), vec![(ALICE, 10)]),
+            Error::<Test>::InsufficientStake
+        );
+        assert_noop!(
+            ZdRefreshReputation::refresh(Origin::signed(SWEEPRT), vec![(ALICE, 10), (BOB, 10)]),
+            Error::<Test>::InsufficientStake
+        );
+    });
+}
+
+#[test]
+fn social_balances_should_work() {
+    new_test_ext().execute_with(|| {
+        let amounts = [100u128, 0, 2000, 31, 7];
+        let accounts = [BOB, CHARLIE, DAVE, EVE, TARGET];
+        for (who, amount) in accounts.iter().zip(amounts.iter()) {
+            if *amount > 0 {
+                assert_ok!(ZdToken::transfer_social(Origin::signed(ALICE), *who, *amount));
+            }
+        }
+        let max_count = <mock::Test as Config>::MaxUpdateCount::get() as usize;
+        let balances = ZdRefreshReputation::social_balances(&accounts[..]);
+        assert_eq!(balances.len(), max_count);
+        for (who, balance) in accounts.iter().zip(balances.iter()) {
+            assert_eq!(ZdToken::social_balance(who), *balance);
+        }
+        assert_eq!(balances, amounts[..max_count].to_vec());
+    });
+}
+
+#[test]
+fn retract_refresh_should_work() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        assert_ok!(ZdRefreshReputation::refresh(
+            Origin::signed(PATHFINDER),
+            vec![(ALICE, 10)]
+        ));
+        let fee = <Records<Test>>::get(&RecordKey::new(round(), PATHFINDER), &TARGET).fee;
+        let total_fee = ZdRefreshReputation::get_payroll(round(), &PATHFINDER).total_fee;
+        let pathfinder_balance = ZdToken::free_balance(&PATHFINDER);
+        let target_balance = ZdToken::free_balance(&TARGET);
+
+        assert_ok!(ZdRefreshReputation::retract_refresh(
+            Origin::signed(PATHFINDER),
+            vec![TARGET]
+        ));
+        assert!(<Records<Test>>::try_get(&RecordKey::new(round(), PATHFINDER), &TARGET).is_err());
+        assert_eq!(ZdReputation::get_reputation_new(&TARGET), None);
+        assert_eq!(
+            ZdToken::free_balance(&PATHFINDER),
+            pathfinder_balance + <mock::Test as Config>::UpdateStakingAmount::get()
+        );
+        assert_eq!(ZdToken::free_balance(&TARGET), target_balance + fee);
+        let payroll = ZdRefreshReputation::get_payroll(round(), &PATHFINDER);
+        assert_eq!(payroll.count, 1);
+        assert_eq!(payroll.total_fee, total_fee - fee);
+        let new_event = Event::zd_refresh_reputation(crate::Event::RefreshRetracted(
+            PATHFINDER, 1, fee,
+        ));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+
+        assert_noop!(
+            ZdRefreshReputation::retract_refresh(Origin::signed(PATHFINDER), vec![TARGET]),
+            Error::<Test>::RecordNotExist
+        );
+        System::set_block_number(2);
+        assert_noop!(
+            ZdRefreshReputation::retract_refresh(Origin::signed(PATHFINDER), vec![ALICE]),
+            Error::<Test>::RetractTooLate
+        );
+    });
+}
+
+#[test]
+fn amend_and_withdraw_refresh_should_work() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        System::set_block_number(50);
+        assert_noop!(
+            ZdRefreshReputation::amend_refresh(Origin::signed(PATHFINDER), TARGET, 100),
+            Error::<Test>::SameReputation
+        );
+        assert_ok!(ZdRefreshReputation::amend_refresh(
+            Origin::signed(PATHFINDER),
+            TARGET,
+            80
+        ));
+        assert_eq!(ZdReputation::get_reputation_new(&TARGET), Some(80));
+        assert_eq!(
+            <Records<Test>>::get(&RecordKey::new(round(), PATHFINDER), &TARGET).update_at,
+            50
+        );
+        assert_eq!(ZdRefreshReputation::get_payroll(round(), &PATHFINDER).update_at, 50);
+        let new_event = Event::zd_refresh_reputation(crate::Event::RefreshAmended(
+            PATHFINDER, TARGET, 80,
+        ));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+
+        assert_noop!(
+            ZdRefreshReputation::withdraw_refresh(Origin::signed(PATHFINDER), ALICE),
+            Error::<Test>::RecordNotExist
+        );
+        System::set_block_number(171);
+        assert_noop!(
+            ZdRefreshReputation::withdraw_refresh(Origin::signed(PATHFINDER), TARGET),
+            Error::<Test>::ConfirmationElapsed
+        );
+
+        System::set_block_number(100);
+        let fee = <Records<Test>>::get(&RecordKey::new(round(), PATHFINDER), &TARGET).fee;
+        let pathfinder_balance = ZdToken::free_balance(&PATHFINDER);
+        let target_balance = ZdToken::free_balance(&TARGET);
+        assert_ok!(ZdRefreshReputation::withdraw_refresh(
+            Origin::signed(PATHFINDER),
+            TARGET
+        ));
+        assert!(<Records<Test>>::try_get(&RecordKey::new(round(), PATHFINDER), &TARGET).is_err());
+        assert!(<Payrolls<Test>>::try_get(round(), &PATHFINDER).is_err());
+        assert_eq!(ZdReputation::get_reputation_new(&TARGET), None);
+        assert_eq!(
+            ZdToken::free_balance(&PATHFINDER),
+            pathfinder_balance + <mock::Test as Config>::UpdateStakingAmount::get()
+        );
+        assert_eq!(ZdToken::free_balance(&TARGET), target_balance + fee);
+        let new_event = Event::zd_refresh_reputation(crate::Event::RefreshWithdrawn(
+            PATHFINDER, TARGET, fee,
+        ));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+    });
+}
+
+#[test]
+fn refresh_in_should_work() {
+    const FINANCE: DomainId = *b"finance\0";
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        assert_noop!(
+            ZdRefreshReputation::refresh_in(
+                Origin::signed(PATHFINDER),
+                FINANCE,
+                vec![(ALICE, 30)]
+            ),
+            zd_reputation::Error::<Test>::UnknownDomain
+        );
+        assert_ok!(ZdReputation::add_domain(Origin::root(), FINANCE));
+        assert_noop!(
+            ZdRefreshReputation::refresh_in(
+                Origin::signed(PATHFINDER),
+                FINANCE,
+                vec![(TARGET, 30)]
+            ),
+            Error::<Test>::RecordExists
+        );
+        assert_ok!(ZdRefreshReputation::refresh_in(
+            Origin::signed(PATHFINDER),
+            FINANCE,
+            vec![(ALICE, 30)]
+        ));
+        assert_eq!(ZdReputation::get_reputation_new_in(&FINANCE, &ALICE), Some(30));
+        assert_eq!(ZdReputation::get_reputation_new(&ALICE), None);
+        let record = <Records<Test>>::get(&RecordKey::new(round(), PATHFINDER), &ALICE);
+        assert_eq!(record.domain, FINANCE);
+        assert_eq!(ZdRefreshReputation::get_payroll(round(), &PATHFINDER).count, 2);
+        let new_event = Event::zd_refresh_reputation(crate::Event::ReputationRefreshedIn(
+            PATHFINDER, FINANCE, 1, record.fee,
+        ));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+
+        assert_noop!(
+            ZdRefreshReputation::amend_refresh(Origin::signed(PATHFINDER), ALICE, 40),
+            Error::<Test>::NotDefaultDomain
+        );
+        assert!(!ZdRefreshReputation::is_challengeable(&PATHFINDER, &ALICE));
+
+        assert_ok!(ZdRefreshReputation::withdraw_refresh(
+            Origin::signed(PATHFINDER),
+            ALICE
+        ));
+        assert_eq!(ZdReputation::get_reputation_new_in(&FINANCE, &ALICE), None);
+        assert_eq!(ZdReputation::get_reputation_new(&TARGET), Some(100));
+        assert!(<Records<Test>>::try_get(&RecordKey::new(round(), PATHFINDER), &ALICE).is_err());
+    });
+}
+
+#[test]
+fn refresh_weight_should_grow_with_trust_count() {
+    let weights = (0..600)
+        .step_by(50)
+        .map(|d| <() as WeightInfo>::refresh(1, d))
+        .collect::<Vec<_>>();
+    assert!(weights.windows(2).all(|w| w[0] < w[1]));
+    assert!(<() as WeightInfo>::refresh(1, 0) < <() as WeightInfo>::refresh(2, 0));
+
+    // The benchmarked weight of refreshing `a` users before trust counts were charged.
+    let benchmarked = |a: Weight| {
+        use frame_support::weights::constants::RocksDbWeight;
+        4_300_286_000 * a + RocksDbWeight::get().reads_writes(7 + 604 * a, 6 + 602 * a)
+    };
+    for a in 0..=MAX_REFRESH {
+        assert!(<() as WeightInfo>::refresh(a, 0) >= benchmarked(a as Weight));
+    }
+}
+
+#[test]
+fn too_many_user_scores_should_fail() {
+    new_test_ext().execute_with(|| {
+        let user_scores = (0..=MAX_REFRESH).map(|a| (a, 0)).collect::<Vec<_>>();
+        assert_noop!(
+            ZdRefreshReputation::refresh(Origin::signed(PATHFINDER), user_scores.clone()),
+            Error::<Test>::TooManyUserScores
+        );
+        let payload = RefreshPayload {
+            pathfinder: PATHFINDER,
+            user_scores,
+            round: round(),
+            nonce: 0,
+        };
+        let signature = TestSignature(PATHFINDER, payload.encode());
+        assert_noop!(
+            ZdRefreshReputation::refresh_signed_payload(
+                Origin::signed(CHARLIE),
+                payload.clone(),
+                signature.clone()
+            ),
+            Error::<Test>::TooManyUserScores
+        );
+        assert_noop!(
+            ZdRefreshReputation::refresh_unsigned(Origin::none(), payload, signature),
+            Error::<Test>::TooManyUserScores
+        );
+    });
+}
+
+#[test]
+fn total_trust_count_should_work() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        assert_eq!(
+            ZdRefreshReputation::total_trust_count(&[(SEED3, 0), (ALICE, 0), (TARGET, 0)]),
+            (ZdTrust::get_trust_count_old(&SEED3)
+                + ZdTrust::get_trust_count_old(&ALICE)
+                + ZdTrust::get_trust_count_old(&TARGET)) as u32
+        );
+        assert_eq!(
+            ZdRefreshReputation::total_trust_count(&[(SEED3, 0), (ALICE, 0)]),
+            4
+        );
+    });
+}
+
+#[test]
+fn round_stats_should_work() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        register(CHALLENGER);
+        assert_ok!(ZdRefreshReputation::refresh(
+            Origin::signed(PATHFINDER),
+            vec![(ALICE, 10)]
+        ));
+        assert_ok!(ZdRefreshReputation::refresh(
+            Origin::signed(CHALLENGER),
+            vec![(BOB, 3)]
+        ));
+        let total_fee = ZdRefreshReputation::get_payroll(round(), &PATHFINDER).total_fee
+            + ZdRefreshReputation::get_payroll(round(), &CHALLENGER).total_fee;
+        let stat = ZdRefreshReputation::round_stats(round());
+        assert_eq!(stat.users, 3);
+        assert_eq!(stat.pathfinders, 2);
+        assert_eq!(stat.total_fee, total_fee);
+        assert_eq!(stat.total_staked, 3 * UpdateStakingAmount::get());
+        assert_eq!(stat.challenges, 0);
+
+        assert_ok!(ZdRefreshReputation::challenge(
+            Origin::signed(SUB_CHALLENGER),
+            TARGET,
+            PATHFINDER,
+            3,
+            20,
+            None
+        ));
+        assert_eq!(ZdRefreshReputation::round_stats(round()).challenges, 1);
+    });
+}
+
+#[test]
+fn round_closed_should_work() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        System::set_block_number(ConfirmationPeriod::get() + 2);
+        ZdRefreshReputation::next_step();
+        let new_event = Event::zd_refresh_reputation(crate::Event::RoundClosed(
+            round(),
+            ZdRefreshReputation::round_stats(round()),
+        ));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+        assert_eq!(ZdRefreshReputation::round_stats(round()).users, 1);
+    });
+}
+
+#[test]
+fn force_new_round_should_work() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        assert_ok!(ZdRefreshReputation::refresh(
+            Origin::signed(PATHFINDER),
+            vec![(ALICE, 10)]
+        ));
+        assert_ok!(ZdRefreshReputation::challenge(
+            Origin::signed(CHALLENGER),
+            TARGET,
+            PATHFINDER,
+            3,
+            20,
+            None
+        ));
+        assert_noop!(
+            ZdRefreshReputation::force_new_round(Origin::signed(ALICE), false),
+            DispatchError::BadOrigin
+        );
+
+        let amount = ZdRefreshReputation::get_payroll(round(), &PATHFINDER)
+            .total_amount::<Test>(round(), &PATHFINDER);
+        let treasury_balance = ZdToken::free_balance(&TreasuryAccountId::get());
+        assert_ok!(ZdRefreshReputation::force_new_round(Origin::root(), false));
+        assert!(!<StartedAt<Test>>::exists());
+        assert!(ZdReputation::is_step(&TIRStep::Free));
+        // The payroll can still be challenged, it is only swept once it has matured.
+        assert!(<Payrolls<Test>>::contains_key(round(), &PATHFINDER));
+        assert!(<Records<Test>>::contains_key(&RecordKey::new(round(), PATHFINDER), &ALICE));
+        assert_eq!(
+            ZdToken::free_balance(&TreasuryAccountId::get()),
+            treasury_balance
+        );
+
+        System::set_block_number(2000);
+        assert_ok!(ZdRefreshReputation::force_new_round(Origin::root(), false));
+        assert!(!<Payrolls<Test>>::contains_key(round(), &PATHFINDER));
+        assert_eq!(
+            ZdRefreshReputation::update_record(&RecordKey::new(round(), PATHFINDER), &ALICE).fee,
+            0
+        );
+        assert_eq!(
+            ZdToken::free_balance(&TreasuryAccountId::get()),
+            treasury_balance + amount
+        );
+        let swept_event = Event::zd_refresh_reputation(crate::Event::PayrollSwept(
+            round(),
+            PATHFINDER,
+            amount,
+        ));
+        assert!(System::events().iter().any(|record| record.event == swept_event));
+        let forced_event = Event::zd_refresh_reputation(crate::Event::RoundForced(false));
+        assert!(System::events().iter().any(|record| record.event == forced_event));
+
+        ZdReputation::set_step(&TIRStep::Reputation);
+        assert_ok!(ZdRefreshReputation::start(Origin::signed(ALICE)));
+        assert!(!<ForceStart<Test>>::get());
+    });
+}
+
+#[test]
+fn force_new_round_should_carry_over_payrolls() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        let payroll = ZdRefreshReputation::get_payroll(round(), &PATHFINDER);
+        assert_ok!(ZdRefreshReputation::force_new_round(Origin::root(), true));
+        assert!(!<StartedAt<Test>>::exists());
+        assert_eq!(ZdRefreshReputation::get_payroll(round(), &PATHFINDER), payroll);
+        assert!(<ForceStart<Test>>::get());
+    });
+}
+
+#[test]
+fn pathfinder_exposure_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            ZdRefreshReputation::pathfinder_exposure(round(), &PATHFINDER),
+            (0, 0, 0, 0)
+        );
+        init_sys(100);
+        assert_ok!(ZdRefreshReputation::refresh(
+            Origin::signed(PATHFINDER),
+            vec![(ALICE, 10)]
+        ));
+        let payroll = ZdRefreshReputation::get_payroll(round(), &PATHFINDER);
+        assert_eq!(
+            ZdRefreshReputation::pathfinder_exposure(round(), &PATHFINDER),
+            (2, payroll.total_fee, 2, 2 * UpdateStakingAmount::get())
+        );
+    });
+}
+
+#[test]
+fn check_payroll_integrity_should_work() {
+    new_test_ext().execute_with(|| {
+        assert!(ZdRefreshReputation::check_payroll_integrity(round(), &PATHFINDER));
+        init_sys(100);
+        assert!(ZdRefreshReputation::check_payroll_integrity(round(), &PATHFINDER));
+
+        <Records<Test>>::mutate(&RecordKey::new(round(), PATHFINDER), &TARGET, |r| r.fee += 1);
+        assert!(!ZdRefreshReputation::check_payroll_integrity(round(), &PATHFINDER));
+        <Records<Test>>::mutate(&RecordKey::new(round(), PATHFINDER), &TARGET, |r| r.fee -= 1);
+        assert!(ZdRefreshReputation::check_payroll_integrity(round(), &PATHFINDER));
+
+        ZdRefreshReputation::mutate_record(round(), &PATHFINDER, &ALICE, &0, &1);
+        assert!(!ZdRefreshReputation::check_payroll_integrity(round(), &PATHFINDER));
+        assert!(ZdRefreshReputation::check_invariants().is_err());
+    });
+}
+
+#[test]
+fn harvest_ref_matured_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ZdToken::staking(&FERDIE, &1_000_000_000_000u128));
+        <Payrolls<Test>>::insert(
+            round(),
+            &PATHFINDER,
+            Payroll {
+                count: 3,
+                total_fee: 30,
+                update_at: 150,
+            },
+        );
+        ZdRefreshReputation::mutate_record(round(), &PATHFINDER, &ALICE, &10, &1);
+        ZdRefreshReputation::mutate_record(round(), &PATHFINDER, &BOB, &10, &1);
+        ZdRefreshReputation::mutate_record(round(), &PATHFINDER, &TARGET, &10, &150);
+        let staking_amount = <mock::Test as Config>::UpdateStakingAmount::get();
+
+        System::set_block_number(200);
+        let old_balance = ZdToken::free_balance(&PATHFINDER);
+        assert_ok!(ZdRefreshReputation::harvest_ref_matured(
+            Origin::signed(PATHFINDER),
+            round()
+        ));
+        assert_eq!(
+            ZdToken::free_balance(&PATHFINDER),
+            old_balance + staking_amount * 2 + 20
+        );
+        assert_eq!(
+            ZdRefreshReputation::get_payroll(round(), &PATHFINDER),
+            Payroll {
+                count: 1,
+                total_fee: 10,
+                update_at: 150,
+            }
+        );
+        assert!(<Records<Test>>::try_get(&RecordKey::new(round(), PATHFINDER), &ALICE).is_err());
+        assert!(<Records<Test>>::try_get(&RecordKey::new(round(), PATHFINDER), &TARGET).is_ok());
+        let new_event = Event::zd_refresh_reputation(crate::Event::RefreshedPartiallyHarvested(
+            PATHFINDER,
+            staking_amount * 2 + 20,
+            1,
+        ));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+
+        assert_noop!(
+            ZdRefreshReputation::harvest_ref_matured(Origin::signed(PATHFINDER), round()),
+            Error::<Test>::NoMaturedRecord
+        );
+
+        System::set_block_number(300);
+        assert_ok!(ZdRefreshReputation::harvest_ref_matured(
+            Origin::signed(PATHFINDER),
+            round()
+        ));
+        assert!(<Payrolls<Test>>::try_get(round(), &PATHFINDER).is_err());
+        assert_eq!(
+            ZdToken::free_balance(&PATHFINDER),
+            old_balance + staking_amount * 3 + 30
+        );
+    });
+}
+
+#[test]
+fn harvest_ref_partial_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ZdToken::staking(&FERDIE, &1_000_000_000_000u128));
+        <Payrolls<Test>>::insert(
+            round(),
+            &PATHFINDER,
+            Payroll {
+                count: 3,
+                total_fee: 30,
+                update_at: 1,
+            },
+        );
+        ZdRefreshReputation::mutate_record(round(), &PATHFINDER, &ALICE, &10, &1);
+        ZdRefreshReputation::mutate_record(round(), &PATHFINDER, &BOB, &10, &1);
+        ZdRefreshReputation::mutate_record(round(), &PATHFINDER, &TARGET, &10, &1);
+        let staking_amount = <mock::Test as Config>::UpdateStakingAmount::get();
+
+        assert_noop!(
+            ZdRefreshReputation::harvest_ref_partial(Origin::signed(PATHFINDER), round(), 2),
+            Error::<Test>::ExcessiveBumberOfSeeds
+        );
+
+        System::set_block_number(200);
+        let old_balance = ZdToken::free_balance(&PATHFINDER);
+        assert_ok!(ZdRefreshReputation::harvest_ref_partial(
+            Origin::signed(PATHFINDER),
+            round(),
+            2
+        ));
+        assert_eq!(
+            ZdToken::free_balance(&PATHFINDER),
+            old_balance + staking_amount * 2 + 20
+        );
+        assert_eq!(ZdRefreshReputation::get_payroll(round(), &PATHFINDER).count, 1);
+        assert_eq!(<Records<Test>>::iter_prefix(&RecordKey::new(round(), PATHFINDER)).count(), 1);
+        assert!(ZdRefreshReputation::check_payroll_integrity(round(), &PATHFINDER));
+
+        assert_ok!(ZdRefreshReputation::harvest_ref_partial(
+            Origin::signed(PATHFINDER),
+            round(),
+            2
+        ));
+        assert!(<Payrolls<Test>>::try_get(round(), &PATHFINDER).is_err());
+        assert_eq!(
+            ZdToken::free_balance(&PATHFINDER),
+            old_balance + staking_amount * 3 + 30
+        );
+        assert_noop!(
+            ZdRefreshReputation::harvest_ref_partial(Origin::signed(PATHFINDER), round(), 2),
+            Error::<Test>::NoPayroll
+        );
+    });
+}
+
+#[test]
+fn can_sweeper_harvest_should_match_harvest() {
+    new_test_ext().execute_with(|| {
+        let check = |is_ok: bool| {
+            assert_eq!(ZdRefreshReputation::can_sweeper_harvest(round(), &PATHFINDER), is_ok);
+            assert_eq!(
+                ZdRefreshReputation::harvest_ref_all_sweeper(Origin::signed(SWEEPRT), round(), PATHFINDER)
+                    .is_ok(),
+                is_ok
+            );
+        };
+        // No payroll.
+        check(false);
+
+        let payroll = Payroll {
+            count: 1,
+            total_fee: 1000,
+            update_at: 1,
+        };
+        <Payrolls<Test>>::insert(round(), &PATHFINDER, payroll.clone());
+        // Still in the confirmation period.
+        System::set_block_number(100);
+        check(false);
+        // Not yet open to sweepers.
+        System::set_block_number(400);
+        check(false);
+        // The staking pool cannot cover the payroll.
+        System::set_block_number(2000);
+        assert_ok!(ZdToken::staking(
+            &ALICE,
+            &(payroll.total_amount::<Test>(round(), &PATHFINDER) - 1)
+        ));
+        check(false);
+
+        assert_ok!(ZdToken::staking(&ALICE, &1));
+        check(true);
+        assert!(<Payrolls<Test>>::try_get(round(), &PATHFINDER).is_err());
+    });
+}
+
+#[test]
+fn harvest_ref_all_sweeper_should_follow_curve() {
+    new_test_ext().execute_with(|| {
+        SweeperFeeCurve::set(&SweeperCurve {
+            delay: 500,
+            ramp: 1000,
+            max: Perbill::from_percent(10),
+        });
+        let payroll = Payroll {
+            count: 1,
+            total_fee: 1000,
+            update_at: 1,
+        };
+        <Payrolls<Test>>::insert(round(), &PATHFINDER, payroll.clone());
+        let total_amount = payroll.total_amount::<Test>(round(), &PATHFINDER);
+        assert_ok!(ZdToken::staking(&ALICE, &total_amount));
+
+        System::set_block_number(491);
+        assert!(!ZdRefreshReputation::can_sweeper_harvest(round(), &PATHFINDER));
+        // Halfway through the ramp.
+        System::set_block_number(1001);
+        assert!(ZdRefreshReputation::can_sweeper_harvest(round(), &PATHFINDER));
+        let sweeper_balance = ZdToken::free_balance(&SWEEPRT);
+        let pathfinder_balance = ZdToken::free_balance(&PATHFINDER);
+        assert_ok!(ZdRefreshReputation::harvest_ref_all_sweeper(
+            Origin::signed(SWEEPRT),
+            round(),
+            PATHFINDER
+        ));
+        let sweeper_fee = Perbill::from_percent(5).mul_floor(total_amount);
+        assert_eq!(ZdToken::free_balance(&SWEEPRT), sweeper_balance + sweeper_fee);
+        assert_eq!(
+            ZdToken::free_balance(&PATHFINDER),
+            pathfinder_balance + total_amount - sweeper_fee
+        );
+        let new_event = Event::zd_refresh_reputation(crate::Event::PayrollProxySettled(
+            SWEEPRT,
+            PATHFINDER,
+            sweeper_fee,
+            total_amount - sweeper_fee,
+        ));
+        assert_eq!(System::events().last().map(|record| &record.event), Some(&new_event));
+    });
+}
+
+#[test]
+fn harvest_ref_all_sweeper_should_respect_grace_period() {
+    new_test_ext().execute_with(|| {
+        let grace = <mock::Test as Config>::ProxyGracePeriod::get();
+        let payroll = Payroll {
+            count: 1,
+            total_fee: 1000,
+            update_at: 1,
+        };
+        let total_amount = payroll.total_amount::<Test>(round(), &PATHFINDER);
+        assert_ok!(ZdToken::staking(&ALICE, &(total_amount * 2)));
+        let strict = payroll.update_at + SWEEPER_PERIOD as BlockNumber + 1;
+
+        // Before the grace period.
+        <Payrolls<Test>>::insert(round(), &PATHFINDER, payroll.clone());
+        System::set_block_number(strict - grace - 1);
+        assert!(!ZdRefreshReputation::can_sweeper_harvest(round(), &PATHFINDER));
+        assert_noop!(
+            ZdRefreshReputation::harvest_ref_all_sweeper(
+                Origin::signed(SWEEPRT),
+                round(),
+                PATHFINDER
+            ),
+            Error::<Test>::FailedSweeper
+        );
+
+        // Within the grace period, without a fee.
+        System::set_block_number(strict - grace);
+        assert!(ZdRefreshReputation::can_sweeper_harvest(round(), &PATHFINDER));
+        let sweeper_balance = ZdToken::free_balance(&SWEEPRT);
+        assert_ok!(ZdRefreshReputation::harvest_ref_all_sweeper(
+            Origin::signed(SWEEPRT),
+            round(),
+            PATHFINDER
+        ));
+        assert_eq!(ZdToken::free_balance(&SWEEPRT), sweeper_balance);
+
+        // At the strict boundary, with the full fee.
+        <Payrolls<Test>>::insert(round(), &PATHFINDER, payroll.clone());
+        System::set_block_number(strict);
+        assert_ok!(ZdRefreshReputation::harvest_ref_all_sweeper(
+            Origin::signed(SWEEPRT),
+            round(),
+            PATHFINDER
+        ));
+        assert_eq!(
+            ZdToken::free_balance(&SWEEPRT),
+            sweeper_balance + SWEEPER_PICKUP_RATIO.mul_floor(total_amount)
+        );
+    });
+}
+
+#[test]
+fn start_should_settle_payrolls_along_curve() {
+    new_test_ext().execute_with(|| {
+        SweeperFeeCurve::set(&SweeperCurve {
+            delay: 500,
+            ramp: 1000,
+            max: Perbill::from_percent(10),
+        });
+        ZdReputation::set_step(&TIRStep::Reputation);
+        assert_ok!(ZdToken::staking(&FERDIE, &1_000_000_000_000u128));
+        let payroll = Payroll {
+            count: 1,
+            total_fee: 1000,
+            update_at: 1,
+        };
+        <Payrolls<Test>>::insert(round(), &ALICE, payroll.clone());
+        <Payrolls<Test>>::insert(
+            round(),
+            &BOB,
+            Payroll {
+                update_at: 600,
+                ..payroll.clone()
+            },
+        );
+
+        // Halfway through the ramp for `ALICE`, while sweepers are not allowed on `BOB` yet.
+        System::set_block_number(1001);
+        let alice_balance = ZdToken::free_balance(&ALICE);
+        assert_ok!(ZdRefreshReputation::start(Origin::signed(SWEEPRT)));
+        let total_amount = payroll.total_amount::<Test>(round(), &ALICE);
+        let sweeper_fee = Perbill::from_percent(5).mul_floor(total_amount);
+        assert_eq!(
+            ZdToken::free_balance(&ALICE),
+            alice_balance + total_amount - sweeper_fee
+        );
+        assert_eq!(ZdRefreshReputation::round_proxy_fee(round()), sweeper_fee);
+        assert!(<Payrolls<Test>>::try_get(round(), &ALICE).is_err());
+        assert!(<Payrolls<Test>>::try_get(round(), &BOB).is_ok());
+    });
+}
+
+#[test]
+fn check_update_length_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ZdRefreshReputation::check_update_length());
+        assert!(ZdRefreshReputation::check_update_length_of(u32::MAX).is_err());
+        assert!(ZdRefreshReputation::check_update_length_of(MAX_REFRESH + 1).is_err());
+    });
+}
+
+#[test]
+fn settle_round_should_page_payrolls() {
+    new_test_ext().execute_with(|| {
+        ZdReputation::set_step(&TIRStep::Reputation);
+        System::set_block_number(2000);
+        assert_ok!(ZdToken::staking(&FERDIE, &1_000_000_000_000u128));
+        for pathfinder in [ALICE, BOB, CHARLIE].iter() {
+            <Payrolls<Test>>::insert(
+                round(),
+                pathfinder,
+                Payroll {
+                    count: 1,
+                    total_fee: 10,
+                    update_at: 1,
+                },
+            );
+        }
+
+        assert_ok!(ZdRefreshReputation::settle_round(Origin::signed(SWEEPRT), 2));
+        assert!(!<StartedAt<Test>>::exists());
+        assert!(ZdRefreshReputation::settlement_cursor().is_some());
+        assert_eq!(<Payrolls<Test>>::iter().count(), 1);
+        let new_event = Event::zd_refresh_reputation(crate::Event::PayrollsDrained(SWEEPRT, 2));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+
+        // A zero limit still scans one payroll.
+        assert_ok!(ZdRefreshReputation::settle_round(Origin::signed(SWEEPRT), 0));
+        assert!(<StartedAt<Test>>::exists());
+        assert!(ZdRefreshReputation::settlement_cursor().is_none());
+        assert_eq!(<Payrolls<Test>>::iter().count(), 0);
+    });
+}
+
+#[test]
+fn start_should_record_round_proxy_fee() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ZdReputation::new_round());
+        ZdReputation::set_step(&TIRStep::Reputation);
+        System::set_block_number(2000);
+        assert_ok!(ZdToken::staking(&FERDIE, &1_000_000_000_000u128));
+        <Payrolls<Test>>::insert(round(), &ALICE, INIT_PAYROLLS[0].clone());
+        <Payrolls<Test>>::insert(round(), &BOB, INIT_PAYROLLS[1].clone());
+
+        let expected_fee = INIT_PAYROLLS[..2]
+            .iter()
+            .zip([ALICE, BOB].iter())
+            .map(|(payroll, pathfinder)| {
+                payroll
+                    .total_amount::<Test>(round(), pathfinder)
+                    .with_fee()
+                    .0
+            })
+            .sum::<Balance>();
+        let sweeper_balance = ZdToken::free_balance(&SWEEPRT);
+        assert_ok!(ZdRefreshReputation::start(Origin::signed(SWEEPRT)));
+
+        let round = ZdReputation::get_round();
+        assert_eq!(round, 1);
+        assert_eq!(ZdRefreshReputation::round_proxy_fee(round), expected_fee);
+        assert_eq!(ZdToken::free_balance(&SWEEPRT), sweeper_balance + expected_fee);
+        let new_event = Event::zd_refresh_reputation(crate::Event::RoundSettled(
+            round,
+            SWEEPRT,
+            ZdRefreshReputation::round_proxy_fee(round),
+        ));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+    });
+}
+
+#[test]
+fn start_reward_should_decay_for_repeated_caller() {
+    new_test_ext().execute_with(|| {
+        ZdReputation::set_step(&TIRStep::Reputation);
+        System::set_block_number(2000);
+        assert_ok!(ZdToken::staking(&FERDIE, &1_000_000_000_000u128));
+
+        let (fee, _) = INIT_PAYROLLS[0].total_amount::<Test>(round(), &ALICE).with_fee();
+        let start_round = |who: AccountId| {
+            <StartedAt<Test>>::kill();
+            <Payrolls<Test>>::insert(round(), &ALICE, INIT_PAYROLLS[0].clone());
+            let balance = ZdToken::free_balance(&who);
+            assert_ok!(ZdRefreshReputation::start(Origin::signed(who)));
+            ZdToken::free_balance(&who) - balance
+        };
+        let decayed = |streak: u32| Perbill::from_rational_approximation(streak, 3).mul_floor(fee);
+
+        assert_eq!(start_round(SWEEPRT), fee);
+        assert_eq!(start_round(SWEEPRT), fee - decayed(1));
+        assert_eq!(start_round(SWEEPRT), fee - decayed(2));
+        assert_eq!(start_round(SWEEPRT), fee - decayed(2));
+        assert_eq!(ZdRefreshReputation::round_trigger(), Some((SWEEPRT, 4)));
+        assert_eq!(
+            ZdToken::free_balance(&TreasuryAccountId::get()),
+            decayed(1) + decayed(2) * 2
+        );
+        let new_event =
+            Event::zd_refresh_reputation(crate::Event::RewardDecayed(SWEEPRT, 3, decayed(2)));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+
+        assert_eq!(start_round(BOB), fee);
+        assert_eq!(start_round(SWEEPRT), fee);
+        assert_eq!(ZdRefreshReputation::round_trigger(), Some((SWEEPRT, 1)));
+    });
+}
+
+#[test]
+fn drained_payrolls_reward_should_decay_for_repeated_caller() {
+    new_test_ext().execute_with(|| {
+        ZdReputation::set_step(&TIRStep::Reputation);
+        System::set_block_number(2000);
+        assert_ok!(ZdToken::staking(&FERDIE, &1_000_000_000_000u128));
+        RoundTrigger::<Test>::put((SWEEPRT, 1));
+
+        let (fee, _) = INIT_PAYROLLS[0].total_amount::<Test>(round(), &ALICE).with_fee();
+        let decayed = Perbill::from_rational_approximation(1u32, 3).mul_floor(fee);
+        <Payrolls<Test>>::insert(round(), &ALICE, INIT_PAYROLLS[0].clone());
+        <Payrolls<Test>>::insert(round(), &BOB, INIT_PAYROLLS[0].clone());
+
+        let balance = ZdToken::free_balance(&SWEEPRT);
+        assert_ok!(ZdRefreshReputation::settle_round(Origin::signed(SWEEPRT), 1));
+        assert!(!<StartedAt<Test>>::exists());
+        assert_eq!(ZdToken::free_balance(&SWEEPRT), balance + fee - decayed);
+        assert_eq!(ZdToken::free_balance(&TreasuryAccountId::get()), decayed);
+        // Draining does not extend the streak, only starting the round does.
+        assert_eq!(ZdRefreshReputation::round_trigger(), Some((SWEEPRT, 1)));
+
+        assert_ok!(ZdRefreshReputation::settle_round(Origin::signed(SWEEPRT), 1));
+        assert!(<StartedAt<Test>>::exists());
+        assert_eq!(ZdToken::free_balance(&SWEEPRT), balance + (fee - decayed) * 2);
+        assert_eq!(ZdToken::free_balance(&TreasuryAccountId::get()), decayed * 2);
+        assert_eq!(ZdRefreshReputation::round_trigger(), Some((SWEEPRT, 2)));
+    });
+}
+
+#[test]
+fn try_state_should_fail_on_inconsistent_state() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        assert_ok!(ZdRefreshReputation::do_try_state(1));
+
+        <Payrolls<Test>>::mutate(round(), &PATHFINDER, |p| p.count += 1);
+        assert!(ZdRefreshReputation::do_try_state(1).is_err());
+        <Payrolls<Test>>::mutate(round(), &PATHFINDER, |p| p.count -= 1);
+
+        <Records<Test>>::mutate(&RecordKey::new(round(), PATHFINDER), &TARGET, |r| r.update_at = 2);
+        assert!(ZdRefreshReputation::do_try_state(1).is_err());
+        assert_ok!(ZdRefreshReputation::do_try_state(2));
+
+        <Payrolls<Test>>::mutate(round(), &PATHFINDER, |p| p.update_at = 3);
+        assert!(ZdRefreshReputation::do_try_state(2).is_err());
+    });
+}
+
+#[test]
+fn refresh_changed_only_should_skip_unchanged() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        zd_reputation::ReputationScores::<Test>::insert(
+            &ALICE,
+            [
+                zd_reputation::ReputationScore {
+                    score: 50,
+                    nonce: 0,
+                },
+                Default::default(),
+            ],
+        );
+        let payroll = ZdRefreshReputation::get_payroll(round(), &PATHFINDER);
+        let free_balance = ZdToken::free_balance(&PATHFINDER);
+
+        assert_ok!(ZdRefreshReputation::refresh_changed_only(
+            Origin::signed(PATHFINDER),
+            vec![(ALICE, 50), (BOB, 20), (CHARLIE, 0)]
+        ));
+
+        assert_eq!(
+            ZdRefreshReputation::get_payroll(round(), &PATHFINDER).count,
+            payroll.count + 1
+        );
+        assert_eq!(
+            ZdToken::free_balance(&PATHFINDER),
+            free_balance - UpdateStakingAmount::get()
+        );
+        assert!(<Records<Test>>::try_get(&RecordKey::new(round(), PATHFINDER), &BOB).is_ok());
+        assert!(<Records<Test>>::try_get(&RecordKey::new(round(), PATHFINDER), &ALICE).is_err());
+        assert!(<Records<Test>>::try_get(&RecordKey::new(round(), PATHFINDER), &CHARLIE).is_err());
+        assert_eq!(ZdReputation::get_reputation_new(&BOB), Some(20));
+        assert_eq!(ZdReputation::get_reputation_new(&ALICE), None);
+
+        let skipped_event =
+            Event::zd_refresh_reputation(crate::Event::UnchangedSkipped(PATHFINDER, 2));
+        assert!(System::events().iter().any(|record| record.event == skipped_event));
+        assert!(System::events().iter().any(|record| matches!(
+            record.event,
+            Event::zd_refresh_reputation(crate::Event::ReputationRefreshed(PATHFINDER, 1, _))
+        )));
+    });
+}
+
+#[test]
+fn all_pending_payrolls_should_paginate() {
+    new_test_ext().execute_with(|| {
+        let pathfinders = (100u64..125).collect::<Vec<AccountId>>();
+        for (i, pathfinder) in pathfinders.iter().enumerate() {
+            <Payrolls<Test>>::insert(
+                pathfinder,
+                Payroll {
+                    count: i as u32 + 1,
+                    total_fee: 10,
+                    update_at: 1,
+                },
+            );
+        }
+
+        let mut collected = Vec::new();
+        let mut start_key = None;
+        loop {
+            let (page, next_key) = ZdRefreshReputation::all_pending_payrolls(7, start_key);
+            assert!(page.len() <= 7);
+            collected.extend(page);
+            if next_key.is_none() {
+                break;
+            }
+            start_key = next_key;
+        }
+
+        collected.sort();
+        assert_eq!(
+            collected,
+            pathfinders
+                .iter()
+                .map(|pathfinder| {
+                    let payroll = ZdRefreshReputation::get_payroll(round(), pathfinder);
+                    (
+                        round(),
+                        *pathfinder,
+                        payroll.count,
+                        payroll.total_amount::<Test>(round(), pathfinder),
+                    )
+                })
+                .collect::<Vec<_>>()
+        );
+    });
+}
+
+#[test]
+fn refresh_should_respect_payroll_cap() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        MaxPayrollRecords::set(&2);
+        assert_noop!(
+            ZdRefreshReputation::refresh(Origin::signed(PATHFINDER), vec![(ALICE, 1), (BOB, 1)]),
+            Error::<Test>::PayrollCapReached
+        );
+        assert_ok!(ZdRefreshReputation::refresh(
+            Origin::signed(PATHFINDER),
+            vec![(ALICE, 1)]
+        ));
+        assert_eq!(ZdRefreshReputation::get_payroll(round(), &PATHFINDER).count, 2);
+
+        MaxPayrollRecords::set(&10);
+        let total_amount = ZdRefreshReputation::get_payroll(round(), &PATHFINDER)
+            .total_amount::<Test>(round(), &PATHFINDER);
+        MaxPayrollAmount::set(&(total_amount + UpdateStakingAmount::get() - 1));
+        assert_noop!(
+            ZdRefreshReputation::refresh(Origin::signed(PATHFINDER), vec![(BOB, 1)]),
+            Error::<Test>::PayrollCapReached
+        );
+    });
+}
+
+#[test]
+fn refresh_best_effort_should_skip_invalid_entries() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        let payroll = ZdRefreshReputation::get_payroll(round(), &PATHFINDER);
+        let free_balance = ZdToken::free_balance(&PATHFINDER);
+
+        assert_ok!(ZdRefreshReputation::refresh_best_effort(
+            Origin::signed(PATHFINDER),
+            vec![(ALICE, 10), (TARGET, 5), (ALICE, 12), (BOB, 3)]
+        ));
+
+        assert_eq!(
+            ZdRefreshReputation::get_payroll(round(), &PATHFINDER).count,
+            payroll.count + 2
+        );
+        assert_eq!(
+            ZdToken::free_balance(&PATHFINDER),
+            free_balance - 2 * UpdateStakingAmount::get()
+        );
+        assert_eq!(ZdReputation::get_reputation_new(&ALICE), Some(10));
+        assert_eq!(ZdReputation::get_reputation_new(&BOB), Some(3));
+        assert_eq!(ZdReputation::get_reputation_new(&TARGET), Some(100));
+
+        let skipped_event = Event::zd_refresh_reputation(crate::Event::RefreshSkipped(
+            PATHFINDER,
+            vec![(1, SkipReason::AlreadyRefreshed), (2, SkipReason::Duplicate)],
+        ));
+        assert!(System::events().iter().any(|record| record.event == skipped_event));
+
+        <Payrolls<Test>>::mutate(round(), &PATHFINDER, |p| p.count = MAX_REFRESH - 2);
+        assert_ok!(ZdRefreshReputation::refresh_best_effort(
+            Origin::signed(PATHFINDER),
+            vec![(CHARLIE, 1), (DAVE, 1)]
+        ));
+        assert_eq!(ZdReputation::get_reputation_new(&CHARLIE), Some(1));
+        assert_eq!(ZdReputation::get_reputation_new(&DAVE), None);
+        let skipped_event = Event::zd_refresh_reputation(crate::Event::RefreshSkipped(
+            PATHFINDER,
+            vec![(1, SkipReason::ExceedMaxRefresh)],
+        ));
+        assert!(System::events().iter().any(|record| record.event == skipped_event));
+    });
+}
+
+#[test]
+fn set_parameters_should_work() {
+    new_test_ext().execute_with(|| {
+        let staking_amount = 2 * UpdateStakingAmount::get();
+        let share_ratio = Perbill::from_percent(20);
+        let ratio = Perbill::from_percent(5);
+        assert_noop!(
+            ZdRefreshReputation::set_parameters(
+                Origin::signed(ALICE),
+                staking_amount,
+                share_ratio,
+                ratio,
+                ratio
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            ZdRefreshReputation::set_parameters(Origin::root(), 0, share_ratio, ratio, ratio),
+            Error::<Test>::ZeroStakingAmount
+        );
+        assert_noop!(
+            ZdRefreshReputation::set_parameters(
+                Origin::root(),
+                staking_amount,
+                Perbill::from_percent(60),
+                Perbill::from_percent(20),
+                Perbill::from_percent(20)
+            ),
+            zd_tokens::Error::<Test>::InvalidRatios
+        );
+
+        assert_ok!(ZdRefreshReputation::set_parameters(
+            Origin::root(),
+            staking_amount,
+            share_ratio,
+            ratio,
+            ratio
+        ));
+        assert_eq!(ZdToken::social_ratios(), (share_ratio, ratio, ratio));
+        assert_eq!(ZdRefreshReputation::next_staking_amount(), staking_amount);
+        assert_eq!(
+            ZdRefreshReputation::staking_amount(round()),
+            UpdateStakingAmount::get()
+        );
+        let new_event = Event::zd_refresh_reputation(crate::Event::ParametersUpdated(
+            staking_amount,
+            share_ratio,
+            ratio,
+            ratio,
+        ));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+
+        ZdReputation::set_step(&TIRStep::Reputation);
+        System::set_block_number(2000);
+        assert_ok!(ZdRefreshReputation::start(Origin::signed(ALICE)));
+        assert_eq!(ZdRefreshReputation::staking_amount(round()), staking_amount);
+    });
+}
+
+#[test]
+fn multi_currency_staking_should_work() {
+    use orml_traits::MultiCurrency;
+
+    new_test_ext().execute_with(|| {
+        let amount = 5_000;
+        init_sys(100);
+        assert_noop!(
+            ZdRefreshReputation::set_staking_currency(Origin::signed(CHALLENGER), Some(STABLE)),
+            Error::<Test>::NotPathfinder
+        );
+        register(CHALLENGER);
+        assert_noop!(
+            ZdRefreshReputation::set_staking_currency(Origin::signed(CHALLENGER), Some(STABLE)),
+            Error::<Test>::CurrencyNotApproved
+        );
+        assert_noop!(
+            ZdRefreshReputation::approve_staking_currency(Origin::signed(ALICE), STABLE, amount),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(ZdRefreshReputation::approve_staking_currency(
+            Origin::root(),
+            STABLE,
+            amount
+        ));
+        assert_ok!(ZdRefreshReputation::set_staking_currency(
+            Origin::signed(CHALLENGER),
+            Some(STABLE)
+        ));
+
+        let stable_balance = <Currencies as MultiCurrency<_>>::free_balance(STABLE, &CHALLENGER);
+        let total_staking = ZdToken::total_staking();
+        assert_ok!(ZdRefreshReputation::refresh(
+            Origin::signed(CHALLENGER),
+            vec![(BOB, 3)]
+        ));
+        assert_eq!(
+            ZdRefreshReputation::payroll_currency(round(), &CHALLENGER),
+            Some((STABLE, amount))
+        );
+        assert_eq!(ZdToken::total_staking_in(STABLE), amount);
+
+        // The payroll keeps the amount of its first refresh.
+        assert_ok!(ZdRefreshReputation::approve_staking_currency(
+            Origin::root(),
+            STABLE,
+            2 * amount
+        ));
+        assert_ok!(ZdRefreshReputation::refresh(
+            Origin::signed(CHALLENGER),
+            vec![(ALICE, 10)]
+        ));
+        // And its currency once it is no longer approved.
+        assert_ok!(ZdRefreshReputation::revoke_staking_currency(Origin::root(), STABLE));
+        assert_noop!(
+            ZdRefreshReputation::revoke_staking_currency(Origin::root(), STABLE),
+            Error::<Test>::CurrencyNotApproved
+        );
+        assert_ok!(ZdRefreshReputation::refresh(
+            Origin::signed(CHALLENGER),
+            vec![(EVE, 10)]
+        ));
+        assert_eq!(ZdToken::total_staking_in(STABLE), 3 * amount);
+        assert_eq!(
+            <Currencies as MultiCurrency<_>>::free_balance(STABLE, &CHALLENGER),
+            stable_balance - 3 * amount
+        );
+        let fee = ZdRefreshReputation::get_payroll(round(), &CHALLENGER).total_fee;
+        assert_eq!(ZdToken::total_staking(), total_staking + fee);
+        assert_eq!(
+            ZdRefreshReputation::pathfinder_exposure(round(), &CHALLENGER),
+            (3, fee, 3, 3 * amount)
+        );
+
+        assert_noop!(
+            ZdRefreshReputation::transfer_payroll(
+                Origin::signed(PATHFINDER),
+                round(),
+                CHALLENGER,
+                true
+            ),
+            Error::<Test>::CurrencyMismatch
+        );
+
+        System::set_block_number(ConfirmationPeriod::get() + 100);
+        let balance = ZdToken::free_balance(&CHALLENGER);
+        assert_ok!(ZdRefreshReputation::harvest_ref_all(
+            Origin::signed(CHALLENGER),
+            round()
+        ));
+        assert_eq!(ZdToken::free_balance(&CHALLENGER), balance + fee);
+        assert_eq!(
+            <Currencies as MultiCurrency<_>>::free_balance(STABLE, &CHALLENGER),
+            stable_balance
+        );
+        assert_eq!(ZdToken::total_staking_in(STABLE), 0);
+        assert_eq!(ZdRefreshReputation::payroll_currency(round(), &CHALLENGER), None);
+    });
+}
+
+#[test]
+fn opt_out_should_work() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        assert_ok!(ZdRefreshReputation::opt_out(Origin::signed(ALICE)));
+        assert!(ZdRefreshReputation::is_opted_out(&ALICE));
+        let new_event = Event::zd_refresh_reputation(crate::Event::UserOptedOut(ALICE));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+        assert_noop!(
+            ZdRefreshReputation::opt_out(Origin::signed(ALICE)),
+            Error::<Test>::AlreadyOptedOut
+        );
+
+        assert_noop!(
+            ZdRefreshReputation::refresh(Origin::signed(PATHFINDER), vec![(BOB, 3), (ALICE, 10)]),
+            Error::<Test>::UserOptedOut
+        );
+        assert_ok!(ZdRefreshReputation::refresh_best_effort(
+            Origin::signed(PATHFINDER),
+            vec![(ALICE, 10), (BOB, 3)]
+        ));
+        assert_eq!(ZdReputation::get_reputation_new(&ALICE), None);
+        assert_eq!(ZdReputation::get_reputation_new(&BOB), Some(3));
+        let skipped_event = Event::zd_refresh_reputation(crate::Event::RefreshSkipped(
+            PATHFINDER,
+            vec![(0, SkipReason::OptedOut)],
+        ));
+        assert!(System::events().iter().any(|record| record.event == skipped_event));
+
+        assert_ok!(ZdRefreshReputation::opt_in(Origin::signed(ALICE)));
+        assert_noop!(
+            ZdRefreshReputation::opt_in(Origin::signed(ALICE)),
+            Error::<Test>::NotOptedOut
+        );
+        assert_ok!(ZdRefreshReputation::refresh(
+            Origin::signed(PATHFINDER),
+            vec![(ALICE, 10)]
+        ));
+        assert_eq!(ZdReputation::get_reputation_new(&ALICE), Some(10));
+    });
+}
+
+#[test]
+fn batch_commitment_should_work() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        let first_root = ZdRefreshReputation::batch_root(&[(TARGET, 100)]);
+        assert!(ZdRefreshReputation::commitment(&(round(), PATHFINDER), first_root).is_some());
+        assert!(ZdRefreshReputation::verify_score(
+            round(),
+            &PATHFINDER,
+            &first_root,
+            &TARGET,
+            100,
+            &[]
+        ));
+
+        let user_scores = vec![(ALICE, 10), (BOB, 3), (CHARLIE, 1)];
+        assert_ok!(ZdRefreshReputation::refresh(
+            Origin::signed(PATHFINDER),
+            user_scores.clone()
+        ));
+        let root = ZdRefreshReputation::batch_root(&user_scores[..]);
+        for (index, (who, score)) in user_scores.iter().enumerate() {
+            let proof = ZdRefreshReputation::batch_proof(&user_scores[..], index);
+            assert!(ZdRefreshReputation::verify_score(
+                round(),
+                &PATHFINDER,
+                &root,
+                who,
+                *score,
+                &proof[..]
+            ));
+            assert!(!ZdRefreshReputation::verify_score(
+                round(),
+                &PATHFINDER,
+                &root,
+                who,
+                score + 1,
+                &proof[..]
+            ));
+            assert!(!ZdRefreshReputation::verify_score(
+                round(),
+                &ALICE,
+                &root,
+                who,
+                *score,
+                &proof[..]
+            ));
+        }
+
+        <Records<Test>>::remove_prefix(&RecordKey::new(round(), PATHFINDER));
+        let proof = ZdRefreshReputation::batch_proof(&user_scores[..], 1);
+        assert!(ZdRefreshReputation::verify_score(
+            round(),
+            &PATHFINDER,
+            &root,
+            &BOB,
+            3,
+            &proof[..]
+        ));
+    });
+}
+
+#[test]
+fn commit_reveal_refresh_should_work() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        register(CHALLENGER);
+        let user_scores = vec![(ALICE, 10)];
+        let salt = sp_core::H256::repeat_byte(7);
+        let hash = ZdRefreshReputation::commitment_hash(&PATHFINDER, &user_scores[..], &salt);
+        assert_ok!(ZdRefreshReputation::commit_refresh(Origin::signed(PATHFINDER), hash));
+        assert_noop!(
+            ZdRefreshReputation::commit_refresh(Origin::signed(PATHFINDER), hash),
+            Error::<Test>::CommitmentExists
+        );
+        assert_ok!(ZdRefreshReputation::commit_refresh(Origin::signed(CHALLENGER), hash));
+        assert_noop!(
+            ZdRefreshReputation::reveal_refresh(
+                Origin::signed(PATHFINDER),
+                user_scores.clone(),
+                salt
+            ),
+            Error::<Test>::RevealTooEarly
+        );
+
+        System::set_block_number(1 + CommitPeriod::get());
+        assert_noop!(
+            ZdRefreshReputation::commit_refresh(Origin::signed(PATHFINDER), hash),
+            Error::<Test>::CommitPeriodElapsed
+        );
+        assert_noop!(
+            ZdRefreshReputation::reveal_refresh(
+                Origin::signed(PATHFINDER),
+                user_scores.clone(),
+                sp_core::H256::repeat_byte(8)
+            ),
+            Error::<Test>::CommitmentMismatch
+        );
+        assert_noop!(
+            ZdRefreshReputation::reveal_refresh(
+                Origin::signed(CHALLENGER),
+                user_scores.clone(),
+                salt
+            ),
+            Error::<Test>::CommitmentMismatch
+        );
+        let payroll = ZdRefreshReputation::get_payroll(round(), &PATHFINDER);
+        assert_ok!(ZdRefreshReputation::reveal_refresh(
+            Origin::signed(PATHFINDER),
+            user_scores.clone(),
+            salt
+        ));
+        assert_eq!(ZdReputation::get_reputation_new(&ALICE), Some(10));
+        assert_eq!(
+            ZdRefreshReputation::get_payroll(round(), &PATHFINDER).count,
+            payroll.count + 1
+        );
+        assert_eq!(ZdRefreshReputation::refresh_commit(round(), &PATHFINDER), None);
+
+        assert_noop!(
+            ZdRefreshReputation::forfeit_commitment(Origin::signed(ALICE), round(), CHALLENGER),
+            Error::<Test>::RevealNotElapsed
+        );
+        System::set_block_number(ZdReputation::round_info().refresh_ends_at);
+        let treasury_balance = ZdToken::free_balance(&TreasuryAccountId::get());
+        assert_ok!(ZdRefreshReputation::forfeit_commitment(
+            Origin::signed(ALICE),
+            round(),
+            CHALLENGER
+        ));
+        assert_eq!(
+            ZdToken::free_balance(&TreasuryAccountId::get()),
+            treasury_balance + CommitDeposit::get()
+        );
+        let new_event = Event::zd_refresh_reputation(crate::Event::CommitmentForfeited(
+            CHALLENGER,
+            CommitDeposit::get(),
+        ));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+        assert_noop!(
+            ZdRefreshReputation::forfeit_commitment(Origin::signed(ALICE), round(), CHALLENGER),
+            Error::<Test>::NoCommitment
+        );
+    });
+}
+
+#[test]
+fn refresh_should_emit_user_scores() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        let old = ZdReputation::get_reputation(&ALICE);
+        assert_ok!(ZdRefreshReputation::refresh(
+            Origin::signed(PATHFINDER),
+            vec![(ALICE, 10), (BOB, 3)]
+        ));
+        let new_event = Event::zd_refresh_reputation(crate::Event::UserScoreRefreshed(
+            ALICE, old, 10, PATHFINDER,
+        ));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+        let bob_topic = <Test as frame_system::Config>::Hashing::hash_of(&BOB);
+        assert!(System::events().iter().any(|record| {
+            record.topics.contains(&bob_topic)
+                && matches!(
+                    record.event,
+                    Event::zd_refresh_reputation(crate::Event::UserScoreRefreshed(BOB, _, 3, _))
+                )
+        }));
+    });
+}
+
+#[test]
+fn refresh_signed_payload_should_work() {
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        let payload = RefreshPayload {
+            pathfinder: PATHFINDER,
+            user_scores: vec![(ALICE, 10)],
+            round: round(),
+            nonce: 0,
+        };
+        let signature = TestSignature(PATHFINDER, payload.encode());
+        let payroll = ZdRefreshReputation::get_payroll(round(), &PATHFINDER);
+        let pathfinder_balance = ZdToken::free_balance(&PATHFINDER);
+        let relayer_balance = ZdToken::free_balance(&CHARLIE);
+
+        assert_noop!(
+            ZdRefreshReputation::refresh_signed_payload(
+                Origin::signed(CHARLIE),
+                payload.clone(),
+                TestSignature(CHARLIE, payload.encode())
+            ),
+            Error::<Test>::BadSignature
+        );
+        let stale_payload = RefreshPayload {
+            round: round() - 1,
+            ..payload.clone()
+        };
+        assert_noop!(
+            ZdRefreshReputation::refresh_signed_payload(
+                Origin::signed(CHARLIE),
+                stale_payload.clone(),
+                TestSignature(PATHFINDER, stale_payload.encode())
+            ),
+            Error::<Test>::StaleRound
+        );
+
+        assert_ok!(ZdRefreshReputation::refresh_signed_payload(
+            Origin::signed(CHARLIE),
+            payload.clone(),
+            signature.clone()
+        ));
+        assert_eq!(
+            ZdRefreshReputation::get_payroll(round(), &PATHFINDER).count,
+            payroll.count + 1
+        );
+        assert_eq!(
+            ZdToken::free_balance(&PATHFINDER),
+            pathfinder_balance - UpdateStakingAmount::get()
+        );
+        assert_eq!(ZdToken::free_balance(&CHARLIE), relayer_balance);
+        assert!(<Payrolls<Test>>::try_get(round(), &CHARLIE).is_err());
+        let new_event =
+            Event::zd_refresh_reputation(crate::Event::PayloadRelayed(CHARLIE, PATHFINDER));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+
+        assert_eq!(ZdRefreshReputation::payload_nonce(round(), &PATHFINDER), 1);
+
+        // Withdrawing the refresh rolls back the reputation of `ALICE`, but not the nonce.
+        assert_ok!(ZdRefreshReputation::withdraw_refresh(
+            Origin::signed(PATHFINDER),
+            ALICE
+        ));
+        assert_noop!(
+            ZdRefreshReputation::refresh_signed_payload(
+                Origin::signed(CHARLIE),
+                payload.clone(),
+                signature
+            ),
+            Error::<Test>::StaleNonce
+        );
+        let next_payload = RefreshPayload { nonce: 1, ..payload };
+        assert_ok!(ZdRefreshReputation::refresh_signed_payload(
+            Origin::signed(CHARLIE),
+            next_payload.clone(),
+            TestSignature(PATHFINDER, next_payload.encode())
+        ));
+    });
+}
+
+#[test]
+fn refresh_unsigned_should_check_nonce_per_round() {
+    use sp_runtime::{
+        traits::ValidateUnsigned,
+        transaction_validity::{InvalidTransaction, TransactionSource},
+    };
+
+    new_test_ext().execute_with(|| {
+        init_sys(100);
+        let payload = RefreshPayload {
+            pathfinder: PATHFINDER,
+            user_scores: vec![(ALICE, 10)],
+            round: round(),
+            nonce: 0,
+        };
+        let signature = TestSignature(PATHFINDER, payload.encode());
+        let validate = |payload: &RefreshPayload<AccountId>, signature: TestSignature| {
+            <ZdRefreshReputation as ValidateUnsigned>::validate_unsigned(
+                TransactionSource::External,
+                &crate::Call::refresh_unsigned(payload.clone(), signature),
+            )
+        };
+
+        assert!(validate(&payload, signature.clone()).is_ok());
+        assert_eq!(
+            validate(&payload, TestSignature(CHARLIE, payload.encode())),
+            Err(InvalidTransaction::BadProof.into())
+        );
+
+        assert_ok!(ZdRefreshReputation::refresh_unsigned(
+            Origin::none(),
+            payload.clone(),
+            signature.clone()
+        ));
+        assert_eq!(ZdRefreshReputation::payload_nonce(round(), &PATHFINDER), 1);
+        assert_eq!(ZdReputation::get_reputation_new(&ALICE), Some(10));
+        assert_eq!(validate(&payload, signature.clone()), Err(InvalidTransaction::Stale.into()));
+        assert_noop!(
+            ZdRefreshReputation::refresh_unsigned(Origin::none(), payload.clone(), signature),
+            Error::<Test>::StaleNonce
+        );
+        let next_payload = RefreshPayload { nonce: 1, ..payload };
+        assert!(validate(&next_payload, TestSignature(PATHFINDER, next_payload.encode())).is_ok());
+    });
+}
+
+#[test]
+fn pathfinder_registration_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ZdRefreshReputation::register_pathfinder(
+                Origin::signed(PATHFINDER),
+                PathfinderBond::get() - 1,
+                None
+            ),
+            Error::<Test>::BondTooLow
+        );
+        let free_balance = ZdToken::free_balance(&PATHFINDER);
+        let endpoint = Some(sp_core::H256::repeat_byte(1));
+        assert_ok!(ZdRefreshReputation::register_pathfinder(
+            Origin::signed(PATHFINDER),
+            PathfinderBond::get(),
+            endpoint
+        ));
+        assert_eq!(ZdToken::free_balance(&PATHFINDER), free_balance - PathfinderBond::get());
+        assert!(ZdRefreshReputation::is_pathfinder(&PATHFINDER));
+        assert_eq!(ZdRefreshReputation::pathfinder(&PATHFINDER).unwrap().endpoint, endpoint);
+        assert_noop!(
+            ZdRefreshReputation::register_pathfinder(
+                Origin::signed(PATHFINDER),
+                PathfinderBond::get(),
+                None
+            ),
+            Error::<Test>::AlreadyPathfinder
+        );
+
+        let challenger_balance = ZdToken::free_balance(&CHALLENGER);
+        assert_eq!(ZdRefreshReputation::slash_bond(&PATHFINDER, 30, &CHALLENGER), Ok(30));
+        assert_eq!(ZdToken::free_balance(&CHALLENGER), challenger_balance + 30);
+        assert_eq!(ZdRefreshReputation::bond_of(&PATHFINDER), PathfinderBond::get() - 30);
+
+        assert_noop!(
+            ZdRefreshReputation::withdraw_bond(Origin::signed(PATHFINDER)),
+            Error::<Test>::StillBonded
+        );
+        assert_ok!(ZdRefreshReputation::deregister_pathfinder(Origin::signed(PATHFINDER)));
+        assert!(!ZdRefreshReputation::is_pathfinder(&PATHFINDER));
+        assert_noop!(
+            ZdRefreshReputation::deregister_pathfinder(Origin::signed(PATHFINDER)),
+            Error::<Test>::AlreadyUnbonding
+        );
+        System::set_block_number(1 + UnbondingPeriod::get() - 1);
+        assert_noop!(
+            ZdRefreshReputation::withdraw_bond(Origin::signed(PATHFINDER)),
+            Error::<Test>::StillBonded
+        );
+        System::set_block_number(1 + UnbondingPeriod::get());
+        assert_ok!(ZdRefreshReputation::withdraw_bond(Origin::signed(PATHFINDER)));
+        assert_eq!(ZdToken::free_balance(&PATHFINDER), free_balance - 30);
+        assert!(ZdRefreshReputation::pathfinder(&PATHFINDER).is_none());
+    });
+}
+
+#[test]
+fn on_runtime_upgrade_should_migrate_to_v2() {
+    use frame_support::{storage::unhashed, traits::OnRuntimeUpgrade, StorageHasher};
+
+    new_test_ext().execute_with(|| {
+        zd_reputation::SystemInfo::<Test>::mutate(|s| s.nonce = 3);
+        let payroll = Payroll {
+            count: 1,
+            total_fee: 10,
+            update_at: 1,
+        };
+        let mut payroll_key = Payrolls::<Test>::final_prefix().to_vec();
+        payroll_key.extend(Twox64Concat::hash(&PATHFINDER.encode()));
+        unhashed::put(&payroll_key, &payroll);
+        let mut record_key = Records::<Test>::final_prefix().to_vec();
+        record_key.extend(Twox64Concat::hash(&PATHFINDER.encode()));
+        record_key.extend(Twox64Concat::hash(&TARGET.encode()));
+        unhashed::put(&record_key, &(1u64, 10u128));
+        assert_eq!(ZdRefreshReputation::storage_version(), Releases::V1);
+
+        ZdRefreshReputation::on_runtime_upgrade();
+        assert_eq!(ZdRefreshReputation::storage_version(), Releases::V2);
+        assert!(!unhashed::exists(&payroll_key));
+        assert!(!unhashed::exists(&record_key));
+        assert_eq!(ZdRefreshReputation::get_payroll(3, &PATHFINDER), payroll);
+        let record = <Records<Test>>::try_get(&RecordKey::new(3, PATHFINDER), &TARGET).unwrap();
+        assert_eq!((record.update_at, record.fee, record.domain), (1, 10, DEFAULT_DOMAIN));
+        assert_eq!(ZdRefreshReputation::refreshed_by(&TARGET), Some((3, PATHFINDER)));
+        let mut tuple_key = Records::<Test>::final_prefix().to_vec();
+        tuple_key.extend(Twox64Concat::hash(&(3 as RoundIndex, PATHFINDER).encode()));
+        tuple_key.extend(Twox64Concat::hash(&TARGET.encode()));
+        assert!(unhashed::exists(&tuple_key));
+
+        // Already migrated.
+        ZdRefreshReputation::on_runtime_upgrade();
+        assert_eq!(ZdRefreshReputation::get_payroll(3, &PATHFINDER), payroll);
+    });
+}
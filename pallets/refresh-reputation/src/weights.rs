@@ -1,22 +1,14 @@
 //! Weights for zd_refresh_reputation
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 3.0.0
-//! DATE: 2021-10-09, STEPS: [50, ], REPEAT: 20, LOW RANGE: [], HIGH RANGE: []
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 128
-
-// Executed Command:
-// ./target/release/zerodao-node
-// benchmark
-// --chain=dev
-// --execution=wasm
-// --wasm-execution=compiled
-// --pallet=zd_refresh_reputation
-// --extrinsic=*
-// --steps=50
-// --repeat=20
-// --heap-pages=4096
-// --output=./pallets/refresh-reputation/src/weights.rs
-// --template=./scripts/pallet-weight-template.hbs
-
+//!
+//! `harvest_ref_all`, `harvest_ref_all_sweeper`, `challenge_update` and `arbitral` were
+//! generated by the Substrate benchmark CLI version 3.0.0 on 2021-10-09, with 50 steps and
+//! 20 repeats of compiled Wasm on the `dev` chain.
+//!
+//! The other weights are hand-estimated upper bounds until their benchmarks are written.
+//! `refresh` and `start` keep the benchmarked figures of their earlier versions, per user and
+//! per call, and add the work done since. `force_new_round` is priced as `start`,
+//! `harvest_ref_matured`, `harvest_ref_partial` and `transfer_payroll` after
+//! `harvest_ref_all`, and the remaining calls at twice their estimated execution time.
 
 #![allow(unused_parens)]
 #![allow(unused_imports)]
@@ -30,46 +22,178 @@ use sp_std::marker::PhantomData;
 
 /// Weight functions needed for zd_refresh_reputation.
 pub trait WeightInfo {
-    fn start() -> Weight;
-    fn refresh(a: u32, ) -> Weight;
+    fn start(p: u32, ) -> Weight;
+    fn force_new_round(p: u32, ) -> Weight;
+    fn register_pathfinder() -> Weight;
+    fn deregister_pathfinder() -> Weight;
+    fn withdraw_bond() -> Weight;
+    fn lift_suspension() -> Weight;
+    fn set_parameters() -> Weight;
+    fn approve_staking_currency() -> Weight;
+    fn revoke_staking_currency() -> Weight;
+    fn set_staking_currency() -> Weight;
+    fn opt_out() -> Weight;
+    fn opt_in() -> Weight;
+    fn commit_refresh() -> Weight;
+    fn forfeit_commitment() -> Weight;
+    fn refresh(a: u32, d: u32, ) -> Weight;
+    fn retract_refresh(a: u32, ) -> Weight;
+    fn withdraw_refresh() -> Weight;
+    fn amend_refresh() -> Weight;
     fn harvest_ref_all() -> Weight;
     fn harvest_ref_all_sweeper() -> Weight;
+    fn harvest_ref_matured() -> Weight;
+    fn harvest_ref_partial(r: u32, ) -> Weight;
+    fn transfer_payroll() -> Weight;
     fn challenge() -> Weight;
     fn challenge_update(a: u32, ) -> Weight;
     fn harvest_challenge() -> Weight;
+    fn back_challenge() -> Weight;
+    fn cancel_challenge() -> Weight;
     fn arbitral(a: u32, ) -> Weight;
+    fn appeal() -> Weight;
+    fn resolve_appeal() -> Weight;
+    fn vote_appeal() -> Weight;
 }
 
 /// Weights for zd_refresh_reputation using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-    fn start() -> Weight {
+    fn start(p: u32, ) -> Weight {
+        (840_100_000 as Weight)
+            .saturating_add((72_050_000 as Weight).saturating_mul(p as Weight))
+            .saturating_add(T::DbWeight::get().reads(31 as Weight))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(p as Weight)))
+            .saturating_add(T::DbWeight::get().writes(26 as Weight))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(p as Weight)))
+    }
+    fn force_new_round(p: u32, ) -> Weight {
         (840_100_000 as Weight)
+            .saturating_add((72_050_000 as Weight).saturating_mul(p as Weight))
             .saturating_add(T::DbWeight::get().reads(27 as Weight))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(p as Weight)))
             .saturating_add(T::DbWeight::get().writes(24 as Weight))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(p as Weight)))
+    }
+    fn register_pathfinder() -> Weight {
+        (105_200_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn deregister_pathfinder() -> Weight {
+        (42_600_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn withdraw_bond() -> Weight {
+        (99_800_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn lift_suspension() -> Weight {
+        (37_200_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_parameters() -> Weight {
+        (34_800_000 as Weight)
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn approve_staking_currency() -> Weight {
+        (28_200_000 as Weight)
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn revoke_staking_currency() -> Weight {
+        (33_600_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
-    fn refresh(a: u32, ) -> Weight {
+    fn set_staking_currency() -> Weight {
+        (39_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn opt_out() -> Weight {
+        (32_400_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn opt_in() -> Weight {
+        (32_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn commit_refresh() -> Weight {
+        (83_400_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn forfeit_commitment() -> Weight {
+        (76_800_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn refresh(a: u32, d: u32, ) -> Weight {
         (0 as Weight)
             .saturating_add((4_300_286_000 as Weight).saturating_mul(a as Weight))
-            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add((7_060_000 as Weight).saturating_mul(d as Weight))
+            .saturating_add(T::DbWeight::get().reads(11 as Weight))
             .saturating_add(T::DbWeight::get().reads((604 as Weight).saturating_mul(a as Weight)))
-            .saturating_add(T::DbWeight::get().writes(6 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(d as Weight)))
+            .saturating_add(T::DbWeight::get().writes(9 as Weight))
             .saturating_add(T::DbWeight::get().writes((602 as Weight).saturating_mul(a as Weight)))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(d as Weight)))
+    }
+    fn retract_refresh(a: u32, ) -> Weight {
+        (76_800_000 as Weight)
+            .saturating_add((122_400_000 as Weight).saturating_mul(a as Weight))
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(a as Weight)))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(a as Weight)))
+    }
+    fn withdraw_refresh() -> Weight {
+        (194_600_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(10 as Weight))
+            .saturating_add(T::DbWeight::get().writes(9 as Weight))
+    }
+    fn amend_refresh() -> Weight {
+        (83_600_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
     }
     fn harvest_ref_all() -> Weight {
         (623_700_000 as Weight)
             .saturating_add(T::DbWeight::get().reads(5 as Weight))
             .saturating_add(T::DbWeight::get().writes(503 as Weight))
     }
+    fn harvest_ref_matured() -> Weight {
+        (623_700_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(505 as Weight))
+            .saturating_add(T::DbWeight::get().writes(503 as Weight))
+    }
+    fn harvest_ref_partial(r: u32, ) -> Weight {
+        (86_400_000 as Weight)
+            .saturating_add((1_250_000 as Weight).saturating_mul(r as Weight))
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(r as Weight)))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(r as Weight)))
+    }
     fn harvest_ref_all_sweeper() -> Weight {
         (692_300_000 as Weight)
             .saturating_add(T::DbWeight::get().reads(6 as Weight))
             .saturating_add(T::DbWeight::get().writes(504 as Weight))
     }
+    fn transfer_payroll() -> Weight {
+        (1_247_400_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1004 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1003 as Weight))
+    }
     fn challenge() -> Weight {
-        (152_700_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(11 as Weight))
-            .saturating_add(T::DbWeight::get().writes(7 as Weight))
+        (310_200_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes(9 as Weight))
     }
     fn challenge_update(a: u32, ) -> Weight {
         (33_367_000 as Weight)
@@ -80,9 +204,19 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(a as Weight)))
     }
     fn harvest_challenge() -> Weight {
-        (149_400_000 as Weight)
+        (333_800_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(10 as Weight))
+            .saturating_add(T::DbWeight::get().writes(8 as Weight))
+    }
+    fn back_challenge() -> Weight {
+        (122_400_000 as Weight)
             .saturating_add(T::DbWeight::get().reads(6 as Weight))
-            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn cancel_challenge() -> Weight {
+        (197_200_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
     }
     fn arbitral(a: u32, ) -> Weight {
         (228_473_000 as Weight)
@@ -92,37 +226,160 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().writes(8 as Weight))
             .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(a as Weight)))
     }
+    fn appeal() -> Weight {
+        (104_600_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn resolve_appeal() -> Weight {
+        (143_600_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(6 as Weight))
+    }
+    fn vote_appeal() -> Weight {
+        (196_800_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(11 as Weight))
+            .saturating_add(T::DbWeight::get().writes(9 as Weight))
+    }
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
-    fn start() -> Weight {
+    fn start(p: u32, ) -> Weight {
+        (840_100_000 as Weight)
+            .saturating_add((72_050_000 as Weight).saturating_mul(p as Weight))
+            .saturating_add(RocksDbWeight::get().reads(31 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(p as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(26 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(p as Weight)))
+    }
+    fn force_new_round(p: u32, ) -> Weight {
         (840_100_000 as Weight)
+            .saturating_add((72_050_000 as Weight).saturating_mul(p as Weight))
             .saturating_add(RocksDbWeight::get().reads(27 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(p as Weight)))
             .saturating_add(RocksDbWeight::get().writes(24 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(p as Weight)))
+    }
+    fn register_pathfinder() -> Weight {
+        (105_200_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn deregister_pathfinder() -> Weight {
+        (42_600_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn withdraw_bond() -> Weight {
+        (99_800_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn lift_suspension() -> Weight {
+        (37_200_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_parameters() -> Weight {
+        (34_800_000 as Weight)
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn approve_staking_currency() -> Weight {
+        (28_200_000 as Weight)
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn revoke_staking_currency() -> Weight {
+        (33_600_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_staking_currency() -> Weight {
+        (39_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
-    fn refresh(a: u32, ) -> Weight {
+    fn opt_out() -> Weight {
+        (32_400_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn opt_in() -> Weight {
+        (32_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn commit_refresh() -> Weight {
+        (83_400_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn forfeit_commitment() -> Weight {
+        (76_800_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn refresh(a: u32, d: u32, ) -> Weight {
         (0 as Weight)
             .saturating_add((4_300_286_000 as Weight).saturating_mul(a as Weight))
-            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add((7_060_000 as Weight).saturating_mul(d as Weight))
+            .saturating_add(RocksDbWeight::get().reads(11 as Weight))
             .saturating_add(RocksDbWeight::get().reads((604 as Weight).saturating_mul(a as Weight)))
-            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(d as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
             .saturating_add(RocksDbWeight::get().writes((602 as Weight).saturating_mul(a as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(d as Weight)))
+    }
+    fn retract_refresh(a: u32, ) -> Weight {
+        (76_800_000 as Weight)
+            .saturating_add((122_400_000 as Weight).saturating_mul(a as Weight))
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((4 as Weight).saturating_mul(a as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(a as Weight)))
+    }
+    fn withdraw_refresh() -> Weight {
+        (194_600_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(10 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
+    }
+    fn amend_refresh() -> Weight {
+        (83_600_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
     }
     fn harvest_ref_all() -> Weight {
         (623_700_000 as Weight)
             .saturating_add(RocksDbWeight::get().reads(5 as Weight))
             .saturating_add(RocksDbWeight::get().writes(503 as Weight))
     }
+    fn harvest_ref_matured() -> Weight {
+        (623_700_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(505 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(503 as Weight))
+    }
+    fn harvest_ref_partial(r: u32, ) -> Weight {
+        (86_400_000 as Weight)
+            .saturating_add((1_250_000 as Weight).saturating_mul(r as Weight))
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(r as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(r as Weight)))
+    }
     fn harvest_ref_all_sweeper() -> Weight {
         (692_300_000 as Weight)
             .saturating_add(RocksDbWeight::get().reads(6 as Weight))
             .saturating_add(RocksDbWeight::get().writes(504 as Weight))
     }
+    fn transfer_payroll() -> Weight {
+        (1_247_400_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1004 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1003 as Weight))
+    }
     fn challenge() -> Weight {
-        (152_700_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(11 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+        (310_200_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
     }
     fn challenge_update(a: u32, ) -> Weight {
         (33_367_000 as Weight)
@@ -133,9 +390,19 @@ impl WeightInfo for () {
             .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(a as Weight)))
     }
     fn harvest_challenge() -> Weight {
-        (149_400_000 as Weight)
+        (333_800_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(10 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(8 as Weight))
+    }
+    fn back_challenge() -> Weight {
+        (122_400_000 as Weight)
             .saturating_add(RocksDbWeight::get().reads(6 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn cancel_challenge() -> Weight {
+        (197_200_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
     }
     fn arbitral(a: u32, ) -> Weight {
         (228_473_000 as Weight)
@@ -145,4 +412,19 @@ impl WeightInfo for () {
             .saturating_add(RocksDbWeight::get().writes(8 as Weight))
             .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(a as Weight)))
     }
+    fn appeal() -> Weight {
+        (104_600_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn resolve_appeal() -> Weight {
+        (143_600_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+    }
+    fn vote_appeal() -> Weight {
+        (196_800_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(11 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
+    }
 }
@@ -22,6 +22,14 @@ impl<T: Config> Pallet<T> {
         system::Module::<T>::block_number()
     }
 
+    /// The score of the candidate `target` a challenge would dispute, and whether it is still
+    /// within the confirmation period.
+    pub fn challenge_state(target: &T::AccountId) -> (u64, bool) {
+        <Candidates<T>>::try_get(target).map_or((0, false), |candidate| {
+            (candidate.score, candidate.add_at + T::ConfirmationPeriod::get() > Self::now())
+        })
+    }
+
     // Make sure path.total < 100, or panic
     /// Calculate the path hash.
     pub fn hash_paths(paths: &[Path<T::AccountId>]) -> Vec<u8> {
@@ -151,17 +159,39 @@ impl<T: Config> Pallet<T> {
         Ok(paths[index].clone())
     }
 
+    /// The error of this pallet for `error` returned by `ChallengeBase`.
+    pub(crate) fn challenge_error(error: ChallengeError) -> DispatchError {
+        match error {
+            ChallengeError::NonExistent => Error::<T>::ChallengeNotExist.into(),
+            ChallengeError::WrongPhase => Error::<T>::ChallengeWrongPhase.into(),
+            ChallengeError::NotYourTurn => Error::<T>::NotYourTurn.into(),
+            ChallengeError::Other(e) => e,
+        }
+    }
+
     pub(crate) fn do_harvest_challenge(
         who: &T::AccountId,
         target: &T::AccountId,
     ) -> DispatchResult {
-        <Candidates<T>>::try_mutate(target, |c| {
-            if let Some(score) = T::ChallengeBase::harvest(who, &APP_ID, target)? {
+        let maybe_score =
+            T::ChallengeBase::harvest(who, &APP_ID, target).map_err(Self::challenge_error)?;
+        Self::settle_candidate(target, maybe_score);
+        Ok(())
+    }
+
+    /// Apply the outcome of the harvested challenge to the candidate `target`, the score it
+    /// proved replaces that of `target` in `ScoreList` so that the seeds are selected on it,
+    /// otherwise the score of `target` is confirmed.
+    pub(crate) fn settle_candidate(target: &T::AccountId, maybe_score: Option<u64>) {
+        let score = <Candidates<T>>::mutate(target, |c| {
+            if let Some(score) = maybe_score {
+                Self::mutate_score(&c.score, &score);
                 c.score = score;
             }
-            Self::remove_challenge(target);
-            Ok(())
-        })
+            c.score
+        });
+        Self::remove_challenge(target);
+        Self::deposit_event(Event::CandidateSettled(target.clone(), score));
     }
 
     pub(crate) fn get_ends(path: &Path<T::AccountId>) -> (&T::AccountId, &T::AccountId) {
@@ -409,7 +439,7 @@ impl<T: Config> Pallet<T> {
             target,
             Zero::zero(),
             Zero::zero(),
-            |_, index, _| -> Result<u64, DispatchError> {
+            |_, index, _, _| -> Result<u64, DispatchError> {
                 let p_path = Self::get_pathfinder_paths(target, &index)?;
                 ensure!((count as u32) == p_path.total, Error::<T>::LengthNotEqual);
                 let (start, stop) = Self::get_ends(&p_path);
@@ -418,7 +448,8 @@ impl<T: Config> Pallet<T> {
                 }
                 Ok(Zero::zero())
             },
-        )?;
+        )
+        .map_err(Self::challenge_error)?;
         Ok(())
     }
 
@@ -427,6 +458,7 @@ impl<T: Config> Pallet<T> {
         target: &T::AccountId,
         nodes: &[T::AccountId],
         index: u32,
+        attachment: Option<Vec<u8>>,
     ) -> DispatchResult {
         Self::check_step()?;
         Self::checked_nodes(nodes, target)?;
@@ -441,7 +473,8 @@ impl<T: Config> Pallet<T> {
             &APP_ID,
             challenger,
             target,
-            |_, order| -> Result<bool, DispatchError> {
+            attachment,
+            |_, order, _| -> Result<bool, DispatchError> {
                 let index = index as usize;
                 match <Paths<T>>::try_get(target) {
                     Ok(path_vec) => {
@@ -490,7 +523,8 @@ impl<T: Config> Pallet<T> {
                     }
                 }
             },
-        )?;
+        )
+        .map_err(Self::challenge_error)?;
 
         match maybe_score {
             Some(score) => Self::restart(target, challenger, &score),
@@ -501,3 +535,19 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 }
+
+impl<T: Config> ChallengeHarvested<T::AccountId, AppId, u64> for Pallet<T> {
+    fn on_harvested(
+        app_id: &AppId,
+        target: &T::AccountId,
+        _challenger: &T::AccountId,
+        _share: Perbill,
+        score: Option<u64>,
+    ) -> DispatchResult {
+        if *app_id != APP_ID {
+            return Ok(());
+        }
+        Self::settle_candidate(target, score);
+        Ok(())
+    }
+}
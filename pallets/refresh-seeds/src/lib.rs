@@ -117,13 +117,16 @@ use frame_support::{
     transactional, RuntimeDebug,
 };
 use frame_system::{self as system};
-use sp_runtime::{traits::Zero, DispatchError, DispatchResult};
+use sp_runtime::{traits::Zero, DispatchError, DispatchResult, Perbill};
 use sp_std::{cmp::Ordering, vec::Vec};
 
 pub use orml_utilities::OrderedSet;
 
 use zd_primitives::{fee::SweeperFee, AppId, Balance, Metadata, Pool, TIRStep};
-use zd_support::{ChallengeBase, MultiBaseToken, Reputation, SeedsBase, TrustBase};
+use zd_support::{
+    ChallengeBase, ChallengeError, ChallengeHarvested, MultiBaseToken, Reputation, SeedsBase,
+    TrustBase,
+};
 
 pub use pallet::*;
 
@@ -148,7 +151,13 @@ pub mod pallet {
     pub trait Config: frame_system::Config {
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
         type Reputation: Reputation<Self::AccountId, Self::BlockNumber, TIRStep>;
-        type ChallengeBase: ChallengeBase<Self::AccountId, AppId, Balance, Self::BlockNumber>;
+        type ChallengeBase: ChallengeBase<
+            Self::AccountId,
+            AppId,
+            Balance,
+            Self::BlockNumber,
+            Score = u64,
+        >;
         type TrustBase: TrustBase<Self::AccountId>;
         type SeedsBase: SeedsBase<Self::AccountId>;
         type MultiBaseToken: MultiBaseToken<Self::AccountId, Balance>;
@@ -255,6 +264,12 @@ pub mod pallet {
         EvidenceOfInvalidPresented(T::AccountId, T::AccountId, u64),
         /// Received the proceeds of the challenge. \[who, candidate\]
         ChallengeHarvested(T::AccountId, T::AccountId),
+        /// The challenge of a candidate was settled, leaving it this score. \[candidate, score\]
+        CandidateSettled(T::AccountId, u64),
+        /// A challenge was backed. \[backer, candidate, amount\]
+        ChallengeBacked(T::AccountId, T::AccountId, Balance),
+        /// The challenger cancelled its challenge. \[challenger, candidate\]
+        ChallengeCancelled(T::AccountId, T::AccountId),
         /// Received seed refresh proceeds. \[who, candidate\]
         SeedHarvested(T::AccountId, T::AccountId),
         /// A challenge was launched. \[candidate, score\]
@@ -345,6 +360,14 @@ pub mod pallet {
         NoPathExists,
         /// Candidate does not exist or has been harvested
         CandidateNotExist,
+        /// The challenge does not exist
+        ChallengeNotExist,
+        /// The challenge does not accept this call in its current state
+        ChallengeWrongPhase,
+        /// The caller may not act on the challenge now
+        NotYourTurn,
+        /// The reputation of the account is frozen
+        ReputationFrozen,
     }
 
     #[pallet::hooks]
@@ -362,7 +385,8 @@ pub mod pallet {
             Ok(().into())
         }
 
-        /// Add `target` as a seed candidate with a centrality score of `score`.
+        /// Add `target` as a seed candidate with a centrality score of `score`, `target` must
+        /// not have its reputation frozen.
         ///
         /// Funds from the caller's `SeedStakingAmount` will be deducted.
         #[pallet::weight(T::WeightInfo::add())]
@@ -378,6 +402,10 @@ pub mod pallet {
                 !<Candidates<T>>::contains_key(target.clone()),
                 Error::<T>::AlreadyExist
             );
+            ensure!(
+                !T::Reputation::is_frozen(&target),
+                Error::<T>::ReputationFrozen
+            );
             T::MultiBaseToken::staking(&pathfinder, &T::SeedStakingAmount::get())?;
             Self::candidate_insert(&target, &pathfinder, &score);
             T::Reputation::set_last_refresh_at();
@@ -386,6 +414,7 @@ pub mod pallet {
         }
 
         /// Launch a challenge to the seed candidate `target` with a new score of `score`.
+        /// `attachment` may reference an off-chain proof, such as an IPFS CID.
         ///
         /// Is a no-op if:
         ///
@@ -398,6 +427,7 @@ pub mod pallet {
             origin: OriginFor<T>,
             target: T::AccountId,
             score: u64,
+            attachment: Option<Vec<u8>>,
         ) -> DispatchResultWithPostInfo {
             let challenger = ensure_signed(origin)?;
             Self::check_step()?;
@@ -427,7 +457,10 @@ pub mod pallet {
                     score,
                     ..Metadata::default()
                 },
-            )?;
+                (score, candidate.score),
+                attachment,
+            )
+            .map_err(Self::challenge_error)?;
             <Candidates<T>>::mutate(&target, |c| c.has_challenge = true);
             T::Reputation::set_last_refresh_at();
             Self::deposit_event(Event::NewChallenge(challenger, target));
@@ -465,7 +498,8 @@ pub mod pallet {
                     );
                 }
             }
-            T::ChallengeBase::examine(&APP_ID, &challenger, &target, index)?;
+            T::ChallengeBase::examine(&APP_ID, &challenger, &target, index)
+                .map_err(Self::challenge_error)?;
             T::Reputation::set_last_refresh_at();
             Self::deposit_event(Event::NewExamine(challenger, target));
             Ok(().into())
@@ -490,7 +524,7 @@ pub mod pallet {
                 &target,
                 quantity,
                 count as u32,
-                |is_all_done, index, order| -> Result<u64, DispatchError> {
+                |is_all_done, index, order, _| -> Result<u64, DispatchError> {
                     let new_order = Self::get_next_order(&target, &order, &(index as usize))?;
                     Self::update_result_hashs(&target, &hashs[..], is_all_done, index, false)?;
                     Self::deposit_event(Event::RepliedHash(
@@ -501,7 +535,8 @@ pub mod pallet {
                     ));
                     Ok(new_order)
                 },
-            )?;
+            )
+            .map_err(Self::challenge_error)?;
             T::Reputation::set_last_refresh_at();
             Ok(().into())
         }
@@ -534,7 +569,8 @@ pub mod pallet {
                     ));
                     Ok((Zero::zero(), index))
                 },
-            )?;
+            )
+            .map_err(Self::challenge_error)?;
             Ok(().into())
         }
 
@@ -573,7 +609,7 @@ pub mod pallet {
                 &target,
                 quantity,
                 count as u32,
-                |is_all_done, index, order| -> Result<u64, DispatchError> {
+                |is_all_done, index, order, _| -> Result<u64, DispatchError> {
                     let index = index as usize;
                     let mut full_order = Self::get_full_order(&r_hashs_sets[..], &order, &index)?;
                     let new_order = full_order.try_to_u64().ok_or(Error::<T>::ConverError)?;
@@ -594,7 +630,8 @@ pub mod pallet {
                     ));
                     Ok(new_order)
                 },
-            )?;
+            )
+            .map_err(Self::challenge_error)?;
 
             T::Reputation::set_last_refresh_at();
             Ok(().into())
@@ -646,7 +683,8 @@ pub mod pallet {
                     ));
                     Ok((order, index))
                 },
-            )?;
+            )
+            .map_err(Self::challenge_error)?;
 
             T::Reputation::set_last_refresh_at();
             Ok(().into())
@@ -702,12 +740,13 @@ pub mod pallet {
             target: T::AccountId,
             nodes: Vec<T::AccountId>,
             index: u32,
+            attachment: Option<Vec<u8>>,
         ) -> DispatchResultWithPostInfo {
             let challenger = ensure_signed(origin)?;
 
             ensure!(!<Paths<T>>::contains_key(&target), Error::<T>::PathUploaded);
 
-            Self::evidence_of_missed(&challenger, &target, &nodes, index)?;
+            Self::evidence_of_missed(&challenger, &target, &nodes, index, attachment)?;
             Ok(().into())
         }
 
@@ -723,12 +762,13 @@ pub mod pallet {
             origin: OriginFor<T>,
             target: T::AccountId,
             nodes: Vec<T::AccountId>,
+            attachment: Option<Vec<u8>>,
         ) -> DispatchResultWithPostInfo {
             let challenger = ensure_signed(origin)?;
 
             ensure!(<Paths<T>>::contains_key(&target), Error::<T>::NoPathExists);
 
-            Self::evidence_of_missed(&challenger, &target, &nodes, Zero::zero())?;
+            Self::evidence_of_missed(&challenger, &target, &nodes, Zero::zero(), attachment)?;
             Ok(().into())
         }
 
@@ -748,6 +788,7 @@ pub mod pallet {
             target: T::AccountId,
             index: u32,
             mid_path: Vec<T::AccountId>,
+            attachment: Option<Vec<u8>>,
         ) -> DispatchResultWithPostInfo {
             let challenger = ensure_signed(origin)?;
             Self::check_step()?;
@@ -761,8 +802,10 @@ pub mod pallet {
                 &APP_ID,
                 &challenger,
                 &target,
-                |_, _| -> Result<bool, DispatchError> { Ok(false) },
-            )?;
+                attachment,
+                |_, _, _| -> Result<bool, DispatchError> { Ok(false) },
+            )
+            .map_err(Self::challenge_error)?;
             Self::restart(&target, &challenger, &maybe_score.unwrap_or_default());
             Self::deposit_event(Event::ShorterPresented(challenger, target, index));
             Ok(().into())
@@ -785,6 +828,7 @@ pub mod pallet {
             target: T::AccountId,
             index: u32,
             mid_paths: Vec<Vec<T::AccountId>>,
+            attachment: Option<Vec<u8>>,
         ) -> DispatchResultWithPostInfo {
             let challenger = ensure_signed(origin)?;
             Self::check_step()?;
@@ -805,7 +849,8 @@ pub mod pallet {
                 &APP_ID,
                 &challenger,
                 &target,
-                |_, _| -> Result<bool, DispatchError> {
+                attachment,
+                |_, _, _| -> Result<bool, DispatchError> {
                     for mid_path in mid_paths.clone() {
                         ensure!(
                             mid_path.len() + 2 == p_path.nodes.len(),
@@ -816,7 +861,8 @@ pub mod pallet {
                     ensure!(mid_paths.len() > p_path_total, Error::<T>::TooFewInNumber);
                     Ok(false)
                 },
-            )?;
+            )
+            .map_err(Self::challenge_error)?;
             Self::restart(&target, &challenger, &maybe_score.unwrap_or_default());
             Self::deposit_event(Event::EvidenceOfNumTooLowPresented(
                 challenger, target, index,
@@ -856,10 +902,11 @@ pub mod pallet {
                 &APP_ID,
                 &challenger,
                 &target,
-                |_, _| -> Result<(bool, bool, u64), DispatchError> {
+                |_, _, _| -> Result<(bool, bool, u64), DispatchError> {
                     Ok((through_target, true, score))
                 },
-            )?;
+            )
+            .map_err(Self::challenge_error)?;
             if through_target {
                 Self::restart(&target, &challenger, &score);
             }
@@ -882,6 +929,42 @@ pub mod pallet {
             Ok(().into())
         }
 
+        /// Back the challenge to the seed candidate `target` by staking `amount` alongside the challenger.
+        ///
+        /// The backers share what the challenger side wins pro rata to their stakes and lose
+        /// them if it loses. They get their stakes back if the challenge is cancelled.
+        #[pallet::weight(T::WeightInfo::back_challenge())]
+        #[transactional]
+        pub fn back_challenge(
+            origin: OriginFor<T>,
+            target: T::AccountId,
+            amount: Balance,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            Self::check_step()?;
+            T::ChallengeBase::back(&APP_ID, &who, &target, amount).map_err(Self::challenge_error)?;
+            Self::deposit_event(Event::ChallengeBacked(who, target, amount));
+            Ok(().into())
+        }
+
+        /// Cancel the challenge to the seed candidate `target` launched by mistake.
+        ///
+        /// Only the challenger may cancel, before the pathfinder replies. The challenge deposit
+        /// minus `CancellationFee` is refunded, the rest of the prize pool goes to the pathfinder.
+        #[pallet::weight(T::WeightInfo::cancel_challenge())]
+        #[transactional]
+        pub fn cancel_challenge(
+            origin: OriginFor<T>,
+            target: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            let challenger = ensure_signed(origin)?;
+            Self::check_step()?;
+            T::ChallengeBase::cancel(&APP_ID, &challenger, &target).map_err(Self::challenge_error)?;
+            Self::remove_challenge(&target);
+            Self::deposit_event(Event::ChallengeCancelled(challenger, target));
+            Ok(().into())
+        }
+
         /// Receive seed proceeds.
         ///
         /// Is a no-op if:
@@ -892,6 +975,9 @@ pub mod pallet {
         /// e.g. if there are 100 seed candidates but the maximum number of seeds
         /// is 90, the 90 with the highest score will be taken. If the 91st and
         /// 90th scores are the same, the first to be claimed will be confirmed.
+        ///
+        /// A candidate whose reputation was frozen after it was added releases its staking
+        /// but does not become a seed.
         #[pallet::weight(T::WeightInfo::harvest_seed())]
         #[transactional]
         pub fn harvest_seed(
@@ -923,8 +1009,9 @@ pub mod pallet {
             } else {
                 T::SeedStakingAmount::get()
             };
+            let is_frozen = T::Reputation::is_frozen(&target);
             let (bonus, maybe_index) =
-                match !score_list.is_empty() && candidate.score >= score_list[0] {
+                match !is_frozen && !score_list.is_empty() && candidate.score >= score_list[0] {
                     true => {
                         if let Ok(index) = score_list.binary_search(&candidate.score) {
                             (
@@ -18,7 +18,10 @@ use crate as zd_refresh_seeds;
 use sp_runtime::{
     traits::{BlakeTwo256, IdentityLookup}, testing::Header,
 };
-use frame_support::{construct_runtime, parameter_types, traits::GenesisBuild};
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::{Contains, GenesisBuild},
+};
 use frame_system as system;
 use orml_currencies::BasicCurrencyAdapter;
 use orml_traits::parameter_type_with_key;
@@ -92,9 +95,6 @@ parameter_types! {
     pub const BlockHashCount: u32 = 250;
     pub const SS58Prefix: u8 = 42;
 
-    pub const ShareRatio: Perbill = Perbill::from_percent(80);
-    pub const FeeRation: Perbill = Perbill::from_percent(3);
-    pub const SelfRation: Perbill = Perbill::from_percent(3);
     pub const MaxUpdateCount: u32 = 4;
 
     pub const DampingFactor: Perbill = Perbill::from_percent(100);
@@ -119,8 +119,16 @@ impl zd_trust::Config for Test {
 	type WeightInfo = ();
 }
 
+parameter_types! {
+    pub const MaxDelegators: u32 = 2;
+}
+
 impl zd_reputation::Config for Test {
     type Event = Event;
+    type HistoryRounds = HistoryRounds;
+    type MaxDelegators = MaxDelegators;
+    type PriorityPerTier = ();
+    type SlashOrigin = system::EnsureRoot<AccountId>;
 }
 
 parameter_types! {
@@ -154,6 +162,43 @@ parameter_types! {
 	pub const ChallengeTimeout: BlockNumber = 100;
     /// Response time period of challenge system.
 	pub const ChallengeStakingAmount: Balance = 100;
+	/// Maximum number of data uploads per call of challenge system.
+	pub const MaxNextSteps: u32 = 257;
+	pub const MinDisputeDelta: u64 = 0;
+	pub const ResponseWindow: BlockNumber = 0;
+	pub const MaxResponseExtension: BlockNumber = 0;
+	pub const MaxReplyCount: u32 = 1000;
+	pub const MaxLegsPerReply: u32 = 257;
+	pub const MaxBackers: u32 = 20;
+	pub const MaxEvidenceLen: u32 = 64;
+	pub const CancellationFee: Balance = 10;
+	pub const AppealPeriod: BlockNumber = 50;
+	pub const AppealStakingAmount: Balance = 200;
+	pub const ArbitralThreshold: u32 = 2;
+	pub const ArbiterStakingAmount: Balance = 50;
+	pub const AppealVotingPeriod: BlockNumber = 100;
+	pub const MaxAutoSettlements: u32 = 10;
+	pub const MaxOffchainSettlements: u32 = 10;
+	pub const UnsignedPriority: u64 = 1 << 20;
+	pub const MaxHistory: u32 = 10;
+	pub const HistoryRounds: u32 = 5;
+	pub const MaxHistoryPrunes: u32 = 10;
+	pub const TreasuryAccountId: AccountId = 10001;
+}
+
+pub struct Arbiters;
+impl Contains<AccountId> for Arbiters {
+    fn sorted_members() -> Vec<AccountId> {
+        Vec::new()
+    }
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Test
+where
+    Call: From<C>,
+{
+    type OverarchingCall = Call;
+    type Extrinsic = UncheckedExtrinsic;
 }
 
 impl zd_challenges::Config for Test {
@@ -161,8 +206,38 @@ impl zd_challenges::Config for Test {
     type CurrencyId = CurrencyId;
     type Reputation = ZdReputation;
     type ZdToken = ZdToken;
+    type CurrencyStaking = ZdToken;
     type ChallengeStakingAmount = ChallengeStakingAmount;
     type ChallengeTimeout = ChallengeTimeout;
+    type MaxNextSteps = MaxNextSteps;
+    type MinDisputeDelta = MinDisputeDelta;
+    type ResponseWindow = ResponseWindow;
+    type MaxResponseExtension = MaxResponseExtension;
+    type MaxReplyCount = MaxReplyCount;
+    type MaxLegsPerReply = MaxLegsPerReply;
+    type AppConfigOrigin = system::EnsureRoot<AccountId>;
+    type PauseOrigin = system::EnsureRoot<AccountId>;
+    type TreasuryAccountId = TreasuryAccountId;
+    type MaxBackers = MaxBackers;
+    type MaxEvidenceLen = MaxEvidenceLen;
+    type CancellationFee = CancellationFee;
+    type AppealPeriod = AppealPeriod;
+    type AppealStakingAmount = AppealStakingAmount;
+    type Arbiters = Arbiters;
+    type ArbitralThreshold = ArbitralThreshold;
+    type ArbiterStakingAmount = ArbiterStakingAmount;
+    type AppealVotingPeriod = AppealVotingPeriod;
+    type MaxAutoSettlements = MaxAutoSettlements;
+    type MaxOffchainSettlements = MaxOffchainSettlements;
+    type UnsignedPriority = UnsignedPriority;
+    type MaxHistory = MaxHistory;
+    type HistoryRounds = HistoryRounds;
+    type MaxHistoryPrunes = MaxHistoryPrunes;
+    type Extra = ();
+    type Score = u64;
+    type OnHarvested = ZdRefreshSeeds;
+    type OnTransition = ();
+    type WeightInfo = ();
 }
 
 impl orml_tokens::Config for Test {
@@ -177,6 +252,7 @@ impl orml_tokens::Config for Test {
 
 parameter_types! {
     pub const SocialPoolAccountId: AccountId = 10000;
+    pub const UserRebateRatio: Perbill = Perbill::zero();
 }
 
 impl zd_tokens::Config for Test {
@@ -187,6 +263,7 @@ impl zd_tokens::Config for Test {
     type SocialPool = SocialPoolAccountId;
     type Amount = Amount;
     type BaceToken = BaceToken;
+    type UserRebateRatio = UserRebateRatio;
 }
 impl system::Config for Test {
     type BaseCallFilter = ();
@@ -227,7 +304,7 @@ construct_runtime!(
         Currencies: orml_currencies::{Module, Call, Event<T>},
         ZdTrust: zd_trust::{Module, Call, Event<T>},
         Tokens: orml_tokens::{Module, Storage, Event<T>, Config<T>},
-        Challenges: zd_challenges::{Module, Storage, Event<T>},
+        Challenges: zd_challenges::{Module, Call, Storage, Event<T>, ValidateUnsigned},
         ZdToken: zd_tokens::{Module, Call, Event<T>},
     }
 );
@@ -269,6 +346,9 @@ impl ExtBuilder {
 
         zd_reputation::GenesisConfig::<Test> {
             period: self.period,
+            refresh_period: 0,
+            confirm_period: 0,
+            reputations: vec![],
         }
         .assimilate_storage(&mut t)
         .unwrap();
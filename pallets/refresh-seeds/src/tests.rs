@@ -136,6 +136,12 @@ fn add_should_work() {
             ZdRefreshSeeds::add(Origin::signed(PATHFINDER), A, 60),
             Error::<Test>::AlreadyExist
         );
+
+        assert_ok!(ZdReputation::freeze_reputation(Origin::root(), B));
+        assert_noop!(
+            ZdRefreshSeeds::add(Origin::signed(PATHFINDER), B, 60),
+            Error::<Test>::ReputationFrozen
+        );
     });
 }
 
@@ -143,26 +149,79 @@ fn add_should_work() {
 fn challenge_should_work() {
     new_test_ext().execute_with(|| {
         init_graph(150);
-        assert_ok!(ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), B, 50,));
+        assert_ok!(ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), B, 50, None));
 
         let event = Event::zd_refresh_seeds(crate::Event::NewChallenge(CHALLENGER, B));
         assert!(System::events().iter().any(|record| record.event == event));
 
         assert!(<Candidates<Test>>::get(B).has_challenge);
-        assert!(ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), B, 50,).is_err());
+        assert!(ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), B, 50, None).is_err());
         assert_noop!(
-            ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), A, 50,),
+            ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), A, 50, None),
             Error::<Test>::NoCandidateExists
         );
         assert_ok!(ZdRefreshSeeds::add(Origin::signed(PATHFINDER), C, 12));
         System::set_block_number(ConfirmationPeriod::get() + 1);
         assert_noop!(
-            ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), C, 50,),
+            ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), C, 50, None),
             Error::<Test>::SeedAlreadyConfirmed
         );
     });
 }
 
+#[test]
+fn challenge_state_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(ZdRefreshSeeds::challenge_state(&A), (0, false));
+        assert_ok!(ZdRefreshSeeds::start(Origin::signed(PATHFINDER),));
+        assert_ok!(ZdRefreshSeeds::add(Origin::signed(PATHFINDER), A, 60));
+        assert_eq!(ZdRefreshSeeds::challenge_state(&A), (60, true));
+        System::set_block_number(ConfirmationPeriod::get() + 1);
+        assert_eq!(ZdRefreshSeeds::challenge_state(&A), (60, false));
+    });
+}
+
+#[test]
+fn harvest_challenge_should_settle_candidate() {
+    new_test_ext().execute_with(|| {
+        init_graph(150);
+        assert_ok!(ZdRefreshSeeds::add(Origin::signed(PATHFINDER), C, 12));
+        assert_ok!(ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), B, 50, None));
+        assert_eq!(ZdRefreshSeeds::get_score_list(), vec![12, 150]);
+
+        // The pathfinder never replied, the score claimed by the challenger stands.
+        System::set_block_number(ChallengeTimeout::get() + 2);
+        assert_ok!(ZdRefreshSeeds::harvest_challenge(Origin::signed(CHALLENGER), B));
+        assert_eq!(<Candidates<Test>>::get(B).score, 50);
+        assert_eq!(ZdRefreshSeeds::get_score_list(), vec![12, 50]);
+        let event = Event::zd_refresh_seeds(crate::Event::CandidateSettled(B, 50));
+        assert!(System::events().iter().any(|record| record.event == event));
+    });
+}
+
+#[test]
+fn cancel_challenge_should_work() {
+    new_test_ext().execute_with(|| {
+        init_graph(150);
+        assert_ok!(ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), B, 50, None));
+        let challenger_balance = ZdToken::free_balance(&CHALLENGER);
+        let pathfinder_balance = ZdToken::free_balance(&PATHFINDER);
+
+        assert_ok!(ZdRefreshSeeds::cancel_challenge(Origin::signed(CHALLENGER), B));
+        let fee = CancellationFee::get();
+        assert_eq!(
+            ZdToken::free_balance(&CHALLENGER),
+            challenger_balance + ChallengeStakingAmount::get() - fee
+        );
+        assert_eq!(
+            ZdToken::free_balance(&PATHFINDER),
+            pathfinder_balance + SeedChallengeAmount::get() + fee
+        );
+        let event = Event::zd_refresh_seeds(crate::Event::ChallengeCancelled(CHALLENGER, B));
+        assert!(System::events().iter().any(|record| record.event == event));
+    });
+}
+
 fn init_challenge(total_bonus: &Balance) {
     assert_ok!(ZdRefreshSeeds::add(Origin::signed(PATHFINDER), D, 50));
     assert_ok!(ZdRefreshSeeds::add(Origin::signed(PATHFINDER), C, 100));
@@ -172,6 +231,7 @@ fn init_challenge(total_bonus: &Balance) {
         Origin::signed(CHALLENGER),
         B,
         150,
+        None,
     ));
 
     // Deep 1:
@@ -259,7 +319,7 @@ fn pathfinder_win() {
 
         assert_ok!(ZdRefreshSeeds::examine(Origin::signed(CHALLENGER), B, 0,));
 
-        assert!(ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), B, 50,).is_err());
+        assert!(ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), B, 50, None).is_err());
         // Path hash:
         // +-----------------------+------------------------------------------+
         // |  "0001,0002,0004,2;"  | a0e8df2a2f413bb7f3339c66130b770debb57796 |
@@ -302,7 +362,7 @@ fn pathfinder_win() {
         let event = Event::zd_refresh_seeds(crate::Event::RepliedPath(PATHFINDER, B, 1, true));
         assert!(System::events().iter().any(|record| record.event == event));
 
-        assert!(ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), B, 50,).is_err());
+        assert!(ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), B, 50, None).is_err());
 
         assert_noop!(
             ZdRefreshSeeds::examine(Origin::signed(CHALLENGER), B, 5,),
@@ -311,7 +371,7 @@ fn pathfinder_win() {
 
         assert_ok!(ZdRefreshSeeds::examine(Origin::signed(CHALLENGER), B, 0,));
 
-        assert!(ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), B, 50,).is_err());
+        assert!(ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), B, 50, None).is_err());
 
         assert_noop!(
             ZdRefreshSeeds::reply_hash(
@@ -365,7 +425,7 @@ fn pathfinder_win() {
             Error::<Test>::IndexExceedsMaximum
         );
 
-        assert!(ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), B, 50,).is_err());
+        assert!(ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), B, 50, None).is_err());
 
         assert!(ZdRefreshSeeds::harvest_challenge(Origin::signed(PATHFINDER), B,).is_err());
 
@@ -442,6 +502,7 @@ fn missed_path_at_rhashs() {
             Origin::signed(CHALLENGER),
             B,
             150,
+            None,
         ));
 
         assert_noop!(
@@ -470,12 +531,12 @@ fn missed_path_at_rhashs() {
         assert!(ZdRefreshSeeds::harvest_challenge(Origin::signed(PATHFINDER), B,).is_err());
 
         assert_noop!(
-            ZdRefreshSeeds::missed_in_hashs(Origin::signed(CHALLENGER), B, vec![A, B, F], 1),
+            ZdRefreshSeeds::missed_in_hashs(Origin::signed(CHALLENGER), B, vec![A, B, F], 1, None),
             Error::<Test>::PathIndexError
         );
 
         assert_noop!(
-            ZdRefreshSeeds::missed_in_hashs(Origin::signed(CHALLENGER), B, vec![A, B, F], 3),
+            ZdRefreshSeeds::missed_in_hashs(Origin::signed(CHALLENGER), B, vec![A, B, F], 3, None),
             Error::<Test>::IndexExceedsMaximum
         );
 
@@ -488,10 +549,11 @@ fn missed_path_at_rhashs() {
             Origin::signed(CHALLENGER),
             B,
             vec![A, B, F],
-            0
+            0,
+            None
         ));
 
-        assert!(ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), B, 50,).is_err());
+        assert!(ZdRefreshSeeds::challenge(Origin::signed(CHALLENGER), B, 50, None).is_err());
 
         assert_noop!(
             ZdRefreshSeeds::invalid_evidence(Origin::signed(CHALLENGER), B, vec![B], 60),
@@ -563,7 +625,8 @@ fn evidence_of_shorter_test() {
             Origin::signed(CHALLENGER),
             B,
             0,
-            vec![]
+            vec![],
+            None
         ));
 
         let event = Event::zd_refresh_seeds(crate::Event::ShorterPresented(CHALLENGER, B, 0));
@@ -613,6 +676,7 @@ fn number_too_low_test() {
                 B,
                 0,
                 vec![vec![B,E], vec![C], vec![F],],
+                None,
             ),
             Error::<Test>::LengthNotEqual
         );
@@ -623,6 +687,7 @@ fn number_too_low_test() {
                 B,
                 0,
                 vec![vec![], vec![C]],
+                None,
             ),
             Error::<Test>::LengthNotEqual
         );
@@ -633,6 +698,7 @@ fn number_too_low_test() {
                 B,
                 0,
                 vec![vec![C], vec![F],],
+                None,
             ),
             Error::<Test>::TooFewInNumber
         );
@@ -642,6 +708,7 @@ fn number_too_low_test() {
             B,
             0,
             vec![vec![B], vec![C], vec![F],],
+            None,
         ));
         let event = Event::zd_refresh_seeds(crate::Event::EvidenceOfNumTooLowPresented(
             CHALLENGER, B, 0,
@@ -733,6 +800,7 @@ fn reply_path_next_test() {
             Origin::signed(CHALLENGER),
             B,
             250,
+            None,
         ));
 
         assert_noop!(
@@ -927,6 +995,7 @@ fn missed_at_paths_test() {
             Origin::signed(CHALLENGER),
             B,
             250,
+            None,
         ));
         // Deep 1:
         assert_ok!(ZdRefreshSeeds::reply_hash(
@@ -991,29 +1060,30 @@ fn missed_at_paths_test() {
         ));
 
         assert_noop!(
-            ZdRefreshSeeds::missed_in_paths(Origin::signed(CHALLENGER), B, vec![A, C, D, E]),
+            ZdRefreshSeeds::missed_in_paths(Origin::signed(CHALLENGER), B, vec![A, C, D, E], None),
             Error::<Test>::NoTargetNode
         );
 
         assert_noop!(
-            ZdRefreshSeeds::missed_in_paths(Origin::signed(CHALLENGER), B, vec![A, B, D, F]),
+            ZdRefreshSeeds::missed_in_paths(Origin::signed(CHALLENGER), B, vec![A, B, D, F], None),
             Error::<Test>::AlreadyExist
         );
 
         assert_noop!(
-            ZdRefreshSeeds::missed_in_paths(Origin::signed(CHALLENGER), B, vec![A, B, E]),
+            ZdRefreshSeeds::missed_in_paths(Origin::signed(CHALLENGER), B, vec![A, B, E], None),
             Error::<Test>::NotMatch
         );
 
         assert_noop!(
-            ZdRefreshSeeds::missed_in_paths(Origin::signed(CHALLENGER), B, vec![A, B, F]),
+            ZdRefreshSeeds::missed_in_paths(Origin::signed(CHALLENGER), B, vec![A, B, F], None),
             Error::<Test>::LengthNotEqual
         );
 
         assert_ok!(ZdRefreshSeeds::missed_in_paths(
             Origin::signed(CHALLENGER),
             B,
-            vec![A, B, G, F]
+            vec![A, B, G, F],
+            None
         ));
     });
 }
@@ -47,6 +47,8 @@ pub trait WeightInfo {
     fn missed_in_paths() -> Weight;
     fn invalid_evidence() -> Weight;
     fn harvest_challenge() -> Weight;
+    fn back_challenge() -> Weight;
+    fn cancel_challenge() -> Weight;
     fn harvest_seed() -> Weight;
 }
 
@@ -138,6 +140,16 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().reads(7 as Weight))
             .saturating_add(T::DbWeight::get().writes(9 as Weight))
     }
+    fn back_challenge() -> Weight {
+        (61_200_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn cancel_challenge() -> Weight {
+        (98_600_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
+    }
     fn harvest_seed() -> Weight {
         (285_400_000 as Weight)
             .saturating_add(T::DbWeight::get().reads(12 as Weight))
@@ -232,6 +244,16 @@ impl WeightInfo for () {
             .saturating_add(RocksDbWeight::get().reads(7 as Weight))
             .saturating_add(RocksDbWeight::get().writes(9 as Weight))
     }
+    fn back_challenge() -> Weight {
+        (61_200_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn cancel_challenge() -> Weight {
+        (98_600_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+    }
     fn harvest_seed() -> Weight {
         (285_400_000 as Weight)
             .saturating_add(RocksDbWeight::get().reads(12 as Weight))
@@ -0,0 +1,70 @@
+// Copyright 2021 ZeroDAO
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for the Reputation module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+use zd_primitives::{DomainId, Phase, RoundInfo};
+
+/// The most accounts `get_reputations` reads in one call.
+pub const MAX_REPUTATION_READS: u32 = 1_000;
+
+sp_api::decl_runtime_apis! {
+    pub trait ReputationApi<AccountId, BlockNumber> where
+        AccountId: Codec,
+        BlockNumber: Codec,
+    {
+        /// The latest verified reputation value of `account`.
+        fn get_reputation(account: AccountId) -> Option<u32>;
+
+        /// The latest verified reputation value of each of `accounts`, in the same order. Only
+        /// the first `MAX_REPUTATION_READS` accounts are read.
+        fn get_reputations(accounts: Vec<AccountId>) -> Vec<Option<u32>>;
+
+        /// The latest verified reputation value of `account` in `domain`.
+        fn get_reputation_in(domain: DomainId, account: AccountId) -> Option<u32>;
+
+        /// The reputation value of `account` refreshed in the current round, which may not
+        /// be verified yet.
+        fn get_reputation_new(account: AccountId) -> Option<u32>;
+
+        /// The reputation value of `account` refreshed in the round before the current one.
+        fn get_reputation_old(account: AccountId) -> Option<u32>;
+
+        /// The index of the current round.
+        fn get_round() -> u32;
+
+        /// The timing of the current round, fixed when it started.
+        fn round_info() -> RoundInfo<BlockNumber>;
+
+        /// The phase of the current round.
+        fn phase() -> Phase;
+
+        /// The reputation `account` votes with, including those delegated to it.
+        fn effective_reputation(account: AccountId) -> u64;
+
+        /// The `(round, score)` pairs of the last `HistoryRounds` rounds of `account`, oldest
+        /// first.
+        fn reputation_history(account: AccountId) -> Vec<(u32, u32)>;
+
+        /// The reputation value of `account` when `round` closed, `None` for a round still
+        /// open or no longer retained.
+        fn reputation_at(round: u32, account: AccountId) -> Option<u32>;
+    }
+}
@@ -25,6 +25,20 @@
 //!
 //!  - `Reputation` -  Provides the ability to obtain and modify user
 //! reputation values and to obtain and record system status.
+//!  - `ReputationTier` - Provides the tier of the reputation of a user.
+//!
+//! ### Genesis Config
+//!
+//!  - `period` - The minimum interval between rounds.
+//!  - `refresh_period`, `confirm_period` - The lengths of the refresh and
+//! confirmation phases of a round, recorded in its `RoundInfo` when it starts.
+//!  - `reputations` - The `(account, score)` pairs verified in round 0, so a network can
+//! boot with existing reputations instead of running a first round to create them.
+//!
+//! ### Signed Extensions
+//!
+//!  - `TierPriority` - Raises the priority of the transactions of a user by
+//! `PriorityPerTier` for each tier of its reputation, the fees are unchanged.
 //!
 //! ## Interface
 //!
@@ -32,6 +46,92 @@
 //!
 //! - `set_period` - Setting the system update interval to a given number
 //! of blocks requires administrator privileges.
+//! - `set_round_periods` - Setting the lengths of the refresh and
+//! confirmation phases of a round, requires administrator privileges.
+//! - `set_reputation_caps` - Setting the most reputation points a round
+//! can mint and the highest score of an account, requires administrator
+//! privileges.
+//! - `set_decay_rate` - Setting the share of a reputation value lost for
+//! each round it is not refreshed, requires administrator privileges.
+//!
+//! - `set_tier_thresholds` - Setting the scores from which each tier
+//! starts, requires administrator privileges.
+//! - `delegate_reputation` - Delegate the use of the reputation of the
+//! caller to another account, the score itself stays with the caller.
+//! - `undelegate` - Take back the use of the reputation of the caller.
+//! - `prune_history` - Prune the pairs older than `HistoryRounds` from the
+//! reputation history of an account.
+//! - `freeze_reputation` - Freeze the reputation of an account, such as one
+//! of a proven sybil cluster, requires `SlashOrigin`.
+//! - `unfreeze_reputation` - Unfreeze the reputation of an account, requires
+//! `SlashOrigin`.
+//! - `slash_reputation` - Take an amount from the reputation of an account,
+//! requires `SlashOrigin`.
+//!
+//! ### History
+//!
+//! The `(round, score)` pairs of the last `HistoryRounds` rounds are kept
+//! for each account in `ReputationHistories`, oldest first, so that its
+//! trajectory can be read next to its current value. Older pairs are pruned
+//! whenever the account is refreshed, or by anyone with `prune_history`.
+//!
+//! The pair of a round is frozen once the round closes, that is once the
+//! system is `Free` again or the next round has started, so `reputation_at`
+//! returns the same value for a closed round while new refreshes land and
+//! challenges adjust the current values.
+//!
+//! ### Phases
+//!
+//! A round is `Updating` until the `refresh_ends_at` of its `RoundInfo`,
+//! then `Confirming` until `confirm_ends_at` and `Settling` until the system
+//! is `Free` again. Both phases restart with `open_refresh` once the
+//! refreshes of the round open, so they bound the refreshes and their
+//! confirmation exactly. The phase is stored in `CurrentPhase` and updated at
+//! the start of each block and whenever the step changes, emitting
+//! `RoundPhaseChanged`.
+//!
+//! ### Reputation Roots
+//!
+//! When a round closes, the Merkle root of the verified `(account, score)`
+//! pairs of all accounts, in the order of `ReputationScores`, is built
+//! `MAX_ROOT_LEAVES` accounts per block in `on_initialize`, then stored in
+//! `ReputationRoots` and emitted in `ReputationRootPublished`. A client that
+//! holds the pairs can prove the reputation of one account against the root
+//! with `merkle_proof`, without trusting an indexer.
+//!
+//! ### Domains
+//!
+//! Reputations can be scored on several topics, each a `DomainId` added
+//! with `add_domain`. The scores of `DEFAULT_DOMAIN` are those the system
+//! has always kept, with their history and tier, and the `_in` variants of
+//! the `Reputation` functions read and refresh the other domains in their
+//! own batches, under the same rounds, freezes and caps. The points minted
+//! in every domain count towards the single `RoundIssuance` of the round, so
+//! all domains share one `RoundCap` rather than each having its own.
+//!
+//! ### Tiers
+//!
+//! The tier of an account is the number of `TierThresholds` its verified
+//! score reaches, from 0 up to `MAX_TIER`. It is recomputed for every
+//! account as the root of a closed round is built and whenever a verified
+//! score is changed, emitting `TierChanged`, so neither a score refreshed in
+//! the current round nor a change of the thresholds applies to an account
+//! before the next round closes.
+//!
+//! ### Delegation
+//!
+//! `effective_reputation` is the reputation an account can vote with: its own
+//! verified value plus those of the accounts delegating to it, or nothing if
+//! it delegated its own. Delegations are one level deep, an account that
+//! delegated cannot be delegated to and an account delegated to cannot
+//! delegate, and at most `MaxDelegators` accounts delegate to each account.
+//!
+//! ### Decay
+//!
+//! Unless `DecayRate` is zero, a reputation value not refreshed in the
+//! latest verified round is still returned by `get_reputation`, reduced by
+//! `DecayRate` for each round since it was refreshed. The stored value is
+//! left as it is, the decay is computed whenever it is read or refreshed.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::unused_unit)]
@@ -39,17 +139,27 @@
 use frame_support::{
     codec::{Decode, Encode},
     ensure, pallet,
+    storage::StoragePrefixedMap,
     traits::Get,
+    weights::Weight,
     RuntimeDebug,
     transactional,
 };
 use frame_system::{self as system};
-use sp_runtime::{traits::Zero, DispatchResult};
-use zd_primitives::TIRStep;
-use zd_support::Reputation;
+use frame_support::sp_std::vec::Vec;
+use sp_runtime::{
+    traits::{Hash, Saturating, Zero},
+    transaction_validity::TransactionPriority,
+    DispatchError, DispatchResult, Perbill,
+};
+use zd_primitives::{storage::iter_map_from, DomainId, Phase, RoundInfo, TIRStep, DEFAULT_DOMAIN};
+use zd_support::{Reputation, ReputationTier};
 
 pub use pallet::*;
 
+pub mod priority;
+pub use priority::TierPriority;
+
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -59,6 +169,10 @@ mod tests;
 pub const MAX_SEED: usize = 500;
 /// Seed user initializes reputation values
 pub const INIT_SEED_RANK: usize = 1000;
+/// The highest tier, the number of thresholds in `TierThresholds` is at most this.
+pub const MAX_TIER: u8 = 5;
+/// The most accounts read into the Merkle root of a closed round per block.
+pub const MAX_ROOT_LEAVES: u32 = 500;
 
 /// The state of the entire reputation system.
 #[derive(Encode, Decode, Clone, PartialEq, Default, Eq, RuntimeDebug)]
@@ -82,6 +196,20 @@ pub struct OperationStatus<BlockNumber> {
     pub step: TIRStep,
 }
 
+/// The Merkle root of a closed round being built, `MAX_ROOT_LEAVES` accounts per block.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct RootBuild<Hash> {
+    /// The round whose verified reputations are the leaves.
+    pub round: u32,
+
+    /// The raw key of the last account read, `None` before the first.
+    pub cursor: Option<Vec<u8>>,
+
+    /// The roots of the complete subtrees of the leaves read so far with their heights, the
+    /// lowest last.
+    pub nodes: Vec<(u32, Hash)>,
+}
+
 /// User reputation value.
 #[derive(Encode, Decode, Clone, PartialEq, Default, Eq, RuntimeDebug)]
 pub struct ReputationScore {
@@ -102,6 +230,22 @@ pub mod pallet {
     #[pallet::config]
     pub trait Config: frame_system::Config {
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+        /// The number of rounds the reputation history of an account is kept for, zero keeps
+        /// no history.
+        #[pallet::constant]
+        type HistoryRounds: Get<u32>;
+
+        /// The maximum number of accounts delegating their reputation to an account.
+        #[pallet::constant]
+        type MaxDelegators: Get<u32>;
+
+        /// The priority `TierPriority` raises a transaction by for each tier of its sender.
+        #[pallet::constant]
+        type PriorityPerTier: Get<TransactionPriority>;
+
+        /// The origin allowed to freeze and slash reputations, such as a council.
+        type SlashOrigin: EnsureOrigin<Self::Origin>;
     }
 
     #[pallet::pallet]
@@ -118,10 +262,136 @@ pub mod pallet {
     pub type ReputationScores<T: Config> =
         StorageMap<_, Twox64Concat, T::AccountId, [ReputationScore; 2], ValueQuery>;
 
-    /// Initializes a `period` to the given value.
+    /// The domains added besides `DEFAULT_DOMAIN`, whose scores are in `ReputationScores`.
+    #[pallet::storage]
+    pub type Domains<T: Config> = StorageMap<_, Twox64Concat, DomainId, (), OptionQuery>;
+
+    /// Stores the last two updates of the reputation value of user `AccountId` in each added
+    /// domain.
+    #[pallet::storage]
+    #[pallet::getter(fn domain_ir)]
+    pub type DomainScores<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        DomainId,
+        Twox64Concat,
+        T::AccountId,
+        [ReputationScore; 2],
+        ValueQuery,
+    >;
+
+    /// The `(round, score)` pairs of the last `HistoryRounds` rounds of each account, oldest
+    /// first.
+    #[pallet::storage]
+    #[pallet::getter(fn reputation_history)]
+    pub type ReputationHistories<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, Vec<(u32, u32)>, ValueQuery>;
+
+    /// The Merkle root of the verified `(account, score)` pairs of all accounts when each of
+    /// the last `HistoryRounds` rounds closed, at least the last one.
+    #[pallet::storage]
+    #[pallet::getter(fn reputation_root)]
+    pub type ReputationRoots<T: Config> = StorageMap<_, Twox64Concat, u32, T::Hash, OptionQuery>;
+
+    /// The Merkle roots of the closed rounds still being built, the oldest first.
+    #[pallet::storage]
+    #[pallet::getter(fn root_builds)]
+    pub type RootBuilds<T: Config> = StorageValue<_, Vec<RootBuild<T::Hash>>, ValueQuery>;
+
+    /// The ascending scores from which each tier above 0 starts.
+    #[pallet::storage]
+    #[pallet::getter(fn tier_thresholds)]
+    pub type TierThresholds<T: Config> = StorageValue<_, Vec<u32>, ValueQuery>;
+
+    /// The tier of the verified reputation of each account, see `update_tier`.
+    #[pallet::storage]
+    #[pallet::getter(fn tier)]
+    pub type Tiers<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u8, ValueQuery>;
+
+    /// The account each account delegated the use of its reputation to.
+    #[pallet::storage]
+    #[pallet::getter(fn delegation_of)]
+    pub type Delegations<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, T::AccountId, OptionQuery>;
+
+    /// The accounts delegating the use of their reputation to the first key.
+    #[pallet::storage]
+    pub type Delegators<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::AccountId,
+        Twox64Concat,
+        T::AccountId,
+        (),
+        OptionQuery,
+    >;
+
+    /// The number of accounts delegating the use of their reputation to each account.
+    #[pallet::storage]
+    #[pallet::getter(fn delegator_count)]
+    pub type DelegatorCounts<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+    /// The number of blocks after the start of a round in which it is refreshed.
+    #[pallet::storage]
+    #[pallet::getter(fn refresh_period)]
+    pub type RefreshPeriod<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+    /// The number of blocks after the refresh phase of a round in which its last refreshes
+    /// are confirmed.
+    #[pallet::storage]
+    #[pallet::getter(fn confirm_period)]
+    pub type ConfirmPeriod<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+    /// The timing of the current round, fixed when it started and when its refreshes opened.
+    #[pallet::storage]
+    #[pallet::getter(fn round_info)]
+    pub type CurrentRound<T: Config> = StorageValue<_, RoundInfo<T::BlockNumber>, ValueQuery>;
+
+    /// The phase of the current round, updated with the step of the system and at the start
+    /// of each block.
+    #[pallet::storage]
+    #[pallet::getter(fn phase)]
+    pub type CurrentPhase<T: Config> = StorageValue<_, Phase, ValueQuery>;
+
+    /// The most reputation points all refreshes of a round can mint, in all domains together,
+    /// zero for no cap.
+    #[pallet::storage]
+    #[pallet::getter(fn round_cap)]
+    pub type RoundCap<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+    /// The highest score an account can be refreshed to, zero for no cap.
+    #[pallet::storage]
+    #[pallet::getter(fn max_score)]
+    pub type MaxScore<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// The reputation points minted by the refreshes of the current round, that is the sum
+    /// of what they raised scores by over their previous value.
+    #[pallet::storage]
+    #[pallet::getter(fn round_issuance)]
+    pub type RoundIssuance<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+    /// The accounts whose reputation is frozen by `SlashOrigin`, they are not refreshed, not
+    /// shared with and cannot be seed candidates.
+    #[pallet::storage]
+    pub type FrozenAccounts<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, (), OptionQuery>;
+
+    /// The share of a reputation value lost for each round it is not refreshed, zero
+    /// disables the decay.
+    #[pallet::storage]
+    #[pallet::getter(fn decay_rate)]
+    pub type DecayRate<T: Config> = StorageValue<_, Perbill, ValueQuery>;
+
+    /// Initializes a `period`, `refresh_period` and `confirm_period` to the given values, times
+    /// round 0 from genesis with them and seeds the verified reputation values of
+    /// `reputations` in round 0.
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub period: T::BlockNumber,
+        pub refresh_period: T::BlockNumber,
+        pub confirm_period: T::BlockNumber,
+        pub reputations: Vec<(T::AccountId, u32)>,
     }
 
     #[cfg(feature = "std")]
@@ -129,6 +399,9 @@ pub mod pallet {
         fn default() -> Self {
             GenesisConfig {
                 period: Zero::zero(),
+                refresh_period: Zero::zero(),
+                confirm_period: Zero::zero(),
+                reputations: Vec::new(),
             }
         }
     }
@@ -138,15 +411,79 @@ pub mod pallet {
         fn build(&self) {
             Pallet::<T>::do_set_period(self.period)
                 .expect("Create PERIOD for OperationStatus cannot fail while building genesis");
+            RefreshPeriod::<T>::put(self.refresh_period);
+            ConfirmPeriod::<T>::put(self.confirm_period);
+            CurrentRound::<T>::put(RoundInfo {
+                index: 0,
+                started_at: Zero::zero(),
+                refresh_ends_at: self.refresh_period,
+                confirm_ends_at: self.refresh_period + self.confirm_period,
+            });
+            for (who, score) in self.reputations.iter() {
+                assert!(
+                    !ReputationScores::<T>::contains_key(who),
+                    "duplicate account in genesis reputations"
+                );
+                ReputationScores::<T>::insert(
+                    who,
+                    [
+                        ReputationScore {
+                            score: *score,
+                            nonce: 0,
+                        },
+                        ReputationScore::default(),
+                    ],
+                );
+                Pallet::<T>::record_history(who, 0, *score);
+                Pallet::<T>::update_tier(who, *score);
+            }
         }
     }
 
     #[pallet::event]
-    #[pallet::metadata(T::AccountId = "AccountId", T::BlockNumber = "BlockNumber")]
+    #[pallet::metadata(
+        T::AccountId = "AccountId",
+        T::BlockNumber = "BlockNumber",
+        T::Hash = "Hash"
+    )]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
         /// Updated period. \[period\]
         UpdatedPeriod(T::BlockNumber),
+        /// Updated the lengths of the phases of a round. \[refresh_period, confirm_period\]
+        UpdatedRoundPeriods(T::BlockNumber, T::BlockNumber),
+        /// A round started. \[index, started_at\]
+        RoundStarted(u32, T::BlockNumber),
+        /// A round moved to another phase. \[index, phase\]
+        RoundPhaseChanged(u32, Phase),
+        /// Updated decay rate. \[rate\]
+        UpdatedDecayRate(Perbill),
+        /// Updated tier thresholds. \[thresholds\]
+        UpdatedTierThresholds(Vec<u32>),
+        /// The tier of an account changed. \[who, old_tier, new_tier\]
+        TierChanged(T::AccountId, u8, u8),
+        /// The use of a reputation was delegated. \[who, to\]
+        ReputationDelegated(T::AccountId, T::AccountId),
+        /// The use of a reputation was taken back. \[who, from\]
+        ReputationUndelegated(T::AccountId, T::AccountId),
+        /// Pairs older than `HistoryRounds` were pruned from the reputation history of an
+        /// account. \[who, count\]
+        HistoryPruned(T::AccountId, u32),
+        /// A reputation was frozen. \[who\]
+        ReputationFrozen(T::AccountId),
+        /// A reputation was unfrozen. \[who\]
+        ReputationUnfrozen(T::AccountId),
+        /// A reputation was slashed. \[who, amount\]
+        ReputationSlashed(T::AccountId, u32),
+        /// The Merkle root of the reputations when a round closed. \[index, root\]
+        ReputationRootPublished(u32, T::Hash),
+        /// A reputation domain was added. \[domain\]
+        DomainAdded(DomainId),
+        /// Updated the reputation caps. \[round_cap, max_score\]
+        UpdatedReputationCaps(u64, u32),
+        /// The refreshes of a round minted `RoundCap` points, no more can be minted until
+        /// the next round. \[index, issuance\]
+        RoundCapReached(u32, u64),
     }
 
     #[pallet::error]
@@ -159,14 +496,55 @@ pub mod pallet {
         UnableToSetPeriod,
         /// Reputation already updated.
         ReputationAlreadyUpdated,
+        /// Reputation not updated in this round.
+        ReputationNotUpdated,
         /// The challenge is not over yet.
         ChallengeNotOverYet,
         /// Too short an interval between renewal periods.
         TooShortAnInterval,
+        /// No pair of the history is older than `HistoryRounds`.
+        NothingToPrune,
+        /// The thresholds are not ascending or more than `MAX_TIER`.
+        InvalidTierThresholds,
+        /// An account cannot delegate to itself.
+        SelfDelegation,
+        /// The reputation is already delegated.
+        AlreadyDelegated,
+        /// The reputation is not delegated.
+        NotDelegated,
+        /// The account delegated its own reputation.
+        DelegateHasDelegated,
+        /// The account is delegated to, so it cannot delegate.
+        HasDelegators,
+        /// The account is delegated to by `MaxDelegators` accounts already.
+        TooManyDelegators,
+        /// The reputation is frozen.
+        ReputationFrozen,
+        /// The reputation is not frozen.
+        NotFrozen,
+        /// The account has no reputation.
+        NoReputation,
+        /// The refresh would mint more than `RoundCap` points in the round.
+        RoundCapExceeded,
+        /// The score is higher than `MaxScore`.
+        ScoreTooHigh,
+        /// The domain has not been added.
+        UnknownDomain,
+        /// The domain already exists.
+        DomainExists,
     }
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+            let built = Self::build_root(MAX_ROOT_LEAVES);
+            if Self::phase() == Phase::Free {
+                return built.saturating_add(T::DbWeight::get().reads(1));
+            }
+            Self::update_phase();
+            built.saturating_add(T::DbWeight::get().reads_writes(3, 2))
+        }
+    }
 
     /// Set the system update interval to the given number of blocks.
     ///
@@ -184,6 +562,215 @@ pub mod pallet {
             Self::deposit_event(Event::UpdatedPeriod(period));
             Ok(().into())
         }
+
+        /// Set the number of blocks a round is refreshed for and the number of blocks its last
+        /// refreshes are confirmed for, from the next round on.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the root.
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,2))]
+        pub fn set_round_periods(
+            origin: OriginFor<T>,
+            refresh_period: T::BlockNumber,
+            confirm_period: T::BlockNumber,
+        ) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+            ensure!(
+                Self::system_info().step == TIRStep::Free,
+                Error::<T>::UnableToSetPeriod
+            );
+            RefreshPeriod::<T>::put(refresh_period);
+            ConfirmPeriod::<T>::put(confirm_period);
+            Self::deposit_event(Event::UpdatedRoundPeriods(refresh_period, confirm_period));
+            Ok(().into())
+        }
+
+        /// Add the reputation domain `domain`, whose scores are refreshed apart from those of
+        /// the other domains.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the root.
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1))]
+        pub fn add_domain(origin: OriginFor<T>, domain: DomainId) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+            ensure!(
+                domain != DEFAULT_DOMAIN && !Domains::<T>::contains_key(&domain),
+                Error::<T>::DomainExists
+            );
+            Domains::<T>::insert(&domain, ());
+            Self::deposit_event(Event::DomainAdded(domain));
+            Ok(().into())
+        }
+
+        /// Set the most reputation points the refreshes of a round can mint and the highest
+        /// score of an account, zero for no cap.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the root.
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(2))]
+        pub fn set_reputation_caps(
+            origin: OriginFor<T>,
+            round_cap: u64,
+            max_score: u32,
+        ) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+            RoundCap::<T>::put(round_cap);
+            MaxScore::<T>::put(max_score);
+            Self::deposit_event(Event::UpdatedReputationCaps(round_cap, max_score));
+            Ok(().into())
+        }
+
+        /// Set the share of a reputation value lost for each round it is not refreshed.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the root.
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        pub fn set_decay_rate(origin: OriginFor<T>, rate: Perbill) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+            DecayRate::<T>::put(rate);
+            Self::deposit_event(Event::UpdatedDecayRate(rate));
+            Ok(().into())
+        }
+
+        /// Set the ascending scores from which each tier above 0 starts, at most `MAX_TIER`.
+        ///
+        /// The dispatch origin for this call must be `Signed` by the root.
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        pub fn set_tier_thresholds(
+            origin: OriginFor<T>,
+            thresholds: Vec<u32>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+            ensure!(
+                thresholds.len() <= MAX_TIER as usize
+                    && thresholds.windows(2).all(|pair| pair[0] < pair[1]),
+                Error::<T>::InvalidTierThresholds
+            );
+            TierThresholds::<T>::put(&thresholds);
+            Self::deposit_event(Event::UpdatedTierThresholds(thresholds));
+            Ok(().into())
+        }
+
+        /// Delegate the use of the reputation of the caller to `to`, for voting with
+        /// `effective_reputation`. The score of the caller is not moved.
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4,3))]
+        pub fn delegate_reputation(
+            origin: OriginFor<T>,
+            to: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(who != to, Error::<T>::SelfDelegation);
+            ensure!(
+                !Delegations::<T>::contains_key(&who),
+                Error::<T>::AlreadyDelegated
+            );
+            ensure!(
+                !Delegations::<T>::contains_key(&to),
+                Error::<T>::DelegateHasDelegated
+            );
+            ensure!(
+                Self::delegator_count(&who).is_zero(),
+                Error::<T>::HasDelegators
+            );
+            DelegatorCounts::<T>::try_mutate(&to, |count| -> DispatchResult {
+                ensure!(
+                    *count < T::MaxDelegators::get(),
+                    Error::<T>::TooManyDelegators
+                );
+                *count += 1;
+                Ok(())
+            })?;
+            Delegations::<T>::insert(&who, &to);
+            Delegators::<T>::insert(&to, &who, ());
+            Self::deposit_event(Event::ReputationDelegated(who, to));
+            Ok(().into())
+        }
+
+        /// Take back the use of the reputation the caller delegated.
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2,3))]
+        pub fn undelegate(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let to = Delegations::<T>::take(&who).ok_or(Error::<T>::NotDelegated)?;
+            Delegators::<T>::remove(&to, &who);
+            DelegatorCounts::<T>::mutate_exists(&to, |count| {
+                *count = count.map(|c| c.saturating_sub(1)).filter(|c| !c.is_zero());
+            });
+            Self::deposit_event(Event::ReputationUndelegated(who, to));
+            Ok(().into())
+        }
+
+        /// Prune the pairs older than `HistoryRounds` from the reputation history of `who`,
+        /// for an account that is no longer refreshed.
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2,1))]
+        pub fn prune_history(
+            origin: OriginFor<T>,
+            who: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+            let round = Self::system_info().nonce;
+            let pruned = ReputationHistories::<T>::try_mutate_exists(&who, |history| {
+                Self::prune(history, round).ok_or(Error::<T>::NothingToPrune)
+            })?;
+            Self::deposit_event(Event::HistoryPruned(who, pruned));
+            Ok(().into())
+        }
+
+        /// Freeze the reputation of `account`, which is then not refreshed, not shared with
+        /// and cannot be added as a seed candidate until it is unfrozen.
+        ///
+        /// The dispatch origin for this call must be `SlashOrigin`.
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1))]
+        pub fn freeze_reputation(
+            origin: OriginFor<T>,
+            account: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            T::SlashOrigin::ensure_origin(origin)?;
+            ensure!(!Self::is_frozen(&account), Error::<T>::ReputationFrozen);
+            FrozenAccounts::<T>::insert(&account, ());
+            Self::deposit_event(Event::ReputationFrozen(account));
+            Ok(().into())
+        }
+
+        /// Unfreeze the reputation of `account`.
+        ///
+        /// The dispatch origin for this call must be `SlashOrigin`.
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1))]
+        pub fn unfreeze_reputation(
+            origin: OriginFor<T>,
+            account: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            T::SlashOrigin::ensure_origin(origin)?;
+            FrozenAccounts::<T>::take(&account).ok_or(Error::<T>::NotFrozen)?;
+            Self::deposit_event(Event::ReputationUnfrozen(account));
+            Ok(().into())
+        }
+
+        /// Take `amount` from both reputation values kept for `account`, the verified one and
+        /// the one refreshed after it. The pairs of closed rounds in its history are kept.
+        ///
+        /// The dispatch origin for this call must be `SlashOrigin`.
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3,3))]
+        pub fn slash_reputation(
+            origin: OriginFor<T>,
+            account: T::AccountId,
+            amount: u32,
+        ) -> DispatchResultWithPostInfo {
+            T::SlashOrigin::ensure_origin(origin)?;
+            ensure!(
+                ReputationScores::<T>::contains_key(&account),
+                Error::<T>::NoReputation
+            );
+            let (nonce, score) = ReputationScores::<T>::mutate(&account, |irs| {
+                for ir in irs.iter_mut() {
+                    ir.score = ir.score.saturating_sub(amount);
+                }
+                (irs[0].nonce, irs[0].score)
+            });
+            Self::update_verified_tier(&account);
+            Self::mutate_open_history(&account, nonce, |pairs| {
+                if let Some(last) = pairs.last_mut() {
+                    last.1 = score;
+                }
+            });
+            Self::deposit_event(Event::ReputationSlashed(account, amount));
+            Ok(().into())
+        }
     }
 }
 
@@ -208,16 +795,449 @@ impl<T: Config> Pallet<T> {
             Ok(())
         })
     }
+
+    /// The phase of the current round at `now`, from the step of the system and the timing
+    /// of the round.
+    pub fn phase_at(now: T::BlockNumber) -> Phase {
+        if Self::system_info().step == TIRStep::Free {
+            return Phase::Free;
+        }
+        let round = Self::round_info();
+        if now < round.refresh_ends_at {
+            Phase::Updating
+        } else if now < round.confirm_ends_at {
+            Phase::Confirming
+        } else {
+            Phase::Settling
+        }
+    }
+
+    /// Stores the phase of the current round, emitting `RoundPhaseChanged` if it changed.
+    pub(crate) fn update_phase() {
+        let phase = Self::phase_at(Self::now());
+        if Self::phase() != phase {
+            CurrentPhase::<T>::put(phase);
+            Self::deposit_event(Event::RoundPhaseChanged(Self::system_info().nonce, phase));
+        }
+    }
+
+    /// The points refreshing to `score` in `round` mints over `old`.
+    fn minted(old: &ReputationScore, round: u32, score: u32) -> u64 {
+        score.saturating_sub(Self::decayed_score(old, round)).into()
+    }
+
+    /// Adds `minted` points to `RoundIssuance` of `round`, failing if that exceeds `RoundCap`.
+    pub(crate) fn issue(round: u32, minted: u64) -> DispatchResult {
+        if minted.is_zero() {
+            return Ok(());
+        }
+        let cap = Self::round_cap();
+        let issuance = RoundIssuance::<T>::try_mutate(|issuance| -> Result<u64, DispatchError> {
+            let new_issuance = issuance.saturating_add(minted);
+            ensure!(
+                cap.is_zero() || new_issuance <= cap,
+                Error::<T>::RoundCapExceeded
+            );
+            *issuance = new_issuance;
+            Ok(new_issuance)
+        })?;
+        if !cap.is_zero() && issuance == cap {
+            Self::deposit_event(Event::RoundCapReached(round, issuance));
+        }
+        Ok(())
+    }
+
+    /// The verified `(account, score)` pairs of all accounts in storage order, which are the
+    /// leaves of `ReputationRoots` when read as a round closes.
+    pub fn reputation_pairs() -> Vec<(T::AccountId, u32)> {
+        ReputationScores::<T>::iter()
+            .filter_map(|(who, irs)| Self::verified_score(&irs).map(|score| (who, score)))
+            .collect()
+    }
+
+    /// The Merkle root of `pairs`.
+    ///
+    /// Each leaf is the hash of an `(account, score)` pair, each pair of nodes is hashed in
+    /// sorted order and the last node of a level with an odd number of nodes is carried up
+    /// as is.
+    pub fn merkle_root(pairs: &[(T::AccountId, u32)]) -> T::Hash {
+        let mut nodes = Self::merkle_leaves(pairs);
+        while nodes.len() > 1 {
+            nodes = Self::merkle_level(&nodes[..]);
+        }
+        nodes.pop().unwrap_or_default()
+    }
+
+    /// The proof of the pair at `index` of `pairs` against `merkle_root`, that is the
+    /// siblings of the nodes on its way to the root.
+    pub fn merkle_proof(pairs: &[(T::AccountId, u32)], mut index: usize) -> Vec<T::Hash> {
+        let mut nodes = Self::merkle_leaves(pairs);
+        let mut proof = Vec::new();
+        while nodes.len() > 1 {
+            if let Some(sibling) = nodes.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+            nodes = Self::merkle_level(&nodes[..]);
+        }
+        proof
+    }
+
+    /// Whether `who` had `score` under `root`, as proven by `proof` from `merkle_proof`.
+    pub fn verify_reputation(
+        root: &T::Hash,
+        who: &T::AccountId,
+        score: u32,
+        proof: &[T::Hash],
+    ) -> bool {
+        let leaf = T::Hashing::hash_of(&(who, score));
+        proof
+            .iter()
+            .fold(leaf, |node, sibling| Self::merkle_pair(&node, sibling))
+            == *root
+    }
+
+    fn merkle_leaves(pairs: &[(T::AccountId, u32)]) -> Vec<T::Hash> {
+        pairs.iter().map(|pair| T::Hashing::hash_of(pair)).collect()
+    }
+
+    fn merkle_level(nodes: &[T::Hash]) -> Vec<T::Hash> {
+        nodes
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => Self::merkle_pair(left, right),
+                _ => pair[0],
+            })
+            .collect()
+    }
+
+    /// Adds `leaf` after the complete subtrees `nodes`, merging those of equal height.
+    fn merkle_push(nodes: &mut Vec<(u32, T::Hash)>, leaf: T::Hash) {
+        let (mut height, mut node) = (0u32, leaf);
+        while let Some(&(last_height, left)) = nodes.last() {
+            if last_height != height {
+                break;
+            }
+            nodes.pop();
+            node = Self::merkle_pair(&left, &node);
+            height += 1;
+        }
+        nodes.push((height, node));
+    }
+
+    /// The root of the complete subtrees `nodes`, carrying the last ones up as `merkle_root`
+    /// carries the last node of an odd level.
+    fn merkle_fold(nodes: &[(u32, T::Hash)]) -> T::Hash {
+        let mut subtrees = nodes.iter().rev().map(|(_, node)| *node);
+        let last = subtrees.next().unwrap_or_default();
+        subtrees.fold(last, |right, left| Self::merkle_pair(&left, &right))
+    }
+
+    fn merkle_pair(a: &T::Hash, b: &T::Hash) -> T::Hash {
+        match a <= b {
+            true => T::Hashing::hash_of(&(a, b)),
+            false => T::Hashing::hash_of(&(b, a)),
+        }
+    }
+
+    /// Starts building the Merkle root of the reputations of the round that just closed, see
+    /// `build_root`.
+    pub(crate) fn publish_root() {
+        <RootBuilds<T>>::append(RootBuild {
+            round: Self::system_info().nonce,
+            cursor: None,
+            nodes: Vec::new(),
+        });
+    }
+
+    /// Reads at most `limit` more accounts into the oldest Merkle root in `RootBuilds`,
+    /// returning the weight consumed.
+    ///
+    /// The tier of each account read is recomputed from its score in the round. Once all
+    /// accounts are read, the root is stored and announced and the root of the round
+    /// `HistoryRounds` before is dropped. An account refreshed twice more before it is read
+    /// has no verified score left for the round and is left out.
+    pub(crate) fn build_root(limit: u32) -> Weight {
+        let mut builds = <RootBuilds<T>>::get();
+        let build = match builds.first_mut() {
+            Some(build) => build,
+            None => return T::DbWeight::get().reads(1),
+        };
+        let decay_rate = Self::decay_rate();
+        let mut accounts = iter_map_from::<T::AccountId, [ReputationScore; 2]>(
+            &ReputationScores::<T>::final_prefix(),
+            build.cursor.take(),
+        );
+        let mut read: Weight = 0;
+        for (who, irs) in accounts.by_ref().take(limit as usize) {
+            read += 1;
+            build.cursor = Some(ReputationScores::<T>::hashed_key_for(&who));
+            let score = Self::score_in_round(&irs, build.round, decay_rate);
+            if let Some(score) = score {
+                Self::merkle_push(&mut build.nodes, T::Hashing::hash_of(&(&who, score)));
+            }
+            Self::update_tier(&who, score.unwrap_or_default());
+        }
+        let mut writes = read + 1;
+        if accounts.next().is_none() {
+            let build = builds.remove(0);
+            let root = Self::merkle_fold(&build.nodes);
+            ReputationRoots::<T>::insert(build.round, root);
+            if let Some(expired) = build.round.checked_sub(T::HistoryRounds::get().max(1)) {
+                ReputationRoots::<T>::remove(expired);
+            }
+            Self::deposit_event(Event::ReputationRootPublished(build.round, root));
+            writes += 2;
+        }
+        <RootBuilds<T>>::put(builds);
+        T::DbWeight::get()
+            .reads_writes(read.saturating_mul(2) + 3, writes)
+            .saturating_add(read.saturating_mul(10_000))
+    }
+
+    /// Whether `domain` is `DEFAULT_DOMAIN` or has been added.
+    pub fn is_domain(domain: &DomainId) -> bool {
+        *domain == DEFAULT_DOMAIN || Domains::<T>::contains_key(domain)
+    }
+
+    /// The last verified round.
+    pub(crate) fn verified_round() -> u32 {
+        let system_info = Self::system_info();
+        // The round being refreshed is not verified until the system is `Free` again.
+        match system_info.step == TIRStep::Free {
+            true => system_info.nonce,
+            false => system_info.nonce.saturating_sub(1),
+        }
+    }
+
+    /// The value of `irs` verified in `round`, decayed by `decay_rate` if it was refreshed
+    /// before.
+    pub(crate) fn score_in_round(
+        irs: &[ReputationScore; 2],
+        round: u32,
+        decay_rate: Perbill,
+    ) -> Option<u32> {
+        let ir = irs.iter().find(|ir| ir.nonce <= round)?;
+        if ir.nonce == round {
+            Some(ir.score)
+        } else if !decay_rate.is_zero() && *ir != ReputationScore::default() {
+            Some(Self::decay_by(ir, round, decay_rate))
+        } else {
+            None
+        }
+    }
+
+    /// The latest verified value of `irs`, decayed if it was not refreshed in the last
+    /// verified round.
+    pub(crate) fn verified_score(irs: &[ReputationScore; 2]) -> Option<u32> {
+        Self::score_in_round(irs, Self::verified_round(), Self::decay_rate())
+    }
+
+    /// The value of `irs` refreshed in the current round.
+    pub(crate) fn new_score(irs: &[ReputationScore; 2]) -> Option<u32> {
+        let new_nonce = Self::system_info().nonce;
+        irs.iter().find(|ir| ir.nonce == new_nonce).map(|ir| ir.score)
+    }
+
+    /// Refreshes `irs` to `score` in round `nonce`, returning whether the score has changed.
+    pub(crate) fn push_score(
+        irs: &mut [ReputationScore; 2],
+        nonce: u32,
+        score: u32,
+    ) -> Result<bool, DispatchError> {
+        let (is_changed, minted) =
+            Self::replace_score(irs, nonce, score, Self::max_score(), Self::decay_rate())?;
+        Self::issue(nonce, minted)?;
+        Ok(is_changed)
+    }
+
+    /// Refreshes `irs` to `score` in round `nonce`, checked against `max_score`, returning
+    /// whether the score has changed and the points it mints over the old one decayed by
+    /// `decay_rate`, which are left to `issue`.
+    fn replace_score(
+        irs: &mut [ReputationScore; 2],
+        nonce: u32,
+        score: u32,
+        max_score: u32,
+        decay_rate: Perbill,
+    ) -> Result<(bool, u64), DispatchError> {
+        ensure!(irs[0].nonce < nonce, Error::<T>::ReputationAlreadyUpdated);
+        ensure!(
+            max_score.is_zero() || score <= max_score,
+            Error::<T>::ScoreTooHigh
+        );
+        let old = irs[0].clone();
+        let decayed = Self::decay_by(&old, nonce, decay_rate);
+        *irs = [ReputationScore { nonce, score }, old];
+        Ok((decayed != score, score.saturating_sub(decayed).into()))
+    }
+
+    /// Undoes the refresh of `irs` in round `nonce`, taking the points it minted back out of
+    /// `RoundIssuance`.
+    fn pop_score(irs: &mut [ReputationScore; 2], nonce: u32) -> DispatchResult {
+        ensure!(irs[0].nonce == nonce, Error::<T>::ReputationNotUpdated);
+        let minted = Self::minted(&irs[1], nonce, irs[0].score);
+        RoundIssuance::<T>::mutate(|issuance| *issuance = issuance.saturating_sub(minted));
+        *irs = [irs[1].clone(), ReputationScore::default()];
+        Ok(())
+    }
+
+    /// Refreshes `who` to `score` in the added `domain`.
+    pub(crate) fn refresh_in_domain(
+        domain: &DomainId,
+        who: &T::AccountId,
+        score: u32,
+    ) -> Result<bool, DispatchError> {
+        ensure!(!Self::is_frozen(who), Error::<T>::ReputationFrozen);
+        let nonce = Self::system_info().nonce;
+        DomainScores::<T>::try_mutate(domain, who, |irs| Self::push_score(irs, nonce, score))
+    }
+
+    /// Whether the reputation of `who` is frozen.
+    pub fn is_frozen(who: &T::AccountId) -> bool {
+        FrozenAccounts::<T>::contains_key(who)
+    }
+
+    /// The tier of `score`, the number of `TierThresholds` it reaches.
+    pub fn tier_of_score(score: u32) -> u8 {
+        Self::tier_thresholds()
+            .iter()
+            .take_while(|threshold| score >= **threshold)
+            .count() as u8
+    }
+
+    /// Recomputes the tier of `who` from its verified `score`, only writing it if it changed.
+    pub(crate) fn update_tier(who: &T::AccountId, score: u32) {
+        let new_tier = Self::tier_of_score(score);
+        let old_tier = Self::tier(who);
+        if old_tier == new_tier {
+            return;
+        }
+        match new_tier.is_zero() {
+            true => Tiers::<T>::remove(who),
+            false => Tiers::<T>::insert(who, new_tier),
+        }
+        Self::deposit_event(Event::TierChanged(who.clone(), old_tier, new_tier));
+    }
+
+    /// Recomputes the tier of `who` after a change of its verified score. The score refreshed
+    /// in a round only counts once the round closes, when `build_root` reads it.
+    pub(crate) fn update_verified_tier(who: &T::AccountId) {
+        Self::update_tier(who, Self::get_reputation(who).unwrap_or_default());
+    }
+
+    /// The reputation `who` votes with, its own verified value plus those of the accounts
+    /// delegating to it, or 0 if it delegated its own.
+    pub fn effective_reputation(who: &T::AccountId) -> u64 {
+        if Delegations::<T>::contains_key(who) {
+            return 0;
+        }
+        Delegators::<T>::iter_prefix(who)
+            .map(|(delegator, _)| delegator)
+            .chain(Some(who.clone()))
+            .map(|account| u64::from(Self::get_reputation(&account).unwrap_or_default()))
+            .fold(0u64, |total, score| total.saturating_add(score))
+    }
+
+    /// Whether `round` is closed, after which its pairs in the history are frozen.
+    pub(crate) fn is_closed(round: u32) -> bool {
+        let info = Self::system_info();
+        round < info.nonce || (round == info.nonce && info.step == TIRStep::Free)
+    }
+
+    /// Updates the pair of `round` at the end of the history of `who` with `f`, unless the
+    /// round is closed. `f` is given the history, which it leaves empty to remove it.
+    fn mutate_open_history(who: &T::AccountId, round: u32, f: impl FnOnce(&mut Vec<(u32, u32)>)) {
+        if Self::is_closed(round) {
+            return;
+        }
+        ReputationHistories::<T>::mutate_exists(who, |history| {
+            if let Some(pairs) = history {
+                if pairs.last().map_or(false, |(r, _)| *r == round) {
+                    f(pairs);
+                }
+                if pairs.is_empty() {
+                    *history = None;
+                }
+            }
+        });
+    }
+
+    /// The reputation value of `who` when `round` closed, the pair of the round in its
+    /// history, or the last pair before it decayed to `round`.
+    pub fn reputation_at(round: u32, who: &T::AccountId) -> Option<u32> {
+        if !Self::is_closed(round) {
+            return None;
+        }
+        let history = Self::reputation_history(who);
+        let (nonce, score) = *history.iter().rev().find(|(r, _)| *r <= round)?;
+        if nonce == round {
+            Some(score)
+        } else if !Self::decay_rate().is_zero() {
+            Some(Self::decayed_score(&ReputationScore { score, nonce }, round))
+        } else {
+            None
+        }
+    }
+
+    /// Removes the pairs of `history` older than `HistoryRounds` in `round`, returns how many
+    /// if any.
+    pub(crate) fn prune(history: &mut Option<Vec<(u32, u32)>>, round: u32) -> Option<u32> {
+        let pairs = history.as_mut()?;
+        let len = pairs.len();
+        let rounds = T::HistoryRounds::get();
+        pairs.retain(|(r, _)| r.saturating_add(rounds) > round);
+        let pruned = (len - pairs.len()) as u32;
+        if pairs.is_empty() {
+            *history = None;
+        }
+        match pruned {
+            0 => None,
+            _ => Some(pruned),
+        }
+    }
+
+    /// Appends `score` of `round` to the reputation history of `who` and prunes it.
+    pub(crate) fn record_history(who: &T::AccountId, round: u32, score: u32) {
+        ReputationHistories::<T>::mutate_exists(who, |history| {
+            history.get_or_insert_with(Vec::new).push((round, score));
+            Self::prune(history, round);
+        });
+    }
+
+    /// The score of `ir` in `round`, less `DecayRate` for each round since it was refreshed.
+    pub fn decayed_score(ir: &ReputationScore, round: u32) -> u32 {
+        Self::decay_by(ir, round, Self::decay_rate())
+    }
+
+    /// The score of `ir` in `round`, less `decay_rate` for each round since it was refreshed.
+    fn decay_by(ir: &ReputationScore, round: u32, decay_rate: Perbill) -> u32 {
+        let rounds = round.saturating_sub(ir.nonce);
+        Perbill::one()
+            .saturating_sub(decay_rate)
+            .saturating_pow(rounds as usize)
+            .mul_floor(ir.score)
+    }
 }
 
 impl<T: Config> Reputation<T::AccountId, T::BlockNumber, TIRStep> for Pallet<T> {
     // Low-level operation. Make changes directly to the latest nonce's REPUTATION
     fn mutate_reputation(target: &T::AccountId, ir: &u32) {
-        ReputationScores::<T>::mutate(&target, |x| x[0].score = *ir);
+        let nonce = ReputationScores::<T>::mutate(&target, |x| {
+            x[0].score = *ir;
+            x[0].nonce
+        });
+        Self::update_verified_tier(target);
+        Self::mutate_open_history(target, nonce, |pairs| {
+            if let Some(last) = pairs.last_mut() {
+                last.1 = *ir;
+            }
+        });
     }
 
     fn set_step(step: &TIRStep) {
         <SystemInfo<T>>::mutate(|operation_status| operation_status.step = *step);
+        Self::update_phase();
     }
 
     fn is_step(step: &TIRStep) -> bool {
@@ -242,62 +1262,173 @@ impl<T: Config> Reputation<T::AccountId, T::BlockNumber, TIRStep> for Pallet<T>
             operation_status.last = now_block_number;
             operation_status.step = TIRStep::Seed;
             Ok(())
-        })
+        })?;
+        RoundIssuance::<T>::kill();
+        let refresh_ends_at = now_block_number + Self::refresh_period();
+        let round = RoundInfo {
+            index: Self::system_info().nonce,
+            started_at: now_block_number,
+            refresh_ends_at,
+            confirm_ends_at: refresh_ends_at + Self::confirm_period(),
+        };
+        Self::deposit_event(Event::RoundStarted(round.index, round.started_at));
+        <CurrentRound<T>>::put(round);
+        Self::update_phase();
+        Ok(())
+    }
+
+    fn open_refresh() {
+        let refresh_ends_at = Self::now() + Self::refresh_period();
+        <CurrentRound<T>>::mutate(|round| {
+            round.refresh_ends_at = refresh_ends_at;
+            round.confirm_ends_at = refresh_ends_at + Self::confirm_period();
+        });
+        Self::update_phase();
     }
 
     fn get_reputation_new(target: &T::AccountId) -> Option<u32> {
-        let new_nonce = Self::system_info().nonce;
-        let irs = Self::get_ir(target);
-        if irs[0].nonce == new_nonce {
-            Some(irs[0].score)
-        } else if irs[1].nonce == new_nonce {
-            Some(irs[1].score)
-        } else {
-            None
-        }
+        Self::new_score(&Self::get_ir(target))
+    }
+
+    fn get_reputation_old(target: &T::AccountId) -> Option<u32> {
+        // No round is refreshed before the first one.
+        let old_nonce = Self::system_info().nonce.checked_sub(1).filter(|n| !n.is_zero())?;
+        Self::get_ir(target)
+            .iter()
+            .find(|ir| ir.nonce == old_nonce)
+            .map(|ir| ir.score)
     }
 
     fn get_reputation(target: &T::AccountId) -> Option<u32> {
-        let system_info = Self::system_info();
-        let nonce = system_info.nonce;
-        let irs = Self::get_ir(target);
-        match system_info.step == TIRStep::Free {
-            true => {
-                if irs[0].nonce == nonce {
-                    return Some(irs[0].score);
-                }
-            }
-            false => {
-                // nonce cannot be smaller than 1
-                if irs[0].nonce == nonce - 1 {
-                    return Some(irs[0].score);
-                } else if irs[1].nonce == nonce - 1 {
-                    return Some(irs[1].score);
-                }
-            }
-        }
-        None
+        Self::verified_score(&Self::get_ir(target))
+    }
+
+    fn get_many(accounts: &[T::AccountId]) -> Vec<Option<u32>> {
+        let round = Self::verified_round();
+        let decay_rate = Self::decay_rate();
+        accounts
+            .iter()
+            .map(|who| Self::score_in_round(&Self::get_ir(who), round, decay_rate))
+            .collect()
+    }
+
+    fn get_reputation_at(round: u32, target: &T::AccountId) -> Option<u32> {
+        Self::reputation_at(round, target)
     }
 
     #[transactional]
     fn refresh_reputation(user_score: &(T::AccountId, u32)) -> DispatchResult {
         let who = &user_score.0;
+        ensure!(!Self::is_frozen(who), Error::<T>::ReputationFrozen);
         let nonce = Self::system_info().nonce;
-        ReputationScores::<T>::try_mutate(&who, |reputation| -> DispatchResult {
-            ensure!(
-                reputation[0].nonce < nonce,
-                Error::<T>::ReputationAlreadyUpdated
-            );
-            let old = reputation[0].clone();
-            *reputation = [
-                ReputationScore {
-                    nonce,
-                    score: user_score.1,
-                },
-                old,
-            ];
-            Ok(())
-        })
+        ReputationScores::<T>::try_mutate(&who, |reputation| {
+            Self::push_score(reputation, nonce, user_score.1)
+        })?;
+        Self::record_history(who, nonce, user_score.1);
+        Ok(())
+    }
+
+    /// Reads `SystemInfo`, `MaxScore` and `DecayRate` once and writes `RoundIssuance` once
+    /// for all of `user_scores`.
+    #[transactional]
+    fn bulk_refresh(user_scores: &[(T::AccountId, u32)]) -> Result<Vec<bool>, DispatchError> {
+        let nonce = Self::system_info().nonce;
+        let max_score = Self::max_score();
+        let decay_rate = Self::decay_rate();
+        let mut minted = 0u64;
+        let changes = user_scores
+            .iter()
+            .map(|(who, score)| {
+                ensure!(!Self::is_frozen(who), Error::<T>::ReputationFrozen);
+                let (is_changed, user_minted) =
+                    ReputationScores::<T>::try_mutate(&who, |reputation| {
+                        Self::replace_score(reputation, nonce, *score, max_score, decay_rate)
+                    })?;
+                minted = minted.saturating_add(user_minted);
+                Self::record_history(who, nonce, *score);
+                Ok(is_changed)
+            })
+            .collect::<Result<Vec<_>, DispatchError>>()?;
+        Self::issue(nonce, minted)?;
+        Ok(changes)
+    }
+
+    fn get_reputation_in(domain: &DomainId, target: &T::AccountId) -> Option<u32> {
+        match *domain == DEFAULT_DOMAIN {
+            true => Self::get_reputation(target),
+            false => Self::verified_score(&Self::domain_ir(domain, target)),
+        }
+    }
+
+    fn get_reputation_new_in(domain: &DomainId, target: &T::AccountId) -> Option<u32> {
+        match *domain == DEFAULT_DOMAIN {
+            true => Self::get_reputation_new(target),
+            false => Self::new_score(&Self::domain_ir(domain, target)),
+        }
+    }
+
+    #[transactional]
+    fn refresh_reputation_in(
+        domain: &DomainId,
+        user_score: &(T::AccountId, u32),
+    ) -> DispatchResult {
+        if *domain == DEFAULT_DOMAIN {
+            return Self::refresh_reputation(user_score);
+        }
+        ensure!(Self::is_domain(domain), Error::<T>::UnknownDomain);
+        Self::refresh_in_domain(domain, &user_score.0, user_score.1).map(|_| ())
+    }
+
+    #[transactional]
+    fn bulk_refresh_in(
+        domain: &DomainId,
+        user_scores: &[(T::AccountId, u32)],
+    ) -> Result<Vec<bool>, DispatchError> {
+        if *domain == DEFAULT_DOMAIN {
+            return Self::bulk_refresh(user_scores);
+        }
+        ensure!(Self::is_domain(domain), Error::<T>::UnknownDomain);
+        user_scores
+            .iter()
+            .map(|(who, score)| Self::refresh_in_domain(domain, who, *score))
+            .collect()
+    }
+
+    #[transactional]
+    fn revert_reputation(who: &T::AccountId) -> DispatchResult {
+        let nonce = Self::system_info().nonce;
+        ReputationScores::<T>::try_mutate(&who, |irs| Self::pop_score(irs, nonce))?;
+        Self::update_verified_tier(who);
+        Self::mutate_open_history(who, nonce, |pairs| {
+            pairs.pop();
+        });
+        Ok(())
+    }
+
+    #[transactional]
+    fn revert_reputation_in(domain: &DomainId, who: &T::AccountId) -> DispatchResult {
+        if *domain == DEFAULT_DOMAIN {
+            return Self::revert_reputation(who);
+        }
+        ensure!(Self::is_domain(domain), Error::<T>::UnknownDomain);
+        let nonce = Self::system_info().nonce;
+        DomainScores::<T>::try_mutate(domain, who, |irs| Self::pop_score(irs, nonce))
+    }
+
+    fn get_round() -> u32 {
+        Self::system_info().nonce
+    }
+
+    fn is_frozen(who: &T::AccountId) -> bool {
+        Self::is_frozen(who)
+    }
+
+    fn round_info() -> RoundInfo<T::BlockNumber> {
+        Self::round_info()
+    }
+
+    fn phase() -> Phase {
+        Self::phase()
     }
 
     fn get_last_refresh_at() -> T::BlockNumber {
@@ -319,5 +1450,13 @@ impl<T: Config> Reputation<T::AccountId, T::BlockNumber, TIRStep> for Pallet<T>
             operation_status.last = now;
             operation_status.step = TIRStep::Free;
         });
+        Self::update_phase();
+        Self::publish_root();
+    }
+}
+
+impl<T: Config> ReputationTier<T::AccountId> for Pallet<T> {
+    fn tier_of(who: &T::AccountId) -> u8 {
+        Self::tier(who)
     }
 }
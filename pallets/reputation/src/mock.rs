@@ -54,10 +54,17 @@ parameter_types! {
 parameter_types! {
     pub const ConfirmationPeriod: BlockNumber = 120;
     pub const ChallengePerior: BlockNumber = 100;
+    pub const HistoryRounds: u32 = 3;
+    pub const MaxDelegators: u32 = 2;
+    pub const PriorityPerTier: u64 = 100;
 }
 
 impl zd_reputation::Config for Test {
     type Event = Event;
+    type HistoryRounds = HistoryRounds;
+    type MaxDelegators = MaxDelegators;
+    type PriorityPerTier = PriorityPerTier;
+    type SlashOrigin = system::EnsureRoot<AccountId>;
 }
 
 impl system::Config for Test {
@@ -87,15 +94,24 @@ impl system::Config for Test {
 
 pub struct ExtBuilder {
     period: BlockNumber,
+    reputations: Vec<(AccountId, u32)>,
 }
 
 impl Default for ExtBuilder {
     fn default() -> Self {
-        Self { period: INIT_PERIOD }
+        Self {
+            period: INIT_PERIOD,
+            reputations: vec![],
+        }
     }
 }
 
 impl ExtBuilder {
+    pub fn reputations(mut self, reputations: Vec<(AccountId, u32)>) -> Self {
+        self.reputations = reputations;
+        self
+    }
+
     pub fn build(self) -> sp_io::TestExternalities {
         let mut t = frame_system::GenesisConfig::default()
             .build_storage::<Test>()
@@ -103,6 +119,9 @@ impl ExtBuilder {
 
         zd_reputation::GenesisConfig::<Test> {
             period: self.period,
+            refresh_period: 0,
+            confirm_period: 0,
+            reputations: self.reputations,
         }
         .assimilate_storage(&mut t)
         .unwrap();
@@ -0,0 +1,86 @@
+// Copyright 2021 ZeroDAO
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+use frame_support::sp_std::{fmt, marker::PhantomData};
+use sp_runtime::{
+    traits::{DispatchInfoOf, SignedExtension},
+    transaction_validity::{
+        TransactionPriority, TransactionValidity, TransactionValidityError, ValidTransaction,
+    },
+};
+
+/// Raises the priority of the transactions of an account by `PriorityPerTier` for each tier
+/// of its reputation, up to `MAX_TIER` tiers.
+///
+/// It only adds to the priority given by the other extensions of the transaction and never
+/// accepts one they reject, so the fees are still charged and a high tier does not let an
+/// account send transactions for free.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct TierPriority<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> TierPriority<T> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+
+    /// The priority the transactions of `who` are raised by.
+    pub fn boost(who: &T::AccountId) -> TransactionPriority {
+        let tier = Pallet::<T>::tier(who).min(MAX_TIER);
+        T::PriorityPerTier::get().saturating_mul(tier.into())
+    }
+}
+
+impl<T: Config + Send + Sync> Default for TierPriority<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Config + Send + Sync> fmt::Debug for TierPriority<T> {
+    #[cfg(feature = "std")]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TierPriority")
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn fmt(&self, _: &mut fmt::Formatter) -> fmt::Result {
+        Ok(())
+    }
+}
+
+impl<T: Config + Send + Sync> SignedExtension for TierPriority<T> {
+    const IDENTIFIER: &'static str = "TierPriority";
+    type AccountId = T::AccountId;
+    type Call = T::Call;
+    type AdditionalSigned = ();
+    type Pre = ();
+
+    fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        who: &Self::AccountId,
+        _call: &Self::Call,
+        _info: &DispatchInfoOf<Self::Call>,
+        _len: usize,
+    ) -> TransactionValidity {
+        Ok(ValidTransaction {
+            priority: Self::boost(who),
+            ..Default::default()
+        })
+    }
+}
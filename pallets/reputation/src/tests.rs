@@ -17,6 +17,11 @@
 use super::*;
 use crate::mock::*;
 use frame_support::{assert_noop, assert_ok, dispatch};
+use frame_support::{traits::OnInitialize, weights::DispatchInfo};
+use sp_runtime::{
+    traits::{DispatchInfoOf, SignedExtension},
+    transaction_validity::{InvalidTransaction, TransactionValidity, TransactionValidityError},
+};
 
 fn new_test_ext() -> sp_io::TestExternalities {
     let mut ext = ExtBuilder::default().build();
@@ -136,6 +141,282 @@ fn refresh_reputation_should_fail() {
     });
 }
 
+#[test]
+fn bulk_refresh_should_work() {
+    const BOB: AccountId = 2;
+    const CHARLIE: AccountId = 3;
+    const DAVE: AccountId = 4;
+    let user_scores = vec![(ALICE, 18), (BOB, 0), (CHARLIE, 25)];
+    let refresh_each = || {
+        new_test_ext().execute_with(|| {
+            assert_ok!(ZdReputation::new_round());
+            assert_ok!(ZdReputation::refresh_reputation(&(ALICE, 18)));
+            ZdReputation::set_step(&TIRStep::Free);
+            System::set_block_number(20);
+            assert_ok!(ZdReputation::new_round());
+            for user_score in user_scores.iter() {
+                assert_ok!(ZdReputation::refresh_reputation(user_score));
+            }
+            let irs = user_scores
+                .iter()
+                .map(|(who, _)| ZdReputation::get_ir(who))
+                .collect::<Vec<_>>();
+            (irs, ZdReputation::round_issuance())
+        })
+    };
+    let refresh_bulk = || {
+        new_test_ext().execute_with(|| {
+            assert_ok!(ZdReputation::new_round());
+            assert_ok!(ZdReputation::refresh_reputation(&(ALICE, 18)));
+            ZdReputation::set_step(&TIRStep::Free);
+            System::set_block_number(20);
+            assert_ok!(ZdReputation::new_round());
+            assert_eq!(
+                ZdReputation::bulk_refresh(&user_scores[..]),
+                Ok(vec![false, true, true])
+            );
+            assert_noop!(
+                ZdReputation::bulk_refresh(&[(DAVE, 1), (ALICE, 1)]),
+                Error::<Test>::ReputationAlreadyUpdated
+            );
+            let irs = user_scores
+                .iter()
+                .map(|(who, _)| ZdReputation::get_ir(who))
+                .collect::<Vec<_>>();
+            (irs, ZdReputation::round_issuance())
+        })
+    };
+    assert_eq!(refresh_each(), refresh_bulk());
+}
+
+#[test]
+fn get_reputation_old_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(ZdReputation::get_reputation_old(&ALICE), None);
+        assert_ok!(ZdReputation::new_round());
+        assert_ok!(ZdReputation::refresh_reputation(&(ALICE, 18)));
+        assert_eq!(ZdReputation::get_reputation_old(&ALICE), None);
+        ZdReputation::set_free();
+
+        System::set_block_number(INIT_PERIOD + 1);
+        assert_ok!(ZdReputation::new_round());
+        assert_eq!(ZdReputation::get_round(), 2);
+        assert_eq!(ZdReputation::get_reputation_old(&ALICE), Some(18));
+        assert_ok!(ZdReputation::refresh_reputation(&(ALICE, 21)));
+        assert_eq!(ZdReputation::get_reputation_new(&ALICE), Some(21));
+        assert_eq!(ZdReputation::get_reputation_old(&ALICE), Some(18));
+        assert_eq!(ZdReputation::get_reputation(&ALICE), Some(18));
+    });
+}
+
+#[test]
+fn decay_rate_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ZdReputation::set_decay_rate(Origin::signed(ALICE), Perbill::from_percent(10)),
+            dispatch::DispatchError::BadOrigin
+        );
+        assert_ok!(ZdReputation::new_round());
+        assert_ok!(ZdReputation::refresh_reputation(&(ALICE, 1000)));
+        ZdReputation::set_step(&TIRStep::Free);
+        assert_eq!(ZdReputation::get_reputation(&ALICE), Some(1000));
+
+        System::set_block_number(20);
+        assert_ok!(ZdReputation::new_round());
+        ZdReputation::set_step(&TIRStep::Free);
+        assert_eq!(ZdReputation::get_reputation(&ALICE), None);
+
+        assert_ok!(ZdReputation::set_decay_rate(Origin::root(), Perbill::from_percent(10)));
+        assert_eq!(ZdReputation::decay_rate(), Perbill::from_percent(10));
+        assert_eq!(ZdReputation::get_reputation(&ALICE), Some(900));
+        System::set_block_number(40);
+        assert_ok!(ZdReputation::new_round());
+        assert_eq!(ZdReputation::get_reputation(&ALICE), Some(900));
+        ZdReputation::set_step(&TIRStep::Free);
+        assert_eq!(ZdReputation::get_reputation(&ALICE), Some(810));
+        assert_eq!(ZdReputation::get_reputation_new(&ALICE), None);
+        assert_eq!(ZdReputation::get_reputation(&2), None);
+
+        assert_eq!(ZdReputation::bulk_refresh(&[(ALICE, 810)]), Ok(vec![false]));
+        assert_eq!(ZdReputation::get_reputation(&ALICE), Some(810));
+    });
+}
+
+#[test]
+fn revert_reputation_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ZdReputation::new_round());
+        assert_noop!(
+            ZdReputation::revert_reputation(&ALICE),
+            Error::<Test>::ReputationNotUpdated
+        );
+        assert_ok!(ZdReputation::refresh_reputation(&(ALICE, 18)));
+        assert_ok!(ZdReputation::revert_reputation(&ALICE));
+        assert_eq!(ZdReputation::get_reputation_new(&ALICE), None);
+        assert_noop!(
+            ZdReputation::revert_reputation(&ALICE),
+            Error::<Test>::ReputationNotUpdated
+        );
+        assert_ok!(ZdReputation::refresh_reputation(&(ALICE, 21)));
+        assert_eq!(ZdReputation::get_reputation_new(&ALICE), Some(21));
+    });
+}
+
+#[test]
+fn reputation_history_should_work() {
+    new_test_ext().execute_with(|| {
+        for round in 1..=4u32 {
+            System::set_block_number(INIT_PERIOD * round as u64);
+            assert_ok!(ZdReputation::new_round());
+            assert_ok!(ZdReputation::refresh_reputation(&(ALICE, round * 10)));
+            if round < 4 {
+                ZdReputation::set_step(&TIRStep::Free);
+            }
+        }
+        assert_eq!(
+            ZdReputation::reputation_history(ALICE),
+            vec![(2, 20), (3, 30), (4, 40)]
+        );
+
+        ZdReputation::mutate_reputation(&ALICE, &45);
+        assert_eq!(ZdReputation::reputation_history(ALICE).last(), Some(&(4, 45)));
+        assert_ok!(ZdReputation::revert_reputation(&ALICE));
+        assert_eq!(ZdReputation::reputation_history(ALICE), vec![(2, 20), (3, 30)]);
+
+        assert_noop!(
+            ZdReputation::prune_history(Origin::signed(ALICE), ALICE),
+            Error::<Test>::NothingToPrune
+        );
+        ZdReputation::set_step(&TIRStep::Free);
+        System::set_block_number(50);
+        assert_ok!(ZdReputation::new_round());
+        assert_ok!(ZdReputation::prune_history(Origin::signed(ALICE), ALICE));
+        assert_eq!(ZdReputation::reputation_history(ALICE), vec![(3, 30)]);
+        let pruned_event = Event::zd_reputation(crate::Event::HistoryPruned(ALICE, 1));
+        assert!(System::events().iter().any(|record| record.event == pruned_event));
+
+        assert_ok!(ZdReputation::bulk_refresh(&[(ALICE, 50)]));
+        assert_eq!(ZdReputation::reputation_history(ALICE), vec![(3, 30), (5, 50)]);
+    });
+}
+
+#[test]
+fn reputation_at_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ZdReputation::new_round());
+        assert_ok!(ZdReputation::refresh_reputation(&(ALICE, 30)));
+        assert_eq!(ZdReputation::reputation_at(1, &ALICE), None);
+        ZdReputation::mutate_reputation(&ALICE, &35);
+        ZdReputation::set_free();
+        assert_eq!(ZdReputation::reputation_at(1, &ALICE), Some(35));
+
+        // Changes after the round closed leave its snapshot as it is.
+        ZdReputation::mutate_reputation(&ALICE, &10);
+        assert_eq!(ZdReputation::get_reputation(&ALICE), Some(10));
+        assert_eq!(ZdReputation::get_reputation_at(1, &ALICE), Some(35));
+        assert_ok!(ZdReputation::revert_reputation(&ALICE));
+        assert_eq!(ZdReputation::reputation_at(1, &ALICE), Some(35));
+
+        System::set_block_number(INIT_PERIOD + 1);
+        assert_ok!(ZdReputation::new_round());
+        assert_ok!(ZdReputation::refresh_reputation(&(ALICE, 60)));
+        assert_eq!(ZdReputation::reputation_at(1, &ALICE), Some(35));
+        assert_eq!(ZdReputation::reputation_at(2, &ALICE), None);
+        ZdReputation::set_free();
+        assert_eq!(ZdReputation::reputation_at(2, &ALICE), Some(60));
+        assert_eq!(ZdReputation::reputation_at(2, &2), None);
+    });
+}
+
+#[test]
+fn tiers_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ZdReputation::set_tier_thresholds(Origin::root(), vec![10, 10]),
+            Error::<Test>::InvalidTierThresholds
+        );
+        assert_noop!(
+            ZdReputation::set_tier_thresholds(Origin::root(), vec![1, 2, 3, 4, 5, 6]),
+            Error::<Test>::InvalidTierThresholds
+        );
+        assert_ok!(ZdReputation::set_tier_thresholds(Origin::root(), vec![10, 100, 1000]));
+        assert_eq!(ZdReputation::tier_of_score(9), 0);
+        assert_eq!(ZdReputation::tier_of_score(100), 2);
+        assert_eq!(ZdReputation::tier_of_score(u32::MAX), 3);
+
+        assert_ok!(ZdReputation::new_round());
+        assert_ok!(ZdReputation::refresh_reputation(&(ALICE, 150)));
+        // The refreshed score is not verified before the round closes.
+        assert_eq!(ZdReputation::tier_of(&ALICE), 0);
+        ZdReputation::set_free();
+        assert_eq!(ZdReputation::tier_of(&ALICE), 0);
+        ZdReputation::build_root(MAX_ROOT_LEAVES);
+        assert_eq!(ZdReputation::tier_of(&ALICE), 2);
+        let changed_event = Event::zd_reputation(crate::Event::TierChanged(ALICE, 0, 2));
+        assert!(System::events().iter().any(|record| record.event == changed_event));
+
+        ZdReputation::mutate_reputation(&ALICE, &1000);
+        assert_eq!(ZdReputation::tier_of(&ALICE), 3);
+        assert_ok!(ZdReputation::revert_reputation(&ALICE));
+        assert_eq!(ZdReputation::tier_of(&ALICE), 0);
+        assert!(!<Tiers<Test>>::contains_key(ALICE));
+        let changed_event = Event::zd_reputation(crate::Event::TierChanged(ALICE, 3, 0));
+        assert!(System::events().iter().any(|record| record.event == changed_event));
+    });
+}
+
+#[test]
+fn delegate_reputation_should_work() {
+    const BOB: AccountId = 2;
+    const CHARLIE: AccountId = 3;
+    const DAVE: AccountId = 4;
+    new_test_ext().execute_with(|| {
+        assert_ok!(ZdReputation::new_round());
+        assert_ok!(ZdReputation::bulk_refresh(&[(ALICE, 10), (BOB, 20), (CHARLIE, 30)]));
+        ZdReputation::set_free();
+
+        assert_noop!(
+            ZdReputation::delegate_reputation(Origin::signed(ALICE), ALICE),
+            Error::<Test>::SelfDelegation
+        );
+        assert_ok!(ZdReputation::delegate_reputation(Origin::signed(BOB), ALICE));
+        assert_ok!(ZdReputation::delegate_reputation(Origin::signed(CHARLIE), ALICE));
+        assert_eq!(ZdReputation::effective_reputation(&ALICE), 60);
+        assert_eq!(ZdReputation::effective_reputation(&BOB), 0);
+        assert_eq!(ZdReputation::get_reputation(&BOB), Some(20));
+        let delegated_event = Event::zd_reputation(crate::Event::ReputationDelegated(BOB, ALICE));
+        assert!(System::events().iter().any(|record| record.event == delegated_event));
+
+        assert_noop!(
+            ZdReputation::delegate_reputation(Origin::signed(BOB), CHARLIE),
+            Error::<Test>::AlreadyDelegated
+        );
+        assert_noop!(
+            ZdReputation::delegate_reputation(Origin::signed(DAVE), BOB),
+            Error::<Test>::DelegateHasDelegated
+        );
+        assert_noop!(
+            ZdReputation::delegate_reputation(Origin::signed(ALICE), DAVE),
+            Error::<Test>::HasDelegators
+        );
+        assert_noop!(
+            ZdReputation::delegate_reputation(Origin::signed(DAVE), ALICE),
+            Error::<Test>::TooManyDelegators
+        );
+
+        assert_ok!(ZdReputation::undelegate(Origin::signed(BOB)));
+        assert_eq!(ZdReputation::effective_reputation(&ALICE), 40);
+        assert_eq!(ZdReputation::effective_reputation(&BOB), 20);
+        assert_eq!(ZdReputation::delegator_count(&ALICE), 1);
+        assert_noop!(
+            ZdReputation::undelegate(Origin::signed(BOB)),
+            Error::<Test>::NotDelegated
+        );
+        assert_ok!(ZdReputation::undelegate(Origin::signed(CHARLIE)));
+        assert!(!<DelegatorCounts<Test>>::contains_key(ALICE));
+    });
+}
+
 #[test]
 fn last_refresh_at_should_work() {
     new_test_ext().execute_with(|| {
@@ -173,3 +454,435 @@ fn set_free_should_work() {
     });
 }
 
+
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Debug)]
+struct RejectPayment;
+
+impl SignedExtension for RejectPayment {
+    const IDENTIFIER: &'static str = "RejectPayment";
+    type AccountId = AccountId;
+    type Call = Call;
+    type AdditionalSigned = ();
+    type Pre = ();
+
+    fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        _who: &Self::AccountId,
+        _call: &Self::Call,
+        _info: &DispatchInfoOf<Self::Call>,
+        _len: usize,
+    ) -> TransactionValidity {
+        Err(InvalidTransaction::Payment.into())
+    }
+}
+
+#[test]
+fn tier_priority_should_work() {
+    const BOB: AccountId = 2;
+    let call = Call::ZdReputation(crate::Call::undelegate());
+    let info = DispatchInfo::default();
+    new_test_ext().execute_with(|| {
+        let valid = TierPriority::<Test>::new().validate(&ALICE, &call, &info, 0).unwrap();
+        assert_eq!(valid.priority, 0);
+
+        assert_ok!(ZdReputation::set_tier_thresholds(Origin::root(), vec![10, 100, 1000]));
+        assert_ok!(ZdReputation::new_round());
+        assert_ok!(ZdReputation::refresh_reputation(&(ALICE, 150)));
+        ZdReputation::set_free();
+        ZdReputation::build_root(MAX_ROOT_LEAVES);
+        let valid = TierPriority::<Test>::new().validate(&ALICE, &call, &info, 0).unwrap();
+        assert_eq!(valid.priority, 200);
+        assert_eq!(TierPriority::<Test>::boost(&BOB), 0);
+
+        <Tiers<Test>>::insert(BOB, u8::MAX);
+        assert_eq!(TierPriority::<Test>::boost(&BOB), 500);
+
+        assert_ok!(TierPriority::<Test>::new().pre_dispatch(&ALICE, &call, &info, 0));
+        assert_eq!(ZdReputation::tier_of(&ALICE), 2);
+    });
+}
+
+#[test]
+fn tier_priority_should_not_waive_fees() {
+    let call = Call::ZdReputation(crate::Call::undelegate());
+    let info = DispatchInfo::default();
+    new_test_ext().execute_with(|| {
+        <Tiers<Test>>::insert(ALICE, MAX_TIER);
+        let extra = (TierPriority::<Test>::new(), RejectPayment);
+        assert_eq!(
+            extra.validate(&ALICE, &call, &info, 0),
+            Err(InvalidTransaction::Payment.into())
+        );
+        assert_eq!(
+            extra.pre_dispatch(&ALICE, &call, &info, 0),
+            Err(InvalidTransaction::Payment.into())
+        );
+    });
+}
+
+#[test]
+fn genesis_reputations_should_work() {
+    const BOB: AccountId = 2;
+    let mut ext = ExtBuilder::default()
+        .reputations(vec![(ALICE, 50), (BOB, 70)])
+        .build();
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        assert_eq!(ZdReputation::get_reputation(&ALICE), Some(50));
+        assert_eq!(ZdReputation::get_reputation(&BOB), Some(70));
+        assert_eq!(ZdReputation::reputation_at(0, &ALICE), Some(50));
+
+        assert_ok!(ZdReputation::new_round());
+        assert_ok!(ZdReputation::refresh_reputation(&(ALICE, 80)));
+        assert_eq!(ZdReputation::get_reputation(&ALICE), Some(50));
+        assert_eq!(ZdReputation::get_reputation_new(&ALICE), Some(80));
+        assert_eq!(ZdReputation::reputation_history(ALICE), vec![(0, 50), (1, 80)]);
+    });
+}
+
+#[test]
+#[should_panic(expected = "duplicate account in genesis reputations")]
+fn genesis_reputations_should_fail() {
+    ExtBuilder::default()
+        .reputations(vec![(ALICE, 50), (ALICE, 70)])
+        .build();
+}
+
+#[test]
+fn freeze_reputation_should_work() {
+    const BOB: AccountId = 2;
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ZdReputation::freeze_reputation(Origin::signed(ALICE), BOB),
+            dispatch::DispatchError::BadOrigin
+        );
+        assert_ok!(ZdReputation::freeze_reputation(Origin::root(), BOB));
+        assert!(ZdReputation::is_frozen(&BOB));
+        let frozen_event = Event::zd_reputation(crate::Event::ReputationFrozen(BOB));
+        assert!(System::events().iter().any(|record| record.event == frozen_event));
+        assert_noop!(
+            ZdReputation::freeze_reputation(Origin::root(), BOB),
+            Error::<Test>::ReputationFrozen
+        );
+
+        assert_ok!(ZdReputation::new_round());
+        assert_noop!(
+            ZdReputation::refresh_reputation(&(BOB, 100)),
+            Error::<Test>::ReputationFrozen
+        );
+        assert_noop!(
+            ZdReputation::bulk_refresh(&[(ALICE, 50), (BOB, 100)]),
+            Error::<Test>::ReputationFrozen
+        );
+
+        assert_noop!(
+            ZdReputation::unfreeze_reputation(Origin::root(), ALICE),
+            Error::<Test>::NotFrozen
+        );
+        assert_ok!(ZdReputation::unfreeze_reputation(Origin::root(), BOB));
+        assert!(!ZdReputation::is_frozen(&BOB));
+        let unfrozen_event = Event::zd_reputation(crate::Event::ReputationUnfrozen(BOB));
+        assert!(System::events().iter().any(|record| record.event == unfrozen_event));
+        assert_ok!(ZdReputation::bulk_refresh(&[(ALICE, 50), (BOB, 100)]));
+    });
+}
+
+#[test]
+fn slash_reputation_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ZdReputation::slash_reputation(Origin::signed(ALICE), ALICE, 10),
+            dispatch::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            ZdReputation::slash_reputation(Origin::root(), ALICE, 10),
+            Error::<Test>::NoReputation
+        );
+        assert_ok!(ZdReputation::set_tier_thresholds(Origin::root(), vec![10, 100]));
+        assert_ok!(ZdReputation::new_round());
+        assert_ok!(ZdReputation::refresh_reputation(&(ALICE, 40)));
+        ZdReputation::set_free();
+        ZdReputation::build_root(MAX_ROOT_LEAVES);
+        System::set_block_number(20);
+        assert_ok!(ZdReputation::new_round());
+        assert_ok!(ZdReputation::refresh_reputation(&(ALICE, 150)));
+        assert_eq!(ZdReputation::tier_of(&ALICE), 1);
+
+        assert_ok!(ZdReputation::slash_reputation(Origin::root(), ALICE, 100));
+        assert_eq!(ZdReputation::get_reputation_new(&ALICE), Some(50));
+        assert_eq!(ZdReputation::get_reputation(&ALICE), Some(0));
+        // The tier follows the verified score, not the one refreshed in this round.
+        assert_eq!(ZdReputation::tier_of(&ALICE), 0);
+        assert_eq!(ZdReputation::reputation_history(ALICE), vec![(1, 40), (2, 50)]);
+        let slashed_event = Event::zd_reputation(crate::Event::ReputationSlashed(ALICE, 100));
+        assert!(System::events().iter().any(|record| record.event == slashed_event));
+    });
+}
+
+#[test]
+fn round_info_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ZdReputation::set_round_periods(Origin::signed(ALICE), 5, 3),
+            dispatch::DispatchError::BadOrigin
+        );
+        assert_ok!(ZdReputation::set_round_periods(Origin::root(), 5, 3));
+        assert_eq!(ZdReputation::refresh_period(), 5);
+        assert_eq!(ZdReputation::confirm_period(), 3);
+        let periods_event = Event::zd_reputation(crate::Event::UpdatedRoundPeriods(5, 3));
+        assert!(System::events().iter().any(|record| record.event == periods_event));
+
+        assert_ok!(ZdReputation::new_round());
+        assert_eq!(
+            ZdReputation::round_info(),
+            RoundInfo {
+                index: 1,
+                started_at: 1,
+                refresh_ends_at: 6,
+                confirm_ends_at: 9,
+            }
+        );
+        let started_event = Event::zd_reputation(crate::Event::RoundStarted(1, 1));
+        assert!(System::events().iter().any(|record| record.event == started_event));
+        assert_noop!(
+            ZdReputation::set_round_periods(Origin::root(), 10, 10),
+            Error::<Test>::UnableToSetPeriod
+        );
+
+        ZdReputation::set_free();
+        assert_eq!(ZdReputation::round_info().index, 1);
+    });
+}
+
+#[test]
+fn phase_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ZdReputation::set_round_periods(Origin::root(), 5, 3));
+        assert_eq!(ZdReputation::phase(), Phase::Free);
+
+        assert_ok!(ZdReputation::new_round());
+        assert_eq!(ZdReputation::phase(), Phase::Updating);
+        let updating_event =
+            Event::zd_reputation(crate::Event::RoundPhaseChanged(1, Phase::Updating));
+        assert!(System::events().iter().any(|record| record.event == updating_event));
+        ZdReputation::set_step(&TIRStep::Reputation);
+        assert_eq!(ZdReputation::phase(), Phase::Updating);
+
+        System::set_block_number(6);
+        ZdReputation::on_initialize(6);
+        assert_eq!(ZdReputation::phase(), Phase::Confirming);
+        let confirming_event =
+            Event::zd_reputation(crate::Event::RoundPhaseChanged(1, Phase::Confirming));
+        assert!(System::events().iter().any(|record| record.event == confirming_event));
+
+        System::set_block_number(9);
+        ZdReputation::on_initialize(9);
+        assert_eq!(ZdReputation::phase(), Phase::Settling);
+
+        ZdReputation::set_free();
+        assert_eq!(ZdReputation::phase(), Phase::Free);
+        let free_event = Event::zd_reputation(crate::Event::RoundPhaseChanged(1, Phase::Free));
+        assert!(System::events().iter().any(|record| record.event == free_event));
+    });
+}
+
+#[test]
+fn reputation_caps_should_work() {
+    const BOB: AccountId = 2;
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ZdReputation::set_reputation_caps(Origin::signed(ALICE), 100, 80),
+            dispatch::DispatchError::BadOrigin
+        );
+        assert_ok!(ZdReputation::set_reputation_caps(Origin::root(), 100, 80));
+        let caps_event = Event::zd_reputation(crate::Event::UpdatedReputationCaps(100, 80));
+        assert!(System::events().iter().any(|record| record.event == caps_event));
+
+        assert_ok!(ZdReputation::new_round());
+        assert_noop!(
+            ZdReputation::refresh_reputation(&(ALICE, 90)),
+            Error::<Test>::ScoreTooHigh
+        );
+        assert_ok!(ZdReputation::refresh_reputation(&(ALICE, 60)));
+        assert_eq!(ZdReputation::round_issuance(), 60);
+        assert_noop!(
+            ZdReputation::bulk_refresh(&[(BOB, 50)]),
+            Error::<Test>::RoundCapExceeded
+        );
+        assert_ok!(ZdReputation::bulk_refresh(&[(BOB, 40)]));
+        assert_eq!(ZdReputation::round_issuance(), 100);
+        let reached_event = Event::zd_reputation(crate::Event::RoundCapReached(1, 100));
+        assert!(System::events().iter().any(|record| record.event == reached_event));
+
+        assert_ok!(ZdReputation::revert_reputation(&BOB));
+        assert_eq!(ZdReputation::round_issuance(), 60);
+
+        ZdReputation::set_free();
+        System::set_block_number(20);
+        assert_ok!(ZdReputation::new_round());
+        assert_eq!(ZdReputation::round_issuance(), 0);
+        assert_ok!(ZdReputation::refresh_reputation(&(ALICE, 70)));
+        assert_eq!(ZdReputation::round_issuance(), 10);
+    });
+}
+
+#[test]
+fn domains_should_work() {
+    const BOB: AccountId = 2;
+    const FINANCE: DomainId = *b"finance\0";
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ZdReputation::add_domain(Origin::signed(ALICE), FINANCE),
+            dispatch::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            ZdReputation::add_domain(Origin::root(), DEFAULT_DOMAIN),
+            Error::<Test>::DomainExists
+        );
+        assert_ok!(ZdReputation::new_round());
+        assert_noop!(
+            ZdReputation::refresh_reputation_in(&FINANCE, &(ALICE, 30)),
+            Error::<Test>::UnknownDomain
+        );
+
+        assert_ok!(ZdReputation::add_domain(Origin::root(), FINANCE));
+        assert!(ZdReputation::is_domain(&FINANCE));
+        let domain_event = Event::zd_reputation(crate::Event::DomainAdded(FINANCE));
+        assert!(System::events().iter().any(|record| record.event == domain_event));
+        assert_noop!(
+            ZdReputation::add_domain(Origin::root(), FINANCE),
+            Error::<Test>::DomainExists
+        );
+
+        assert_ok!(ZdReputation::refresh_reputation_in(&FINANCE, &(ALICE, 30)));
+        assert_eq!(ZdReputation::bulk_refresh_in(&FINANCE, &[(BOB, 20)]), Ok(vec![true]));
+        assert_ok!(ZdReputation::refresh_reputation_in(&DEFAULT_DOMAIN, &(ALICE, 50)));
+        assert_eq!(ZdReputation::get_reputation_new_in(&FINANCE, &ALICE), Some(30));
+        assert_eq!(ZdReputation::get_reputation_new(&ALICE), Some(50));
+        assert_eq!(ZdReputation::get_reputation_new_in(&DEFAULT_DOMAIN, &BOB), None);
+        assert_noop!(
+            ZdReputation::bulk_refresh_in(&FINANCE, &[(ALICE, 40)]),
+            Error::<Test>::ReputationAlreadyUpdated
+        );
+        assert_eq!(ZdReputation::round_issuance(), 100);
+
+        assert_ok!(ZdReputation::revert_reputation_in(&FINANCE, &BOB));
+        assert_eq!(ZdReputation::get_reputation_new_in(&FINANCE, &BOB), None);
+        assert_eq!(ZdReputation::get_reputation_new(&ALICE), Some(50));
+        assert_eq!(ZdReputation::round_issuance(), 80);
+        assert_noop!(
+            ZdReputation::revert_reputation_in(&FINANCE, &BOB),
+            Error::<Test>::ReputationNotUpdated
+        );
+        assert_noop!(
+            ZdReputation::revert_reputation_in(b"unknown\0", &ALICE),
+            Error::<Test>::UnknownDomain
+        );
+        assert_ok!(ZdReputation::bulk_refresh_in(&FINANCE, &[(BOB, 20)]));
+
+        ZdReputation::set_free();
+        assert_eq!(ZdReputation::get_reputation_in(&FINANCE, &ALICE), Some(30));
+        assert_eq!(ZdReputation::get_reputation_in(&FINANCE, &BOB), Some(20));
+        assert_eq!(ZdReputation::get_reputation_in(&DEFAULT_DOMAIN, &ALICE), Some(50));
+    });
+}
+
+#[test]
+fn reputation_root_should_work() {
+    const BOB: AccountId = 2;
+    const CHARLIE: AccountId = 3;
+    new_test_ext().execute_with(|| {
+        assert_ok!(ZdReputation::new_round());
+        assert_ok!(ZdReputation::bulk_refresh(&[(CHARLIE, 30), (ALICE, 10), (BOB, 20)]));
+        assert_eq!(ZdReputation::reputation_root(1), None);
+        ZdReputation::set_free();
+        assert_eq!(ZdReputation::reputation_root(1), None);
+        ZdReputation::build_root(MAX_ROOT_LEAVES);
+
+        let pairs = ZdReputation::reputation_pairs();
+        let mut sorted = pairs.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec![(ALICE, 10), (BOB, 20), (CHARLIE, 30)]);
+        let root = ZdReputation::merkle_root(&pairs);
+        assert_eq!(ZdReputation::reputation_root(1), Some(root));
+        let root_event = Event::zd_reputation(crate::Event::ReputationRootPublished(1, root));
+        assert!(System::events().iter().any(|record| record.event == root_event));
+
+        for (index, (who, score)) in pairs.iter().enumerate() {
+            let proof = ZdReputation::merkle_proof(&pairs, index);
+            assert!(ZdReputation::verify_reputation(&root, who, *score, &proof));
+            assert!(!ZdReputation::verify_reputation(&root, who, score + 1, &proof));
+        }
+
+        for round in 2..=4 {
+            System::set_block_number(round as u64 * 20);
+            assert_ok!(ZdReputation::new_round());
+            ZdReputation::set_free();
+            ZdReputation::build_root(MAX_ROOT_LEAVES);
+            assert!(ZdReputation::reputation_root(round).is_some());
+        }
+        assert_eq!(ZdReputation::reputation_root(1), None);
+        assert!(ZdReputation::reputation_root(2).is_some());
+    });
+}
+
+#[test]
+fn reputation_root_should_be_built_incrementally() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ZdReputation::new_round());
+        let user_scores = (1..=7).map(|who| (who, who as u32 * 10)).collect::<Vec<_>>();
+        assert_ok!(ZdReputation::bulk_refresh(&user_scores));
+        ZdReputation::set_free();
+        assert_eq!(ZdReputation::root_builds().len(), 1);
+
+        for _ in 0..3 {
+            ZdReputation::build_root(2);
+            assert_eq!(ZdReputation::reputation_root(1), None);
+        }
+        // The refreshes of the next round do not change the leaves of the closed one.
+        System::set_block_number(20);
+        assert_ok!(ZdReputation::new_round());
+        assert_ok!(ZdReputation::refresh_reputation(&(1, 1)));
+        ZdReputation::build_root(2);
+        assert!(ZdReputation::root_builds().is_empty());
+
+        // Read during the next round, the pairs are still those verified in the closed one.
+        let pairs = ZdReputation::reputation_pairs();
+        assert_eq!(pairs.len(), 7);
+        assert!(pairs.contains(&(1, 10)));
+        assert_eq!(ZdReputation::reputation_root(1), Some(ZdReputation::merkle_root(&pairs)));
+    });
+}
+
+#[test]
+fn get_many_should_work() {
+    const BOB: AccountId = 2;
+    const CHARLIE: AccountId = 3;
+    new_test_ext().execute_with(|| {
+        assert_eq!(ZdReputation::get_many(&[]), vec![]);
+        assert_ok!(ZdReputation::new_round());
+        assert_ok!(ZdReputation::bulk_refresh(&[(ALICE, 100), (CHARLIE, 30)]));
+        ZdReputation::set_free();
+        assert_eq!(
+            ZdReputation::get_many(&[ALICE, BOB, CHARLIE, ALICE]),
+            vec![Some(100), None, Some(30), Some(100)]
+        );
+
+        assert_ok!(ZdReputation::set_decay_rate(Origin::root(), Perbill::from_percent(10)));
+        System::set_block_number(20);
+        assert_ok!(ZdReputation::new_round());
+        assert_ok!(ZdReputation::refresh_reputation(&(CHARLIE, 40)));
+        ZdReputation::set_free();
+        let accounts = [ALICE, BOB, CHARLIE];
+        let reputations = accounts
+            .iter()
+            .map(ZdReputation::get_reputation)
+            .collect::<Vec<_>>();
+        assert_eq!(reputations, vec![Some(90), None, Some(40)]);
+        assert_eq!(ZdReputation::get_many(&accounts), reputations);
+    });
+}
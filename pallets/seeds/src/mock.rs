@@ -56,10 +56,16 @@ parameter_types! {
 parameter_types! {
     pub const ConfirmationPeriod: BlockNumber = 120;
     pub const ChallengePerior: BlockNumber = 100;
+    pub const HistoryRounds: u32 = 3;
+    pub const MaxDelegators: u32 = 2;
 }
 
 impl zd_reputation::Config for Test {
     type Event = Event;
+    type HistoryRounds = HistoryRounds;
+    type MaxDelegators = MaxDelegators;
+    type PriorityPerTier = ();
+    type SlashOrigin = system::EnsureRoot<AccountId>;
 }
 
 impl zd_seeds::Config for Test {
@@ -110,6 +116,9 @@ impl ExtBuilder {
 
         zd_reputation::GenesisConfig::<Test> {
             period: self.period,
+            refresh_period: 0,
+            confirm_period: 0,
+            reputations: vec![],
         }
         .assimilate_storage(&mut t)
         .unwrap();
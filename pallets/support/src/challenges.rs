@@ -12,15 +12,61 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use sp_runtime::{DispatchError, DispatchResult};
-use zd_primitives::{ChallengeStatus, Metadata};
+use codec::Codec;
+use sp_runtime::{
+    traits::Member,
+    DispatchError, DispatchResult, Perbill, RuntimeDebug,
+};
+use sp_std::vec::Vec;
+use zd_primitives::{ChallengeState, ChallengeStatus, Metadata, Progress, SettlementOutcome};
+
+/// Why a `ChallengeBase` call failed, so that the application calling it can tell the usual
+/// failures apart and report them as its own errors.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum ChallengeError {
+    /// There is no such challenge.
+    NonExistent,
+    /// The challenge is not in a state that accepts the call.
+    WrongPhase,
+    /// The caller is not the one expected to act on the challenge.
+    NotYourTurn,
+    /// Any other failure, including those returned by `up`.
+    Other(DispatchError),
+}
+
+impl From<DispatchError> for ChallengeError {
+    fn from(error: DispatchError) -> Self {
+        ChallengeError::Other(error)
+    }
+}
 
 pub trait ChallengeBase<AccountId, AppId, Balance, BlockNumber> {
+    /// Data of the caller kept alongside each challenge, handed back to the `up` of
+    /// `reply`, `evidence`, `arbitral` and `resolve_appeal`.
+    type Extra: Codec + Member + Default;
+
+    /// The score a challenge disputes, as claimed by the challenger, recorded by the app
+    /// and ruled by `arbitral`, `settle` and the appeals. It is kept in `Metadata` and handed
+    /// to every `up`, it need not be unsigned nor an integer, a signed or fixed-point score
+    /// will do.
+    type Score: Codec + Member + Copy + Default;
+
+    /// Keep `extra` alongside the challenge against `target` under `app_id`, it is reset
+    /// when a new challenge is launched.
+    fn set_extra(
+        app_id: &AppId,
+        target: &AccountId,
+        extra: Self::Extra,
+    ) -> Result<(), ChallengeError>;
+
+    /// The `Extra` kept alongside the challenge against `target` under `app_id`.
+    fn get_extra(app_id: &AppId, target: &AccountId) -> Self::Extra;
+
     /// Directly modify the data of the challenge game.
     fn set_metadata(
         app_id: &AppId,
         target: &AccountId,
-        metadata: &Metadata<AccountId, BlockNumber>,
+        metadata: &Metadata<AccountId, BlockNumber, Self::Score>,
     );
 
     /// Whether the challenges under `app_id` are all settled.
@@ -32,17 +78,32 @@ pub trait ChallengeBase<AccountId, AppId, Balance, BlockNumber> {
     /// Set the status of the challenge against `target` under `app_id`.
     fn set_status(app_id: &AppId, target: &AccountId, status: &ChallengeStatus);
 
+    /// Returns the `challenger` of the challenge against `target` under `app_id` and the
+    /// share of its prize pool `pathfinder` would receive if it were harvested now.
+    fn outcome(app_id: &AppId, target: &AccountId) -> Option<(AccountId, Perbill)>;
+
     /// Launch a challenge against `target` under `app_id`, `metadata` is used to
     /// set the initial challenge status.
+    ///
+    /// `scores` is the `(claimed, recorded)` score of `target`, they must differ by at least
+    /// the `min_dispute_delta` of the app.
+    ///
+    /// `attachment` is an optional reference of the challenger to an off-chain proof, such
+    /// as an IPFS CID, of at most `MaxEvidenceLen` bytes.
     fn launch(
         app_id: &AppId,
         target: &AccountId,
-        metadata: &Metadata<AccountId, BlockNumber>,
-    ) -> DispatchResult;
+        metadata: &Metadata<AccountId, BlockNumber, Self::Score>,
+        scores: (Self::Score, Self::Score),
+        attachment: Option<Vec<u8>>,
+    ) -> Result<(), ChallengeError>;
 
     /// Continued uploading `count` data from the challenge against `target` under
     /// `app_id`, `who` is used to verify the original initiator.
     ///
+    /// At most `MaxNextSteps` data can be uploaded per call, the returned `Progress`
+    /// lets the caller resume from where it left off.
+    ///
     /// `up` passes three arguments to the caller.
     ///
     /// - `score` - The score currently recorded for this challenge.
@@ -57,21 +118,29 @@ pub trait ChallengeBase<AccountId, AppId, Balance, BlockNumber> {
         who: &AccountId,
         target: &AccountId,
         count: &u32,
-        up: impl FnMut(u64, u32, bool) -> Result<(u64, u32), DispatchError>,
-    ) -> DispatchResult;
+        up: impl FnMut(Self::Score, u32, bool) -> Result<(Self::Score, u32), DispatchError>,
+    ) -> Result<Progress, ChallengeError>;
 
     /// Challenge the data under `index`
-    fn examine(app_id: &AppId, who: &AccountId, target: &AccountId, index: u32) -> DispatchResult;
+    fn examine(
+        app_id: &AppId,
+        who: &AccountId,
+        target: &AccountId,
+        index: u32,
+    ) -> Result<(), ChallengeError>;
 
     /// In response to the `examine` query, you need to upload a total of
     /// `total` data. This upload `count` entries.
     ///
-    /// `up` passes three arguments to the caller.
+    /// `total` and `count` are bounded by `MaxReplyCount` and `MaxLegsPerReply`.
+    ///
+    /// `up` passes four arguments to the caller.
     ///
     /// - `is_all_done` - Whether the data has all been uploaded.
-    /// - `score` - The score currently recorded for this challenge.
     /// - `remark` - The current note information for this challenge makes
     /// it easy for the caller to record information about the challenge.
+    /// - `score` - The score currently recorded for this challenge.
+    /// - `extra` - The `Extra` kept alongside this challenge.
     ///
     /// Update the challenge only when `up` returns `Ok()`, and update `score`.
     fn reply(
@@ -80,16 +149,17 @@ pub trait ChallengeBase<AccountId, AppId, Balance, BlockNumber> {
         target: &AccountId,
         total: u32,
         count: u32,
-        up: impl Fn(bool, u32, u64) -> Result<u64, DispatchError>,
-    ) -> DispatchResult;
+        up: impl Fn(bool, u32, Self::Score, &Self::Extra) -> Result<Self::Score, DispatchError>,
+    ) -> Result<(), ChallengeError>;
 
     /// Submitting evidence
     ///
-    /// `up` passes two arguments to the caller.
+    /// `up` passes three arguments to the caller.
     ///
     /// - `remark` - The current note information for this challenge makes
     /// it easy for the caller to record information about the challenge.
     /// - `score` - Challenge the current recorded score.
+    /// - `extra` - The `Extra` kept alongside this challenge.
     ///
     /// Update the challenge only when `up` returns `Ok(needs_arbitration)`
     /// and enter the corresponding state according to its value.
@@ -97,22 +167,26 @@ pub trait ChallengeBase<AccountId, AppId, Balance, BlockNumber> {
     /// - `true` - A successful challenge will be initialised with `restart`.
     /// - `false` - The evidence is not strong enough and the challenge will
     /// go to arbitration.
+    ///
+    /// `attachment` is kept as in `launch`.
     fn evidence(
         app_id: &AppId,
         who: &AccountId,
         target: &AccountId,
-        up: impl Fn(u32, u64) -> Result<bool, DispatchError>,
-    ) -> Result<Option<u64>, DispatchError>;
+        attachment: Option<Vec<u8>>,
+        up: impl Fn(u32, Self::Score, &Self::Extra) -> Result<bool, DispatchError>,
+    ) -> Result<Option<Self::Score>, ChallengeError>;
 
     /// Arbitration of submitted data, this is generally used for data that
     /// cannot be computed directly on the chain but can be verified, for
     /// example **shortest path**.
     ///
-    /// `up` passes two arguments to the caller.
+    /// `up` passes three arguments to the caller.
     ///
     /// - `score` - The score currently recorded for this challenge.
     /// - `remark` - The current note information for this challenge makes
     /// it easy for the caller to record information about the challenge.
+    /// - `extra` - The `Extra` kept alongside this challenge.
     ///
     /// Update the challenge only when `up` returns `Ok(joint_benefits, restart, score)`
     /// and enter the corresponding state according to its value.
@@ -122,12 +196,30 @@ pub trait ChallengeBase<AccountId, AppId, Balance, BlockNumber> {
     /// - `restart` - Whether the challenge needs to be initialized, when the
     /// original `challenger` will accept the challenge.
     /// - `score` - Record the score to the challenge system.
+    ///
+    /// The score of the pathfinder is upheld if the challenge is not restarted and `score`
+    /// equals the recorded score, the ruling then goes against the challenger, and against
+    /// the pathfinder otherwise. A tie is inconclusive and resolved in favour of the
+    /// existing record, so it is upheld.
     fn arbitral(
         app_id: &AppId,
         who: &AccountId,
         target: &AccountId,
-        up: impl Fn(u64, u32) -> Result<(bool, bool, u64), DispatchError>,
-    ) -> DispatchResult;
+        up: impl Fn(
+            Self::Score,
+            u32,
+            &Self::Extra,
+        ) -> Result<(bool, bool, Self::Score), DispatchError>,
+    ) -> Result<(), ChallengeError>;
+
+    /// The progress of the challenge against `target` under `app_id`, `None` if there is no
+    /// such challenge.
+    fn state(app_id: &AppId, target: &AccountId) -> Option<ChallengeState>;
+
+    /// Whether `launch` would accept a new challenge against `target` under `app_id` at `now`:
+    /// no appeal is pending, any existing challenge is `Free` and its ruling, if any, can no
+    /// longer be appealed. Checks specific to the application are left to its caller.
+    fn is_challengeable(app_id: &AppId, target: &AccountId, now: &BlockNumber) -> bool;
 
     /// Receive the challenge benefits. Assigned according to `ChallengeStatus`,
     /// `is_all_done`, `joint_benefits`.
@@ -140,12 +232,22 @@ pub trait ChallengeBase<AccountId, AppId, Balance, BlockNumber> {
     ///
     /// In the `Arbitral` state, settlement is according to `joint_benefits`, if
     /// `true`, then `pathfinder` and `challenger`, otherwise all rewards go to
-    /// `pathfinder`.
+    /// `pathfinder`. The split between them and the treasury follows the reward curve of
+    /// `app_id`.
+    ///
+    /// What goes to `challenger` is shared with the backers of the challenge pro rata to
+    /// their stakes, the challenger counting for its deposit.
     fn harvest(
         who: &AccountId,
         app_id: &AppId,
         target: &AccountId,
-    ) -> Result<Option<u64>, DispatchError>;
+    ) -> Result<Option<Self::Score>, ChallengeError>;
+
+    /// `who` tries to harvest up to `max` challenges under `app_id` it took part in as
+    /// challenger or pathfinder, `max` may be capped by the implementation, skipping those that
+    /// cannot be harvested yet. Each is handed to `ChallengeHarvested` as if the application had
+    /// harvested it. Returns the number of challenges harvested.
+    fn harvest_many(who: &AccountId, app_id: &AppId, max: u32) -> Result<u32, ChallengeError>;
 
     /// Settle the current challenge. This is a low level operation.
     ///
@@ -156,11 +258,219 @@ pub trait ChallengeBase<AccountId, AppId, Balance, BlockNumber> {
 	///
 	/// When `restart` is `false`, modify `joint_benefits` and `score` in the
     /// challenge system.
+    ///
+    /// Returns how the prize pool ends up split between the two sides, before any sweeper
+    /// fee, which is also recorded until the challenge is harvested.
     fn settle(
         app_id: &AppId,
         target: &AccountId,
         joint_benefits: bool,
         restart: bool,
-        score: u64,
+        score: Self::Score,
+    ) -> Result<SettlementOutcome<Self::Score>, ChallengeError>;
+
+    /// `who` backs the open challenge against `target` under `app_id` by staking `amount`
+    /// alongside the challenger, sharing its rewards or losses. Neither the challenger nor the
+    /// pathfinder may back it, nor anyone once it has been ruled on.
+    fn back(
+        app_id: &AppId,
+        who: &AccountId,
+        target: &AccountId,
+        amount: Balance,
+    ) -> Result<(), ChallengeError>;
+
+    /// `who` cancels the challenge it launched against `target` under `app_id`, which is only
+    /// allowed while the pathfinder has yet to answer it, that is in the `Examine` state or
+    /// before all its data has been uploaded.
+    ///
+    /// The backers get their stakes back and `who` its deposit minus `CancellationFee`, the
+    /// rest of the prize pool goes to `pathfinder`.
+    fn cancel(app_id: &AppId, who: &AccountId, target: &AccountId) -> Result<(), ChallengeError>;
+
+    /// `who` appeals the last `arbitral` or `settle` of the challenge against `target` under
+    /// `app_id` by staking `AppealStakingAmount`. Only the side the ruling went against may
+    /// appeal, within `AppealPeriod` of the ruling, and the challenge cannot be harvested
+    /// until the appeal is resolved.
+    fn appeal(app_id: &AppId, who: &AccountId, target: &AccountId) -> Result<(), ChallengeError>;
+
+    /// Resolve the appeal of the challenge against `target` under `app_id`, returns whether
+    /// the ruling was overturned. Only allowed once `AppealVotingPeriod` has passed since the
+    /// appeal was made, the `Arbiters` not having reached `ArbitralThreshold` on it.
+    ///
+    /// `up` is called as in `arbitral` and the challenge settled with its result, which
+    /// overturns the ruling if it restarts the challenge or changes the score the ruling
+    /// recorded, whether or not the ruling upheld the score of the pathfinder.
+    /// The bond is returned to the appellant if the ruling is overturned, otherwise it is
+    /// added to the prize pool. The arbiters who voted for the resolved score get their
+    /// stakes back, the others forfeit them.
+    fn resolve_appeal(
+        app_id: &AppId,
+        target: &AccountId,
+        up: impl Fn(
+            Self::Score,
+            u32,
+            &Self::Extra,
+        ) -> Result<(bool, bool, Self::Score), DispatchError>,
+    ) -> Result<bool, ChallengeError>;
+
+    /// `arbiter`, one of the `Arbiters`, votes for `score` on the appeal of the challenge
+    /// against `target` under `app_id`, staking `ArbiterStakingAmount`.
+    ///
+    /// Once `ArbitralThreshold` arbiters have voted for `score`, the appeal is resolved with
+    /// `up` as by `resolve_appeal` and whether the ruling was overturned is returned, `None`
+    /// while the matching votes fall short.
+    fn vote_appeal(
+        app_id: &AppId,
+        arbiter: &AccountId,
+        target: &AccountId,
+        score: Self::Score,
+        up: impl Fn(
+            Self::Score,
+            u32,
+            &Self::Extra,
+        ) -> Result<(bool, bool, Self::Score), DispatchError>,
+    ) -> Result<Option<bool>, ChallengeError>;
+}
+
+/// Notified when the challenge system harvests a challenge by itself, such as a timed out
+/// challenge settled in `on_initialize`, so that the application that launched it can do
+/// what it does after its own `harvest`.
+pub trait ChallengeHarvested<AccountId, AppId, Score> {
+    /// The challenge against `target` under `app_id` was harvested, `challenger` and `share`
+    /// are as returned by `ChallengeBase::outcome` and `score` by `ChallengeBase::harvest`.
+    fn on_harvested(
+        app_id: &AppId,
+        target: &AccountId,
+        challenger: &AccountId,
+        share: Perbill,
+        score: Option<Score>,
     ) -> DispatchResult;
 }
+
+impl<AccountId, AppId, Score> ChallengeHarvested<AccountId, AppId, Score> for () {
+    fn on_harvested(
+        _app_id: &AppId,
+        _target: &AccountId,
+        _challenger: &AccountId,
+        _share: Perbill,
+        _score: Option<Score>,
+    ) -> DispatchResult {
+        Ok(())
+    }
+}
+
+impl<AccountId, AppId, Score, A, B> ChallengeHarvested<AccountId, AppId, Score> for (A, B)
+where
+    Score: Copy,
+    A: ChallengeHarvested<AccountId, AppId, Score>,
+    B: ChallengeHarvested<AccountId, AppId, Score>,
+{
+    fn on_harvested(
+        app_id: &AppId,
+        target: &AccountId,
+        challenger: &AccountId,
+        share: Perbill,
+        score: Option<Score>,
+    ) -> DispatchResult {
+        A::on_harvested(app_id, target, challenger, share, score)?;
+        B::on_harvested(app_id, target, challenger, share, score)
+    }
+}
+
+/// Notified of each step of a challenge, so that the application that launched it can act
+/// as it goes, for example restore or slash what the pathfinder staked.
+pub trait OnChallengeTransition<AccountId, AppId> {
+    /// `challenger` launched a challenge against `target` under `app_id`.
+    fn on_launched(
+        _app_id: &AppId,
+        _target: &AccountId,
+        _challenger: &AccountId,
+    ) -> DispatchResult {
+        Ok(())
+    }
+
+    /// `pathfinder` replied to the examine of the challenge against `target` under `app_id`.
+    fn on_replied(_app_id: &AppId, _target: &AccountId, _pathfinder: &AccountId) -> DispatchResult {
+        Ok(())
+    }
+
+    /// `challenger` presented evidence against `target` under `app_id`, `restart` if it
+    /// proved the pathfinder wrong, otherwise the challenge goes to arbitration.
+    fn on_evidence(
+        _app_id: &AppId,
+        _target: &AccountId,
+        _challenger: &AccountId,
+        _restart: bool,
+    ) -> DispatchResult {
+        Ok(())
+    }
+
+    /// The challenge against `target` under `app_id` was arbitrated, `upheld` if the score
+    /// of the pathfinder stands and the ruling went against the challenger.
+    fn on_arbitrated(_app_id: &AppId, _target: &AccountId, _upheld: bool) -> DispatchResult {
+        Ok(())
+    }
+
+    /// The challenge against `target` under `app_id` was settled by its application,
+    /// `upheld` as in `on_arbitrated`.
+    fn on_settled(_app_id: &AppId, _target: &AccountId, _upheld: bool) -> DispatchResult {
+        Ok(())
+    }
+
+    /// The challenge against `target` under `app_id` was harvested, `pathfinder_share` of its
+    /// prize pool went to the pathfinder.
+    fn on_harvested(
+        _app_id: &AppId,
+        _target: &AccountId,
+        _pathfinder_share: Perbill,
+    ) -> DispatchResult {
+        Ok(())
+    }
+}
+
+impl<AccountId, AppId> OnChallengeTransition<AccountId, AppId> for () {}
+
+impl<AccountId, AppId, A, B> OnChallengeTransition<AccountId, AppId> for (A, B)
+where
+    A: OnChallengeTransition<AccountId, AppId>,
+    B: OnChallengeTransition<AccountId, AppId>,
+{
+    fn on_launched(app_id: &AppId, target: &AccountId, challenger: &AccountId) -> DispatchResult {
+        A::on_launched(app_id, target, challenger)?;
+        B::on_launched(app_id, target, challenger)
+    }
+
+    fn on_replied(app_id: &AppId, target: &AccountId, pathfinder: &AccountId) -> DispatchResult {
+        A::on_replied(app_id, target, pathfinder)?;
+        B::on_replied(app_id, target, pathfinder)
+    }
+
+    fn on_evidence(
+        app_id: &AppId,
+        target: &AccountId,
+        challenger: &AccountId,
+        restart: bool,
+    ) -> DispatchResult {
+        A::on_evidence(app_id, target, challenger, restart)?;
+        B::on_evidence(app_id, target, challenger, restart)
+    }
+
+    fn on_arbitrated(app_id: &AppId, target: &AccountId, upheld: bool) -> DispatchResult {
+        A::on_arbitrated(app_id, target, upheld)?;
+        B::on_arbitrated(app_id, target, upheld)
+    }
+
+    fn on_settled(app_id: &AppId, target: &AccountId, upheld: bool) -> DispatchResult {
+        A::on_settled(app_id, target, upheld)?;
+        B::on_settled(app_id, target, upheld)
+    }
+
+    fn on_harvested(
+        app_id: &AppId,
+        target: &AccountId,
+        pathfinder_share: Perbill,
+    ) -> DispatchResult {
+        A::on_harvested(app_id, target, pathfinder_share)?;
+        B::on_harvested(app_id, target, pathfinder_share)
+    }
+}
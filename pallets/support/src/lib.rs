@@ -13,14 +13,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-pub use reputation::Reputation;
+pub use reputation::{Reputation, ReputationTier};
 pub use trust::TrustBase;
 pub use seeds::SeedsBase;
-pub use challenges::ChallengeBase;
-pub use token::MultiBaseToken;
+pub use challenges::{ChallengeBase, ChallengeError, ChallengeHarvested, OnChallengeTransition};
+pub use token::{MultiBaseToken, MultiCurrencyStaking};
+pub use pathfinder::PathfinderBase;
 
 pub mod reputation;
 pub mod trust;
 pub mod seeds;
 pub mod challenges;
 pub mod token;
+pub mod pathfinder;
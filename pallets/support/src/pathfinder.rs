@@ -0,0 +1,30 @@
+// Copyright 2021 ZeroDAO
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sp_runtime::DispatchError;
+
+pub trait PathfinderBase<AccountId, Balance> {
+    /// Whether `who` is a registered pathfinder that is not unbonding.
+    fn is_pathfinder(who: &AccountId) -> bool;
+
+    /// Returns the bond of `who`, zero if it is not registered.
+    fn bond_of(who: &AccountId) -> Balance;
+
+    /// Slash up to `amount` of the bond of `who` to `to`, returning the amount slashed.
+    fn slash_bond(
+        who: &AccountId,
+        amount: Balance,
+        to: &AccountId,
+    ) -> Result<Balance, DispatchError>;
+}
@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use sp_runtime::DispatchResult;
+use sp_runtime::{DispatchError, DispatchResult};
+use sp_std::vec::Vec;
+use zd_primitives::{DomainId, Phase, RoundInfo};
 
 pub trait Reputation<AccountId, BlockNumber, TIRStep> {
     /// The first nonce in `target` has a reputation value that is modified.
@@ -29,13 +31,67 @@ pub trait Reputation<AccountId, BlockNumber, TIRStep> {
     /// reputation value.
     fn get_reputation_new(target: &AccountId) -> Option<u32>;
 
-    /// Returns the latest verified reputation value of `target`.
+    /// Returns the reputation value of `target` refreshed in the round before the current
+    /// one, if any.
+    fn get_reputation_old(target: &AccountId) -> Option<u32>;
+
+    /// Returns the latest verified reputation value of `target`, decayed for the rounds it
+    /// was not refreshed in if the implementation decays reputations.
     fn get_reputation(target: &AccountId) -> Option<u32>;
 
+    /// Returns `get_reputation` of each of `accounts`, in the same order, reading the state of
+    /// the system once for all of them.
+    fn get_many(accounts: &[AccountId]) -> Vec<Option<u32>>;
+
+    /// Returns the reputation value `target` had when `round` closed, which later refreshes
+    /// and challenges do not change, `None` for a round still open or no longer retained.
+    fn get_reputation_at(round: u32, target: &AccountId) -> Option<u32>;
+
     /// Accepts a tuple of `AccountId`, `u32` and performs a refresh only if the
     /// user is not refreshed.
     fn refresh_reputation(user_score: &(AccountId, u32)) -> DispatchResult;
 
+    /// Refresh all of `user_scores` in one pass, with the same rules as
+    /// `refresh_reputation`, returning for each entry whether the score has changed.
+    fn bulk_refresh(user_scores: &[(AccountId, u32)]) -> Result<Vec<bool>, DispatchError>;
+
+    /// `get_reputation` in `domain`, the same for `DEFAULT_DOMAIN`.
+    fn get_reputation_in(domain: &DomainId, target: &AccountId) -> Option<u32>;
+
+    /// `get_reputation_new` in `domain`, the same for `DEFAULT_DOMAIN`.
+    fn get_reputation_new_in(domain: &DomainId, target: &AccountId) -> Option<u32>;
+
+    /// `refresh_reputation` in `domain`, the same for `DEFAULT_DOMAIN`.
+    fn refresh_reputation_in(domain: &DomainId, user_score: &(AccountId, u32)) -> DispatchResult;
+
+    /// `bulk_refresh` in `domain`, the same for `DEFAULT_DOMAIN`, so that each domain is
+    /// refreshed in its own batches.
+    fn bulk_refresh_in(
+        domain: &DomainId,
+        user_scores: &[(AccountId, u32)],
+    ) -> Result<Vec<bool>, DispatchError>;
+
+    /// Undo the refresh of `who` in the current round and restore the previous
+    /// reputation value.
+    fn revert_reputation(who: &AccountId) -> DispatchResult;
+
+    /// `revert_reputation` in `domain`, the same for `DEFAULT_DOMAIN`.
+    fn revert_reputation_in(domain: &DomainId, who: &AccountId) -> DispatchResult;
+
+    /// Returns the `nonce` of the current round.
+    fn get_round() -> u32;
+
+    /// Returns the timing of the current round, fixed when it started and when its
+    /// refreshes opened.
+    fn round_info() -> RoundInfo<BlockNumber>;
+
+    /// Returns the phase of the current round.
+    fn phase() -> Phase;
+
+    /// Whether the reputation of `who` is frozen, in which case it is not refreshed, not
+    /// shared with and cannot be a seed candidate.
+    fn is_frozen(who: &AccountId) -> bool;
+
     /// Return to the system level for the last update block.
     fn get_last_refresh_at() -> BlockNumber;
 
@@ -47,4 +103,15 @@ pub trait Reputation<AccountId, BlockNumber, TIRStep> {
 
     /// Start a new round.
     fn new_round() -> DispatchResult;
+
+    /// Restart the refresh and confirmation phases of the current round at the current
+    /// block, once its refreshes open.
+    fn open_refresh();
+}
+
+/// The tier of the reputation of an account, for features gated on a level rather than
+/// the raw score.
+pub trait ReputationTier<AccountId> {
+    /// The tier of the verified reputation of `who`, 0 below the lowest threshold.
+    fn tier_of(who: &AccountId) -> u8;
 }
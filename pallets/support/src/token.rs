@@ -12,12 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use sp_runtime::DispatchResult;
+use sp_runtime::{DispatchError, DispatchResult, Perbill};
 
 pub trait MultiBaseToken<AccountId, Balance> {
     /// Get the current `bonus` amount.
     fn get_bonus_amount() -> Balance;
 
+    /// Get the total amount currently held in staking.
+    fn get_total_staking() -> Balance;
+
     /// `who` staking `amount`。
     fn staking(who: &AccountId, amount: &Balance) -> DispatchResult;
 
@@ -31,7 +34,16 @@ pub trait MultiBaseToken<AccountId, Balance> {
     fn social_balance(who: &AccountId) -> Balance;
 
     /// Split `who`s social currency proportionally and return the fee amount.
-    fn share(who: &AccountId, target: &[AccountId]) -> Balance;
+    ///
+    /// Nothing is changed if the burned part cannot be withdrawn from the social pool.
+    fn share(who: &AccountId, target: &[AccountId]) -> Result<Balance, DispatchError>;
+
+    /// Same as `share`, but the shared part is split between `targets` in proportion
+    /// to their weights.
+    fn share_with_weights(
+        who: &AccountId,
+        targets: &[(AccountId, u32)],
+    ) -> Result<Balance, DispatchError>;
 
     /// `who` injects `bonus` in the amount of `amount` into the pool, prioritising 
     /// the deduction of `pending` from `who`.
@@ -58,4 +70,23 @@ pub trait MultiBaseToken<AccountId, Balance> {
 
     /// Take the `pending` out of `who`.
     fn claim(who: &AccountId) -> DispatchResult;
+
+    /// Returns the `(share, reserved, fee)` ratios used to split social currency.
+    fn social_ratios() -> (Perbill, Perbill, Perbill);
+
+    /// Set the `share`, `reserved` and `fee` ratios used to split social currency.
+    ///
+    /// Fails if they exceed 100% together with the burned part.
+    fn set_social_ratios(share: Perbill, reserved: Perbill, fee: Perbill) -> DispatchResult;
+}
+
+pub trait MultiCurrencyStaking<AccountId, CurrencyId, Balance> {
+    /// Get the total amount of `currency` currently held in staking.
+    fn get_total_staking_in(currency: CurrencyId) -> Balance;
+
+    /// `who` staking `amount` of `currency`.
+    fn staking_in(currency: CurrencyId, who: &AccountId, amount: &Balance) -> DispatchResult;
+
+    /// Release the staked `currency` of `amount` to the account of `who`.
+    fn release_in(currency: CurrencyId, who: &AccountId, amount: &Balance) -> DispatchResult;
 }
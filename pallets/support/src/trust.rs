@@ -31,12 +31,19 @@ pub trait TrustBase<AccountId> {
     /// Returns whether `who` trusts `target` before the refresh started.
     fn is_trust_old(who: &AccountId, target: &AccountId) -> bool;
 
+    /// Returns the strength of the trust from `who` to `target`, `1` unless it has been set.
+    fn trust_strength(who: &AccountId, target: &AccountId) -> u32;
+
+    /// Returns the strength of the trust from `who` to `target` before the refresh started.
+    fn trust_strength_old(who: &AccountId, target: &AccountId) -> u32;
+
     /// Returns the user trusted by `who` before the refresh started.
     fn get_trust_old(who: &AccountId) -> Vec<AccountId>;
 
     /// Returns the total length of the `users` path as a tuple, which is passed 
     /// to the end user's reputation value, the first user is not the seed user, 
-    /// or an error in the path will return `Error`.
+    /// or an error in the path will return `Error`. The score passed is on the
+    /// score curve set by governance.
     fn computed_path(users: &[AccountId]) -> Result<(u32, u32), DispatchError>;
 
     /// `Ok` will be returned if the path is correct.
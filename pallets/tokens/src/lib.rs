@@ -40,12 +40,15 @@ use frame_support::{pallet_prelude::*, transactional};
 use frame_system::{ensure_signed, pallet_prelude::*};
 use sp_runtime::{
     traits::{MaybeSerializeDeserialize, Member, Saturating, StaticLookup, Zero},
-    DispatchResult,
+    DispatchResult, Perbill,
+};
+use sp_std::{
+    convert::{TryFrom, TryInto},
+    vec::Vec,
 };
-use sp_std::convert::{TryFrom, TryInto};
 
 use zd_primitives::{per_social_currency, Balance};
-use zd_support::MultiBaseToken;
+use zd_support::{MultiBaseToken, MultiCurrencyStaking};
 
 use orml_traits::{
     arithmetic::{self, Signed},
@@ -77,6 +80,40 @@ pub struct SocialAccount<Balance> {
     pub social: Balance,
 }
 
+/// The adjustable proportions in which a social balance is split. `PRE_BURN` is fixed and
+/// the remainder goes to the bonus.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct SocialRatios {
+    /// Transfer to the social currency of users trusted by the owner.
+    pub share: Perbill,
+    /// Reserve the owner's free balance.
+    pub reserved: Perbill,
+    /// Pathfinder's fee.
+    pub fee: Perbill,
+}
+
+impl Default for SocialRatios {
+    fn default() -> Self {
+        SocialRatios {
+            share: per_social_currency::PRE_SHARE,
+            reserved: per_social_currency::PRE_RESERVED,
+            fee: per_social_currency::PRE_FEE,
+        }
+    }
+}
+
+impl SocialRatios {
+    /// Whether the ratios, together with `PRE_BURN`, do not exceed 100%.
+    pub fn is_valid(&self) -> bool {
+        let burn = per_social_currency::PRE_BURN;
+        let total = [self.share, self.reserved, self.fee, burn]
+            .iter()
+            .map(|ratio| ratio.deconstruct() as u64)
+            .sum::<u64>();
+        total <= Perbill::one().deconstruct() as u64
+    }
+}
+
 impl<Balance: Saturating + Copy + Ord> SocialAccount<Balance> {
     /// The total balance in this account ignoring any frozen.
     fn total(&self) -> Balance {
@@ -120,6 +157,10 @@ pub mod module {
         #[pallet::constant]
         type SocialPool: Get<Self::AccountId>;
 
+        /// The proportion of the refresh fee that is returned to the refreshed user.
+        #[pallet::constant]
+        type UserRebateRatio: Get<Perbill>;
+
         /// Weight information for extrinsics in this module.
         type WeightInfo: WeightInfo;
     }
@@ -131,6 +172,8 @@ pub mod module {
         BonusTooLow,
         /// Total staking amount too low
         StakingAmountTooLow,
+        /// The social currency ratios exceed 100%
+        InvalidRatios,
     }
 
     #[pallet::event]
@@ -140,6 +183,8 @@ pub mod module {
         TransferSocial(T::AccountId, T::AccountId, Balance),
         /// Transferr `pending` Tokens to `free` \[who\]
         Claim(T::AccountId),
+        /// Part of the fee is returned to the shared user \[who, fee, rebate\]
+        Rebate(T::AccountId, Balance, Balance),
     }
 
     #[pallet::pallet]
@@ -161,6 +206,17 @@ pub mod module {
     #[pallet::getter(fn total_staking)]
     pub type TotalStaking<T: Config> = StorageValue<_, Balance, ValueQuery>;
 
+    /// The total amount held in staking of each currency other than `BaceToken`.
+    #[pallet::storage]
+    #[pallet::getter(fn total_staking_in)]
+    pub type TotalStakingIn<T: Config> =
+        StorageMap<_, Twox64Concat, T::CurrencyId, Balance, ValueQuery>;
+
+    /// The ratios currently used to split social currency.
+    #[pallet::storage]
+    #[pallet::getter(fn ratios)]
+    pub type Ratios<T: Config> = StorageValue<_, SocialRatios, ValueQuery>;
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Transfer some balance to another social-currency account
@@ -204,27 +260,34 @@ impl<T: Config> Pallet<T> {
         });
     }
 
+    /// Each trustee receives `total_share_amount / max(total_weight, MIN_TRUST_COUNT)` per
+    /// unit of weight, the remainder stays with `from`.
     pub(crate) fn share_and_reserv(
         from: &T::AccountId,
-        trustees: &[T::AccountId],
+        trustees: &[(T::AccountId, u32)],
         total_share_amount: Balance,
         reserved_amount: Balance,
     ) {
         let mut remaining_share: Balance = Zero::zero();
-        if !trustees.is_empty() && total_share_amount != 0 {
+        let total_weight = trustees
+            .iter()
+            .fold(0u32, |acc, (_, weight)| acc.saturating_add(*weight));
+        if total_weight != 0 && total_share_amount != 0 {
             if let Some(share_amount) = total_share_amount.checked_div(
-                (trustees.len() as u32)
+                total_weight
                     .max(per_social_currency::MIN_TRUST_COUNT)
                     .into(),
             ) {
-                trustees.iter().for_each(|trustee| {
+                trustees.iter().for_each(|(trustee, weight)| {
                     <Accounts<T>>::mutate(trustee, |account| {
-                        account.social = account.social.saturating_add(share_amount);
+                        account.social = account
+                            .social
+                            .saturating_add(share_amount.saturating_mul((*weight).into()));
                     });
                 });
 
                 remaining_share = total_share_amount
-                    .saturating_sub(share_amount.saturating_mul(trustees.len() as Balance));
+                    .saturating_sub(share_amount.saturating_mul(total_weight.into()));
             } else {
                 remaining_share = total_share_amount;
             }
@@ -280,6 +343,10 @@ impl<T: Config> MultiBaseToken<T::AccountId, Balance> for Pallet<T> {
         Self::get_bonus()
     }
 
+    fn get_total_staking() -> Balance {
+        Self::total_staking()
+    }
+
     fn actual_balance(who: &T::AccountId) -> Balance {
         let free_balance = T::Currency::free_balance(T::BaceToken::get(), who);
         free_balance.saturating_add(Self::accounts(who).total())
@@ -344,13 +411,25 @@ impl<T: Config> MultiBaseToken<T::AccountId, Balance> for Pallet<T> {
         T::Currency::free_balance(T::BaceToken::get(), who)
     }
 
-    fn share(who: &T::AccountId, targets: &[T::AccountId]) -> Balance {
+    fn share(who: &T::AccountId, targets: &[T::AccountId]) -> Result<Balance, DispatchError> {
+        let targets = targets
+            .iter()
+            .map(|target| (target.clone(), 1u32))
+            .collect::<Vec<_>>();
+        Self::share_with_weights(who, &targets[..])
+    }
+
+    fn share_with_weights(
+        who: &T::AccountId,
+        targets: &[(T::AccountId, u32)],
+    ) -> Result<Balance, DispatchError> {
         let social_balance = Self::social_balance(who);
+        let ratios = Self::ratios();
 
-        let total_share_amount = per_social_currency::PRE_SHARE.mul_floor(social_balance);
-        let reserved_amount = per_social_currency::PRE_RESERVED.mul_floor(social_balance);
+        let total_share_amount = ratios.share.mul_floor(social_balance);
+        let reserved_amount = ratios.reserved.mul_floor(social_balance);
         let burn_amount = per_social_currency::PRE_BURN.mul_floor(social_balance);
-        let fee_amount = per_social_currency::PRE_FEE.mul_floor(social_balance);
+        let fee_amount = ratios.fee.mul_floor(social_balance);
 
         let pre_reward = social_balance
             .saturating_sub(total_share_amount)
@@ -358,12 +437,25 @@ impl<T: Config> MultiBaseToken<T::AccountId, Balance> for Pallet<T> {
             .saturating_sub(burn_amount)
             .saturating_sub(fee_amount);
 
-        let _ = T::Currency::slash(T::BaceToken::get(), &T::SocialPool::get(), burn_amount);
+        // The rebate is carved out of the fee, so the ratios still add up to `social_balance`.
+        let rebate_amount = T::UserRebateRatio::get().mul_floor(fee_amount);
+        let fee_amount = fee_amount.saturating_sub(rebate_amount);
+
+        // The only fallible step comes first, so a failed share leaves no trace.
+        T::Currency::withdraw(T::BaceToken::get(), &T::SocialPool::get(), burn_amount)?;
 
-        Self::share_and_reserv(who, targets, total_share_amount, reserved_amount);
+        Self::share_and_reserv(
+            who,
+            targets,
+            total_share_amount,
+            reserved_amount.saturating_add(rebate_amount),
+        );
         Self::do_staking(&fee_amount);
         Self::add_bonus(&pre_reward);
-        fee_amount
+        if !rebate_amount.is_zero() {
+            Self::deposit_event(Event::Rebate(who.clone(), fee_amount, rebate_amount));
+        }
+        Ok(fee_amount)
     }
 
     #[transactional]
@@ -388,4 +480,46 @@ impl<T: Config> MultiBaseToken<T::AccountId, Balance> for Pallet<T> {
             Ok(())
         })
     }
+
+    fn social_ratios() -> (Perbill, Perbill, Perbill) {
+        let ratios = Self::ratios();
+        (ratios.share, ratios.reserved, ratios.fee)
+    }
+
+    fn set_social_ratios(share: Perbill, reserved: Perbill, fee: Perbill) -> DispatchResult {
+        let ratios = SocialRatios {
+            share,
+            reserved,
+            fee,
+        };
+        ensure!(ratios.is_valid(), Error::<T>::InvalidRatios);
+        <Ratios<T>>::put(ratios);
+        Ok(())
+    }
+}
+
+impl<T: Config> MultiCurrencyStaking<T::AccountId, T::CurrencyId, Balance> for Pallet<T> {
+    fn get_total_staking_in(currency: T::CurrencyId) -> Balance {
+        Self::total_staking_in(currency)
+    }
+
+    #[transactional]
+    fn staking_in(currency: T::CurrencyId, who: &T::AccountId, amount: &Balance) -> DispatchResult {
+        let total_staking = Self::total_staking_in(currency)
+            .checked_add(*amount)
+            .ok_or(Error::<T>::Overflow)?;
+        T::Currency::transfer(currency, who, &T::SocialPool::get(), *amount)?;
+        <TotalStakingIn<T>>::insert(currency, total_staking);
+        Ok(())
+    }
+
+    #[transactional]
+    fn release_in(currency: T::CurrencyId, who: &T::AccountId, amount: &Balance) -> DispatchResult {
+        let total_staking = Self::total_staking_in(currency)
+            .checked_sub(*amount)
+            .ok_or(Error::<T>::StakingAmountTooLow)?;
+        T::Currency::transfer(currency, &T::SocialPool::get(), who, *amount)?;
+        <TotalStakingIn<T>>::insert(currency, total_staking);
+        Ok(())
+    }
 }
@@ -19,6 +19,7 @@ use frame_support::{construct_runtime, parameter_types, traits::GenesisBuild};
 use frame_support::sp_runtime::{
     testing::Header,
     traits::{BlakeTwo256, IdentityLookup, Zero},
+    Perbill,
 };
 use frame_system as system;
 use sp_core::H256;
@@ -136,6 +137,7 @@ impl pallet_balances::Config for Test {
 
 parameter_types! {
     pub const SocialPoolAccountId: AccountId = 10000;
+    pub storage UserRebateRatio: Perbill = Perbill::zero();
 }
 
 impl zd_tokens::Config for Test {
@@ -146,6 +148,7 @@ impl zd_tokens::Config for Test {
     type SocialPool = SocialPoolAccountId;
     type Amount = Amount;
     type BaceToken = BaceToken;
+    type UserRebateRatio = UserRebateRatio;
 }
 
 pub struct ExtBuilder {
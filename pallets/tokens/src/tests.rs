@@ -120,6 +120,37 @@ fn release_test() {
     });
 }
 
+#[test]
+fn staking_in_test() {
+    new_test_ext().execute_with(|| {
+        let old_balance = <Currencies as MultiCurrency<_>>::free_balance(DOT, &ALICE);
+        assert_ok!(ZdToken::staking_in(DOT, &ALICE, &100));
+        assert_eq!(ZdToken::total_staking_in(DOT), 100);
+        assert_eq!(ZdToken::total_staking(), 0);
+        assert_eq!(
+            <Currencies as MultiCurrency<_>>::free_balance(DOT, &ALICE),
+            old_balance - 100
+        );
+
+        assert_noop!(
+            ZdToken::release_in(DOT, &BOB, &101),
+            Error::<Test>::StakingAmountTooLow
+        );
+        assert_noop!(
+            ZdToken::release_in(BTC, &BOB, &100),
+            Error::<Test>::StakingAmountTooLow
+        );
+
+        let old_balance = <Currencies as MultiCurrency<_>>::free_balance(DOT, &BOB);
+        assert_ok!(ZdToken::release_in(DOT, &BOB, &100));
+        assert_eq!(
+            <Currencies as MultiCurrency<_>>::free_balance(DOT, &BOB),
+            old_balance + 100
+        );
+        assert_eq!(ZdToken::total_staking_in(DOT), 0);
+    });
+}
+
 macro_rules! share_test {
     ($($name:ident: $value:expr,)*) => {
         $(
@@ -146,7 +177,7 @@ macro_rules! share_test {
 
                     let old_total_issuance = <Currencies as MultiCurrency<_>>::total_issuance(BaceToken::get());
 
-                    assert_eq!(ZdToken::share(&CHARLIE, &targets[..]), fee_amount);
+                    assert_eq!(ZdToken::share(&CHARLIE, &targets[..]), Ok(fee_amount));
 
                     let count = targets.len() as u128;
 
@@ -210,3 +241,121 @@ fn claim_test() {
         assert!(ZdToken::claim(Origin::signed(CHARLIE)).is_err());
     });
 }
+
+#[test]
+fn share_with_rebate_test() {
+    new_test_ext().execute_with(|| {
+        UserRebateRatio::set(&Perbill::from_percent(20));
+        let total_social_balance = 10000;
+        assert_ok!(ZdToken::transfer_social(
+            Origin::signed(ALICE),
+            CHARLIE,
+            total_social_balance
+        ));
+
+        let reserved_amount = per_social_currency::PRE_RESERVED.mul_floor(total_social_balance);
+        let fee_amount = per_social_currency::PRE_FEE.mul_floor(total_social_balance);
+        let rebate_amount = Perbill::from_percent(20).mul_floor(fee_amount);
+
+        assert_eq!(
+            ZdToken::share(&CHARLIE, &[BOB]),
+            Ok(fee_amount - rebate_amount)
+        );
+        assert_eq!(ZdToken::total_staking(), fee_amount - rebate_amount);
+        assert_eq!(
+            ZdToken::pending_balance(&CHARLIE),
+            reserved_amount + rebate_amount
+        );
+
+        let rebate_event = Event::zd_tokens(crate::Event::Rebate(
+            CHARLIE,
+            fee_amount - rebate_amount,
+            rebate_amount,
+        ));
+        assert!(System::events().iter().any(|record| record.event == rebate_event));
+    });
+}
+
+#[test]
+fn share_with_weights_test() {
+    new_test_ext().execute_with(|| {
+        let total_social_balance = 10000;
+        assert_ok!(ZdToken::transfer_social(
+            Origin::signed(ALICE),
+            CHARLIE,
+            total_social_balance
+        ));
+
+        let targets = vec![(100, 3), (101, 1), (102, 2)];
+        let total_share_amount = per_social_currency::PRE_SHARE.mul_floor(total_social_balance);
+        let share_amount =
+            total_share_amount / 6u128.max(per_social_currency::MIN_TRUST_COUNT as u128);
+
+        assert_ok!(ZdToken::share_with_weights(&CHARLIE, &targets[..]));
+
+        for (target, weight) in targets.iter() {
+            assert_eq!(
+                ZdToken::social_balance(target),
+                share_amount * (*weight as u128)
+            );
+        }
+        assert_eq!(
+            targets
+                .iter()
+                .map(|(target, _)| ZdToken::social_balance(target))
+                .sum::<u128>()
+                + ZdToken::social_balance(&CHARLIE),
+            total_share_amount
+        );
+    });
+}
+
+#[test]
+fn share_should_be_atomic() {
+    new_test_ext().execute_with(|| {
+        // Social currency the social pool does not hold, so the burn cannot be withdrawn.
+        <Accounts<Test>>::mutate(CHARLIE, |account| {
+            account.social = 10000;
+        });
+        assert_noop!(
+            ZdToken::share(&CHARLIE, &[BOB]),
+            orml_tokens::Error::<Test>::BalanceTooLow
+        );
+        assert_eq!(ZdToken::social_balance(&CHARLIE), 10000);
+        assert_eq!(ZdToken::social_balance(&BOB), 0);
+        assert_eq!(ZdToken::total_staking(), 0);
+    });
+}
+
+#[test]
+fn set_social_ratios_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(ZdToken::ratios(), SocialRatios::default());
+        assert_noop!(
+            ZdToken::set_social_ratios(
+                Perbill::from_percent(50),
+                Perbill::from_percent(30),
+                Perbill::from_percent(20),
+            ),
+            Error::<Test>::InvalidRatios
+        );
+        assert_ok!(ZdToken::set_social_ratios(
+            Perbill::from_percent(50),
+            Perbill::from_percent(20),
+            Perbill::from_percent(20),
+        ));
+
+        let total_social_balance = 10000;
+        assert_ok!(ZdToken::transfer_social(
+            Origin::signed(ALICE),
+            CHARLIE,
+            total_social_balance
+        ));
+        let fee_amount = Perbill::from_percent(20).mul_floor(total_social_balance);
+        assert_eq!(ZdToken::share(&CHARLIE, &[BOB]), Ok(fee_amount));
+        assert_eq!(
+            ZdToken::pending_balance(&CHARLIE),
+            Perbill::from_percent(20).mul_floor(total_social_balance)
+        );
+    });
+}
@@ -31,6 +31,8 @@
 //!
 //! - `trust` - The caller trusts the incoming user.
 //! - `do_untrust` - The caller untrusts the incoming user.
+//! - `set_trust_strength` - The caller sets the strength of its trust in the incoming user.
+//! - `set_score_curve` - Root sets the curve applied to the score of each path.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::unused_unit)]
@@ -42,11 +44,11 @@ use frame_support::{
     transactional,
 };
 use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
-use frame_system::{ensure_signed, pallet_prelude::*};
+use frame_system::{ensure_root, ensure_signed, pallet_prelude::*};
 use orml_utilities::OrderedSet;
 use sp_runtime::{DispatchError, DispatchResult, Perbill};
 use sp_std::vec::Vec;
-use zd_primitives::{appro_ln, TIRStep};
+use zd_primitives::{appro_ln, ScoreCurve, TIRStep};
 use zd_support::{Reputation, SeedsBase, TrustBase};
 
 #[cfg(test)]
@@ -60,6 +62,8 @@ pub use weights::WeightInfo;
 
 pub const INIT_SEED_RANK: u32 = 1000;
 pub const MIN_TRUST_COUNT: u32 = 5;
+pub const DEFAULT_TRUST_STRENGTH: u32 = 1;
+pub const MAX_TRUST_STRENGTH: u32 = 100;
 
 /// Trusted relationship cache.
 ///
@@ -111,6 +115,40 @@ pub mod module {
     pub type TrustTempList<T: Config> =
         StorageMap<_, Twox64Concat, T::AccountId, TrustTemp<T::AccountId>, ValueQuery>;
 
+    /// The strength of the trust from one user to another, if it is not the default.
+    #[pallet::storage]
+    #[pallet::getter(fn trust_strengths)]
+    pub type TrustStrengths<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::AccountId,
+        Twox64Concat,
+        T::AccountId,
+        u32,
+        OptionQuery,
+    >;
+
+    /// The strength of the trust from one user to another before the refresh started,
+    /// cached when it is changed during the refresh period and emptied at the end of the
+    /// refresh.
+    #[pallet::storage]
+    #[pallet::getter(fn trust_strength_temp)]
+    pub type TrustStrengthTemps<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::AccountId,
+        Twox64Concat,
+        T::AccountId,
+        u32,
+        OptionQuery,
+    >;
+
+    /// The curve applied to the score computed along each path, linear at full ratio by
+    /// default.
+    #[pallet::storage]
+    #[pallet::getter(fn score_curve)]
+    pub type PathScoreCurve<T: Config> = StorageValue<_, ScoreCurve, ValueQuery>;
+
     #[pallet::event]
     #[pallet::metadata(T::AccountId = "AccountId")]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -119,6 +157,10 @@ pub mod module {
         Trusted(T::AccountId, T::AccountId),
         /// A user untrusted another user. \[who, target\]
         Untrusted(T::AccountId, T::AccountId),
+        /// A user set the strength of a trust. \[who, target, strength\]
+        TrustStrengthSet(T::AccountId, T::AccountId, u32),
+        /// Updated the curve of path scores. \[curve\]
+        ScoreCurveSet(ScoreCurve),
     }
 
     #[pallet::error]
@@ -137,6 +179,10 @@ pub mod module {
         NotSeed,
         /// Exceeding the maximum number of trust limits
         TooMuchTrust,
+        /// The trust strength is zero or exceeds the maximum
+        InvalidStrength,
+        /// The score curve cannot be changed while reputations are being refreshed
+        UnableToSetScoreCurve,
     }
 
     #[pallet::hooks]
@@ -167,6 +213,52 @@ pub mod module {
             Self::deposit_event(Event::Untrusted(who, target));
             Ok(().into())
         }
+
+        /// The caller sets the strength of its trust in `target` to `strength`.
+        ///
+        /// `target` must already be trusted, and `strength` must be between `1` and
+        /// `MAX_TRUST_STRENGTH`.
+        #[pallet::weight(T::WeightInfo::set_trust_strength())]
+        #[transactional]
+        pub fn set_trust_strength(
+            origin: OriginFor<T>,
+            target: T::AccountId,
+            strength: u32,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                strength > 0 && strength <= MAX_TRUST_STRENGTH,
+                Error::<T>::InvalidStrength
+            );
+            ensure!(Self::is_trust(&who, &target), Error::<T>::NonExistent);
+            Self::cache_trust_strength(&who, &target);
+            if strength == DEFAULT_TRUST_STRENGTH {
+                <TrustStrengths<T>>::remove(&who, &target);
+            } else {
+                <TrustStrengths<T>>::insert(&who, &target, strength);
+            }
+            Self::deposit_event(Event::TrustStrengthSet(who, target, strength));
+            Ok(().into())
+        }
+
+        /// Sets the curve applied to the score of each path computed by `computed_path`.
+        ///
+        /// Can only be set while the system is `Free`, so that all the paths of a round are
+        /// scored with the same curve.
+        #[pallet::weight(T::WeightInfo::set_score_curve())]
+        pub fn set_score_curve(
+            origin: OriginFor<T>,
+            curve: ScoreCurve,
+        ) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+            ensure!(
+                T::Reputation::is_step(&TIRStep::Free),
+                Error::<T>::UnableToSetScoreCurve
+            );
+            <PathScoreCurve<T>>::put(curve);
+            Self::deposit_event(Event::ScoreCurveSet(curve));
+            Ok(().into())
+        }
     }
 }
 
@@ -202,6 +294,8 @@ impl<T: Config> Pallet<T> {
             ensure!(t.remove(target), Error::<T>::NonExistent);
             Ok(())
         })?;
+        Self::cache_trust_strength(who, target);
+        <TrustStrengths<T>>::remove(&who, &target);
 
         if !T::Reputation::is_step(&TIRStep::Free) {
             let mut trust_temp_list = Self::trust_temp_list(&who);
@@ -214,11 +308,22 @@ impl<T: Config> Pallet<T> {
         }
         Ok(())
     }
+
+    /// Keeps the strength of the trust from `who` to `target` before it changes, if the
+    /// refresh has started and it has not been kept yet.
+    fn cache_trust_strength(who: &T::AccountId, target: &T::AccountId) {
+        if !T::Reputation::is_step(&TIRStep::Free)
+            && !<TrustStrengthTemps<T>>::contains_key(who, target)
+        {
+            <TrustStrengthTemps<T>>::insert(who, target, Self::trust_strength(who, target));
+        }
+    }
 }
 
 impl<T: Config> TrustBase<T::AccountId> for Pallet<T> {
     fn remove_all_tmp() {
         <TrustTempList<T>>::remove_all();
+        <TrustStrengthTemps<T>>::remove_all();
     }
 
     fn get_trust_count(who: &T::AccountId) -> usize {
@@ -248,6 +353,15 @@ impl<T: Config> TrustBase<T::AccountId> for Pallet<T> {
             || (Self::is_trust(who, target) && !temp_list.untrust.contains(target))
     }
 
+    fn trust_strength(who: &T::AccountId, target: &T::AccountId) -> u32 {
+        Self::trust_strengths(who, target).unwrap_or(DEFAULT_TRUST_STRENGTH)
+    }
+
+    fn trust_strength_old(who: &T::AccountId, target: &T::AccountId) -> u32 {
+        Self::trust_strength_temp(who, target)
+            .unwrap_or_else(|| Self::trust_strength(who, target))
+    }
+
     fn get_trust_old(who: &T::AccountId) -> Vec<T::AccountId> {
         let mut trusted_user = Self::trust_list(&who);
         let mut temp_list = Self::trust_temp_list(&who);
@@ -279,6 +393,6 @@ impl<T: Config> TrustBase<T::AccountId> for Pallet<T> {
                     / dist;
                 Ok((acc.0.saturating_add(dist as u32), item_score))
             })?;
-        Ok((dist, score))
+        Ok((dist, Self::score_curve().apply(score)))
     }
 }
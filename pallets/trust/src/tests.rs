@@ -268,3 +268,87 @@ fn untrust_should_fail() {
         );
     });
 }
+
+#[test]
+fn set_trust_strength_should_work() {
+    new_test_ext().execute_with(|| {
+        initialize_trust();
+        assert_eq!(ZdTrust::trust_strength(&ALICE, &BOB), DEFAULT_TRUST_STRENGTH);
+        assert_noop!(
+            ZdTrust::set_trust_strength(Origin::signed(ALICE), DAVE, 3),
+            Error::<Test>::NonExistent
+        );
+        assert_noop!(
+            ZdTrust::set_trust_strength(Origin::signed(ALICE), BOB, 0),
+            Error::<Test>::InvalidStrength
+        );
+        assert_noop!(
+            ZdTrust::set_trust_strength(Origin::signed(ALICE), BOB, MAX_TRUST_STRENGTH + 1),
+            Error::<Test>::InvalidStrength
+        );
+
+        assert_ok!(ZdTrust::set_trust_strength(Origin::signed(ALICE), BOB, 3));
+        assert_eq!(ZdTrust::trust_strength(&ALICE, &BOB), 3);
+        assert_eq!(ZdTrust::trust_strength(&ALICE, &CHARLIE), DEFAULT_TRUST_STRENGTH);
+        let new_event = Event::zd_trust(crate::Event::TrustStrengthSet(ALICE, BOB, 3));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+
+        assert_ok!(ZdTrust::untrust(Origin::signed(ALICE), BOB));
+        assert_eq!(ZdTrust::trust_strengths(&ALICE, &BOB), None);
+    });
+}
+
+#[test]
+fn trust_strength_old_should_work() {
+    new_test_ext().execute_with(|| {
+        initialize_trust();
+        // (ALICE, CHARLIE), (ALICE, BOB)
+        assert_ok!(ZdTrust::set_trust_strength(Origin::signed(ALICE), BOB, 3));
+
+        ZdReputation::set_step(&TIRStep::Seed);
+        assert_ok!(ZdTrust::set_trust_strength(Origin::signed(ALICE), BOB, 5));
+        assert_ok!(ZdTrust::set_trust_strength(Origin::signed(ALICE), BOB, 7));
+        assert_eq!(ZdTrust::trust_strength(&ALICE, &BOB), 7);
+        assert_eq!(ZdTrust::trust_strength_old(&ALICE, &BOB), 3);
+
+        assert_ok!(ZdTrust::set_trust_strength(Origin::signed(ALICE), CHARLIE, 2));
+        assert_ok!(ZdTrust::untrust(Origin::signed(ALICE), CHARLIE));
+        assert_eq!(ZdTrust::trust_strength(&ALICE, &CHARLIE), DEFAULT_TRUST_STRENGTH);
+        assert_eq!(ZdTrust::trust_strength_old(&ALICE, &CHARLIE), DEFAULT_TRUST_STRENGTH);
+
+        ZdTrust::remove_all_tmp();
+        assert_eq!(ZdTrust::trust_strength_temp(&ALICE, &BOB), None);
+        assert_eq!(ZdTrust::trust_strength_old(&ALICE, &BOB), 7);
+    });
+}
+
+#[test]
+fn score_curve_should_work() {
+    new_test_ext().execute_with(|| {
+        initialize_trust();
+        assert_ok!(ZdSeeds::new_seed(Origin::root(), ALICE));
+        let capped = ScoreCurve::Capped { max: 10 };
+        assert_noop!(
+            ZdTrust::set_score_curve(Origin::signed(ALICE), capped),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        assert_ok!(ZdTrust::set_score_curve(Origin::root(), capped));
+        assert_eq!(ZdTrust::score_curve(), capped);
+        let new_event = Event::zd_trust(crate::Event::ScoreCurveSet(capped));
+        assert!(System::events().iter().any(|record| record.event == new_event));
+        assert_ok!(ZdTrust::computed_path(&[ALICE, BOB]), (7, 10));
+        assert_ok!(ZdTrust::computed_path(&[ALICE, BOB, CHARLIE]), (1 + 7, 5));
+
+        // 28 has 5 bits
+        let log = ScoreCurve::Logarithmic { scale: 2 };
+        assert_ok!(ZdTrust::set_score_curve(Origin::root(), log));
+        assert_ok!(ZdTrust::computed_path(&[ALICE, BOB]), (7, 10));
+
+        assert_ok!(ZdReputation::new_round());
+        assert_noop!(
+            ZdTrust::set_score_curve(Origin::root(), ScoreCurve::default()),
+            Error::<Test>::UnableToSetScoreCurve
+        );
+    });
+}
@@ -31,6 +31,8 @@ use sp_std::marker::PhantomData;
 pub trait WeightInfo {
     fn trust() -> Weight;
     fn untrust() -> Weight;
+    fn set_trust_strength() -> Weight;
+    fn set_score_curve() -> Weight;
 }
 
 /// Weights for zd_trust using the Substrate node and recommended hardware.
@@ -43,9 +45,19 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     }
     fn untrust() -> Weight {
         (84_200_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn set_trust_strength() -> Weight {
+        (32_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
             .saturating_add(T::DbWeight::get().writes(2 as Weight))
     }
+    fn set_score_curve() -> Weight {
+        (18_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
 }
 
 // For backwards compatibility and tests
@@ -57,7 +69,17 @@ impl WeightInfo for () {
     }
     fn untrust() -> Weight {
         (84_200_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn set_trust_strength() -> Weight {
+        (32_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
             .saturating_add(RocksDbWeight::get().writes(2 as Weight))
     }
+    fn set_score_curve() -> Weight {
+        (18_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
 }
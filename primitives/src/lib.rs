@@ -33,6 +33,12 @@ pub enum CurrencyId {
 
 pub type AppId = [u8; 8];
 
+/// The topic a reputation is scored on, such as `*b"finance\0"`.
+pub type DomainId = [u8; 8];
+
+/// The domain of the reputation the system refreshed before it had domains.
+pub const DEFAULT_DOMAIN: DomainId = [0; 8];
+
 /// Balance of an account.
 pub type Balance = u128;
 
@@ -76,9 +82,81 @@ impl Default for TIRStep {
     }
 }
 
+/// The phase of the round of the reputation system, telling off-chain actors what they may
+/// do.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum Phase {
+    /// No round is running.
+    Free,
+    /// Until `refresh_ends_at` of the round, seeds and reputations are refreshed.
+    Updating,
+    /// Until `confirm_ends_at` of the round, the last refreshes can still be challenged.
+    Confirming,
+    /// The challenges of the round are settled before it closes.
+    Settling,
+}
+
+impl Default for Phase {
+    fn default() -> Phase {
+        Phase::Free
+    }
+}
+
 pub mod fee {
     use super::*;
 
+    /// The share of the proceeds a `sweeper` receives, growing linearly from zero to `max`
+    /// over the `ramp` blocks following the first `delay` blocks after the last update, in
+    /// which sweepers are not allowed.
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+    pub struct SweeperCurve {
+        /// The number of blocks after the last update before sweepers are allowed.
+        pub delay: u64,
+        /// The number of blocks after `delay` until the share reaches `max`, `0` for `max`
+        /// as soon as sweepers are allowed.
+        pub ramp: u64,
+        /// The maximum share of a sweeper.
+        pub max: Perbill,
+    }
+
+    /// A fixed `SWEEPER_PICKUP_RATIO` once `SWEEPER_PERIOD` has passed.
+    pub const SWEEPER_CURVE: SweeperCurve = SweeperCurve {
+        delay: SWEEPER_PERIOD,
+        ramp: 0,
+        max: SWEEPER_PICKUP_RATIO,
+    };
+
+    impl Default for SweeperCurve {
+        fn default() -> Self {
+            SWEEPER_CURVE
+        }
+    }
+
+    impl SweeperCurve {
+        /// The share of a sweeper `elapsed` blocks after the last update, `None` if sweepers
+        /// are not allowed yet.
+        pub fn ratio(&self, elapsed: u64) -> Option<Perbill> {
+            if elapsed <= self.delay {
+                return None;
+            }
+            let grown = elapsed - self.delay;
+            if grown >= self.ramp {
+                return Some(self.max);
+            }
+            let parts = Perbill::from_rational_approximation(grown, self.ramp)
+                .mul_floor(self.max.deconstruct());
+            Some(Perbill::from_parts(parts))
+        }
+
+        /// Whether to allow `sweeper` participation when the last active time is `last`
+        /// and the current time is `now`.
+        pub fn is_allowed<B: AtLeast32BitUnsigned>(&self, last: B, now: B) -> bool {
+            TryInto::<u64>::try_into(now.saturating_sub(last))
+                .map_or(false, |elapsed| self.ratio(elapsed).is_some())
+        }
+    }
+
     pub trait SweeperFee
     where
         Self: Sized,
@@ -94,6 +172,15 @@ pub mod fee {
             now: B,
         ) -> Option<(Self, Self)>;
 
+        /// Returns the `fee` on `curve` and the remaining amount, `None` if `sweeper` is
+        /// not allowed yet.
+        fn checked_with_curve<B: AtLeast32BitUnsigned>(
+            &self,
+            last: B,
+            now: B,
+            curve: &SweeperCurve,
+        ) -> Option<(Self, Self)>;
+
         ///  Returns the unchecked `fee` and the remaining amount.
         fn with_fee(&self) -> (Self, Self);
     }
@@ -116,6 +203,17 @@ pub mod fee {
             }
         }
 
+        fn checked_with_curve<B: AtLeast32BitUnsigned>(
+            &self,
+            last: B,
+            now: B,
+            curve: &SweeperCurve,
+        ) -> Option<(Self, Self)> {
+            let elapsed = TryInto::<u64>::try_into(now.saturating_sub(last)).ok()?;
+            let sweeper_fee = curve.ratio(elapsed)?.mul_floor(*self);
+            Some((sweeper_fee, self.saturating_sub(sweeper_fee)))
+        }
+
         fn with_fee(&self) -> (Self, Self) {
             let sweeper_fee = SWEEPER_PICKUP_RATIO.mul_floor(*self);
             (sweeper_fee, self.saturating_sub(sweeper_fee))
@@ -144,6 +242,40 @@ pub fn appro_ln(value: u32) -> u32 {
     }
 }
 
+/// The curve turning the score computed along a trust path into the score it adds to the
+/// reputation of its target.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum ScoreCurve {
+    /// `ratio` of the score.
+    Linear { ratio: Perbill },
+    /// `scale` for each bit of the score, that is `scale * (log2(score) + 1)`.
+    Logarithmic { scale: u32 },
+    /// The score, up to `max`.
+    Capped { max: u32 },
+}
+
+impl Default for ScoreCurve {
+    fn default() -> Self {
+        ScoreCurve::Linear {
+            ratio: Perbill::one(),
+        }
+    }
+}
+
+impl ScoreCurve {
+    /// The point of the curve at `score`.
+    pub fn apply(&self, score: u32) -> u32 {
+        match self {
+            ScoreCurve::Linear { ratio } => ratio.mul_floor(score),
+            ScoreCurve::Logarithmic { scale } => {
+                let bits = 32 - score.leading_zeros();
+                scale.saturating_mul(bits)
+            }
+            ScoreCurve::Capped { max } => score.min(*max),
+        }
+    }
+}
+
 /// The state of the challenge game.
 #[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -161,6 +293,156 @@ impl Default for ChallengeStatus {
     }
 }
 
+/// The progress of a challenge as shown to users, derived from its `ChallengeStatus`, its
+/// ruling and whether it has timed out.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum ChallengeState {
+    /// Waiting for the pathfinder to answer the examine.
+    Examining,
+    /// The pathfinder is uploading its data.
+    Replying,
+    /// Waiting for the challenger to present evidence.
+    Evidence,
+    /// Waiting for arbitration.
+    Arbitral,
+    /// Ruled on or restarted, but not harvestable yet.
+    Settled,
+    /// Settled and ready to be harvested.
+    Harvestable,
+    /// The side due to act has not done so in time, the challenge can be harvested as is.
+    TimedOut,
+}
+
+/// A harvested challenge, kept for auditing the dispute history of its target.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct ChallengeRecord<AccountId, Score = u64> {
+    /// The round in which the challenge was harvested.
+    pub round: u32,
+
+    /// The `AccountId` of the challenger.
+    pub challenger: AccountId,
+
+    /// The `AccountId` of the pathfinder.
+    pub pathfinder: AccountId,
+
+    /// The status the challenge ended in.
+    pub status: ChallengeStatus,
+
+    /// The share of the prize pool that went to `pathfinder`.
+    pub pathfinder_share: Perbill,
+
+    /// The last score of the challenge.
+    pub score: Score,
+
+    /// The last remark of the challenge.
+    pub remark: u32,
+}
+
+/// The challenge parameters of an app, in place of the constants of the challenge system.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct AppConfig<BlockNumber, Score = u64> {
+    /// The price of admission to the challenge game.
+    pub staking_amount: Balance,
+
+    /// The time window left for each step of the challenge.
+    pub timeout: BlockNumber,
+
+    /// Maximum number of data uploads per `next`.
+    pub max_next_steps: u32,
+
+    /// Minimum difference between the claimed and the recorded score to launch a challenge.
+    pub min_dispute_delta: Score,
+
+    /// The time `reply` and `evidence` push the deadline of a challenge forward by.
+    pub response_window: BlockNumber,
+
+    /// Maximum total time the deadline of a challenge can be pushed forward by.
+    pub max_response_extension: BlockNumber,
+}
+
+/// What launching a challenge would take and apply now, for a client to show before it is
+/// signed.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct ChallengePreview<BlockNumber> {
+    /// The amount the challenger stakes to launch the challenge.
+    pub deposit: Balance,
+
+    /// The score currently recorded for the target, which the challenge disputes.
+    pub score: u64,
+
+    /// The deadline of the first step of the challenge.
+    pub deadline: BlockNumber,
+
+    /// Whether the target is still within its confirmation period.
+    pub in_confirmation: bool,
+}
+
+/// The timing of a round of the reputation system, fixed when the round starts and when
+/// its refreshes open.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct RoundInfo<BlockNumber> {
+    /// The index of the round, the `nonce` of the system.
+    pub index: u32,
+
+    /// The block the round started at.
+    pub started_at: BlockNumber,
+
+    /// The block the refresh phase of the round ends at.
+    pub refresh_ends_at: BlockNumber,
+
+    /// The block the confirmation of the last refreshes of the round ends at.
+    pub confirm_ends_at: BlockNumber,
+}
+
+/// How a settlement splits the prize pool of a challenge between its two sides.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct SettlementOutcome<Score = u64> {
+    /// Paid to the challenger side, by the settlement itself or once the challenge is
+    /// harvested, the treasury cut of the reward curve deducted.
+    pub challenger_amount: Balance,
+
+    /// Due to the pathfinder of the challenge before the settlement once it is harvested.
+    pub pathfinder_amount: Balance,
+
+    /// The score recorded in the challenge system.
+    pub score: Score,
+
+    /// Whether the challenge was restarted.
+    pub restarted: bool,
+}
+
+/// How the prize pool of a challenge under an app is split.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct RewardCurve {
+    /// The share of the amount won by the challenger side that is paid out to it, the rest
+    /// goes to the treasury.
+    pub challenger_share: Perbill,
+
+    /// The cut of the prize pool the challenger takes for an arbitration with joint benefits,
+    /// the rest stays with the pathfinder.
+    pub arbiter_cut: Perbill,
+}
+
+impl Default for RewardCurve {
+    fn default() -> Self {
+        RewardCurve {
+            challenger_share: Perbill::one(),
+            arbiter_cut: Perbill::from_percent(50),
+        }
+    }
+}
+
+/// An amount won at harvest and held in escrow during the payout delay of its app.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct Payout<BlockNumber> {
+    /// The amount held.
+    pub amount: Balance,
+
+    /// The block of the app clock from which the amount can be claimed.
+    pub unlock_at: BlockNumber,
+}
+
 /// A pool of funds secured by staking and earnings.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
 pub struct Pool {
@@ -175,9 +457,9 @@ pub struct Progress {
     pub done: u32,
 }
 
-/// Challenge game metadata.
+/// Challenge game metadata, `Score` is the score type of the challenge system.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
-pub struct Metadata<AccountId, BlockNumber> {
+pub struct Metadata<AccountId, BlockNumber, Score = u64> {
     /// Current pool of funds.
     pub pool: Pool,
 
@@ -194,7 +476,7 @@ pub struct Metadata<AccountId, BlockNumber> {
     pub remark: u32,
 
     /// Current score.
-    pub score: u64,
+    pub score: Score,
 
     /// `AccountId` of `pathfinder`.
     pub pathfinder: AccountId,
@@ -206,7 +488,7 @@ pub struct Metadata<AccountId, BlockNumber> {
     pub challenger: AccountId,
 }
 
-impl<AccountId, BlockNumber> Metadata<AccountId, BlockNumber>
+impl<AccountId, BlockNumber, Score> Metadata<AccountId, BlockNumber, Score>
 where
     AccountId: Ord + Clone,
     BlockNumber: Copy + AtLeast32Bit,
@@ -264,6 +546,100 @@ where
     }
 }
 
+/// The last arbitration of a challenge, which `loser` may appeal for a while.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct Ruling<AccountId, BlockNumber> {
+    /// When the ruling was made.
+    pub at: BlockNumber,
+
+    /// The side the ruling went against.
+    pub loser: AccountId,
+}
+
+/// An appeal against the ruling of a challenge, waiting to be resolved.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct Appeal<AccountId, BlockNumber> {
+    /// The `AccountId` of the loser who appealed.
+    pub appellant: AccountId,
+
+    /// Amount staked by `appellant`.
+    pub bond: Balance,
+
+    /// When the appeal was made, the arbiters may vote on it for the voting period from then.
+    pub at: BlockNumber,
+}
+
+/// Iteration over maps in storage from the raw key of an entry, for scans that resume from
+/// a cursor in a later block.
+pub mod storage {
+    use super::*;
+    use frame_support::{storage::unhashed, ReversibleStorageHasher, Twox64Concat};
+    use sp_std::vec::Vec;
+
+    /// The entries stored under `prefix` after `previous_key`, in the order of their raw keys,
+    /// each decoded from its raw key without `prefix` and its raw value.
+    ///
+    /// Entries that fail to decode are skipped.
+    pub struct PrefixIterator<T> {
+        prefix: Vec<u8>,
+        previous_key: Vec<u8>,
+        decode: fn(&[u8], &[u8]) -> Result<T, codec::Error>,
+    }
+
+    impl<T> Iterator for PrefixIterator<T> {
+        type Item = T;
+
+        fn next(&mut self) -> Option<T> {
+            loop {
+                let key = sp_io::storage::next_key(&self.previous_key)
+                    .filter(|key| key.starts_with(&self.prefix))?;
+                let raw_value = unhashed::get_raw(&key);
+                self.previous_key = key;
+                let raw_key = &self.previous_key[self.prefix.len()..];
+                if let Some(Ok(item)) = raw_value.map(|value| (self.decode)(raw_key, &value)) {
+                    return Some(item);
+                }
+            }
+        }
+    }
+
+    /// The entries `(key, value)` of the `Twox64Concat` map stored under `prefix`, after the
+    /// entry with the raw key `start`, or from the first one if `start` is `None`.
+    pub fn iter_map_from<K: Decode, V: Decode>(
+        prefix: &[u8],
+        start: Option<Vec<u8>>,
+    ) -> PrefixIterator<(K, V)> {
+        PrefixIterator {
+            prefix: prefix.to_vec(),
+            previous_key: start.unwrap_or_else(|| prefix.to_vec()),
+            decode: |raw_key, mut raw_value| {
+                let mut key = Twox64Concat::reverse(raw_key);
+                Ok((K::decode(&mut key)?, V::decode(&mut raw_value)?))
+            },
+        }
+    }
+
+    /// The entries `(key1, key2, value)` of the double map stored under `prefix` with two
+    /// `Twox64Concat` hashers, after the entry with the raw key `start`, or from the first one
+    /// if `start` is `None`.
+    pub fn iter_double_map_from<K1: Decode, K2: Decode, V: Decode>(
+        prefix: &[u8],
+        start: Option<Vec<u8>>,
+    ) -> PrefixIterator<(K1, K2, V)> {
+        PrefixIterator {
+            prefix: prefix.to_vec(),
+            previous_key: start.unwrap_or_else(|| prefix.to_vec()),
+            decode: |raw_key, mut raw_value| {
+                let mut key = Twox64Concat::reverse(raw_key);
+                let key1 = K1::decode(&mut key)?;
+                let mut key = Twox64Concat::reverse(key);
+                let key2 = K2::decode(&mut key)?;
+                Ok((key1, key2, V::decode(&mut raw_value)?))
+            },
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -348,6 +724,94 @@ mod tests {
 
 
 	}
-}
 
+    #[test]
+    fn score_curve_apply() {
+        let linear = ScoreCurve::default();
+        assert_eq!(linear.apply(0), 0);
+        assert_eq!(linear.apply(u32::MAX), u32::MAX);
+        let half = ScoreCurve::Linear {
+            ratio: Perbill::from_percent(50),
+        };
+        assert_eq!(half.apply(101), 50);
+
+        let log = ScoreCurve::Logarithmic { scale: 10 };
+        assert_eq!(log.apply(0), 0);
+        assert_eq!(log.apply(1), 10);
+        assert_eq!(log.apply(1000), 100);
+        assert_eq!(log.apply(1024), 110);
+        assert_eq!(ScoreCurve::Logarithmic { scale: u32::MAX }.apply(2), u32::MAX);
+
+        let capped = ScoreCurve::Capped { max: 100 };
+        assert_eq!(capped.apply(99), 99);
+        assert_eq!(capped.apply(1000), 100);
+    }
+
+    #[test]
+    fn sweeper_curve_ratio() {
+        use fee::{SweeperCurve, SweeperFee};
+
+        let curve = SweeperCurve {
+            delay: 100,
+            ramp: 200,
+            max: Perbill::from_percent(10),
+        };
+        assert_eq!(curve.ratio(100), None);
+        assert_eq!(curve.ratio(101), Some(Perbill::from_parts(500_000)));
+        assert_eq!(curve.ratio(200), Some(Perbill::from_percent(5)));
+        assert_eq!(curve.ratio(300), Some(Perbill::from_percent(10)));
+        assert_eq!(curve.ratio(u64::MAX), Some(Perbill::from_percent(10)));
+        assert!(!curve.is_allowed(50u32, 150u32));
+        assert!(curve.is_allowed(50u32, 151u32));
+
+        assert_eq!(1000u128.checked_with_curve(0u32, 100u32, &curve), None);
+        assert_eq!(
+            1000u128.checked_with_curve(0u32, 200u32, &curve),
+            Some((50, 950))
+        );
+        assert_eq!(
+            1000u128.checked_with_curve(0u32, 400u32, &curve),
+            Some((100, 900))
+        );
+
+        let fixed = fee::SWEEPER_CURVE;
+        let last = 10u32;
+        let now = last + SWEEPER_PERIOD as u32 + 1;
+        assert_eq!(
+            1000u128.checked_with_curve(last, now, &fixed),
+            1000u128.checked_with_fee(last, now)
+        );
+    }
 
+    #[test]
+    fn iter_double_map_from_should_resume_after_start() {
+        use frame_support::{storage::unhashed, StorageHasher, Twox64Concat};
+
+        sp_io::TestExternalities::default().execute_with(|| {
+            let prefix = [7u8; 32];
+            let key_of = |k1: u32, k2: AccountId| {
+                let mut key = prefix.to_vec();
+                key.extend(Twox64Concat::hash(&k1.encode()));
+                key.extend(Twox64Concat::hash(&k2.encode()));
+                key
+            };
+            for (k1, k2) in [(1u32, 10u32), (1, 11), (2, 10)].iter() {
+                unhashed::put(&key_of(*k1, *k2), &(k1 + k2));
+            }
+            unhashed::put(&[8u8; 32], &0u32);
+
+            let all = storage::iter_double_map_from::<u32, AccountId, u32>(&prefix, None)
+                .collect::<Vec<_>>();
+            assert_eq!(all.len(), 3);
+            assert!(all.iter().all(|(k1, k2, v)| k1 + k2 == *v));
+
+            let (k1, k2, _) = all[0];
+            let rest = storage::iter_double_map_from::<u32, AccountId, u32>(
+                &prefix,
+                Some(key_of(k1, k2)),
+            )
+            .collect::<Vec<_>>();
+            assert_eq!(rest, all[1..].to_vec());
+        });
+    }
+}
@@ -6,4 +6,6 @@ pub mod utils;
 pub mod zd_tokens;
 pub mod zd_trust;
 pub mod zd_refresh_reputation;
-pub mod zd_refresh_seeds;
\ No newline at end of file
+pub mod zd_refresh_seeds;
+pub mod zd_reputation;
+pub mod zd_challenges;
\ No newline at end of file
@@ -0,0 +1,297 @@
+#[warn(unused_must_use)]
+use crate::{
+    AccountId, AppealPeriod, BlockNumber, ChallengeStakingAmount, ChallengeTimeout, Currencies,
+    CurrencyId, GetNativeCurrencyId, MaxHistory, MaxLegsPerReply, MaxNextSteps,
+    MaxResponseExtension, Runtime, System, ZdChallenges, ZdToken,
+};
+use frame_benchmarking::{account, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_runtime::{traits::Zero, DispatchError};
+use sp_std::prelude::*;
+use zd_challenges::{Histories, Payouts};
+use zd_primitives::{
+    AppConfig, AppId, ChallengeRecord, Metadata, Payout, Pool, Progress, RewardCurve,
+};
+
+use orml_benchmarking::runtime_benchmarks;
+use orml_traits::MultiCurrency;
+use zd_support::{ChallengeBase, MultiBaseToken};
+
+use frame_support::assert_ok;
+
+const APP_ID: AppId = *b"benchmrk";
+const NATIVE: CurrencyId = GetNativeCurrencyId::get();
+const MAX_NEXT_STEPS: u32 = MaxNextSteps::get();
+const MAX_LEGS_PER_REPLY: u32 = MaxLegsPerReply::get();
+const MAX_HISTORY: u32 = MaxHistory::get();
+const MAX_HARVEST: u32 = zd_challenges::MAX_HARVEST_ALL;
+
+fn fund_pool() {
+    let vault: AccountId = account("vault", 0, 0);
+    let _ = Currencies::deposit(NATIVE, &vault, 1_000_000_000_000u128);
+    assert_ok!(ZdToken::staking(&vault, &1_000_000_000u128));
+}
+
+/// Launch a challenge of `challenger` against `target` that needs `total` uploads.
+fn launch(challenger: &AccountId, target: &AccountId, total: u32) {
+    let pathfinder: AccountId = account("pathfinder", 0, 0);
+    let _ = Currencies::deposit(NATIVE, challenger, 1_000_000_000_000u128);
+    assert_ok!(<ZdChallenges as ChallengeBase<_, _, _, _>>::launch(
+        &APP_ID,
+        target,
+        &Metadata {
+            pool: Pool {
+                staking: Zero::zero(),
+                earnings: 1_000,
+            },
+            pathfinder,
+            challenger: challenger.clone(),
+            progress: Progress { total, done: 0 },
+            ..Metadata::default()
+        },
+        (10, 0),
+        None
+    ));
+}
+
+/// Launch a challenge against `target` with all its data uploaded by `challenger`, ready for
+/// the reply of the pathfinder.
+fn launch_uploaded(challenger: &AccountId, target: &AccountId) {
+    launch(challenger, target, 1);
+    assert_ok!(<ZdChallenges as ChallengeBase<_, _, _, _>>::next(
+        &APP_ID,
+        challenger,
+        target,
+        &1,
+        |score, remark, _| -> Result<(u64, u32), DispatchError> { Ok((score, remark)) },
+    ));
+}
+
+/// Launch a challenge against `target` that the pathfinder has fully replied to.
+fn launch_replied(challenger: &AccountId, target: &AccountId) {
+    launch_uploaded(challenger, target);
+    let pathfinder: AccountId = account("pathfinder", 0, 0);
+    assert_ok!(<ZdChallenges as ChallengeBase<_, _, _, _>>::reply(
+        &APP_ID,
+        &pathfinder,
+        target,
+        1,
+        1,
+        |_, _, score, _| -> Result<u64, DispatchError> { Ok(score) },
+    ));
+}
+
+/// Launch a challenge against `target` and settle it, so that it can be harvested at
+/// `harvestable_at`.
+fn launch_settled(challenger: &AccountId, target: &AccountId) {
+    launch(challenger, target, 0);
+    assert_ok!(<ZdChallenges as ChallengeBase<_, _, _, _>>::settle(
+        &APP_ID, target, false, false, 10
+    ));
+}
+
+fn harvestable_at() -> BlockNumber {
+    System::block_number()
+        + ChallengeTimeout::get()
+        + AppealPeriod::get()
+        + MaxResponseExtension::get()
+        + 1
+}
+
+runtime_benchmarks! {
+    { Runtime, zd_challenges }
+
+    _ {}
+
+    launch {
+        let challenger: AccountId = whitelisted_caller();
+        let target: AccountId = account("target", 0, 0);
+        let pathfinder: AccountId = account("pathfinder", 0, 0);
+        let _ = Currencies::deposit(NATIVE, &challenger, 1_000_000_000_000u128);
+        let metadata = Metadata {
+            pathfinder,
+            challenger: challenger.clone(),
+            progress: Progress { total: 1, done: 0 },
+            ..Metadata::default()
+        };
+    }: {
+        assert_ok!(<ZdChallenges as ChallengeBase<_, _, _, _>>::launch(
+            &APP_ID, &target, &metadata, (10, 0), None
+        ));
+    }
+
+    // `a` is the number of paths uploaded.
+    next {
+        let a in 1 .. MAX_NEXT_STEPS;
+
+        let challenger: AccountId = whitelisted_caller();
+        let target: AccountId = account("target", 0, 0);
+        launch(&challenger, &target, a);
+    }: {
+        assert_ok!(<ZdChallenges as ChallengeBase<_, _, _, _>>::next(
+            &APP_ID,
+            &challenger,
+            &target,
+            &a,
+            |score, remark, _| -> Result<(u64, u32), DispatchError> { Ok((score, remark)) },
+        ));
+    }
+
+    examine {
+        let challenger: AccountId = whitelisted_caller();
+        let target: AccountId = account("target", 0, 0);
+        launch_replied(&challenger, &target);
+    }: {
+        assert_ok!(<ZdChallenges as ChallengeBase<_, _, _, _>>::examine(
+            &APP_ID, &challenger, &target, 0
+        ));
+    }
+
+    // `a` is the number of legs replied.
+    reply {
+        let a in 1 .. MAX_LEGS_PER_REPLY;
+
+        let challenger: AccountId = account("challenger", 0, 0);
+        let target: AccountId = account("target", 0, 0);
+        let pathfinder: AccountId = account("pathfinder", 0, 0);
+        launch_uploaded(&challenger, &target);
+    }: {
+        assert_ok!(<ZdChallenges as ChallengeBase<_, _, _, _>>::reply(
+            &APP_ID,
+            &pathfinder,
+            &target,
+            a,
+            a,
+            |_, _, score, _| -> Result<u64, DispatchError> { Ok(score) },
+        ));
+    }
+
+    evidence {
+        let challenger: AccountId = whitelisted_caller();
+        let target: AccountId = account("target", 0, 0);
+        launch_replied(&challenger, &target);
+    }: {
+        assert_ok!(<ZdChallenges as ChallengeBase<_, _, _, _>>::evidence(
+            &APP_ID,
+            &challenger,
+            &target,
+            None,
+            |_, _, _| -> Result<bool, DispatchError> { Ok(false) },
+        ));
+    }
+
+    arbitral {
+        let challenger: AccountId = whitelisted_caller();
+        let target: AccountId = account("target", 0, 0);
+        launch_replied(&challenger, &target);
+    }: {
+        assert_ok!(<ZdChallenges as ChallengeBase<_, _, _, _>>::arbitral(
+            &APP_ID,
+            &challenger,
+            &target,
+            |score, _, _| -> Result<(bool, bool, u64), DispatchError> {
+                Ok((false, false, score + 1))
+            },
+        ));
+    }
+
+    harvest {
+        fund_pool();
+        let challenger: AccountId = whitelisted_caller();
+        let target: AccountId = account("target", 0, 0);
+        launch_settled(&challenger, &target);
+        System::set_block_number(harvestable_at());
+    }: {
+        assert_ok!(<ZdChallenges as ChallengeBase<_, _, _, _>>::harvest(
+            &challenger, &APP_ID, &target
+        ));
+    }
+
+    // `h` is the number of challenges tried, all of them are harvested.
+    harvest_all {
+        let h in 1 .. MAX_HARVEST;
+
+        fund_pool();
+        let challenger: AccountId = whitelisted_caller();
+        for t in 0..h {
+            let target: AccountId = account("target", 0, t);
+            launch_settled(&challenger, &target);
+        }
+        System::set_block_number(harvestable_at());
+    }: _(RawOrigin::Signed(challenger.clone()), APP_ID, h)
+
+    settle_expired {
+        fund_pool();
+        let challenger: AccountId = account("challenger", 0, 0);
+        let target: AccountId = account("target", 0, 0);
+        launch_settled(&challenger, &target);
+        System::set_block_number(harvestable_at());
+    }: _(RawOrigin::None, APP_ID, target)
+
+    // `h` is the number of records in the history.
+    prune_history {
+        let h in 0 .. MAX_HISTORY;
+
+        let caller: AccountId = whitelisted_caller();
+        let target: AccountId = account("target", 0, 0);
+        Histories::<Runtime>::insert(APP_ID, &target, vec![ChallengeRecord::default(); h as usize]);
+    }: _(RawOrigin::Signed(caller.clone()), APP_ID, target)
+
+    set_app_config {
+        let config = AppConfig {
+            staking_amount: ChallengeStakingAmount::get(),
+            timeout: ChallengeTimeout::get(),
+            max_next_steps: MAX_NEXT_STEPS,
+            min_dispute_delta: 2,
+            response_window: 10,
+            max_response_extension: MaxResponseExtension::get(),
+        };
+    }: _(RawOrigin::Root, APP_ID, Some(config))
+
+    set_reward_curve {
+    }: _(RawOrigin::Root, APP_ID, Some(RewardCurve::default()))
+
+    set_payout_delay {
+    }: _(RawOrigin::Root, APP_ID, Some(10))
+
+    set_deposit_currency {
+    }: _(RawOrigin::Root, APP_ID, Some(NATIVE))
+
+    claim_payout {
+        fund_pool();
+        let caller: AccountId = whitelisted_caller();
+        let target: AccountId = account("target", 0, 0);
+        Payouts::<Runtime>::insert((APP_ID, target.clone()), &caller, Payout {
+            amount: 1_000,
+            unlock_at: 0,
+        });
+    }: _(RawOrigin::Signed(caller.clone()), APP_ID, target)
+
+    dispute_payout {
+        fund_pool();
+        let who: AccountId = account("pathfinder", 0, 0);
+        let target: AccountId = account("target", 0, 0);
+        Payouts::<Runtime>::insert((APP_ID, target.clone()), &who, Payout {
+            amount: 1_000,
+            unlock_at: 0,
+        });
+    }: _(RawOrigin::Root, APP_ID, target, who)
+
+    pause_app {
+    }: _(RawOrigin::Root, APP_ID)
+
+    unpause_app {
+        assert_ok!(ZdChallenges::pause_app(RawOrigin::Root.into(), APP_ID));
+        System::set_block_number(System::block_number() + 10);
+    }: _(RawOrigin::Root, APP_ID)
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmarking::utils::tests::new_test_ext;
+    use orml_benchmarking::impl_benchmark_test_suite;
+
+    impl_benchmark_test_suite!(new_test_ext(),);
+}
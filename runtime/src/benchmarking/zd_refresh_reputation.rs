@@ -1,17 +1,21 @@
 #[warn(unused_must_use)]
 use crate::{
-    AccountId, Currencies, CurrencyId, GetNativeCurrencyId, MaxSeedCount, MaxTrustCount,
-    MaxUpdateCount, Runtime, System, ZdRefreshReputation, ZdReputation, ZdSeeds, ZdToken, ZdTrust,
+    AccountId, AppealVotingPeriod, Arbiters, CommitDeposit, Currencies, CurrencyId,
+    GetNativeCurrencyId, Hash, MaxSeedCount, MaxTrustCount, MaxUpdateCount, PathfinderBond, Runtime, System,
+    UnbondingPeriod, ZdChallenges, ZdRefreshReputation, ZdReputation, ZdSeeds, ZdToken, ZdTrust,
 };
 use frame_benchmarking::{account, whitelisted_caller};
 use frame_system::RawOrigin;
+use sp_runtime::Perbill;
 use sp_std::prelude::*;
 use zd_primitives::TIRStep;
-use zd_refresh_reputation::Path;
+use zd_refresh_reputation::{
+    Path, PathfinderStatus, PathfinderStatuses, RefreshCommits, APP_ID, MAX_START_SETTLEMENTS,
+};
 
 use orml_benchmarking::runtime_benchmarks;
 use orml_traits::MultiCurrency;
-use zd_support::{MultiBaseToken, Reputation, SeedsBase, TrustBase};
+use zd_support::{ChallengeBase, MultiBaseToken, Reputation, SeedsBase, TrustBase};
 
 use frame_support::assert_ok;
 
@@ -30,12 +34,13 @@ fn init_harvest(pathfinder: &AccountId) {
     let _ = ZdRefreshReputation::start(RawOrigin::Signed(pathfinder.clone()).into());
 
     let now = System::block_number();
+    let round = ZdReputation::get_round();
 
     for t in 1..MAX_REFRESH {
         let targer: AccountId = account("targer", 0, t);
-        ZdRefreshReputation::mutate_record(pathfinder, &targer.clone(), &200u128, &now);
+        ZdRefreshReputation::mutate_record(round, pathfinder, &targer.clone(), &200u128, &now);
     }
-    let _ = ZdRefreshReputation::mutate_payroll(pathfinder, &2000u128, &MAX_REFRESH, &now);
+    let _ = ZdRefreshReputation::mutate_payroll(round, pathfinder, &2000u128, &MAX_REFRESH, &now);
     ZdReputation::set_step(&TIRStep::Reputation);
 }
 
@@ -47,12 +52,38 @@ fn init_challenge(challenger: &AccountId, targer: &AccountId, score: u32) {
     let pathfinder: AccountId = account("pathfinder", 0, 0);
     let _ = Currencies::deposit(NATIVE, &pathfinder, 1_000_000_000_000u128);
     let _ = ZdRefreshReputation::start(RawOrigin::Signed(pathfinder.clone()).into());
+    register(&pathfinder);
 
     let _ = ZdRefreshReputation::refresh(RawOrigin::Signed(pathfinder.clone()).into(), accounts);
 
     let _ = Currencies::deposit(NATIVE, challenger, 1_000_000_000_000u128);
 }
 
+fn init_appeal(challenger: &AccountId, targer: &AccountId, pathfinder: &AccountId) {
+    init_challenge(challenger, targer, 2);
+    let seed: AccountId = account("seed", 0, 1);
+    <ZdSeeds as SeedsBase<_>>::add_seed(&seed);
+    assert_ok!(ZdRefreshReputation::challenge(
+        RawOrigin::Signed(challenger.clone()).into(),
+        targer.clone(),
+        pathfinder.clone(),
+        0,
+        3,
+        None
+    ));
+    assert_ok!(<ZdChallenges as ChallengeBase<_, _, _, _>>::settle(
+        &APP_ID, targer, false, false, 3
+    ));
+}
+
+fn register(pathfinder: &AccountId) {
+    assert_ok!(ZdRefreshReputation::register_pathfinder(
+        RawOrigin::Signed(pathfinder.clone()).into(),
+        PathfinderBond::get(),
+        None
+    ));
+}
+
 fn checked_trust(source: &AccountId, targer: &AccountId) {
     if !<ZdTrust as TrustBase<_>>::is_trust(source, targer) {
         let _ = ZdTrust::trust(
@@ -67,18 +98,21 @@ runtime_benchmarks! {
 
     _ {}
 
-    // Construct 10 unfilled orders
+    // `p` is the number of matured payrolls settled by `start`.
     start {
+        let p in 0 .. MAX_START_SETTLEMENTS;
+
         ZdReputation::set_step(&TIRStep::Reputation);
         System::set_block_number(1);
 
         let vault = account("vault", 0, 0);
 
-        for i in 2..12 {
+        for i in 0..p {
             let finder: AccountId = account("finder", 0, i);
             let total_fee = 1_000;
             ZdRefreshReputation::mutate_payroll(
-               &finder,
+                ZdReputation::get_round(),
+                &finder,
                 &total_fee.clone(),
                 &20,
                 &1
@@ -92,8 +126,105 @@ runtime_benchmarks! {
 
     }: _(RawOrigin::Signed(starter.clone()))
 
+    force_new_round {
+        let p in 0 .. MAX_START_SETTLEMENTS;
+
+        ZdReputation::set_step(&TIRStep::Reputation);
+        System::set_block_number(1);
+
+        let vault = account("vault", 0, 0);
+
+        for i in 0..p {
+            let finder: AccountId = account("finder", 0, i);
+            ZdRefreshReputation::mutate_payroll(ZdReputation::get_round(), &finder, &1_000, &20, &1)?;
+        }
+        Currencies::deposit(NATIVE, &vault, 1_000_000_000_000u128)?;
+        assert_ok!(ZdToken::staking(&vault, &1_000_000_000_000u128));
+        assert_ok!(ZdRefreshReputation::start(RawOrigin::Signed(vault.clone()).into()));
+    }: _(RawOrigin::Root, false)
+
+    register_pathfinder {
+        let caller: AccountId = whitelisted_caller();
+        let _ = Currencies::deposit(NATIVE, &caller, 1_000_000_000_000u128)?;
+    }: _(RawOrigin::Signed(caller.clone()), PathfinderBond::get(), Some(Default::default()))
+
+    deregister_pathfinder {
+        let caller: AccountId = whitelisted_caller();
+        let _ = Currencies::deposit(NATIVE, &caller, 1_000_000_000_000u128)?;
+        register(&caller);
+    }: _(RawOrigin::Signed(caller.clone()))
+
+    withdraw_bond {
+        let caller: AccountId = whitelisted_caller();
+        let _ = Currencies::deposit(NATIVE, &caller, 1_000_000_000_000u128)?;
+        register(&caller);
+        assert_ok!(ZdRefreshReputation::deregister_pathfinder(RawOrigin::Signed(caller.clone()).into()));
+        System::set_block_number(System::block_number() + UnbondingPeriod::get());
+    }: _(RawOrigin::Signed(caller.clone()))
+
+    lift_suspension {
+        let pathfinder: AccountId = account("pathfinder", 0, 0);
+        PathfinderStatuses::<Runtime>::insert(&pathfinder, PathfinderStatus {
+            suspended_until: ZdReputation::get_round() + 1,
+            ..PathfinderStatus::default()
+        });
+    }: _(RawOrigin::Root, pathfinder)
+
+    set_parameters {
+        let share_ratio = Perbill::from_percent(20);
+        let ratio = Perbill::from_percent(5);
+    }: _(RawOrigin::Root, 2_000_000_000u128, share_ratio, ratio, ratio)
+
+    approve_staking_currency {
+    }: _(RawOrigin::Root, CurrencyId::SOCI, 1_000_000_000u128)
+
+    revoke_staking_currency {
+        assert_ok!(ZdRefreshReputation::approve_staking_currency(RawOrigin::Root.into(), CurrencyId::SOCI, 1_000_000_000u128));
+    }: _(RawOrigin::Root, CurrencyId::SOCI)
+
+    set_staking_currency {
+        let caller: AccountId = whitelisted_caller();
+        let _ = Currencies::deposit(NATIVE, &caller, 1_000_000_000_000u128)?;
+        register(&caller);
+        assert_ok!(ZdRefreshReputation::approve_staking_currency(RawOrigin::Root.into(), CurrencyId::SOCI, 1_000_000_000u128));
+    }: _(RawOrigin::Signed(caller.clone()), Some(CurrencyId::SOCI))
+
+    opt_out {
+        let caller: AccountId = whitelisted_caller();
+    }: _(RawOrigin::Signed(caller.clone()))
+
+    opt_in {
+        let caller: AccountId = whitelisted_caller();
+        assert_ok!(ZdRefreshReputation::opt_out(RawOrigin::Signed(caller.clone()).into()));
+    }: _(RawOrigin::Signed(caller.clone()))
+
+    commit_refresh {
+        let vault = account("vault", 0, 0);
+        assert_ok!(Currencies::deposit(NATIVE, &vault, 1_000_000_000_000u128));
+        assert_ok!(ZdReputation::new_round());
+        ZdReputation::set_step(&TIRStep::Reputation);
+        System::set_block_number(2000);
+        assert_ok!(ZdRefreshReputation::start(RawOrigin::Signed(vault.clone()).into()));
+        let caller: AccountId = whitelisted_caller();
+        let _ = Currencies::deposit(NATIVE, &caller, 1_000_000_000_000u128)?;
+        register(&caller);
+    }: _(RawOrigin::Signed(caller.clone()), Default::default())
+
+    forfeit_commitment {
+        let pathfinder: AccountId = account("pathfinder", 0, 0);
+        let vault = account("vault", 0, 0);
+        assert_ok!(Currencies::deposit(NATIVE, &vault, 1_000_000_000_000u128));
+        assert_ok!(ZdToken::staking(&vault, &CommitDeposit::get()));
+        let round = ZdReputation::get_round();
+        RefreshCommits::<Runtime>::insert(round, &pathfinder, (Hash::default(), CommitDeposit::get()));
+        assert_ok!(ZdReputation::new_round());
+        let caller: AccountId = whitelisted_caller();
+    }: _(RawOrigin::Signed(caller.clone()), round, pathfinder)
+
+    // `d` is the total number of trustees shared to, all trusted by the first target.
     refresh {
-        let a in 0 .. MAX_UPDATE_COUNT;
+        let a in 1 .. MAX_UPDATE_COUNT;
+        let d in 0 .. MAX_TRUST_COUNT;
 
         let vault = account("vault", 0, 0);
         assert_ok!(Currencies::deposit(NATIVE, &vault, 1_000_000_000_000u128));
@@ -102,10 +233,10 @@ runtime_benchmarks! {
             let targer_account: AccountId = account("targer", 0, targer);
             accounts.push((targer_account.clone(),100));
             let _ = <ZdToken as MultiBaseToken<_,_>>::transfer_social(&vault.clone(), &targer_account.clone(), 10_000);
-            for trustee in 1..MAX_TRUST_COUNT {
-                let trustee_account: AccountId = account("trustee", targer, trustee);
-                checked_trust(&targer_account,&trustee_account);
-            }
+        }
+        for trustee in 0..d {
+            let trustee_account: AccountId = account("trustee", 0, trustee);
+            checked_trust(&accounts[0].0, &trustee_account);
         }
         assert_ok!(ZdReputation::new_round());
         ZdReputation::set_step(&TIRStep::Reputation);
@@ -113,20 +244,100 @@ runtime_benchmarks! {
         let _ = Currencies::deposit(NATIVE, &caller, 1_000_000_000_000u128)?;
         System::set_block_number(2000);
         assert_ok!(ZdRefreshReputation::start(RawOrigin::Signed(vault.clone()).into()));
+        register(&caller);
     }: _(RawOrigin::Signed(caller.clone()),accounts)
 
+    retract_refresh {
+        let a in 1 .. MAX_UPDATE_COUNT;
+
+        let vault = account("vault", 0, 0);
+        assert_ok!(Currencies::deposit(NATIVE, &vault, 1_000_000_000_000u128));
+        let mut accounts: Vec<(AccountId,u32)> = vec![];
+        for targer in 0..a {
+            let targer_account: AccountId = account("targer", 0, targer);
+            accounts.push((targer_account.clone(),100));
+            let _ = <ZdToken as MultiBaseToken<_,_>>::transfer_social(&vault.clone(), &targer_account.clone(), 10_000);
+        }
+        assert_ok!(ZdReputation::new_round());
+        ZdReputation::set_step(&TIRStep::Reputation);
+        let caller: AccountId = whitelisted_caller();
+        let _ = Currencies::deposit(NATIVE, &caller, 1_000_000_000_000u128)?;
+        System::set_block_number(2000);
+        assert_ok!(ZdRefreshReputation::start(RawOrigin::Signed(vault.clone()).into()));
+        register(&caller);
+        assert_ok!(ZdRefreshReputation::refresh(RawOrigin::Signed(caller.clone()).into(), accounts.clone()));
+        let targets = accounts.into_iter().map(|(a, _)| a).collect::<Vec<AccountId>>();
+    }: _(RawOrigin::Signed(caller.clone()), targets)
+
+    withdraw_refresh {
+        let vault = account("vault", 0, 0);
+        assert_ok!(Currencies::deposit(NATIVE, &vault, 1_000_000_000_000u128));
+        let target: AccountId = account("targer", 0, 0);
+        let _ = <ZdToken as MultiBaseToken<_,_>>::transfer_social(&vault.clone(), &target.clone(), 10_000);
+        assert_ok!(ZdReputation::new_round());
+        ZdReputation::set_step(&TIRStep::Reputation);
+        let caller: AccountId = whitelisted_caller();
+        let _ = Currencies::deposit(NATIVE, &caller, 1_000_000_000_000u128)?;
+        System::set_block_number(2000);
+        assert_ok!(ZdRefreshReputation::start(RawOrigin::Signed(vault.clone()).into()));
+        register(&caller);
+        assert_ok!(ZdRefreshReputation::refresh(RawOrigin::Signed(caller.clone()).into(), vec![(target.clone(), 100)]));
+    }: _(RawOrigin::Signed(caller.clone()), target)
+
+    amend_refresh {
+        let vault = account("vault", 0, 0);
+        assert_ok!(Currencies::deposit(NATIVE, &vault, 1_000_000_000_000u128));
+        let target: AccountId = account("targer", 0, 0);
+        let _ = <ZdToken as MultiBaseToken<_,_>>::transfer_social(&vault.clone(), &target.clone(), 10_000);
+        assert_ok!(ZdReputation::new_round());
+        ZdReputation::set_step(&TIRStep::Reputation);
+        let caller: AccountId = whitelisted_caller();
+        let _ = Currencies::deposit(NATIVE, &caller, 1_000_000_000_000u128)?;
+        System::set_block_number(2000);
+        assert_ok!(ZdRefreshReputation::start(RawOrigin::Signed(vault.clone()).into()));
+        register(&caller);
+        assert_ok!(ZdRefreshReputation::refresh(RawOrigin::Signed(caller.clone()).into(), vec![(target.clone(), 100)]));
+        System::set_block_number(2001);
+    }: _(RawOrigin::Signed(caller.clone()), target, 200)
+
     harvest_ref_all {
         let pathfinder: AccountId = account("pathfinder", 0, 0);
         init_harvest(&pathfinder);
         System::set_block_number(2000);
-    }: _(RawOrigin::Signed(pathfinder.clone()))
+        let round = ZdReputation::get_round();
+    }: _(RawOrigin::Signed(pathfinder.clone()), round)
+
+    harvest_ref_matured {
+        let pathfinder: AccountId = account("pathfinder", 0, 0);
+        init_harvest(&pathfinder);
+        System::set_block_number(2000);
+        let round = ZdReputation::get_round();
+    }: _(RawOrigin::Signed(pathfinder.clone()), round)
+
+    harvest_ref_partial {
+        let r in 1 .. MAX_REFRESH;
+
+        let pathfinder: AccountId = account("pathfinder", 0, 0);
+        init_harvest(&pathfinder);
+        System::set_block_number(2000);
+        let round = ZdReputation::get_round();
+    }: _(RawOrigin::Signed(pathfinder.clone()), round, r)
 
     harvest_ref_all_sweeper {
         let pathfinder: AccountId = account("pathfinder", 0, 0);
         init_harvest(&pathfinder);
         System::set_block_number(2000);
         let sweeper: AccountId = account("sweeper", 0, 0);
-    }: _(RawOrigin::Signed(sweeper.clone()),pathfinder)
+        let round = ZdReputation::get_round();
+    }: _(RawOrigin::Signed(sweeper.clone()), round, pathfinder)
+
+    transfer_payroll {
+        let pathfinder: AccountId = account("pathfinder", 0, 0);
+        init_harvest(&pathfinder);
+        let to: AccountId = account("to", 0, 0);
+        let round = ZdReputation::get_round();
+        let _ = ZdRefreshReputation::mutate_payroll(round, &to, &2000u128, &0, &System::block_number());
+    }: _(RawOrigin::Signed(pathfinder.clone()), round, to, true)
 
     challenge {
         let challenger = account("challenger", 0, 0);
@@ -137,7 +348,7 @@ runtime_benchmarks! {
             <ZdSeeds as SeedsBase<_>>::add_seed(&new_seed);
         }
         let pathfinder: AccountId = account("pathfinder", 0, 0);
-    }: _(RawOrigin::Signed(challenger.clone()),targer.clone(),pathfinder,1,2)
+    }: _(RawOrigin::Signed(challenger.clone()),targer.clone(),pathfinder,1,2,None)
 
     challenge_update {
         let a in 1 .. MAX_SEED_COUNT;
@@ -168,7 +379,7 @@ runtime_benchmarks! {
         assert_ok!(ZdToken::staking(&vault, &1_000_000_000u128));
 
         let pathfinder: AccountId = account("pathfinder", 0, 0);
-        let _ = ZdRefreshReputation::challenge(RawOrigin::Signed(challenger.clone()).into(),targer.clone(),pathfinder,a - 1,2)?;
+        let _ = ZdRefreshReputation::challenge(RawOrigin::Signed(challenger.clone()).into(),targer.clone(),pathfinder,a - 1,2,None)?;
     }: _(RawOrigin::Signed(challenger.clone()),targer.clone(),seeds,paths)
 
     harvest_challenge {
@@ -184,11 +395,45 @@ runtime_benchmarks! {
         let vault = account("vault", 0, 0);
         Currencies::deposit(NATIVE, &vault, 1_000_000_000_000u128)?;
         assert_ok!(ZdToken::staking(&vault, &1_000_000_000u128));
-        let _ = ZdRefreshReputation::challenge(RawOrigin::Signed(challenger.clone()).into(),targer.clone(),pathfinder,1,2)?;
+        let _ = ZdRefreshReputation::challenge(RawOrigin::Signed(challenger.clone()).into(),targer.clone(),pathfinder,1,2,None)?;
         System::set_block_number(2000);
         let sweeper: AccountId = account("sweeper", 0, 0);
     }: _(RawOrigin::Signed(sweeper.clone()),targer.clone())
 
+    back_challenge {
+        let challenger = account("challenger", 0, 0);
+        let targer: AccountId = account("targer", 0, 0);
+        let pathfinder: AccountId = account("pathfinder", 0, 0);
+
+        init_challenge(&challenger,&targer,2);
+        for s in 1..2 {
+            let new_seed: AccountId = account("seed", 0, s);
+            <ZdSeeds as SeedsBase<_>>::add_seed(&new_seed);
+        }
+        let vault = account("vault", 0, 0);
+        Currencies::deposit(NATIVE, &vault, 1_000_000_000_000u128)?;
+        assert_ok!(ZdToken::staking(&vault, &1_000_000_000u128));
+        let _ = ZdRefreshReputation::challenge(RawOrigin::Signed(challenger.clone()).into(),targer.clone(),pathfinder,1,2,None)?;
+        let backer: AccountId = account("backer", 0, 0);
+        Currencies::deposit(NATIVE, &backer, 1_000_000_000_000u128)?;
+    }: _(RawOrigin::Signed(backer.clone()),targer.clone(),1_000u128)
+
+    cancel_challenge {
+        let challenger = account("challenger", 0, 0);
+        let targer: AccountId = account("targer", 0, 0);
+        let pathfinder: AccountId = account("pathfinder", 0, 0);
+
+        init_challenge(&challenger,&targer,2);
+        for s in 1..2 {
+            let new_seed: AccountId = account("seed", 0, s);
+            <ZdSeeds as SeedsBase<_>>::add_seed(&new_seed);
+        }
+        let vault = account("vault", 0, 0);
+        Currencies::deposit(NATIVE, &vault, 1_000_000_000_000u128)?;
+        assert_ok!(ZdToken::staking(&vault, &1_000_000_000u128));
+        let _ = ZdRefreshReputation::challenge(RawOrigin::Signed(challenger.clone()).into(),targer.clone(),pathfinder,1,2,None)?;
+    }: _(RawOrigin::Signed(challenger.clone()),targer.clone())
+
     arbitral {
         let a in 0 .. MAX_SEED_COUNT;
 
@@ -227,7 +472,7 @@ runtime_benchmarks! {
         }
 
         init_challenge(&challenger,&targer,2);
-        let _ = ZdRefreshReputation::challenge(RawOrigin::Signed(challenger.clone()).into(),targer.clone(),pathfinder,1,2)?;
+        let _ = ZdRefreshReputation::challenge(RawOrigin::Signed(challenger.clone()).into(),targer.clone(),pathfinder,1,2,None)?;
         // RawOrigin::Signed(challenger.clone()),targer.clone(),seeds,paths
         let seed_1: AccountId = account("seed", 0, 1);
         let nodes_1: Vec<AccountId> = vec![
@@ -244,6 +489,37 @@ runtime_benchmarks! {
         System::set_block_number(2000);
     }: _(RawOrigin::Signed(who.clone()),targer.clone(),seeds,paths)
 
+    appeal {
+        let challenger = account("challenger", 0, 0);
+        let targer: AccountId = account("targer", 0, 0);
+        let pathfinder: AccountId = account("pathfinder", 0, 0);
+        init_appeal(&challenger,&targer,&pathfinder);
+    }: _(RawOrigin::Signed(pathfinder.clone()),targer.clone())
+
+    resolve_appeal {
+        let challenger = account("challenger", 0, 0);
+        let targer: AccountId = account("targer", 0, 0);
+        let pathfinder: AccountId = account("pathfinder", 0, 0);
+        init_appeal(&challenger,&targer,&pathfinder);
+        let _ = ZdRefreshReputation::appeal(RawOrigin::Signed(pathfinder.clone()).into(),targer.clone())?;
+        System::set_block_number(System::block_number() + AppealVotingPeriod::get() + 1);
+    }: _(RawOrigin::Root,targer.clone(),2)
+
+    vote_appeal {
+        let challenger = account("challenger", 0, 0);
+        let targer: AccountId = account("targer", 0, 0);
+        let pathfinder: AccountId = account("pathfinder", 0, 0);
+        init_appeal(&challenger,&targer,&pathfinder);
+        let _ = ZdRefreshReputation::appeal(RawOrigin::Signed(pathfinder.clone()).into(),targer.clone())?;
+        let voter: AccountId = account("arbiter", 0, 0);
+        let arbiter: AccountId = account("arbiter", 0, 1);
+        for who in [voter.clone(), arbiter.clone()].iter() {
+            let _ = Currencies::deposit(NATIVE, who, 1_000_000_000_000u128);
+            let _ = Arbiters::add_member(RawOrigin::Root.into(), who.clone())?;
+        }
+        let _ = ZdRefreshReputation::vote_appeal(RawOrigin::Signed(voter.clone()).into(),targer.clone(),2)?;
+    }: _(RawOrigin::Signed(arbiter.clone()),targer.clone(),2)
+
 }
 
 #[cfg(test)]
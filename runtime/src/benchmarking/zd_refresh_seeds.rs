@@ -34,6 +34,7 @@ fn do_challenge(pathfinder_score: u64, challenger_score: u64) {
         RawOrigin::Signed(challenger.clone()).into(),
         target,
         challenger_score,
+        None,
     );
 }
 
@@ -190,7 +191,7 @@ runtime_benchmarks! {
         let challenger: AccountId = account("challenger", 0, 0);
         let target: AccountId = account("target", 0, 0);
         Currencies::deposit(NATIVE, &challenger, 1_000_000_000_000u128)?;
-    }: _(RawOrigin::Signed(challenger.clone()),target,22)
+    }: _(RawOrigin::Signed(challenger.clone()),target,22,None)
 
     examine {
         let pathfinder: AccountId = account("pathfinder", 0, 0);
@@ -291,7 +292,7 @@ runtime_benchmarks! {
 
     }: _(RawOrigin::Signed(challenger.clone()),target,0,vec![
         mid_node
-    ])
+    ],None)
 
     number_too_low {
         let a in 2 .. 99;
@@ -324,7 +325,7 @@ runtime_benchmarks! {
         ]);
 
         ZdRefreshSeeds::reply_path(RawOrigin::Signed(pathfinder.clone()).into(),target.clone(),paths,a - 1)?;
-    }: _(RawOrigin::Signed(challenger.clone()),target,0,mid_paths)
+    }: _(RawOrigin::Signed(challenger.clone()),target,0,mid_paths,None)
 
     missed_in_hashs {
         let pathfinder: AccountId = account("pathfinder", 0, 0);
@@ -374,7 +375,7 @@ runtime_benchmarks! {
         mock_start,
         target,
         mock_end
-    ], index as u32)
+    ], index as u32, None)
 
     missed_in_paths {
         let pathfinder: AccountId = account("pathfinder", 0, 0);
@@ -402,7 +403,7 @@ runtime_benchmarks! {
         mid_node,
         target,
         end_node
-    ])
+    ],None)
 
     invalid_evidence {
         let pathfinder: AccountId = account("pathfinder", 0, 0);
@@ -444,7 +445,7 @@ runtime_benchmarks! {
             mock_start,
             target.clone(),
             mock_end
-        ],1)?;
+        ],1,None)?;
 
     }: _(RawOrigin::Signed(challenger.clone()),target.clone(),vec![],6000u64)
 
@@ -455,6 +456,19 @@ runtime_benchmarks! {
         let sweeper: AccountId = account("sweeper", 0, 0);
     }: _(RawOrigin::Signed(sweeper.clone()),target.clone())
 
+    back_challenge {
+        let target: AccountId = account("target", 0, 0);
+        do_challenge(200u64, 100u64);
+        let backer: AccountId = account("backer", 0, 0);
+        Currencies::deposit(NATIVE, &backer, 1_000_000_000_000u128)?;
+    }: _(RawOrigin::Signed(backer.clone()),target.clone(),1_000u128)
+
+    cancel_challenge {
+        let challenger: AccountId = account("challenger", 0, 0);
+        let target: AccountId = account("target", 0, 0);
+        do_challenge(200u64, 100u64);
+    }: _(RawOrigin::Signed(challenger.clone()),target.clone())
+
     harvest_seed {
         System::set_block_number(2000);
 
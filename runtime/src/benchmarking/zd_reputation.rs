@@ -0,0 +1,49 @@
+use crate::{AccountId, MaxUpdateCount, Runtime, ZdReputation};
+use frame_benchmarking::account;
+use sp_std::prelude::*;
+
+use orml_benchmarking::runtime_benchmarks;
+use zd_support::Reputation;
+
+use frame_support::assert_ok;
+
+const MAX_UPDATE_COUNT: u32 = MaxUpdateCount::get();
+
+fn init_user_scores(a: u32) -> Vec<(AccountId, u32)> {
+    assert_ok!(ZdReputation::new_round());
+    (0..a)
+        .map(|i| (account("targer", 0, i), 100))
+        .collect::<Vec<(AccountId, u32)>>()
+}
+
+runtime_benchmarks! {
+    { Runtime, zd_reputation }
+
+    _ {}
+
+    // Refresh `a` users one at a time, to compare with `bulk_refresh`.
+    refresh_reputation {
+        let a in 1 .. MAX_UPDATE_COUNT;
+        let user_scores = init_user_scores(a);
+    }: {
+        for user_score in user_scores.iter() {
+            assert_ok!(ZdReputation::refresh_reputation(user_score));
+        }
+    }
+
+    bulk_refresh {
+        let a in 1 .. MAX_UPDATE_COUNT;
+        let user_scores = init_user_scores(a);
+    }: {
+        assert_ok!(ZdReputation::bulk_refresh(&user_scores[..]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmarking::utils::tests::new_test_ext;
+    use orml_benchmarking::impl_benchmark_test_suite;
+
+    impl_benchmark_test_suite!(new_test_ext(),);
+}
@@ -63,6 +63,12 @@ runtime_benchmarks! {
 		let _ = ZdTrust::trust(RawOrigin::Signed(who.clone()).into(), target.clone().into());
     }: _(RawOrigin::Signed(who.clone()), target.into())
 
+    set_trust_strength {
+        let target: AccountId = account("target", 0, SEED);
+        let who: AccountId = account("who", 0, SEED);
+        let _ = ZdTrust::trust(RawOrigin::Signed(who.clone()).into(), target.clone().into());
+    }: _(RawOrigin::Signed(who.clone()), target.into(), 10)
+
 }
 
 #[cfg(test)]
@@ -8,11 +8,11 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
-use sp_std::prelude::*;
+use sp_std::{convert::TryInto, prelude::*};
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature,
-	transaction_validity::{TransactionValidity, TransactionSource},
+	transaction_validity::{TransactionPriority, TransactionValidity, TransactionSource},
 };
 use sp_runtime::traits::{
 	AccountIdLookup, BlakeTwo256, Block as BlockT, Verify, IdentifyAccount, NumberFor, Zero
@@ -33,7 +33,7 @@ pub use pallet_balances::Call as BalancesCall;
 pub use sp_runtime::{Permill, Perbill};
 pub use frame_support::{
 	construct_runtime, parameter_types, StorageValue,
-	traits::{KeyOwnerProofSystem, Randomness},
+	traits::{FindAuthor, Get, KeyOwnerProofSystem, Randomness},
 	weights::{
 		Weight, IdentityFee,
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
@@ -43,6 +43,10 @@ use pallet_transaction_payment::CurrencyAdapter;
 
 use orml_currencies::BasicCurrencyAdapter;
 use orml_traits::{parameter_type_with_key};
+use zd_primitives::{
+	fee::SweeperCurve, AppId, ChallengePreview, DomainId, Phase, RoundInfo, SWEEPER_PERIOD,
+};
+use zd_support::Reputation;
 
 mod benchmarking;
 
@@ -104,10 +108,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("ZeroDAO-node"),
 	impl_name: create_runtime_str!("ZeroDAO-node"),
 	authoring_version: 1,
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 };
 
 
@@ -298,8 +302,18 @@ impl orml_tokens::Config for Runtime {
     type OnDust = ();
 }
 
+parameter_types! {
+    pub const ReputationHistoryRounds: u32 = 16;
+    pub const MaxDelegators: u32 = 100;
+    pub const PriorityPerTier: TransactionPriority = 1_000_000_000;
+}
+
 impl zd_reputation::Config for Runtime {
     type Event = Event;
+    type HistoryRounds = ReputationHistoryRounds;
+    type MaxDelegators = MaxDelegators;
+    type PriorityPerTier = PriorityPerTier;
+    type SlashOrigin = frame_system::EnsureRoot<AccountId>;
 }
 
 impl zd_seeds::Config for Runtime {
@@ -325,6 +339,11 @@ parameter_types! {
     pub SocialPoolAccountId: AccountId = AccountId::from([0u8; 32]);
 }
 
+parameter_types! {
+    /// No part of the refresh fee is returned to the refreshed user.
+    pub const UserRebateRatio: Perbill = Perbill::zero();
+}
+
 impl zd_tokens::Config for Runtime {
     type Event = Event;
     type CurrencyId = CurrencyId;
@@ -333,6 +352,7 @@ impl zd_tokens::Config for Runtime {
     type SocialPool = SocialPoolAccountId;
     type Amount = Amount;
     type BaceToken = GetNativeCurrencyId;
+    type UserRebateRatio = UserRebateRatio;
 }
 
 parameter_types! {
@@ -340,39 +360,181 @@ parameter_types! {
     pub const ChallengeTimeout: BlockNumber = 100;
         /// Response time period of challenge system.
     pub const ChallengeStakingAmount: Balance = 100;
+    /// Maximum number of data uploads per call of challenge system.
+    pub const MaxNextSteps: u32 = 257;
+    /// Minimum difference between the claimed and the recorded score of a challenge.
+    pub const MinDisputeDelta: u64 = 2;
+    /// Time a reply or evidence pushes the deadline of a challenge forward by.
+    pub const ResponseWindow: BlockNumber = 10;
+    /// Maximum total time the deadline of a challenge can be pushed forward by.
+    pub const MaxResponseExtension: BlockNumber = 50;
+    /// Maximum `total` of data announced by a reply of challenge system.
+    pub const MaxReplyCount: u32 = 1000;
+    /// Maximum number of data uploaded by a reply of challenge system.
+    pub const MaxLegsPerReply: u32 = 257;
+    pub const MaxBackers: u32 = 20;
+    pub const MaxEvidenceLen: u32 = 64;
+    pub const CancellationFee: Balance = 10;
+    pub const AppealPeriod: BlockNumber = 50;
+    pub const AppealStakingAmount: Balance = 200;
+    /// Matching votes of the arbiters that resolve an appeal.
+    pub const ArbitralThreshold: u32 = 2;
+    /// Staked by an arbiter on each vote on an appeal.
+    pub const ArbiterStakingAmount: Balance = 50;
+    /// How long the arbiters may vote on an appeal before `AppealOrigin` may resolve it.
+    pub const AppealVotingPeriod: BlockNumber = 200;
+    pub const MaxAutoSettlements: u32 = 10;
+    pub const MaxHistory: u32 = 10;
+    pub const HistoryRounds: u32 = 5;
+    pub const MaxHistoryPrunes: u32 = 10;
+    /// Maximum number of expired challenges settled by the offchain worker in each block.
+    pub const MaxOffchainSettlements: u32 = 10;
+    /// Priority of unsigned settlements submitted by offchain workers.
+    pub const ChallengeUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+    Call: From<C>,
+{
+    type OverarchingCall = Call;
+    type Extrinsic = UncheckedExtrinsic;
 }
 
 impl zd_challenges::Config for Runtime {
     type Event = Event;
     type CurrencyId = CurrencyId;
     type ZdToken = ZdToken;
+    type CurrencyStaking = ZdToken;
     type Reputation = ZdReputation;
     type ChallengeStakingAmount = ChallengeStakingAmount;
     type ChallengeTimeout = ChallengeTimeout;
+    type MaxNextSteps = MaxNextSteps;
+    type MinDisputeDelta = MinDisputeDelta;
+    type ResponseWindow = ResponseWindow;
+    type MaxResponseExtension = MaxResponseExtension;
+    type MaxReplyCount = MaxReplyCount;
+    type MaxLegsPerReply = MaxLegsPerReply;
+    type AppConfigOrigin = frame_system::EnsureRoot<AccountId>;
+    type PauseOrigin = frame_system::EnsureRoot<AccountId>;
+    type TreasuryAccountId = TreasuryAccountId;
+    type MaxBackers = MaxBackers;
+    type MaxEvidenceLen = MaxEvidenceLen;
+    type CancellationFee = CancellationFee;
+    type AppealPeriod = AppealPeriod;
+    type AppealStakingAmount = AppealStakingAmount;
+    type Arbiters = Arbiters;
+    type ArbitralThreshold = ArbitralThreshold;
+    type ArbiterStakingAmount = ArbiterStakingAmount;
+    type AppealVotingPeriod = AppealVotingPeriod;
+    type MaxAutoSettlements = MaxAutoSettlements;
+    type MaxOffchainSettlements = MaxOffchainSettlements;
+    type UnsignedPriority = ChallengeUnsignedPriority;
+    type MaxHistory = MaxHistory;
+    type HistoryRounds = HistoryRounds;
+    type MaxHistoryPrunes = MaxHistoryPrunes;
+    type Extra = ();
+    type Score = u64;
+    type OnHarvested = (ZdRefreshReputation, ZdRefreshSeeds);
+    type OnTransition = ();
+    type WeightInfo = zd_challenges::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
-    /// The reputation must be refreshed within this time period.
-    pub const RefRepuTiomeOut: BlockNumber = 14_400;
+    /// Sweepers may settle a payroll this many blocks early, without a fee.
+    pub const ProxyGracePeriod: BlockNumber = 5;
     /// Amount needed for staking when refreshing reputation and seeds.
     pub const UpdateStakingAmount: Balance = 1_000_000_000;
     /// Maximum number of uploads, which is a security setting
     pub const MaxUpdateCount: u32 = 20;
+    /// Maximum number of unharvested refreshes in the payroll of a pathfinder.
+    pub const MaxPayrollRecords: u32 = 200;
+    /// Maximum unharvested staking and fees in the payroll of a pathfinder.
+    pub const MaxPayrollAmount: Balance = 1_000_000_000_000;
     /// Challenges to reputation will not be allowed beyond this time period.
     pub const ConfirmationPeriod: BlockNumber = 10;
+    /// Consecutive rounds started by the same account that decay its sweeper fee.
+    pub const RewardDecayWindow: u32 = 3;
+    /// Receives the decayed part of the sweeper fee and of slashed pathfinder stakes.
+    pub TreasuryAccountId: AccountId = AccountId::from([1u8; 32]);
+    /// Part of the staking of a pathfinder losing a challenge awarded to the challenger.
+    pub const ChallengerSlashRatio: Perbill = Perbill::from_percent(50);
+    /// Minimum bond to register as a pathfinder.
+    pub const PathfinderBond: Balance = 10_000_000_000;
+    /// Blocks before a deregistered pathfinder can withdraw its bond.
+    pub const UnbondingPeriod: BlockNumber = 7 * DAYS;
+    /// Challenges a pathfinder can lose within `LossWindow` rounds before it is suspended.
+    pub const MaxChallengeLosses: u32 = 3;
+    /// Rounds in which the challenge losses of a pathfinder are counted.
+    pub const LossWindow: u32 = 10;
+    /// Rounds a suspended pathfinder cannot refresh.
+    pub const SuspensionRounds: u32 = 5;
+    /// Blocks after the start of a round in which refreshes can be committed.
+    pub const CommitPeriod: BlockNumber = 10;
+    /// Deposit of a committed refresh, forfeited if it is never revealed.
+    pub const CommitDeposit: Balance = 1_000_000_000;
+    /// Records checked for pruning in each block.
+    pub const MaxRecordPrunes: u32 = 50;
+    /// Priority of unsigned refreshes submitted by offchain workers.
+    pub const RefreshUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2;
+    /// The sweeper share of overdue proceeds grows to 5% over the day after sweepers are allowed.
+    pub const SweeperFeeCurve: SweeperCurve = SweeperCurve {
+        delay: SWEEPER_PERIOD,
+        ramp: DAYS as u64,
+        max: Perbill::from_percent(5),
+    };
+    /// A user refreshed by a pathfinder cannot be refreshed by another one in the same round.
+    pub const RefreshConflictPolicy: zd_refresh_reputation::RefreshConflict =
+        zd_refresh_reputation::RefreshConflict::Reject;
+}
+
+/// The account of the Aura authority that authored the current block.
+pub struct AuraAuthor;
+
+impl Get<Option<AccountId>> for AuraAuthor {
+    fn get() -> Option<AccountId> {
+        let digest = System::digest();
+        let pre_runtime_digests = digest.logs.iter().filter_map(|d| d.as_pre_runtime());
+        let index = Aura::find_author(pre_runtime_digests)?;
+        let authority = Aura::authorities().get(index as usize)?.clone();
+        let public: [u8; 32] = authority.as_ref().try_into().ok()?;
+        Some(AccountId::from(public))
+    }
 }
 
 impl zd_refresh_reputation::Config for Runtime {
     type Event = Event;
     type MultiBaseToken = ZdToken;
     type MaxUpdateCount = MaxUpdateCount;
+    type MaxPayrollRecords = MaxPayrollRecords;
+    type MaxPayrollAmount = MaxPayrollAmount;
     type UpdateStakingAmount = UpdateStakingAmount;
-    type ConfirmationPeriod = ConfirmationPeriod;
     type Reputation = ZdReputation;
     type TrustBase = ZdTrust;
     type ChallengeBase = ZdChallenges;
+    type CurrencyId = CurrencyId;
+    type CurrencyStaking = ZdToken;
     type SeedsBase = ZdSeeds;
-    type RefRepuTiomeOut = RefRepuTiomeOut;
+    type ProxyGracePeriod = ProxyGracePeriod;
+    type RewardDecayWindow = RewardDecayWindow;
+    type TreasuryAccountId = TreasuryAccountId;
+    type ChallengerSlashRatio = ChallengerSlashRatio;
+    type OffchainSignature = Signature;
+    type OffchainPublic = <Signature as Verify>::Signer;
+    type PathfinderBond = PathfinderBond;
+    type UnbondingPeriod = UnbondingPeriod;
+    type MaxChallengeLosses = MaxChallengeLosses;
+    type LossWindow = LossWindow;
+    type SuspensionRounds = SuspensionRounds;
+    type CommitPeriod = CommitPeriod;
+    type CommitDeposit = CommitDeposit;
+    type MaxRecordPrunes = MaxRecordPrunes;
+    type BlockAuthor = AuraAuthor;
+    type SweeperCurve = SweeperFeeCurve;
+    type ConflictPolicy = RefreshConflictPolicy;
+    type ParametersOrigin = frame_system::EnsureRoot<AccountId>;
+    type AppealOrigin = frame_system::EnsureRoot<AccountId>;
+    type UnsignedPriority = RefreshUnsignedPriority;
 	type WeightInfo = ();
 }
 
@@ -404,6 +566,18 @@ impl pallet_utility::Config for Runtime {
 	type WeightInfo = pallet_utility::weights::SubstrateWeight<Runtime>;
 }
 
+/// The committee voting on the appeals of challenges.
+impl pallet_membership::Config<pallet_membership::Instance1> for Runtime {
+	type Event = Event;
+	type AddOrigin = frame_system::EnsureRoot<AccountId>;
+	type RemoveOrigin = frame_system::EnsureRoot<AccountId>;
+	type SwapOrigin = frame_system::EnsureRoot<AccountId>;
+	type ResetOrigin = frame_system::EnsureRoot<AccountId>;
+	type PrimeOrigin = frame_system::EnsureRoot<AccountId>;
+	type MembershipInitialized = ();
+	type MembershipChanged = ();
+}
+
 // Create the runtime by composing the FRAME pallets that were previously configured.
 construct_runtime!(
 	pub enum Runtime where
@@ -421,6 +595,7 @@ construct_runtime!(
 		Sudo: pallet_sudo::{Module, Call, Config<T>, Storage, Event<T>},
 
 		Utility: pallet_utility::{Module, Call, Event},
+		Arbiters: pallet_membership::<Instance1>::{Module, Call, Storage, Event<T>},
 
 		Tokens: orml_tokens::{Module, Storage, Event<T>, Config<T>},
         Currencies: orml_currencies::{Module, Storage, Event<T>},
@@ -429,8 +604,8 @@ construct_runtime!(
 		ZdSeeds: zd_seeds::{Module, Call, Storage, Event<T>},
 		ZdTrust: zd_trust::{Module, Call, Storage, Event<T>},
 		ZdToken: zd_tokens::{Module, Call, Storage, Event<T>},
-		ZdChallenges: zd_challenges::{Module, Storage, Event<T>},
-		ZdRefreshReputation: zd_refresh_reputation::{Module, Call, Storage, Event<T>},
+		ZdChallenges: zd_challenges::{Module, Call, Storage, Event<T>, ValidateUnsigned},
+		ZdRefreshReputation: zd_refresh_reputation::{Module, Call, Config, Storage, Event<T>, ValidateUnsigned},
 		ZdRefreshSeeds: zd_refresh_seeds::{Module, Call, Storage, Event<T>},
 	}
 );
@@ -453,7 +628,8 @@ pub type SignedExtra = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>
+	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	zd_reputation::TierPriority<Runtime>,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
@@ -600,6 +776,97 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl zd_refresh_reputation_rpc_runtime_api::RefreshReputationApi<Block, AccountId, Balance> for Runtime {
+		fn is_challengeable(pathfinder: AccountId, target: AccountId) -> bool {
+			ZdRefreshReputation::is_challengeable(&pathfinder, &target)
+		}
+
+		fn social_balances(accounts: Vec<AccountId>) -> Vec<Balance> {
+			ZdRefreshReputation::social_balances(&accounts[..])
+		}
+
+		fn check_payroll_integrity(round: u32, pathfinder: AccountId) -> bool {
+			ZdRefreshReputation::check_payroll_integrity(round, &pathfinder)
+		}
+
+		fn can_sweeper_harvest(round: u32, pathfinder: AccountId) -> bool {
+			ZdRefreshReputation::can_sweeper_harvest(round, &pathfinder)
+		}
+
+		fn pathfinder_exposure(round: u32, pathfinder: AccountId) -> (u32, Balance, u32, Balance) {
+			ZdRefreshReputation::pathfinder_exposure(round, &pathfinder)
+		}
+
+		fn all_pending_payrolls(
+			limit: u32,
+			start_key: Option<Vec<u8>>,
+		) -> (Vec<(u32, AccountId, u32, Balance)>, Option<Vec<u8>>) {
+			ZdRefreshReputation::all_pending_payrolls(limit, start_key)
+		}
+	}
+
+	impl zd_reputation_rpc_runtime_api::ReputationApi<Block, AccountId, BlockNumber> for Runtime {
+		fn get_reputation(account: AccountId) -> Option<u32> {
+			ZdReputation::get_reputation(&account)
+		}
+
+		fn get_reputations(accounts: Vec<AccountId>) -> Vec<Option<u32>> {
+			let max = zd_reputation_rpc_runtime_api::MAX_REPUTATION_READS as usize;
+			ZdReputation::get_many(&accounts[..accounts.len().min(max)])
+		}
+
+		fn get_reputation_in(domain: DomainId, account: AccountId) -> Option<u32> {
+			ZdReputation::get_reputation_in(&domain, &account)
+		}
+
+		fn get_reputation_new(account: AccountId) -> Option<u32> {
+			ZdReputation::get_reputation_new(&account)
+		}
+
+		fn get_reputation_old(account: AccountId) -> Option<u32> {
+			ZdReputation::get_reputation_old(&account)
+		}
+
+		fn get_round() -> u32 {
+			ZdReputation::get_round()
+		}
+
+		fn round_info() -> RoundInfo<BlockNumber> {
+			ZdReputation::round_info()
+		}
+
+		fn phase() -> Phase {
+			ZdReputation::phase()
+		}
+
+		fn effective_reputation(account: AccountId) -> u64 {
+			ZdReputation::effective_reputation(&account)
+		}
+
+		fn reputation_history(account: AccountId) -> Vec<(u32, u32)> {
+			ZdReputation::reputation_history(&account)
+		}
+
+		fn reputation_at(round: u32, account: AccountId) -> Option<u32> {
+			ZdReputation::reputation_at(round, &account)
+		}
+	}
+
+	impl zd_challenges_rpc_runtime_api::ChallengesApi<Block, AccountId, BlockNumber> for Runtime {
+		fn can_challenge(
+			app_id: AppId,
+			_challenger: AccountId,
+			target: AccountId,
+		) -> ChallengePreview<BlockNumber> {
+			let (score, in_confirmation) = match app_id {
+				zd_refresh_reputation::APP_ID => ZdRefreshReputation::challenge_state(&target),
+				zd_refresh_seeds::APP_ID => ZdRefreshSeeds::challenge_state(&target),
+				_ => (0, false),
+			};
+			ChallengePreview { score, in_confirmation, ..ZdChallenges::preview(&app_id) }
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(
@@ -636,6 +903,8 @@ impl_runtime_apis! {
 			orml_add_benchmark!(params, batches, zd_trust, benchmarking::zd_trust);
 			orml_add_benchmark!(params, batches, zd_refresh_reputation, benchmarking::zd_refresh_reputation);
 			orml_add_benchmark!(params, batches, zd_refresh_seeds, benchmarking::zd_refresh_seeds);
+			orml_add_benchmark!(params, batches, zd_reputation, benchmarking::zd_reputation);
+			orml_add_benchmark!(params, batches, zd_challenges, benchmarking::zd_challenges);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)